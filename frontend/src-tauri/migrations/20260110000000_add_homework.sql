@@ -0,0 +1,35 @@
+-- Migration: Add homework tracking between lessons
+-- This migration adds:
+--   1. homework_items table (homework extracted from session summaries or added manually)
+--   2. lesson_schedule table (upcoming lessons, used as the default homework due date)
+
+-- Homework items carried between sessions
+--   - status: 'open', 'done' or 'skipped'
+--   - source_meeting_id: session in which the homework was assigned
+--   - reviewed_in_meeting_id: later session whose notes report the homework as reviewed
+--   - reminder_sent_at: set once a due reminder notification has been shown
+CREATE TABLE IF NOT EXISTS homework_items (
+    id TEXT PRIMARY KEY,
+    source_meeting_id TEXT NOT NULL,
+    description TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    due_date TEXT,
+    reviewed_in_meeting_id TEXT,
+    reminder_sent_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (source_meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_homework_items_source_meeting_id ON homework_items(source_meeting_id);
+CREATE INDEX IF NOT EXISTS idx_homework_items_status_due_date ON homework_items(status, due_date);
+
+-- Upcoming scheduled lessons
+CREATE TABLE IF NOT EXISTS lesson_schedule (
+    id TEXT PRIMARY KEY,
+    title TEXT,
+    scheduled_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_lesson_schedule_scheduled_at ON lesson_schedule(scheduled_at);
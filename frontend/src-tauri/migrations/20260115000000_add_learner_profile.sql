@@ -0,0 +1,12 @@
+-- Migration: Add learner profile
+-- Single-row table (id '1') holding the values used for template variables
+-- such as {{target_language}} and {{learner_level}}
+
+CREATE TABLE IF NOT EXISTS learner_profile (
+    id TEXT PRIMARY KEY,
+    target_language TEXT,
+    native_language TEXT,
+    learner_level TEXT,
+    tutor_name TEXT,
+    updated_at TEXT NOT NULL
+);
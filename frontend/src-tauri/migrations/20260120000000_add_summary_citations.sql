@@ -0,0 +1,18 @@
+-- Migration: Add transcript citations for summary items
+-- Each row links a note item of a session summary to the transcript segment
+-- (and audio position) it cites via a [mm:ss] marker
+
+CREATE TABLE IF NOT EXISTS summary_citations (
+    id TEXT PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    line_index INTEGER NOT NULL,
+    section TEXT,
+    item_text TEXT NOT NULL,
+    timestamp_label TEXT NOT NULL,
+    audio_start_time REAL NOT NULL,
+    transcript_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_summary_citations_meeting_id ON summary_citations(meeting_id);
@@ -0,0 +1,32 @@
+-- Migration: Add summary version history
+-- Every generated summary is recorded here, so regenerating never loses earlier
+-- results. summary_processes.result stays the current version.
+--   - source: 'generated', 'edited' (user edits captured before a restore) or 'restored'
+--   - restored_from: version number a 'restored' version was copied from
+
+CREATE TABLE IF NOT EXISTS summary_versions (
+    id TEXT PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    version_number INTEGER NOT NULL,
+    source TEXT NOT NULL DEFAULT 'generated',
+    restored_from INTEGER,
+    provider TEXT,
+    model TEXT,
+    template_id TEXT,
+    template_version INTEGER,
+    prompt_hash TEXT,
+    result TEXT NOT NULL,
+    metadata TEXT,
+    started_at TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE,
+    UNIQUE (meeting_id, version_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_summary_versions_meeting_id ON summary_versions(meeting_id);
+
+-- Existing summaries become version 1 of their session
+INSERT INTO summary_versions (id, meeting_id, version_number, source, result, metadata, started_at, created_at)
+SELECT 'version-' || lower(hex(randomblob(16))), meeting_id, 1, 'generated', result, metadata, start_time, COALESCE(end_time, updated_at)
+FROM summary_processes
+WHERE result IS NOT NULL AND meeting_id IN (SELECT id FROM meetings);
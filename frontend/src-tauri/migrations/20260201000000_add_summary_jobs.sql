@@ -0,0 +1,30 @@
+-- Migration: Add persistent summary job queue
+-- Summary requests are queued here instead of running as detached tasks, so they
+-- survive restarts and can be retried. The transcript itself is read from
+-- transcript_chunks when the job runs.
+--   - status: 'queued', 'running', 'done', 'failed' or 'cancelled'
+--   - attempts: number of times the job was started
+--   - next_attempt_at: earliest time a queued job may run (set when retrying)
+
+CREATE TABLE IF NOT EXISTS summary_jobs (
+    id TEXT PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    template_id TEXT NOT NULL,
+    custom_prompt TEXT NOT NULL DEFAULT '',
+    previous_session_count INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    next_attempt_at TEXT NOT NULL,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    started_at TEXT,
+    finished_at TEXT,
+    FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_summary_jobs_status ON summary_jobs(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_summary_jobs_meeting_id ON summary_jobs(meeting_id);
@@ -0,0 +1,34 @@
+-- Migration: Add session tags and batch summary regeneration
+--   - meeting_tags: free-form labels used to select sessions (e.g. "tutoring", "B1")
+--   - summary_batches: one regeneration run over many sessions; its jobs are the
+--     summary_jobs rows with the same batch_id
+--   - summary_batches.status: 'running', 'completed' or 'cancelled'
+--   - summary_batches.skipped: selected sessions that couldn't be queued
+
+CREATE TABLE IF NOT EXISTS meeting_tags (
+    meeting_id TEXT NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (meeting_id, tag),
+    FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_meeting_tags_tag ON meeting_tags(tag);
+
+CREATE TABLE IF NOT EXISTS summary_batches (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'running',
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    template_id TEXT NOT NULL,
+    selection TEXT NOT NULL,
+    total INTEGER NOT NULL DEFAULT 0,
+    skipped INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    finished_at TEXT
+);
+
+ALTER TABLE summary_jobs ADD COLUMN batch_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_summary_jobs_batch_id ON summary_jobs(batch_id);
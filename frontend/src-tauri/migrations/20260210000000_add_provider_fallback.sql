@@ -0,0 +1,4 @@
+-- Migration: Add provider fallback settings for summary generation
+
+-- This column stores: {chain: [{provider, model}], neverSendProviders: [provider]}
+ALTER TABLE settings ADD COLUMN providerFallbackConfig TEXT;
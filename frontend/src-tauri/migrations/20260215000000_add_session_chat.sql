@@ -0,0 +1,19 @@
+-- Migration: Add chat with a session
+-- Questions about a session's transcript and their answers, one conversation per session
+--   - role: 'user' or 'assistant'
+--   - citations: JSON array of the transcript segments an answer cites (assistant messages only)
+--   - provider, model: what generated the answer (assistant messages only)
+
+CREATE TABLE IF NOT EXISTS chat_messages (
+    id TEXT PRIMARY KEY,
+    meeting_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    content TEXT NOT NULL,
+    citations TEXT,
+    provider TEXT,
+    model TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_meeting_id ON chat_messages(meeting_id, created_at);
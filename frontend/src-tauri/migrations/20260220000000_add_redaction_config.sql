@@ -0,0 +1,4 @@
+-- Migration: Add personal data redaction settings for cloud providers
+
+-- This column stores: {enabled, names: [name], allowedTerms: [term]}
+ALTER TABLE settings ADD COLUMN redactionConfig TEXT;
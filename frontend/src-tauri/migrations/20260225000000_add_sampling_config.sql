@@ -0,0 +1,4 @@
+-- Migration: Add sampling parameters for providers without a configuration of their own
+
+-- This column stores: {maxTokens, temperature, topP}, used for Gemini and Ollama
+ALTER TABLE settings ADD COLUMN samplingConfig TEXT;
@@ -0,0 +1,6 @@
+-- Migration: Record where homework items came from
+
+-- origin: 'extracted' from the session notes or 'manual' when added by the user.
+-- Only extracted items are replaced when the notes are regenerated; items stored
+-- before this migration can't be told apart and are treated as extracted.
+ALTER TABLE homework_items ADD COLUMN origin TEXT NOT NULL DEFAULT 'extracted';
@@ -6,6 +6,8 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::network::{self, Channel};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyticsConfig {
     pub api_key: String,
@@ -90,6 +92,7 @@ impl AnalyticsClient {
             }
         }
         
+        self.check_network()?;
         if let Err(e) = client.capture(event).await {
             eprintln!("Failed to identify user: {}", e);
         }
@@ -133,6 +136,7 @@ impl AnalyticsClient {
             }
         }
         
+        self.check_network()?;
         if let Err(e) = client.capture(event).await {
             log::warn!("Failed to track event {}: {}", event_name, e);
         }
@@ -393,6 +397,12 @@ impl AnalyticsClient {
         self.track_event("analytics_transparency_viewed", Some(properties)).await
     }
 
+    /// Checks the PostHog host with the network gate before an event is sent
+    fn check_network(&self) -> Result<(), String> {
+        let host = self.config.host.as_deref().unwrap_or("https://us.i.posthog.com");
+        network::check(host, Channel::Analytics).map_err(String::from)
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.config.enabled && self.client.is_some()
     }
@@ -420,6 +430,7 @@ impl AnalyticsClient {
             }
         }
         
+        self.check_network()?;
         if let Err(e) = client.capture(event).await {
             eprintln!("Failed to set user properties: {}", e);
         }
@@ -8,13 +8,17 @@ use crate::{
     database::{
         models::MeetingModel,
         repositories::{
-            meeting::MeetingsRepository, setting::SettingsRepository,
-            transcript::TranscriptsRepository,
+            meeting::MeetingsRepository, meeting_tag::MeetingTagsRepository,
+            setting::SettingsRepository, transcript::TranscriptsRepository,
         },
     },
+    network::{self, Channel},
     onboarding::load_onboarding_status,
     state::AppState,
-    summary::CustomOpenAIConfig,
+    summary::{
+        fallback::ProviderFallbackConfig, redaction::RedactionConfig, CustomOpenAIConfig,
+        SamplingConfig,
+    },
 };
 
 // Hardcoded server URL
@@ -250,6 +254,7 @@ async fn make_api_request<R: Runtime, T: for<'de> Deserialize<'de>>(
 
     let url = format!("{}{}", server_url, endpoint);
     log_info!("Making {} request to: {}", method, url);
+    network::check(&url, Channel::AppServer)?;
 
     let mut request = match method.to_uppercase().as_str() {
         "GET" => client.get(&url),
@@ -482,7 +487,7 @@ pub async fn api_get_model_config<R: Runtime>(
                 &config.whisper_model,
                 &config.ollama_endpoint
             );
-            match SettingsRepository::get_api_key(pool, &config.provider).await {
+            match SettingsRepository::get_api_key_for_display(pool, &config.provider).await {
                 Ok(api_key) => {
                     log_info!("Successfully retrieved model config and API key.");
                     Ok(Some(ModelConfig {
@@ -532,6 +537,9 @@ pub async fn api_save_model_config<R: Runtime>(
         &whisper_model,
         &ollama_endpoint
     );
+    if let Some(key) = &api_key {
+        crate::secrets::redact::register(key);
+    }
     let pool = state.db_manager.pool();
 
     if let Err(e) = SettingsRepository::save_model_config(
@@ -582,7 +590,7 @@ pub async fn api_get_api_key<R: Runtime>(
         "api_get_api_key called (native) for provider '{}'",
         &provider
     );
-    match SettingsRepository::get_api_key(&state.db_manager.pool(), &provider).await {
+    match SettingsRepository::get_api_key_for_display(&state.db_manager.pool(), &provider).await {
         Ok(key) => {
             log_info!(
                 "Successfully retrieved API key for provider '{}'.",
@@ -613,7 +621,7 @@ pub async fn api_get_transcript_config<R: Runtime>(
                 &config.provider,
                 &config.model
             );
-            match SettingsRepository::get_transcript_api_key(pool, &config.provider).await {
+            match SettingsRepository::get_transcript_api_key_for_display(&config.provider).await {
                 Ok(api_key) => {
                     log_info!("Successfully retrieved transcript config and API key.");
                     Ok(Some(TranscriptConfig {
@@ -660,6 +668,9 @@ pub async fn api_save_transcript_config<R: Runtime>(
         "api_save_transcript_config called (native) for provider '{}'",
         &provider
     );
+    if let Some(key) = &api_key {
+        crate::secrets::redact::register(key);
+    }
     let pool = state.db_manager.pool();
 
     if let Err(e) = SettingsRepository::save_transcript_config(pool, &provider, &model).await {
@@ -687,7 +698,7 @@ pub async fn api_save_transcript_config<R: Runtime>(
 #[tauri::command]
 pub async fn api_get_transcript_api_key<R: Runtime>(
     _app: AppHandle<R>,
-    state: tauri::State<'_, AppState>,
+    _state: tauri::State<'_, AppState>,
     provider: String,
     _auth_token: Option<String>,
 ) -> Result<String, String> {
@@ -695,7 +706,7 @@ pub async fn api_get_transcript_api_key<R: Runtime>(
         "api_get_transcript_api_key called (native) for provider '{}'",
         &provider
     );
-    match SettingsRepository::get_transcript_api_key(&state.db_manager.pool(), &provider).await {
+    match SettingsRepository::get_transcript_api_key_for_display(&provider).await {
         Ok(key) => {
             log_info!(
                 "Successfully retrieved transcript API key for provider '{}'.",
@@ -927,6 +938,49 @@ pub async fn api_save_session_title<R: Runtime>(
     }
 }
 
+#[tauri::command]
+pub async fn api_get_session_tags<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+) -> Result<Vec<String>, String> {
+    MeetingTagsRepository::get_tags(state.db_manager.pool(), &meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load session tags: {}", e))
+}
+
+/// Replaces the tags of a session; tags are used to select sessions, e.g. for batch regeneration
+#[tauri::command]
+pub async fn api_save_session_tags<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    tags: Vec<String>,
+) -> Result<Vec<String>, String> {
+    log_info!("api_save_session_tags called for meeting_id: {}", meeting_id);
+    let pool = state.db_manager.pool();
+    MeetingTagsRepository::set_tags(pool, &meeting_id, &tags)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to save tags for {}: {}", meeting_id, e);
+            format!("Failed to save session tags: {}", e)
+        })?;
+    MeetingTagsRepository::get_tags(pool, &meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load session tags: {}", e))
+}
+
+/// Lists all tags in use with the number of sessions carrying them
+#[tauri::command]
+pub async fn api_list_session_tags<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<(String, i64)>, String> {
+    MeetingTagsRepository::list_tags(state.db_manager.pool())
+        .await
+        .map_err(|e| format!("Failed to list session tags: {}", e))
+}
+
 #[tauri::command]
 pub async fn api_save_transcript<R: Runtime>(
     _app: AppHandle<R>,
@@ -1088,6 +1142,7 @@ pub async fn test_backend_connection<R: Runtime>(
 
     log_debug!("Testing connection to: {}", server_url);
 
+    network::check(&server_url, Channel::AppServer)?;
     let mut request = client.get(&format!("{}/docs", server_url));
 
     if let Some(token) = auth_token {
@@ -1130,6 +1185,8 @@ pub async fn debug_backend_connection<R: Runtime>(app: AppHandle<R>) -> Result<S
 
     log_debug!("Testing connection to: {}", test_url);
 
+    network::check(&test_url, Channel::AppServer)?;
+
     match client.get(&test_url).send().await {
         Ok(response) => {
             let status = response.status();
@@ -1185,6 +1242,9 @@ pub async fn api_save_custom_openai_config<R: Runtime>(
         &endpoint,
         &model
     );
+    if let Some(key) = &api_key {
+        crate::secrets::redact::register(key);
+    }
 
     // Validate required fields
     if endpoint.trim().is_empty() {
@@ -1269,6 +1329,154 @@ pub async fn api_get_custom_openai_config<R: Runtime>(
     }
 }
 
+// ===== PROVIDER FALLBACK COMMANDS =====
+
+/// Saves the provider fallback configuration
+/// The chain is tried in order when the selected provider can't be reached, is
+/// rate limited or times out; "never send" providers never receive transcripts
+#[tauri::command]
+pub async fn api_save_provider_fallback_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: ProviderFallbackConfig,
+) -> Result<serde_json::Value, String> {
+    log_info!(
+        "api_save_provider_fallback_config called: {} fallback(s), {} blocked provider(s)",
+        config.chain.len(),
+        config.never_send_providers.len()
+    );
+
+    config.validate()?;
+
+    let pool = state.db_manager.pool();
+
+    match SettingsRepository::save_provider_fallback_config(pool, &config).await {
+        Ok(true) => Ok(serde_json::json!({
+            "status": "success",
+            "message": "Provider fallback configuration saved successfully"
+        })),
+        Ok(false) => Err("Save a summary model configuration first".to_string()),
+        Err(e) => {
+            log_error!("❌ Failed to save provider fallback config: {}", e);
+            Err(format!("Failed to save provider fallback configuration: {}", e))
+        }
+    }
+}
+
+/// Gets the provider fallback configuration (empty if none was saved)
+#[tauri::command]
+pub async fn api_get_provider_fallback_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProviderFallbackConfig, String> {
+    SettingsRepository::get_provider_fallback_config(state.db_manager.pool())
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| {
+            log_error!("❌ Failed to get provider fallback config: {}", e);
+            format!("Failed to get provider fallback configuration: {}", e)
+        })
+}
+
+// ===== REDACTION COMMANDS =====
+
+/// Saves the personal data redaction configuration
+/// Emails, phone numbers, addresses and the listed names are replaced with
+/// placeholders before transcripts are sent to cloud providers
+#[tauri::command]
+pub async fn api_save_redaction_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: RedactionConfig,
+) -> Result<serde_json::Value, String> {
+    log_info!(
+        "api_save_redaction_config called: enabled={}, {} name(s), {} allowed term(s)",
+        config.enabled,
+        config.names.len(),
+        config.allowed_terms.len()
+    );
+
+    config.validate()?;
+
+    let pool = state.db_manager.pool();
+
+    match SettingsRepository::save_redaction_config(pool, &config).await {
+        Ok(true) => Ok(serde_json::json!({
+            "status": "success",
+            "message": "Redaction configuration saved successfully"
+        })),
+        Ok(false) => Err("Save a summary model configuration first".to_string()),
+        Err(e) => {
+            log_error!("❌ Failed to save redaction config: {}", e);
+            Err(format!("Failed to save redaction configuration: {}", e))
+        }
+    }
+}
+
+/// Gets the personal data redaction configuration (enabled with no names if none was saved)
+#[tauri::command]
+pub async fn api_get_redaction_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RedactionConfig, String> {
+    SettingsRepository::get_redaction_config(state.db_manager.pool())
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| {
+            log_error!("❌ Failed to get redaction config: {}", e);
+            format!("Failed to get redaction configuration: {}", e)
+        })
+}
+
+// ===== SAMPLING COMMANDS =====
+
+/// Saves the sampling parameters used for Gemini and Ollama
+/// (the custom OpenAI endpoint keeps its own parameters)
+#[tauri::command]
+pub async fn api_save_sampling_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    config: SamplingConfig,
+) -> Result<serde_json::Value, String> {
+    log_info!(
+        "api_save_sampling_config called: max_tokens={:?}, temperature={:?}, top_p={:?}",
+        config.max_tokens,
+        config.temperature,
+        config.top_p
+    );
+
+    config.validate()?;
+
+    let pool = state.db_manager.pool();
+
+    match SettingsRepository::save_sampling_config(pool, &config).await {
+        Ok(true) => Ok(serde_json::json!({
+            "status": "success",
+            "message": "Sampling configuration saved successfully"
+        })),
+        Ok(false) => Err("Save a summary model configuration first".to_string()),
+        Err(e) => {
+            log_error!("❌ Failed to save sampling config: {}", e);
+            Err(format!("Failed to save sampling configuration: {}", e))
+        }
+    }
+}
+
+/// Gets the sampling parameters used for Gemini and Ollama (provider defaults if none were saved)
+#[tauri::command]
+pub async fn api_get_sampling_config<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SamplingConfig, String> {
+    SettingsRepository::get_sampling_config(state.db_manager.pool())
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| {
+            log_error!("❌ Failed to get sampling config: {}", e);
+            format!("Failed to get sampling configuration: {}", e)
+        })
+}
+
 /// Tests the connection to a custom OpenAI-compatible endpoint
 /// Makes a minimal request to verify the endpoint is reachable and responds correctly
 #[tauri::command]
@@ -1283,6 +1491,9 @@ pub async fn api_test_custom_openai_connection<R: Runtime>(
         &endpoint,
         &model
     );
+    if let Some(key) = &api_key {
+        crate::secrets::redact::register(key);
+    }
 
     // Validate endpoint URL format
     if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
@@ -1291,6 +1502,7 @@ pub async fn api_test_custom_openai_connection<R: Runtime>(
 
     // Build the URL - append /chat/completions to the base endpoint
     let url = format!("{}/chat/completions", endpoint.trim_end_matches('/'));
+    network::check(&url, Channel::LlmProvider)?;
 
     // Create a minimal test request
     let test_request = serde_json::json!({
@@ -712,8 +712,8 @@ pub fn write_transcript_to_file(
 
     let file_path = final_output_path.join(format!("transcript_{}.txt", timestamp));
 
-    // Write transcript to file
-    std::fs::write(&file_path, transcript_text)?;
+    // Write transcript to file (encrypted when encryption is on)
+    crate::encryption::files::write(&file_path, transcript_text.as_bytes())?;
 
     Ok(file_path.to_string_lossy().to_string())
 }
@@ -759,7 +759,7 @@ pub fn write_transcript_json_to_file(
 
     // Write JSON to file with pretty formatting
     let json_string = serde_json::to_string_pretty(&transcript_json)?;
-    std::fs::write(&file_path, json_string)?;
+    crate::encryption::files::write(&file_path, json_string.as_bytes())?;
 
     Ok(file_path.to_string_lossy().to_string())
 }
@@ -1,11 +1,130 @@
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use anyhow::{Result, anyhow};
 use log::{info, warn, error};
-use super::encode::encode_single_audio;
 use super::recording_state::AudioChunk;
 use serde::{Serialize, Deserialize};
 
 use super::ffmpeg::find_ffmpeg_path;
+use crate::encryption::files as encrypted_files;
+use crate::encryption::vault::vault;
+
+/// Runs FFmpeg with `input` on stdin and returns what it wrote to stdout
+///
+/// Decrypted audio only ever passes through the pipes, never the disk.
+fn run_ffmpeg_piped(args: &[&str], input: Vec<u8>) -> Result<Vec<u8>> {
+    let ffmpeg_path = find_ffmpeg_path()
+        .ok_or_else(|| anyhow!("FFmpeg not found. Please install FFmpeg to save recordings."))?;
+
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Hide console window on Windows to prevent CMD popup during recording
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = command.spawn()?;
+    let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to open FFmpeg stdin"))?;
+    // Written from another thread, as FFmpeg fills stdout before it has read all of stdin
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    let written = writer.join().map_err(|_| anyhow!("FFmpeg input thread panicked"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        error!("FFmpeg failed: {}", stderr);
+        return Err(anyhow!("FFmpeg failed: {}", stderr));
+    }
+    written?;
+    Ok(output.stdout)
+}
+
+/// Encodes samples to an AAC checkpoint, encrypted when encryption is on
+fn write_checkpoint(samples: &[f32], sample_rate: u32, path: &Path) -> Result<()> {
+    let sample_rate = sample_rate.to_string();
+    let encoded = run_ffmpeg_piped(
+        &[
+            "-f", "f32le",
+            "-ar", &sample_rate,
+            "-ac", "1",              // mono
+            "-i", "pipe:0",
+            "-c:a", "aac",
+            "-b:a", "192k",
+            "-profile:a", "aac_low",
+            // Fragmented, since a plain MP4 can't be written to a pipe
+            "-movflags", "frag_keyframe+empty_moov",
+            "-f", "mp4",
+            "pipe:1",
+        ],
+        bytemuck::cast_slice(samples).to_vec(),
+    )?;
+    encrypted_files::write(path, &encoded)?;
+    Ok(())
+}
+
+/// Merges checkpoints into one audio file without re-encoding
+///
+/// Each checkpoint is decrypted in memory and piped through FFmpeg to extract
+/// its AAC stream; the concatenated streams are then muxed into `output`.
+/// With encryption on, the output is muxed in memory too and encrypted before
+/// it is written.
+fn merge_checkpoint_files(checkpoints: &[PathBuf], output: &Path) -> Result<()> {
+    let mut aac = Vec::new();
+    for path in checkpoints {
+        let data = encrypted_files::read(path)
+            .map_err(|e| anyhow!("Failed to read checkpoint {}: {}", path.display(), e))?;
+        aac.extend(run_ffmpeg_piped(
+            &["-i", "pipe:0", "-c", "copy", "-f", "adts", "pipe:1"],
+            data,
+        )?);
+    }
+
+    let encrypted = vault().sealing_keys().map_err(|e| anyhow!(e))?.is_some();
+    if encrypted {
+        let muxed = run_ffmpeg_piped(
+            &[
+                "-f", "aac",
+                "-i", "pipe:0",
+                "-c", "copy",        // Copy codec - no re-encoding!
+                "-movflags", "frag_keyframe+empty_moov",
+                "-f", "mp4",
+                "pipe:1",
+            ],
+            aac,
+        )?;
+        encrypted_files::write(output, &muxed)?;
+    } else {
+        let output_str = output
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid output path: {}", output.display()))?;
+        run_ffmpeg_piped(
+            &[
+                "-f", "aac",
+                "-i", "pipe:0",
+                "-c", "copy",        // Copy codec - no re-encoding!
+                "-movflags", "+faststart",
+                "-y",                // Overwrite output file
+                output_str,
+            ],
+            aac,
+        )?;
+    }
+
+    // Verify output file was created
+    if !output.exists() {
+        return Err(anyhow!("Merged audio file was not created: {}", output.display()));
+    }
+    Ok(())
+}
 
 /// Audio data without device type (we only store mixed audio)
 #[derive(Clone)]
@@ -93,12 +212,7 @@ impl IncrementalAudioSaver {
             .join(format!("audio_chunk_{:03}.mp4", self.checkpoint_count));
 
         // Encode and save checkpoint
-        encode_single_audio(
-            bytemuck::cast_slice(&audio_data),
-            self.sample_rate,
-            1,  // mono
-            &checkpoint_path
-        )?;
+        write_checkpoint(&audio_data, self.sample_rate, &checkpoint_path)?;
 
         let duration_seconds = audio_data.len() as f32 / self.sample_rate as f32;
         self.checkpoint_count += 1;
@@ -128,7 +242,7 @@ impl IncrementalAudioSaver {
             return Err(anyhow!("No audio checkpoints to merge - recording may have failed"));
         }
 
-        // Merge all checkpoints using FFmpeg concat
+        // Merge all checkpoints into the final file
         let final_audio_path = self.session_folder.join("audio.mp4");
         self.merge_checkpoints(&final_audio_path).await?;
 
@@ -144,15 +258,11 @@ impl IncrementalAudioSaver {
         Ok(final_audio_path)
     }
 
-    /// Merge all checkpoint files into final audio.mp4 using FFmpeg concat
-    /// Uses concat demuxer for fast merging without re-encoding
+    /// Merge all checkpoint files into final audio.mp4 without re-encoding
     async fn merge_checkpoints(&self, output: &PathBuf) -> Result<()> {
         info!("Merging {} checkpoints into final audio file...", self.checkpoint_count);
 
-        // Create concat list file for FFmpeg
-        let list_file = self.checkpoints_dir.join("concat_list.txt");
-        let mut list_content = String::new();
-
+        let mut checkpoints = Vec::new();
         for i in 0..self.checkpoint_count {
             let checkpoint_path = self.checkpoints_dir
                 .join(format!("audio_chunk_{:03}.mp4", i));
@@ -161,52 +271,10 @@ impl IncrementalAudioSaver {
             if !checkpoint_path.exists() {
                 return Err(anyhow!("Checkpoint file missing: {}", checkpoint_path.display()));
             }
-
-            // Use absolute path for FFmpeg (required for safe mode)
-            let abs_path = checkpoint_path.canonicalize()?;
-            list_content.push_str(&format!("file '{}'\n", abs_path.display()));
+            checkpoints.push(checkpoint_path);
         }
 
-        std::fs::write(&list_file, list_content)?;
-
-        let ffmpeg_path = find_ffmpeg_path()
-            .ok_or_else(|| anyhow!("FFmpeg not found. Please install FFmpeg to finalize recordings."))?;
-        info!("Using FFmpeg at: {:?}", ffmpeg_path);
-
-        // Run FFmpeg concat command
-        // Using concat demuxer with copy codec for fast merging (no re-encoding)
-        
-        let mut command = std::process::Command::new(ffmpeg_path);
-        
-        command.args(&[
-            "-f", "concat",          // Use concat demuxer
-            "-safe", "0",            // Allow absolute paths
-            "-i", list_file.to_str().unwrap(),
-            "-c", "copy",            // Copy codec - no re-encoding!
-            "-y",                    // Overwrite output file
-            output.to_str().unwrap()
-        ]);
-
-        // Hide console window on Windows to prevent CMD popup during finalization
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            command.creation_flags(CREATE_NO_WINDOW);
-        }
-
-        let ffmpeg_output = command.output()?;
-
-        if !ffmpeg_output.status.success() {
-            let stderr = String::from_utf8_lossy(&ffmpeg_output.stderr);
-            error!("FFmpeg merge failed: {}", stderr);
-            return Err(anyhow!("FFmpeg concat failed: {}", stderr));
-        }
-
-        // Verify output file was created
-        if !output.exists() {
-            return Err(anyhow!("Merged audio file was not created: {}", output.display()));
-        }
+        merge_checkpoint_files(&checkpoints, output)?;
 
         info!("Successfully merged {} checkpoints → {}",
               self.checkpoint_count, output.display());
@@ -259,6 +327,9 @@ pub async fn recover_audio_from_checkpoints(
         });
     }
 
+    // Decrypted copies left behind by earlier versions
+    encrypted_files::remove_plain_copies(&checkpoints_dir);
+
     // Scan for checkpoint files
     let mut checkpoint_files: Vec<_> = std::fs::read_dir(&checkpoints_dir)
         .map_err(|e| format!("Failed to read checkpoints directory: {}", e))?
@@ -287,55 +358,15 @@ pub async fn recover_audio_from_checkpoints(
 
     info!("Found {} checkpoint files, estimated duration: {:.2}s", chunk_count, estimated_duration);
 
-    // Create FFmpeg concat file
-    let concat_file_path = checkpoints_dir.join("concat_list.txt");
-    let mut concat_content = String::new();
-
-    for entry in &checkpoint_files {
-        let path = entry.path().canonicalize()
-            .map_err(|e| format!("Failed to canonicalize path: {}", e))?;
-        concat_content.push_str(&format!("file '{}'\n", path.display()));
-    }
-
-    std::fs::write(&concat_file_path, concat_content)
-        .map_err(|e| format!("Failed to write concat file: {}", e))?;
-
     // Run FFmpeg to merge chunks
     let output_path = folder_path.join("audio.mp4");
     let output_path_str = output_path.to_str()
         .ok_or("Invalid output path")?
         .to_string();
+    let checkpoint_paths: Vec<PathBuf> = checkpoint_files.iter().map(|entry| entry.path()).collect();
 
-    let ffmpeg_path = find_ffmpeg_path()
-        .ok_or_else(|| "FFmpeg not found. Please install FFmpeg to recover audio.".to_string())?;
-    info!("Using FFmpeg at: {:?}", ffmpeg_path);
-
-    let mut command = std::process::Command::new(ffmpeg_path);
-
-    command.args(&[
-        "-f", "concat",
-        "-safe", "0",
-        "-i", concat_file_path.to_str().unwrap(),
-        "-c", "copy",
-        "-y", // Overwrite if exists
-        &output_path_str
-    ]);
-
-    // Hide console window on Windows
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        command.creation_flags(CREATE_NO_WINDOW);
-    }
-
-    let ffmpeg_result = command.output();
-
-    match ffmpeg_result {
-        Ok(output) if output.status.success() => {
-            // Clean up concat file
-            let _ = std::fs::remove_file(concat_file_path);
-
+    match merge_checkpoint_files(&checkpoint_paths, &output_path) {
+        Ok(()) => {
             info!("Successfully recovered audio: {}", output_path_str);
 
             Ok(AudioRecoveryStatus {
@@ -346,25 +377,14 @@ pub async fn recover_audio_from_checkpoints(
                 message: format!("Successfully recovered {} audio chunks", chunk_count),
             })
         }
-        Ok(output) => {
-            let error = String::from_utf8_lossy(&output.stderr);
-            error!("FFmpeg recovery failed: {}", error);
-            Ok(AudioRecoveryStatus {
-                status: "failed".to_string(),
-                chunk_count,
-                estimated_duration_seconds: estimated_duration,
-                audio_file_path: None,
-                message: format!("FFmpeg failed: {}", error),
-            })
-        }
         Err(e) => {
-            error!("Failed to run FFmpeg: {}", e);
+            error!("Audio recovery failed: {}", e);
             Ok(AudioRecoveryStatus {
                 status: "failed".to_string(),
                 chunk_count,
                 estimated_duration_seconds: estimated_duration,
                 audio_file_path: None,
-                message: format!("Failed to run FFmpeg: {}", e),
+                message: format!("Failed to merge audio chunks: {}", e),
             })
         }
     }
@@ -276,7 +276,7 @@ impl RecordingSaver {
         let temp_path = folder.join(".metadata.json.tmp");
 
         let json_string = serde_json::to_string_pretty(metadata)?;
-        std::fs::write(&temp_path, json_string)?;
+        crate::encryption::files::write(&temp_path, json_string.as_bytes())?;
         std::fs::rename(&temp_path, &metadata_path)?;  // Atomic
 
         Ok(())
@@ -313,7 +313,7 @@ impl RecordingSaver {
             })?;
 
         // Write to temp file with error handling
-        std::fs::write(&temp_path, &json_string)
+        crate::encryption::files::write(&temp_path, json_string.as_bytes())
             .map_err(|e| {
                 error!("Failed to write transcript temp file to {}: {}", temp_path.display(), e);
                 anyhow::anyhow!("Failed to write temp file: {}", e)
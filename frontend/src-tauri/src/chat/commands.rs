@@ -0,0 +1,76 @@
+use crate::chat::service::{ChatService, ChatTurn};
+use crate::database::models::ChatMessageRecord;
+use crate::database::repositories::chat::ChatRepository;
+use crate::state::AppState;
+use log::{error as log_error, info as log_info};
+use tauri::{AppHandle, Runtime};
+
+/// Asks a question about a session and returns it with the answer
+///
+/// The answer is streamed with `chat-progress` events while it is generated,
+/// and stored under `message_id` (generated when not given). Pass the same id
+/// to `api_chat_cancel` to stop it.
+#[tauri::command]
+pub async fn api_chat_send_message<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    question: String,
+    message_id: Option<String>,
+) -> Result<ChatTurn, String> {
+    let message_id = message_id.unwrap_or_else(ChatRepository::new_message_id);
+    log_info!(
+        "api_chat_send_message called for meeting_id: {}, message_id: {}",
+        meeting_id,
+        message_id
+    );
+
+    ChatService::ask(
+        &app,
+        state.db_manager.pool(),
+        &meeting_id,
+        &message_id,
+        &question,
+    )
+    .await
+    .map_err(|e| {
+        log_error!("Failed to answer question for {}: {}", meeting_id, e);
+        e
+    })
+}
+
+/// Lists a session's conversation, oldest message first
+#[tauri::command]
+pub async fn api_chat_list_messages<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+) -> Result<Vec<ChatMessageRecord>, String> {
+    ChatRepository::list_messages(state.db_manager.pool(), &meeting_id)
+        .await
+        .map_err(|e| format!("Failed to list chat messages: {}", e))
+}
+
+/// Deletes a session's conversation, returning the number of deleted messages
+#[tauri::command]
+pub async fn api_chat_clear<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+) -> Result<u64, String> {
+    log_info!("api_chat_clear called for meeting_id: {}", meeting_id);
+
+    ChatRepository::clear_messages(state.db_manager.pool(), &meeting_id)
+        .await
+        .map_err(|e| format!("Failed to clear chat: {}", e))
+}
+
+/// Cancels the answer being generated under a message id
+#[tauri::command]
+pub async fn api_chat_cancel<R: Runtime>(
+    _app: AppHandle<R>,
+    message_id: String,
+) -> Result<bool, String> {
+    log_info!("api_chat_cancel called for message_id: {}", message_id);
+    Ok(ChatService::cancel(&message_id))
+}
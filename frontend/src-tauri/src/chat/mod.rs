@@ -0,0 +1,14 @@
+/// Chat module - answers questions about a session from its transcript
+///
+/// This module contains:
+/// - Keyword (BM25) retrieval of the transcript segments relevant to a question
+/// - Prompts with the retrieved excerpts and the recent conversation
+/// - Service answering with the configured summary model, citing segment timestamps
+/// - Tauri commands for asking, listing, clearing and cancelling
+
+pub mod commands;
+pub mod prompt;
+pub mod retrieval;
+pub mod service;
+
+pub use service::{ChatService, ChatTurn, CHAT_PROGRESS_EVENT};
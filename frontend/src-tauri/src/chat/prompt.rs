@@ -0,0 +1,143 @@
+use crate::database::models::ChatMessageRecord;
+use crate::summary::citations;
+
+/// Earlier messages of the conversation included in the prompt
+pub const MAX_HISTORY_MESSAGES: usize = 6;
+
+pub const SYSTEM_PROMPT: &str = "You answer questions about one recorded language lesson between a teacher and a student. Use only the transcript excerpts you are given. If they don't contain the answer, say so instead of guessing. Answer in the language of the question, briefly and in markdown.";
+
+/// Instruction added when the excerpts carry timestamps
+const CITATION_INSTRUCTION: &str = "The transcript lines start with `[mm:ss]` timestamps. End every statement with the timestamp of the transcript line it is based on, copied exactly (e.g. `You corrected 'I goed' to 'I went' [12:05]`). Only cite timestamps that appear in the excerpts.";
+
+/// Joins the selected passages, marking where parts of the transcript were left out
+///
+/// # Arguments
+/// * `passages` - All passages of the transcript (`[mm:ss] text` lines when timed)
+/// * `selected` - Indices of the passages to include, in transcript order
+pub fn build_excerpts(passages: &[String], selected: &[usize]) -> String {
+    let mut excerpts = Vec::with_capacity(selected.len() + 2);
+    if selected.first().is_some_and(|&first| first > 0) {
+        excerpts.push("…".to_string());
+    }
+    for (n, &i) in selected.iter().enumerate() {
+        if n > 0 && i > selected[n - 1] + 1 {
+            excerpts.push("…".to_string());
+        }
+        excerpts.push(passages[i].clone());
+    }
+    if selected
+        .last()
+        .is_some_and(|&last| last + 1 < passages.len())
+    {
+        excerpts.push("…".to_string());
+    }
+    excerpts.join("\n")
+}
+
+/// Terms the excerpts are retrieved with
+///
+/// Follow-up questions ("and what about the second one?") rarely repeat what
+/// they refer to, so the previous question is searched for as well.
+pub fn retrieval_query(question: &str, history: &[ChatMessageRecord]) -> String {
+    match history.iter().rev().find(|m| m.role == "user") {
+        Some(previous) => format!("{}\n{}", question, previous.content),
+        None => question.to_string(),
+    }
+}
+
+/// Builds the user prompt from the excerpts, the recent conversation and the question
+///
+/// Timestamps are stripped from earlier answers, so only timestamps of the
+/// excerpts can be cited.
+pub fn build_user_prompt(
+    excerpts: &str,
+    timed: bool,
+    history: &[ChatMessageRecord],
+    question: &str,
+) -> String {
+    let mut prompt = String::new();
+    if timed {
+        prompt.push_str(CITATION_INSTRUCTION);
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("<transcript_excerpts>\n");
+    prompt.push_str(excerpts);
+    prompt.push_str("\n</transcript_excerpts>\n\n");
+
+    let recent = &history[history.len().saturating_sub(MAX_HISTORY_MESSAGES)..];
+    if !recent.is_empty() {
+        prompt.push_str("<conversation>\n");
+        for message in recent {
+            let speaker = if message.role == "user" {
+                "Question"
+            } else {
+                "Answer"
+            };
+            prompt.push_str(&format!(
+                "{}: {}\n",
+                speaker,
+                citations::strip_citations(&message.content).trim()
+            ));
+        }
+        prompt.push_str("</conversation>\n\n");
+    }
+
+    prompt.push_str(&format!("Question: {}", question.trim()));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessageRecord {
+        ChatMessageRecord {
+            id: format!("chat-{}", content.len()),
+            meeting_id: "meeting-1".to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            citations: None,
+            provider: None,
+            model: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_excerpts_mark_gaps() {
+        let passages: Vec<String> = (0..6).map(|i| format!("[00:0{}] line {}", i, i)).collect();
+        assert_eq!(
+            build_excerpts(&passages, &[1, 2, 4]),
+            "…\n[00:01] line 1\n[00:02] line 2\n…\n[00:04] line 4\n…"
+        );
+        assert_eq!(
+            build_excerpts(&passages[..2], &[0, 1]),
+            "[00:00] line 0\n[00:01] line 1"
+        );
+    }
+
+    #[test]
+    fn test_follow_up_prompt() {
+        let history = vec![
+            message("user", "Which verbs did I get wrong?"),
+            message("assistant", "You said 'goed' instead of 'went' [03:12]."),
+        ];
+        assert_eq!(
+            retrieval_query("And how do I fix it?", &history),
+            "And how do I fix it?\nWhich verbs did I get wrong?"
+        );
+
+        let prompt = build_user_prompt(
+            "[03:12] Student: I goed",
+            true,
+            &history,
+            "And how do I fix it?",
+        );
+        assert!(prompt.starts_with(CITATION_INSTRUCTION));
+        assert!(prompt.contains("Answer: You said 'goed' instead of 'went' .\n"));
+        assert!(prompt.ends_with("Question: And how do I fix it?"));
+
+        let prompt = build_user_prompt("Student: I goed", false, &[], "Why?");
+        assert!(!prompt.contains("<conversation>") && !prompt.contains("[mm:ss]"));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// BM25 term frequency saturation
+const BM25_K1: f64 = 1.2;
+/// BM25 passage length normalization
+const BM25_B: f64 = 0.75;
+/// Query terms of at least this many characters also match words they start with,
+/// so "verb" finds "verbs" without a stemmer for every language
+const MIN_PREFIX_CHARS: usize = 4;
+
+/// Lowercased words of `text` (UAX #29), so ideographs come out one per term
+fn terms(text: &str) -> Vec<String> {
+    text.unicode_words().map(str::to_lowercase).collect()
+}
+
+fn term_matches(query_term: &str, term: &str) -> bool {
+    term == query_term
+        || (query_term.chars().count() >= MIN_PREFIX_CHARS && term.starts_with(query_term))
+}
+
+/// Ranks passages by their BM25 relevance to the query
+///
+/// # Returns
+/// Indices and scores of the passages matching at least one query term, best first
+pub fn rank_passages(query: &str, passages: &[&str]) -> Vec<(usize, f64)> {
+    let docs: Vec<Vec<String>> = passages.iter().map(|p| terms(p)).collect();
+    let total_terms: usize = docs.iter().map(Vec::len).sum();
+    if total_terms == 0 {
+        return Vec::new();
+    }
+    let avg_len = total_terms as f64 / docs.len() as f64;
+
+    let query_terms: HashSet<String> = terms(query).into_iter().collect();
+    let mut scores: HashMap<usize, f64> = HashMap::new();
+    for query_term in &query_terms {
+        let frequencies: Vec<(usize, usize)> = docs
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| {
+                (
+                    i,
+                    doc.iter().filter(|t| term_matches(query_term, t)).count(),
+                )
+            })
+            .filter(|(_, tf)| *tf > 0)
+            .collect();
+        if frequencies.is_empty() {
+            continue;
+        }
+
+        let n = docs.len() as f64;
+        let df = frequencies.len() as f64;
+        let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+        for (i, tf) in frequencies {
+            let tf = tf as f64;
+            let norm = 1.0 - BM25_B + BM25_B * docs[i].len() as f64 / avg_len;
+            *scores.entry(i).or_default() += idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm);
+        }
+    }
+
+    let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked
+}
+
+/// Picks the passages to answer a question from, in transcript order
+///
+/// Everything is used when it fits `token_budget`. Otherwise the best matches
+/// are taken with the passage before and after each, since a question and its
+/// answer are often said in neighbouring segments. Without any match, the
+/// passages from the start of the transcript are used.
+pub fn select_passages(
+    query: &str,
+    passages: &[&str],
+    token_budget: usize,
+    count_tokens: impl Fn(&str) -> usize,
+) -> Vec<usize> {
+    let costs: Vec<usize> = passages.iter().map(|p| count_tokens(p)).collect();
+    if costs.iter().sum::<usize>() <= token_budget {
+        return (0..passages.len()).collect();
+    }
+
+    let ranked = rank_passages(query, passages);
+    let mut selected = BTreeSet::new();
+    let mut used = 0;
+    // Adds a passage if it fits, returning whether it is selected
+    let mut take = |i: usize, selected: &mut BTreeSet<usize>| {
+        if !selected.contains(&i) && used + costs[i] <= token_budget {
+            used += costs[i];
+            selected.insert(i);
+        }
+        selected.contains(&i)
+    };
+
+    if ranked.is_empty() {
+        for i in 0..passages.len() {
+            if !take(i, &mut selected) {
+                break;
+            }
+        }
+    } else {
+        for (i, _) in ranked {
+            if !take(i, &mut selected) {
+                break;
+            }
+            if i > 0 {
+                take(i - 1, &mut selected);
+            }
+            if i + 1 < passages.len() {
+                take(i + 1, &mut selected);
+            }
+        }
+    }
+    selected.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LESSON: &[&str] = &[
+        "Teacher: Good morning, how was your weekend?",
+        "Student: It was fine, I visited my grandmother.",
+        "Teacher: Today we review irregular verbs in the past tense.",
+        "Student: I goed to the market yesterday.",
+        "Teacher: Careful, 'go' is irregular: I went to the market.",
+        "Student: Can we practise pronunciation of 'thought' and 'taught'?",
+        "Teacher: For homework, write ten sentences with irregular verbs.",
+    ];
+
+    #[test]
+    fn test_rank_prefers_rare_terms() {
+        let ranked = rank_passages("Which irregular verb did I get wrong?", LESSON);
+        let top: Vec<usize> = ranked.iter().take(3).map(|(i, _)| *i).collect();
+        assert!(top.contains(&2) && top.contains(&6), "{:?}", ranked);
+
+        let ranked = rank_passages("pronunciation", LESSON);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0, 5);
+    }
+
+    #[test]
+    fn test_rank_matches_ideographs() {
+        let passages = [
+            "老师：今天我们复习过去时。",
+            "学生：昨天我去了市场。",
+            "老师：作业是写十个句子。",
+        ];
+        let ranked = rank_passages("作业是什么？", &passages);
+        assert_eq!(ranked[0].0, 2);
+    }
+
+    #[test]
+    fn test_select_everything_within_budget() {
+        let selected = select_passages("homework", LESSON, 10_000, |p| p.len());
+        assert_eq!(selected, (0..LESSON.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_select_matches_with_neighbours() {
+        // Room for three passages of about 50 characters
+        let selected =
+            select_passages("What did I say about the market?", LESSON, 160, |p| p.len());
+        assert!(
+            selected.contains(&3) || selected.contains(&4),
+            "{:?}",
+            selected
+        );
+        assert!(selected.windows(2).all(|w| w[0] < w[1]));
+        assert!(selected.iter().map(|&i| LESSON[i].len()).sum::<usize>() <= 160);
+
+        // Nothing matches: the start of the lesson is used
+        let selected = select_passages("xylophone", LESSON, 100, |p| p.len());
+        assert_eq!(selected, vec![0, 1]);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::chat::{prompt, retrieval};
+use crate::database::models::ChatMessageRecord;
+use crate::database::repositories::{
+    chat::ChatRepository, setting::SettingsRepository, transcript::TranscriptsRepository,
+};
+use crate::summary::citations::{self, TimedSegment};
+use crate::summary::fallback::{self, FallbackProvider, ProviderFallbackConfig};
+use crate::summary::llm_client::{generate_summary, LlmError};
+use crate::summary::processor::{clean_llm_markdown_output, rough_token_count};
+use crate::summary::redaction;
+use crate::summary::streaming::StreamProgress;
+use crate::summary::SummaryService;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tracing::{info, warn};
+
+/// Event emitted with a `ChatProgress` while an answer is streamed
+pub const CHAT_PROGRESS_EVENT: &str = "chat-progress";
+
+/// Transcript tokens sent with a question, even when the model could take more
+const MAX_EXCERPT_TOKENS: usize = 12_000;
+
+/// Tokens kept free in the context window for the answer
+const ANSWER_RESERVE_TOKENS: usize = 500;
+
+/// Partial answer to a question about a session
+#[derive(Debug, Serialize)]
+pub struct ChatProgress<'a> {
+    pub meeting_id: &'a str,
+    /// Id the answer will be stored under, and cancelled with
+    pub message_id: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    #[serde(flatten)]
+    pub progress: &'a StreamProgress,
+}
+
+/// A question and its answer, as stored in the conversation
+#[derive(Debug, Serialize)]
+pub struct ChatTurn {
+    pub question: ChatMessageRecord,
+    pub answer: ChatMessageRecord,
+}
+
+/// Key of an answer in the summary cancellation registry
+pub fn chat_cancellation_key(message_id: &str) -> String {
+    format!("chat:{}", message_id)
+}
+
+/// Chat service - answers questions about a session from its transcript
+pub struct ChatService;
+
+impl ChatService {
+    /// Answers a question about a session with the configured summary model
+    ///
+    /// The transcript segments relevant to the question (and to the previous
+    /// question, for follow-ups) are sent along with the recent conversation.
+    /// Cited `[mm:ss]` timestamps are validated against those segments. Like
+    /// summaries, answers fall back to the configured providers and are never
+    /// sent to a provider marked as "never send". The question and answer are
+    /// only stored once the answer is complete, so a cancelled or failed turn
+    /// leaves the conversation unchanged.
+    ///
+    /// # Arguments
+    /// * `app` - Tauri app handle (for app data paths and `chat-progress` events)
+    /// * `pool` - SQLx connection pool
+    /// * `meeting_id` - Session the question is about
+    /// * `message_id` - Id to store the answer under; `cancel` takes it while the answer is generated
+    /// * `question` - The question
+    pub async fn ask<R: Runtime>(
+        app: &AppHandle<R>,
+        pool: &SqlitePool,
+        meeting_id: &str,
+        message_id: &str,
+        question: &str,
+    ) -> Result<ChatTurn, String> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err("Question cannot be empty".to_string());
+        }
+
+        // The configured model first, then its fallbacks; fails for a "never send" provider
+        let candidates = chat_candidates(pool).await?;
+
+        let history = ChatRepository::list_messages(pool, meeting_id)
+            .await
+            .map_err(|e| format!("Failed to load conversation: {}", e))?;
+
+        // One passage per segment; timestamps are only available for segments
+        // with recording times, otherwise the transcript lines are used
+        let segments: Vec<TimedSegment> =
+            match TranscriptsRepository::get_timed_transcripts(pool, meeting_id).await {
+                Ok(transcripts) => TimedSegment::from_transcripts(transcripts)
+                    .into_iter()
+                    .filter(|s| !s.text.trim().is_empty())
+                    .collect(),
+                Err(e) => {
+                    warn!(
+                        "Failed to load transcript timestamps for {}: {}",
+                        meeting_id, e
+                    );
+                    Vec::new()
+                }
+            };
+        let timed = !segments.is_empty();
+        let passages: Vec<String> = if timed {
+            segments
+                .iter()
+                .map(|s| {
+                    format!(
+                        "[{}] {}",
+                        citations::format_timestamp(s.start),
+                        s.text.trim()
+                    )
+                })
+                .collect()
+        } else {
+            TranscriptsRepository::get_full_transcript_text(pool, meeting_id)
+                .await
+                .map_err(|e| format!("Failed to load transcript: {}", e))?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        if passages.is_empty() {
+            return Err("This session has no transcript to answer from".to_string());
+        }
+        let passage_refs: Vec<&str> = passages.iter().map(String::as_str).collect();
+        let query = prompt::retrieval_query(question, &history);
+        let overhead = rough_token_count(prompt::SYSTEM_PROMPT)
+            + rough_token_count(&prompt::build_user_prompt("", timed, &history, question))
+            + ANSWER_RESERVE_TOKENS;
+
+        let cancellation_key = chat_cancellation_key(message_id);
+        let cancellation_token = SummaryService::register_cancellation_token(&cancellation_key);
+        let app_data_dir = app.path().app_data_dir().ok();
+        let client = reqwest::Client::new();
+
+        // Move on to the next provider while the current one can't be reached,
+        // is rate limited or times out
+        let mut result = Err(LlmError::Other("No provider available".to_string()));
+        let mut used = candidates[0].clone();
+        let mut selected = Vec::new();
+        let mut redactor = None;
+        for (index, candidate) in candidates.iter().enumerate() {
+            let settings = match SummaryService::load_provider_settings(pool, candidate).await {
+                Ok(settings) => settings,
+                // A misconfigured selected provider fails the answer; a misconfigured fallback is skipped
+                Err(e) if index == 0 => {
+                    SummaryService::cleanup_cancellation_token(&cancellation_key);
+                    return Err(e);
+                }
+                Err(e) => {
+                    warn!("Skipping fallback provider {}: {}", candidate.provider, e);
+                    continue;
+                }
+            };
+
+            // Fit the excerpts next to the instructions, conversation and answer
+            let budget = settings
+                .token_threshold
+                .min(MAX_EXCERPT_TOKENS)
+                .saturating_sub(overhead);
+            selected = retrieval::select_passages(&query, &passage_refs, budget, rough_token_count);
+            info!(
+                "Answering question for {} with {}/{} from {} of {} transcript segment(s)",
+                meeting_id,
+                candidate.provider,
+                candidate.model,
+                selected.len(),
+                passages.len()
+            );
+            let excerpts = prompt::build_excerpts(&passages, &selected);
+            let user_prompt = prompt::build_user_prompt(&excerpts, timed, &history, question);
+
+            // Personal data is replaced with placeholders for providers outside this machine
+            let endpoint = settings
+                .ollama_endpoint
+                .as_deref()
+                .or(settings.custom_openai_endpoint.as_deref());
+            redactor = if redaction::is_local_provider(&settings.provider, endpoint) {
+                None
+            } else {
+                SummaryService::load_redactor(pool).await
+            };
+            let user_prompt = match redactor.as_mut() {
+                Some(redactor) => redactor.redact(&user_prompt),
+                None => user_prompt,
+            };
+
+            used = candidate.clone();
+            let on_progress = |progress: &StreamProgress| {
+                let restored;
+                let progress = match &redactor {
+                    Some(redactor) => {
+                        restored = StreamProgress {
+                            text: redactor.restore(&progress.text),
+                            ..progress.clone()
+                        };
+                        &restored
+                    }
+                    None => progress,
+                };
+                let event = ChatProgress {
+                    meeting_id,
+                    message_id,
+                    provider: &candidate.provider,
+                    model: &candidate.model,
+                    progress,
+                };
+                if let Err(e) = app.emit(CHAT_PROGRESS_EVENT, &event) {
+                    warn!("Failed to emit {}: {}", CHAT_PROGRESS_EVENT, e);
+                }
+            };
+            result = generate_summary(
+                &client,
+                &settings.provider,
+                &candidate.model,
+                &settings.api_key,
+                prompt::SYSTEM_PROMPT,
+                &user_prompt,
+                settings.ollama_endpoint.as_deref(),
+                settings.ollama_num_ctx,
+                settings.custom_openai_endpoint.as_deref(),
+                settings.max_tokens,
+                settings.temperature,
+                settings.top_p,
+                app_data_dir.as_ref(),
+                None,
+                Some(&cancellation_token),
+                Some(&on_progress),
+            )
+            .await;
+
+            match &result {
+                Err(e) if fallback::is_fallback_error(e) => {
+                    warn!(
+                        "{}/{} failed to answer for {}: {}",
+                        candidate.provider, candidate.model, meeting_id, e
+                    );
+                }
+                _ => break,
+            }
+        }
+        SummaryService::cleanup_cancellation_token(&cancellation_key);
+        if used != candidates[0] && result.is_ok() {
+            info!(
+                "Answer for {} generated by fallback provider {}/{}",
+                meeting_id, used.provider, used.model
+            );
+        }
+
+        let answer = match result {
+            Ok(answer) => match &redactor {
+                Some(redactor) => {
+                    info!(
+                        "Redaction report for the question about {} sent to {}: {} replaced ({})",
+                        meeting_id,
+                        used.provider,
+                        redactor.report().total(),
+                        redactor.report()
+                    );
+                    clean_llm_markdown_output(&redactor.restore(&answer))
+                }
+                None => clean_llm_markdown_output(&answer),
+            },
+            Err(LlmError::Cancelled) => return Err("Answer was cancelled".to_string()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        // Drop citations of segments that weren't in the excerpts
+        let (answer, note_citations) = if timed {
+            let excerpt_segments: Vec<TimedSegment> =
+                selected.iter().map(|&i| segments[i].clone()).collect();
+            let (answer, report) = citations::validate_citations(&answer, &excerpt_segments);
+            if !report.invalid_timestamps.is_empty() {
+                warn!(
+                    "Removed {} citation(s) matching no excerpt from the answer for {}: {:?}",
+                    report.invalid_timestamps.len(),
+                    meeting_id,
+                    report.invalid_timestamps
+                );
+            }
+            let json = serde_json::to_string(&report.citations)
+                .map_err(|e| format!("Failed to serialize citations: {}", e))?;
+            (answer, Some(json))
+        } else {
+            (answer, None)
+        };
+
+        let question = ChatRepository::add_message(
+            pool,
+            &ChatRepository::new_message_id(),
+            meeting_id,
+            "user",
+            question,
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to save question: {}", e))?;
+        let answer = ChatRepository::add_message(
+            pool,
+            message_id,
+            meeting_id,
+            "assistant",
+            &answer,
+            note_citations.as_deref(),
+            Some(&used.provider),
+            Some(&used.model),
+        )
+        .await
+        .map_err(|e| format!("Failed to save answer: {}", e))?;
+
+        Ok(ChatTurn { question, answer })
+    }
+
+    /// Cancels the answer being generated under a message id
+    pub fn cancel(message_id: &str) -> bool {
+        SummaryService::cancel_summary(&chat_cancellation_key(message_id))
+    }
+}
+
+/// The configured summary model followed by its fallbacks
+///
+/// # Errors
+/// If no model is configured, or its provider is marked as "never send"
+async fn chat_candidates(pool: &SqlitePool) -> Result<Vec<FallbackProvider>, String> {
+    let config = SettingsRepository::get_model_config(pool)
+        .await
+        .map_err(|e| format!("Failed to load model config: {}", e))?
+        .ok_or_else(|| "No summary model configured".to_string())?;
+    let fallback_config = match SettingsRepository::get_provider_fallback_config(pool).await {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to load provider fallback config: {}, using none", e);
+            ProviderFallbackConfig::default()
+        }
+    };
+    fallback_config.candidates(&config.provider, &config.model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manager::DatabaseManager;
+
+    fn entry(provider: &str, model: &str) -> FallbackProvider {
+        FallbackProvider {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_candidates_respect_never_send_providers() {
+        let pool = DatabaseManager::test_pool().await;
+        SettingsRepository::save_model_config(&pool, "groq", "llama-3.3-70b", "large-v3", None)
+            .await
+            .unwrap();
+        let mut config = ProviderFallbackConfig {
+            chain: vec![entry("ollama", "llama3.2:latest")],
+            never_send_providers: Vec::new(),
+        };
+        SettingsRepository::save_provider_fallback_config(&pool, &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            chat_candidates(&pool).await.unwrap(),
+            vec![
+                entry("groq", "llama-3.3-70b"),
+                entry("ollama", "llama3.2:latest")
+            ]
+        );
+
+        // A blocked fallback is left out
+        config.never_send_providers = vec!["ollama".to_string()];
+        SettingsRepository::save_provider_fallback_config(&pool, &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            chat_candidates(&pool).await.unwrap(),
+            vec![entry("groq", "llama-3.3-70b")]
+        );
+
+        // A blocked selected provider rejects the question
+        config.never_send_providers = vec!["groq".to_string()];
+        SettingsRepository::save_provider_fallback_config(&pool, &config)
+            .await
+            .unwrap();
+        let error = chat_candidates(&pool).await.unwrap_err();
+        assert!(error.contains("never send"), "{}", error);
+    }
+}
@@ -1,5 +1,3 @@
-#[cfg(target_os = "windows")]
-use env_logger;
 #[cfg(target_os = "macos")]
 use std::process::Command;
 #[cfg(target_os = "windows")]
@@ -32,7 +30,7 @@ pub fn show_console() -> Result<String, String> {
             }
             // Reinitialize stdout, stdin, stderr for the new console
             std::env::set_var("RUST_LOG", "info");
-            env_logger::init();
+            crate::secrets::redact::init_logger();
         } else {
             // Show existing console window
             ShowWindow(console_window, SW_SHOW);
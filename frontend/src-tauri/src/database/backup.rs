@@ -0,0 +1,513 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use log::{info, warn};
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnection;
+use sqlx::{Connection, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manager::DatabaseManager;
+
+/// Backups directory in the app data directory
+const BACKUP_DIR: &str = "backups";
+
+const FILE_PREFIX: &str = "meeting_minutes-";
+
+const FILE_EXTENSION: &str = ".sqlite";
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+/// Timestamps of backups made before they had milliseconds
+const LEGACY_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Length of the `YYYYmmdd-` date in a timestamp
+const DATE_LEN: usize = "YYYYmmdd-".len();
+
+/// Snapshots taken before a restore that are kept
+const PRE_RESTORE_KEEP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupKind {
+    Scheduled,
+    Manual,
+    /// The database as it was before a restore
+    PreRestore,
+}
+
+impl BackupKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Manual => "manual",
+            Self::PreRestore => "pre-restore",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "scheduled" => Some(Self::Scheduled),
+            "manual" => Some(Self::Manual),
+            "pre-restore" => Some(Self::PreRestore),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub kind: BackupKind,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+pub fn backup_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(BACKUP_DIR)
+}
+
+fn backup_file_name(created_at: DateTime<Utc>, kind: BackupKind) -> String {
+    format!(
+        "{}{}-{}{}",
+        FILE_PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        kind.as_str(),
+        FILE_EXTENSION
+    )
+}
+
+/// Creation time and kind of a backup from its file name
+fn parse_backup_file_name(file_name: &str) -> Option<(DateTime<Utc>, BackupKind)> {
+    let stem = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(FILE_EXTENSION)?;
+    let timestamp_len = DATE_LEN + stem.get(DATE_LEN..)?.find('-')?;
+    let timestamp = &stem[..timestamp_len];
+    let kind = BackupKind::parse(&stem[timestamp_len + 1..])?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, LEGACY_TIMESTAMP_FORMAT))
+        .ok()?
+        .and_utc();
+    Some((created_at, kind))
+}
+
+/// Backups in `backup_dir`, newest first
+pub fn list_backups(backup_dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(backup_dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_str()?.to_string();
+            let (created_at, kind) = parse_backup_file_name(&file_name)?;
+            Some(BackupInfo {
+                file_name,
+                kind,
+                created_at,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+/// The newest backup of a kind
+pub fn latest_backup(backup_dir: &Path, kind: BackupKind) -> Option<BackupInfo> {
+    list_backups(backup_dir)
+        .into_iter()
+        .find(|backup| backup.kind == kind)
+}
+
+/// Path of a backup by file name, refusing names that aren't backups
+fn backup_path(backup_dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    if parse_backup_file_name(file_name).is_none() {
+        return Err(format!("Not a database backup: {}", file_name));
+    }
+    let path = backup_dir.join(file_name);
+    if !path.is_file() {
+        return Err(format!("Backup not found: {}", file_name));
+    }
+    Ok(path)
+}
+
+/// Copies the live database to a new backup with `VACUUM INTO`
+///
+/// The copy is consistent while the app keeps writing, and encrypted with the
+/// database key when encryption is on. `keep` limits the backups of the kind;
+/// older ones are deleted. Manual backups are never deleted this way.
+pub async fn create_backup(
+    pool: &SqlitePool,
+    backup_dir: &Path,
+    kind: BackupKind,
+    keep: usize,
+) -> Result<BackupInfo, String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    // Milliseconds, as kept in the file name; a name already taken by a backup
+    // made in the same millisecond moves on to the next one
+    let mut created_at =
+        DateTime::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap_or_default();
+    while backup_dir.join(backup_file_name(created_at, kind)).exists() {
+        created_at += Duration::milliseconds(1);
+    }
+    let file_name = backup_file_name(created_at, kind);
+    let path = backup_dir.join(&file_name);
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to back up database: {}", e))?;
+    if let Err(e) = check_backup_encrypted(pool.connect_options().get_filename(), &path) {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    info!("Database backed up to {} ({} bytes)", file_name, size_bytes);
+
+    if kind != BackupKind::Manual {
+        rotate_backups(backup_dir, kind, keep);
+    }
+    Ok(BackupInfo {
+        file_name,
+        kind,
+        created_at,
+        size_bytes,
+    })
+}
+
+/// Refuses a backup written unencrypted from an encrypted database
+///
+/// `read_only_options` opens plaintext backups without a key, so a backup
+/// that lost its encryption would otherwise go unnoticed.
+fn check_backup_encrypted(database: &Path, backup: &Path) -> Result<(), String> {
+    let database_encrypted = matches!(DatabaseManager::is_plaintext_database(database), Ok(false));
+    let backup_plaintext = matches!(DatabaseManager::is_plaintext_database(backup), Ok(true));
+    if database_encrypted && backup_plaintext {
+        return Err("Backup was written unencrypted from the encrypted database".to_string());
+    }
+    Ok(())
+}
+
+/// Deletes all but the newest `keep` backups of a kind
+fn rotate_backups(backup_dir: &Path, kind: BackupKind, keep: usize) {
+    for backup in list_backups(backup_dir)
+        .into_iter()
+        .filter(|backup| backup.kind == kind)
+        .skip(keep.max(1))
+    {
+        match fs::remove_file(backup_dir.join(&backup.file_name)) {
+            Ok(()) => info!("Removed old backup {}", backup.file_name),
+            Err(e) => warn!("Failed to remove old backup {}: {}", backup.file_name, e),
+        }
+    }
+}
+
+pub fn delete_backup(backup_dir: &Path, file_name: &str) -> Result<(), String> {
+    let path = backup_path(backup_dir, file_name)?;
+    fs::remove_file(path).map_err(|e| format!("Failed to delete backup: {}", e))
+}
+
+/// Checks that a backup opens with the current key and passes `quick_check`
+async fn verify_backup(path: &Path) -> Result<(), String> {
+    let unreadable =
+        |e: sqlx::Error| format!("Backup can't be read with the current settings: {}", e);
+    let options = DatabaseManager::read_only_options(path).map_err(unreadable)?;
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(unreadable)?;
+    let result: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar("PRAGMA quick_check")
+        .fetch_all(&mut conn)
+        .await;
+    let _ = conn.close().await;
+
+    let problems = result.map_err(unreadable)?;
+    if problems != ["ok"] {
+        return Err(format!("Backup is damaged: {}", problems.join("; ")));
+    }
+    Ok(())
+}
+
+/// Database file staged by a restore, put in place at the next launch
+fn pending_restore_path(database_path: &Path) -> PathBuf {
+    database_path.with_extension("sqlite.restore")
+}
+
+/// Stages a backup to replace the database at the next launch
+///
+/// The backup is verified first, and the current database is saved as a
+/// pre-restore snapshot, so a restore can itself be undone.
+///
+/// # Returns
+/// The pre-restore snapshot
+pub async fn stage_restore(
+    pool: &SqlitePool,
+    database_path: &Path,
+    backup_dir: &Path,
+    file_name: &str,
+) -> Result<BackupInfo, String> {
+    let backup = backup_path(backup_dir, file_name)?;
+    verify_backup(&backup).await?;
+
+    let snapshot =
+        create_backup(pool, backup_dir, BackupKind::PreRestore, PRE_RESTORE_KEEP).await?;
+
+    let pending = pending_restore_path(database_path);
+    let temp_path = pending.with_extension("restore.tmp");
+    fs::copy(&backup, &temp_path)
+        .and_then(|_| fs::rename(&temp_path, &pending))
+        .map_err(|e| format!("Failed to stage backup for restore: {}", e))?;
+    info!("Staged backup {} for restore at next launch", file_name);
+    Ok(snapshot)
+}
+
+/// Replaces the database with a staged backup, before it is opened
+///
+/// Returns whether a backup was restored.
+pub fn apply_pending_restore(database_path: &Path) -> std::io::Result<bool> {
+    let pending = pending_restore_path(database_path);
+    if !pending.exists() {
+        return Ok(false);
+    }
+    // The old WAL belongs to the replaced database and must not be applied to the backup
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = database_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        match fs::remove_file(&sidecar) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    fs::rename(&pending, database_path)?;
+    info!("Restored database from backup");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    /// SQLCipher raw key, as `Keys::database_key_sql` gives it
+    const TEST_KEY_SQL: &str =
+        "'x''00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff'''";
+
+    #[test]
+    fn test_backup_file_names() {
+        let created_at =
+            Utc.with_ymd_and_hms(2026, 3, 1, 9, 5, 30).unwrap() + Duration::milliseconds(42);
+        let name = backup_file_name(created_at, BackupKind::PreRestore);
+        assert_eq!(
+            name,
+            "meeting_minutes-20260301-090530042-pre-restore.sqlite"
+        );
+        assert_eq!(
+            parse_backup_file_name(&name),
+            Some((created_at, BackupKind::PreRestore))
+        );
+        // Names of backups made before they had milliseconds
+        assert_eq!(
+            parse_backup_file_name("meeting_minutes-20260301-090530-manual.sqlite"),
+            Some((
+                Utc.with_ymd_and_hms(2026, 3, 1, 9, 5, 30).unwrap(),
+                BackupKind::Manual
+            ))
+        );
+
+        assert_eq!(parse_backup_file_name("meeting_minutes.sqlite"), None);
+        assert_eq!(
+            parse_backup_file_name("meeting_minutes-20260301-090530-other.sqlite"),
+            None
+        );
+        assert_eq!(
+            parse_backup_file_name("meeting_minutes-20260301-090530-manual.sqlite/../x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_of_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        for (day, kind) in [
+            (1, BackupKind::Scheduled),
+            (2, BackupKind::Manual),
+            (3, BackupKind::Scheduled),
+            (4, BackupKind::Scheduled),
+        ] {
+            let created_at = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+            fs::write(dir.path().join(backup_file_name(created_at, kind)), b"db").unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), b"not a backup").unwrap();
+
+        rotate_backups(dir.path(), BackupKind::Scheduled, 2);
+
+        let remaining: Vec<(u32, BackupKind)> = list_backups(dir.path())
+            .iter()
+            .map(|backup| {
+                (
+                    backup.created_at.format("%d").to_string().parse().unwrap(),
+                    backup.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            remaining,
+            [
+                (4, BackupKind::Scheduled),
+                (3, BackupKind::Scheduled),
+                (2, BackupKind::Manual)
+            ]
+        );
+        assert!(backup_path(dir.path(), "notes.txt").is_err());
+    }
+
+    #[test]
+    fn test_apply_pending_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("meeting_minutes.sqlite");
+        assert!(!apply_pending_restore(&database).unwrap());
+
+        fs::write(&database, b"current").unwrap();
+        fs::write(dir.path().join("meeting_minutes.sqlite-wal"), b"wal").unwrap();
+        fs::write(pending_restore_path(&database), b"backup").unwrap();
+
+        assert!(apply_pending_restore(&database).unwrap());
+        assert_eq!(fs::read(&database).unwrap(), b"backup");
+        assert!(!dir.path().join("meeting_minutes.sqlite-wal").exists());
+        assert!(!pending_restore_path(&database).exists());
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_stage_restore() {
+        // File-backed, as `VACUUM INTO` from an in-memory pool writes nowhere
+        let dir = tempfile::tempdir().unwrap();
+        let backup_dir = dir.path().join(BACKUP_DIR);
+        let database = dir.path().join("meeting_minutes.sqlite");
+        let options = SqliteConnectOptions::new()
+            .filename(&database)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO meetings (id, title, created_at, updated_at) VALUES ('m1', 'Lesson 1', ?1, ?1)",
+        )
+        .bind(Utc::now())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Backups made in quick succession don't collide
+        let first = create_backup(&pool, &backup_dir, BackupKind::Manual, 0)
+            .await
+            .unwrap();
+        let second = create_backup(&pool, &backup_dir, BackupKind::Manual, 0)
+            .await
+            .unwrap();
+        assert_ne!(first.file_name, second.file_name);
+        assert_eq!(list_backups(&backup_dir).len(), 2);
+
+        // A damaged backup is refused before anything is staged
+        let damaged = backup_file_name(
+            Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            BackupKind::Manual,
+        );
+        fs::write(
+            backup_dir.join(&damaged),
+            b"SQLite format 3\0 but not really",
+        )
+        .unwrap();
+        assert!(stage_restore(&pool, &database, &backup_dir, &damaged)
+            .await
+            .is_err());
+        assert!(!pending_restore_path(&database).exists());
+
+        let snapshot = stage_restore(&pool, &database, &backup_dir, &first.file_name)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.kind, BackupKind::PreRestore);
+        assert!(backup_dir.join(&snapshot.file_name).is_file());
+
+        // Restores are applied at the next launch, with the pool closed
+        sqlx::query("DELETE FROM meetings")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        assert!(apply_pending_restore(&database).unwrap());
+        let options = DatabaseManager::read_only_options(&database).unwrap();
+        let mut conn = SqliteConnection::connect_with(&options).await.unwrap();
+        let title: String = sqlx::query_scalar("SELECT title FROM meetings WHERE id = 'm1'")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(title, "Lesson 1");
+        conn.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backup_of_encrypted_database_is_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("meeting_minutes.sqlite");
+        let options = SqliteConnectOptions::new()
+            .filename(&database)
+            .create_if_missing(true)
+            .pragma("key", TEST_KEY_SQL);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE notes (text TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes VALUES ('el pretérito indefinido')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let backup_dir = dir.path().join(BACKUP_DIR);
+        let backup = create_backup(&pool, &backup_dir, BackupKind::Manual, 0)
+            .await
+            .unwrap();
+        let path = backup_dir.join(&backup.file_name);
+        assert!(!DatabaseManager::is_plaintext_database(&path).unwrap());
+        let contents = fs::read(&path).unwrap();
+        let text = "pretérito".as_bytes();
+        assert!(!contents.windows(text.len()).any(|w| w == text));
+
+        // It opens with the database key only
+        let keyed = SqliteConnectOptions::new()
+            .filename(&path)
+            .read_only(true)
+            .pragma("key", TEST_KEY_SQL);
+        let mut conn = SqliteConnection::connect_with(&keyed).await.unwrap();
+        let text: String = sqlx::query_scalar("SELECT text FROM notes")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(text, "el pretérito indefinido");
+        conn.close().await.unwrap();
+        let unkeyed = SqliteConnectOptions::new().filename(&path).read_only(true);
+        let mut conn = SqliteConnection::connect_with(&unkeyed).await.unwrap();
+        assert!(sqlx::query("SELECT * FROM notes")
+            .fetch_all(&mut conn)
+            .await
+            .is_err());
+
+        // An unencrypted copy of the encrypted database is refused
+        let plaintext = dir.path().join("plaintext.sqlite");
+        fs::write(&plaintext, b"SQLite format 3\0").unwrap();
+        assert!(check_backup_encrypted(&database, &plaintext).is_err());
+        assert!(check_backup_encrypted(&database, &path).is_ok());
+    }
+}
@@ -3,7 +3,9 @@ use serde::Serialize;
 use std::path::PathBuf;
 use tauri::{AppHandle, Emitter, Manager};
 
-use super::manager::DatabaseManager;
+use super::backup::{self, BackupInfo, BackupKind};
+use super::maintenance::{self, IntegrityReport, MaintenanceSettings};
+use super::manager::{DatabaseManager, DATABASE_FILE};
 use crate::state::AppState;
 
 #[derive(Serialize)]
@@ -161,10 +163,14 @@ pub async fn import_and_initialize_database(
         })?;
 
     // Update app state with the new manager
+    let pool = db_manager.pool().clone();
     app.manage(AppState { db_manager });
 
     info!("Legacy database imported and initialized successfully");
 
+    // Imported databases may carry damage from the old install
+    maintenance::run_startup_integrity_check(&app, pool);
+
     // Emit event to notify frontend that database is ready
     app.emit("database-initialized", ())
         .map_err(|e| format!("Failed to emit database-initialized event: {}", e))?;
@@ -273,3 +279,109 @@ pub async fn open_database_folder(app: AppHandle) -> Result<(), String> {
     info!("Opened database folder: {}", folder_path);
     Ok(())
 }
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+fn database_pool(app: &AppHandle) -> Result<sqlx::SqlitePool, String> {
+    app.try_state::<AppState>()
+        .map(|state| state.db_manager.pool().clone())
+        .ok_or_else(|| "Database is not initialized".to_string())
+}
+
+/// List database backups, newest first
+#[tauri::command]
+pub async fn api_list_database_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    Ok(backup::list_backups(&backup::backup_dir(&app_data_dir(&app)?)))
+}
+
+/// Back up the database now; manual backups are kept until deleted
+#[tauri::command]
+pub async fn api_create_database_backup(app: AppHandle) -> Result<BackupInfo, String> {
+    let pool = database_pool(&app)?;
+    let backup_dir = backup::backup_dir(&app_data_dir(&app)?);
+    backup::create_backup(&pool, &backup_dir, BackupKind::Manual, 0)
+        .await
+        .map_err(|e| {
+            error!("{}", e);
+            e
+        })
+}
+
+/// Restore a backup at the next launch, after saving a pre-restore snapshot
+///
+/// The frontend relaunches the app to complete the restore.
+#[tauri::command]
+pub async fn api_restore_database_backup(
+    app: AppHandle,
+    file_name: String,
+) -> Result<BackupInfo, String> {
+    let pool = database_pool(&app)?;
+    let app_data_dir = app_data_dir(&app)?;
+    backup::stage_restore(
+        &pool,
+        &app_data_dir.join(DATABASE_FILE),
+        &backup::backup_dir(&app_data_dir),
+        &file_name,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to restore backup {}: {}", file_name, e);
+        e
+    })
+}
+
+#[tauri::command]
+pub async fn api_delete_database_backup(app: AppHandle, file_name: String) -> Result<(), String> {
+    backup::delete_backup(&backup::backup_dir(&app_data_dir(&app)?), &file_name)?;
+    info!("Deleted database backup {}", file_name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn api_get_database_maintenance_settings(
+    app: AppHandle,
+) -> Result<MaintenanceSettings, String> {
+    Ok(MaintenanceSettings::load(&app_data_dir(&app)?))
+}
+
+#[tauri::command]
+pub async fn api_save_database_maintenance_settings(
+    app: AppHandle,
+    settings: MaintenanceSettings,
+) -> Result<(), String> {
+    let app_data_dir = app_data_dir(&app)?;
+    // The last VACUUM time is kept by the maintenance task, not the settings form
+    let settings = MaintenanceSettings {
+        last_vacuum_at: MaintenanceSettings::load(&app_data_dir).last_vacuum_at,
+        ..settings
+    };
+    settings.save(&app_data_dir)
+}
+
+/// The report of the integrity check run at startup, if it has completed
+#[tauri::command]
+pub async fn api_get_database_integrity_report() -> Result<Option<IntegrityReport>, String> {
+    Ok(maintenance::last_integrity_report())
+}
+
+#[tauri::command]
+pub async fn api_check_database_integrity(app: AppHandle) -> Result<IntegrityReport, String> {
+    let pool = database_pool(&app)?;
+    Ok(maintenance::check_integrity(&pool).await)
+}
+
+/// Run VACUUM and ANALYZE now
+#[tauri::command]
+pub async fn api_run_database_maintenance(app: AppHandle) -> Result<(), String> {
+    let pool = database_pool(&app)?;
+    let app_data_dir = app_data_dir(&app)?;
+    maintenance::run_maintenance(&pool).await?;
+
+    let mut settings = MaintenanceSettings::load(&app_data_dir);
+    settings.last_vacuum_at = Some(chrono::Utc::now());
+    settings.save(&app_data_dir)
+}
@@ -0,0 +1,270 @@
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use super::backup::{self, BackupKind};
+use crate::state::AppState;
+
+/// Maintenance settings file in the app data directory
+const SETTINGS_FILE: &str = "database_maintenance.json";
+
+/// How often the loop checks whether a backup or VACUUM is due
+const MAINTENANCE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+const VACUUM_INTERVAL_DAYS: i64 = 7;
+
+/// Problems listed by `PRAGMA integrity_check` at most
+const INTEGRITY_CHECK_MAX_ERRORS: u32 = 100;
+
+pub const INTEGRITY_REPORT_EVENT: &str = "database-integrity-report";
+
+static LAST_INTEGRITY_REPORT: Lazy<RwLock<Option<IntegrityReport>>> =
+    Lazy::new(|| RwLock::new(None));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MaintenanceSettings {
+    pub backups_enabled: bool,
+    pub backup_interval_hours: u32,
+    /// Scheduled backups kept; older ones are deleted
+    pub backup_keep_count: u32,
+    /// Run VACUUM and ANALYZE weekly
+    pub vacuum_enabled: bool,
+    pub last_vacuum_at: Option<DateTime<Utc>>,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            backups_enabled: true,
+            backup_interval_hours: 24,
+            backup_keep_count: 7,
+            vacuum_enabled: false,
+            last_vacuum_at: None,
+        }
+    }
+}
+
+impl MaintenanceSettings {
+    fn path(app_data_dir: &Path) -> PathBuf {
+        app_data_dir.join(SETTINGS_FILE)
+    }
+
+    pub fn load(app_data_dir: &Path) -> Self {
+        match fs::read(Self::path(app_data_dir)) {
+            Ok(json) => serde_json::from_slice(&json).unwrap_or_else(|e| {
+                warn!(
+                    "Invalid database maintenance settings, using defaults: {}",
+                    e
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        if self.backup_interval_hours == 0 {
+            return Err("Backup interval must be at least one hour".to_string());
+        }
+        if self.backup_keep_count == 0 {
+            return Err("At least one backup must be kept".to_string());
+        }
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize maintenance settings: {}", e))?;
+        let path = Self::path(app_data_dir);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .map_err(|e| format!("Failed to save maintenance settings: {}", e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub checked_at: DateTime<Utc>,
+    pub ok: bool,
+    /// Problems found, or the error that stopped the check
+    pub problems: Vec<String>,
+}
+
+/// The report of the last integrity check, if one ran
+pub fn last_integrity_report() -> Option<IntegrityReport> {
+    LAST_INTEGRITY_REPORT.read().unwrap().clone()
+}
+
+/// Runs `PRAGMA integrity_check` and keeps the report
+pub async fn check_integrity(pool: &SqlitePool) -> IntegrityReport {
+    run_check(pool, "integrity_check").await
+}
+
+/// Runs `PRAGMA quick_check`, which skips the slower index checks of
+/// `integrity_check`, and keeps the report
+pub async fn quick_check(pool: &SqlitePool) -> IntegrityReport {
+    run_check(pool, "quick_check").await
+}
+
+async fn run_check(pool: &SqlitePool, pragma: &str) -> IntegrityReport {
+    let result: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar(&format!(
+        "PRAGMA {}({})",
+        pragma, INTEGRITY_CHECK_MAX_ERRORS
+    ))
+    .fetch_all(pool)
+    .await;
+
+    let problems = match result {
+        Ok(rows) if rows == ["ok"] => Vec::new(),
+        Ok(rows) => rows,
+        Err(e) => vec![format!("Integrity check failed: {}", e)],
+    };
+    let report = IntegrityReport {
+        checked_at: Utc::now(),
+        ok: problems.is_empty(),
+        problems,
+    };
+    if report.ok {
+        info!("Database {} passed", pragma);
+    } else {
+        warn!(
+            "Database {} found {} problem(s): {}",
+            pragma,
+            report.problems.len(),
+            report.problems.join("; ")
+        );
+    }
+    *LAST_INTEGRITY_REPORT.write().unwrap() = Some(report.clone());
+    report
+}
+
+/// Checks the database after it is opened and tells the window about problems
+pub fn run_startup_integrity_check(app: &AppHandle<Wry>, pool: SqlitePool) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let report = check_integrity(&pool).await;
+        if !report.ok {
+            // Give React listeners time to register, as for first-launch-detected
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            if let Err(e) = app.emit(INTEGRITY_REPORT_EVENT, &report) {
+                warn!("Failed to emit integrity report: {}", e);
+            }
+        }
+    });
+}
+
+/// Rebuilds the database file to reclaim space, then refreshes the query
+/// planner statistics
+pub async fn run_maintenance(pool: &SqlitePool) -> Result<(), String> {
+    sqlx::query("VACUUM")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("VACUUM failed: {}", e))?;
+    sqlx::query("ANALYZE")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("ANALYZE failed: {}", e))?;
+    info!("Database VACUUM and ANALYZE completed");
+    Ok(())
+}
+
+/// Whether `interval` has passed since `last`
+fn is_due(last: Option<DateTime<Utc>>, interval: Duration, now: DateTime<Utc>) -> bool {
+    last.map_or(true, |last| now - last >= interval)
+}
+
+/// Starts a background task for scheduled backups and VACUUM
+pub fn start_database_maintenance_loop(app: AppHandle<Wry>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_maintenance(&app).await {
+                warn!("Database maintenance failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn run_due_maintenance(app: &AppHandle<Wry>) -> Result<(), String> {
+    // The database isn't available until first-launch setup or unlock has completed
+    let pool = match app.try_state::<AppState>() {
+        Some(state) => state.db_manager.pool().clone(),
+        None => return Ok(()),
+    };
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let mut settings = MaintenanceSettings::load(&app_data_dir);
+    let now = Utc::now();
+
+    if settings.backups_enabled {
+        let backup_dir = backup::backup_dir(&app_data_dir);
+        let last_backup =
+            backup::latest_backup(&backup_dir, BackupKind::Scheduled).map(|b| b.created_at);
+        let backup_interval = Duration::hours(settings.backup_interval_hours.into());
+        if is_due(last_backup, backup_interval, now) {
+            // A damaged database must not rotate out the good backups; checked
+            // before every backup, as it may have been damaged since launch
+            let report = quick_check(&pool).await;
+            if report.ok {
+                backup::create_backup(
+                    &pool,
+                    &backup_dir,
+                    BackupKind::Scheduled,
+                    settings.backup_keep_count as usize,
+                )
+                .await?;
+            } else {
+                warn!("Skipping scheduled backup, the database failed its integrity check");
+            }
+        }
+    }
+
+    if settings.vacuum_enabled
+        && is_due(
+            settings.last_vacuum_at,
+            Duration::days(VACUUM_INTERVAL_DAYS),
+            now,
+        )
+    {
+        run_maintenance(&pool).await?;
+        settings.last_vacuum_at = Some(now);
+        settings.save(&app_data_dir)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let day = Duration::hours(24);
+        assert!(is_due(None, day, now));
+        assert!(is_due(Some(now - Duration::hours(25)), day, now));
+        assert!(!is_due(Some(now - Duration::hours(23)), day, now));
+    }
+
+    #[test]
+    fn test_settings_defaults_fill_missing_fields() {
+        let settings: MaintenanceSettings =
+            serde_json::from_str(r#"{"backupKeepCount": 3}"#).unwrap();
+        assert_eq!(
+            settings,
+            MaintenanceSettings {
+                backup_keep_count: 3,
+                ..MaintenanceSettings::default()
+            }
+        );
+    }
+}
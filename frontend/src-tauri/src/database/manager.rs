@@ -1,8 +1,21 @@
-use sqlx::{migrate::MigrateDatabase, Result, Sqlite, SqlitePool, Transaction};
-use std::fs;
+use crate::encryption::vault::vault;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{migrate::MigrateDatabase, Connection, Result, Sqlite, SqlitePool, Transaction};
+use std::fs::{self, File};
+use std::io::Read;
 use std::path::Path;
+use std::str::FromStr;
 use tauri::Manager;
 
+/// Database file in the app data directory
+pub const DATABASE_FILE: &str = "meeting_minutes.sqlite";
+
+/// First bytes of an unencrypted SQLite database
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// `ATTACH … KEY` value of an unencrypted database
+const PLAINTEXT_KEY_SQL: &str = "''";
+
 #[derive(Clone)]
 pub struct DatabaseManager {
     pool: SqlitePool,
@@ -30,13 +43,128 @@ impl DatabaseManager {
             }
         }
 
-        let pool = SqlitePool::connect(tauri_db_path).await?;
+        // A restore from backup is put in place before the database is opened
+        super::backup::apply_pending_restore(Path::new(tauri_db_path)).map_err(sqlx::Error::Io)?;
+
+        let key = Self::prepare_encryption(Path::new(tauri_db_path)).await?;
+        let mut options = SqliteConnectOptions::from_str(tauri_db_path)?;
+        if let Some(key) = key {
+            options = options.pragma("key", key);
+        }
+        let pool = SqlitePool::connect_with(options).await?;
 
         sqlx::migrate!("./migrations").run(&pool).await?;
 
         Ok(DatabaseManager { pool })
     }
 
+    /// Brings the database file in line with the encryption setting
+    ///
+    /// SQLCipher can only convert a database that isn't open, so a database
+    /// is encrypted (or decrypted, when encryption is being turned off) here,
+    /// at the first open after the change. Returns the key to open it with.
+    async fn prepare_encryption(path: &Path) -> Result<Option<String>> {
+        if !vault().is_enabled() {
+            return Ok(None);
+        }
+        let keys = vault().keys().ok_or_else(|| {
+            sqlx::Error::Configuration("Encryption is locked, unlock it to open the database".into())
+        })?;
+        let key = keys.database_key_sql();
+        let plaintext = Self::is_plaintext_database(path).map_err(sqlx::Error::Io)?;
+
+        if vault().is_disable_pending() {
+            if !plaintext && fs::metadata(path).map_err(sqlx::Error::Io)?.len() > 0 {
+                log::info!("Decrypting database");
+                Self::convert_database(path, Some(&key), PLAINTEXT_KEY_SQL).await?;
+            }
+            return Ok(None);
+        }
+        if plaintext {
+            log::info!("Encrypting database");
+            Self::convert_database(path, None, &key).await?;
+        }
+        Ok(Some(key))
+    }
+
+    /// Options to read another database file, e.g. a backup, with the current key
+    pub(crate) fn read_only_options(path: &Path) -> Result<SqliteConnectOptions> {
+        let mut options = SqliteConnectOptions::new().filename(path).read_only(true);
+        if !Self::is_plaintext_database(path).map_err(sqlx::Error::Io)? {
+            if let Some(keys) = vault().keys() {
+                options = options.pragma("key", keys.database_key_sql());
+            }
+        }
+        Ok(options)
+    }
+
+    /// Whether a file starts with the header of an unencrypted database
+    pub(crate) fn is_plaintext_database(path: &Path) -> std::io::Result<bool> {
+        let mut header = [0u8; SQLITE_HEADER.len()];
+        match File::open(path)?.read_exact(&mut header) {
+            Ok(()) => Ok(&header == SQLITE_HEADER),
+            // A new, empty database
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Rewrites the database under another key with `sqlcipher_export`
+    ///
+    /// `from_key` is `None` for an unencrypted database. The copy replaces the
+    /// database only once it is complete.
+    async fn convert_database(path: &Path, from_key: Option<&str>, to_key: &str) -> Result<()> {
+        let converted_path = path.with_extension("sqlite.converting");
+        if converted_path.exists() {
+            fs::remove_file(&converted_path).map_err(sqlx::Error::Io)?;
+        }
+
+        // ATTACH opens the copy with the connection's flags, which must allow creating it
+        let mut options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        if let Some(key) = from_key {
+            options = options.pragma("key", key.to_string());
+        }
+        let mut conn = SqliteConnection::connect_with(&options).await?;
+        // Plain SQLite ignores `PRAGMA key`, which would leave the data unencrypted
+        let cipher_version: Option<String> = sqlx::query_scalar("PRAGMA cipher_version")
+            .fetch_optional(&mut conn)
+            .await?;
+        if cipher_version.is_none() {
+            conn.close().await?;
+            return Err(sqlx::Error::Configuration(
+                "SQLite was built without SQLCipher, the database can't be encrypted".into(),
+            ));
+        }
+
+        sqlx::query(&format!(
+            "ATTACH DATABASE '{}' AS converted KEY {}",
+            converted_path.to_string_lossy().replace('\'', "''"),
+            to_key
+        ))
+        .execute(&mut conn)
+        .await?;
+        sqlx::query("SELECT sqlcipher_export('converted')")
+            .execute(&mut conn)
+            .await?;
+        sqlx::query("DETACH DATABASE converted")
+            .execute(&mut conn)
+            .await?;
+        conn.close().await?;
+
+        // The old WAL belongs to the old file and must not be applied to the new one
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(suffix);
+            if Path::new(&sidecar).exists() {
+                fs::remove_file(&sidecar).map_err(sqlx::Error::Io)?;
+            }
+        }
+        fs::rename(&converted_path, path).map_err(sqlx::Error::Io)?;
+        Ok(())
+    }
+
     // NOTE: So for the first time users they needs to start the application
     // after they can just delete the existing .sqlite file and then copy the existing .db file to
     // the current app dir, So the system detects legacy db and copy it and starts with that data
@@ -53,7 +181,7 @@ impl DatabaseManager {
 
         // Define database paths
         let tauri_db_path = app_data_dir
-            .join("meeting_minutes.sqlite")
+            .join(DATABASE_FILE)
             .to_string_lossy()
             .to_string();
         // Legacy backend DB path (for auto-migration if exists)
@@ -124,7 +252,7 @@ impl DatabaseManager {
             .app_data_dir()
             .expect("failed to get app data dir");
 
-        let tauri_db_path = app_data_dir.join("meeting_minutes.sqlite");
+        let tauri_db_path = app_data_dir.join(DATABASE_FILE);
 
         Ok(!tauri_db_path.exists())
     }
@@ -161,6 +289,22 @@ impl DatabaseManager {
         &self.pool
     }
 
+    /// In-memory database with all migrations applied, for repository tests
+    #[cfg(test)]
+    pub(crate) async fn test_pool() -> SqlitePool {
+        // A single connection, since every in-memory connection is a separate database
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to open in-memory database");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        pool
+    }
+
     pub async fn with_transaction<T, F, Fut>(&self, f: F) -> Result<T>
     where
         F: FnOnce(&mut Transaction<'_, Sqlite>) -> Fut,
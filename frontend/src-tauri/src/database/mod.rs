@@ -1,4 +1,6 @@
+pub mod backup;
 pub mod commands;
+pub mod maintenance;
 pub mod manager;
 pub mod models;
 pub mod repositories;
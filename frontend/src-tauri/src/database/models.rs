@@ -54,6 +54,132 @@ pub struct SummaryProcess {
     pub result_backup_timestamp: Option<chrono::DateTime<chrono::Utc>>, // When backup was created
 }
 
+/// Stored summary of an earlier session, used as context for later summaries
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PreviousSessionSummary {
+    pub meeting_id: String,
+    pub title: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub result: String, // JSON
+}
+
+/// One recorded version of a session's summary
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SummaryVersion {
+    pub id: String,
+    pub meeting_id: String,
+    pub version_number: i64,
+    pub source: String, // generated | edited | restored
+    pub restored_from: Option<i64>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub template_id: Option<String>,
+    pub template_version: Option<i64>,
+    pub prompt_hash: Option<String>,
+    pub result: String,           // JSON, same format as summary_processes.result
+    pub metadata: Option<String>, // JSON
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A queued or finished summary generation request
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SummaryJob {
+    pub id: String,
+    pub meeting_id: String,
+    pub status: String, // queued | running | done | failed | cancelled
+    pub provider: String,
+    pub model: String,
+    pub template_id: String,
+    pub custom_prompt: String,
+    pub previous_session_count: i64,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+    pub last_error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub batch_id: Option<String>,
+}
+
+/// A regeneration run over many sessions
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SummaryBatch {
+    pub id: String,
+    pub status: String, // running | completed | cancelled
+    pub provider: String,
+    pub model: String,
+    pub template_id: String,
+    pub selection: String, // JSON of the filters used to select sessions
+    pub total: i64,
+    pub skipped: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A summary note item linked to the transcript segment it cites
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SummaryCitation {
+    pub id: String,
+    pub meeting_id: String,
+    pub line_index: i64,
+    pub section: Option<String>,
+    pub item_text: String,
+    pub timestamp_label: String,
+    pub audio_start_time: f64,
+    pub transcript_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A question about a session or the answer to it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChatMessageRecord {
+    pub id: String,
+    pub meeting_id: String,
+    pub role: String, // user | assistant
+    pub content: String,
+    pub citations: Option<String>, // JSON array of NoteCitation
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Homework assigned in a session and carried over to later sessions
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct HomeworkItem {
+    pub id: String,
+    pub source_meeting_id: String,
+    pub description: String,
+    pub status: String, // open | done | skipped
+    pub origin: String, // extracted | manual
+    pub due_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub reviewed_in_meeting_id: Option<String>,
+    pub reminder_sent_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An upcoming lesson in the user's schedule
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ScheduledLesson {
+    pub id: String,
+    pub title: Option<String>,
+    pub scheduled_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Learner details used to fill in template variables
+#[derive(Debug, Clone, Default, FromRow, Serialize, Deserialize)]
+pub struct LearnerProfile {
+    pub target_language: Option<String>,
+    pub native_language: Option<String>,
+    pub learner_level: Option<String>,
+    pub tutor_name: Option<String>,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct TranscriptChunk {
     pub meeting_id: String,
@@ -74,21 +200,7 @@ pub struct Setting {
     #[sqlx(rename = "whisperModel")]
     #[serde(rename = "whisperModel")]
     pub whisper_model: String,
-    #[sqlx(rename = "groqApiKey")]
-    #[serde(rename = "groqApiKey")]
-    pub groq_api_key: Option<String>,
-    #[sqlx(rename = "openaiApiKey")]
-    #[serde(rename = "openaiApiKey")]
-    pub openai_api_key: Option<String>,
-    #[sqlx(rename = "anthropicApiKey")]
-    #[serde(rename = "anthropicApiKey")]
-    pub anthropic_api_key: Option<String>,
-    #[sqlx(rename = "ollamaApiKey")]
-    #[serde(rename = "ollamaApiKey")]
-    pub ollama_api_key: Option<String>,
-    #[sqlx(rename = "openRouterApiKey")]
-    #[serde(rename = "openRouterApiKey")]
-    pub open_router_api_key: Option<String>,
+    // API keys are kept in the secret store (`crate::secrets`), not in this table
     #[sqlx(rename = "ollamaEndpoint")]
     #[serde(rename = "ollamaEndpoint")]
     pub ollama_endpoint: Option<String>,
@@ -112,19 +224,5 @@ pub struct TranscriptSetting {
     pub id: String,
     pub provider: String,
     pub model: String,
-    #[sqlx(rename = "whisperApiKey")]
-    #[serde(rename = "whisperApiKey")]
-    pub whisper_api_key: Option<String>,
-    #[sqlx(rename = "deepgramApiKey")]
-    #[serde(rename = "deepgramApiKey")]
-    pub deepgram_api_key: Option<String>,
-    #[sqlx(rename = "elevenLabsApiKey")]
-    #[serde(rename = "elevenLabsApiKey")]
-    pub eleven_labs_api_key: Option<String>,
-    #[sqlx(rename = "groqApiKey")]
-    #[serde(rename = "groqApiKey")]
-    pub groq_api_key: Option<String>,
-    #[sqlx(rename = "openaiApiKey")]
-    #[serde(rename = "openaiApiKey")]
-    pub openai_api_key: Option<String>,
+    // API keys are kept in the secret store (`crate::secrets`), not in this table
 }
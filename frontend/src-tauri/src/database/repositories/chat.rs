@@ -0,0 +1,193 @@
+use crate::database::models::ChatMessageRecord;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub struct ChatRepository;
+
+impl ChatRepository {
+    /// Generates an id for a new message.
+    pub fn new_message_id() -> String {
+        format!("chat-{}", Uuid::new_v4())
+    }
+
+    /// Appends a message to a session's conversation under the given id.
+    ///
+    /// `provider` and `model` record what generated an assistant message.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_message(
+        pool: &SqlitePool,
+        id: &str,
+        meeting_id: &str,
+        role: &str,
+        content: &str,
+        citations: Option<&str>,
+        provider: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<ChatMessageRecord, sqlx::Error> {
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO chat_messages (id, meeting_id, role, content, citations, provider, model, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(meeting_id)
+        .bind(role)
+        .bind(content)
+        .bind(citations)
+        .bind(provider)
+        .bind(model)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(ChatMessageRecord {
+            id: id.to_string(),
+            meeting_id: meeting_id.to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            citations: citations.map(str::to_string),
+            provider: provider.map(str::to_string),
+            model: model.map(str::to_string),
+            created_at: now,
+        })
+    }
+
+    /// Lists a session's conversation, oldest message first.
+    pub async fn list_messages(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<ChatMessageRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ChatMessageRecord>(
+            "SELECT * FROM chat_messages WHERE meeting_id = ? ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Deletes a session's conversation, returning the number of deleted messages.
+    pub async fn clear_messages(pool: &SqlitePool, meeting_id: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM chat_messages WHERE meeting_id = ?")
+            .bind(meeting_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manager::DatabaseManager;
+
+    async fn insert_meeting(pool: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO meetings (id, title, created_at, updated_at) VALUES (?1, ?1, ?2, ?2)",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_messages_round_trip_in_order() {
+        let pool = DatabaseManager::test_pool().await;
+        insert_meeting(&pool, "m1").await;
+        insert_meeting(&pool, "m2").await;
+
+        let question_id = ChatRepository::new_message_id();
+        ChatRepository::add_message(
+            &pool,
+            &question_id,
+            "m1",
+            "user",
+            "¿Qué es el subjuntivo?",
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        ChatRepository::add_message(
+            &pool,
+            "chat-answer",
+            "m1",
+            "assistant",
+            "Un modo verbal [02:05]",
+            Some(r#"[{"start":125.0}]"#),
+            Some("ollama"),
+            Some("llama3.2:latest"),
+        )
+        .await
+        .unwrap();
+        ChatRepository::add_message(&pool, "chat-other", "m2", "user", "Hola", None, None, None)
+            .await
+            .unwrap();
+
+        let messages = ChatRepository::list_messages(&pool, "m1").await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, question_id);
+        assert_eq!(messages[0].role, "user");
+        assert_eq!(messages[0].content, "¿Qué es el subjuntivo?");
+        assert_eq!(messages[0].citations, None);
+        assert_eq!(messages[0].provider, None);
+        assert_eq!(messages[1].id, "chat-answer");
+        assert_eq!(messages[1].meeting_id, "m1");
+        assert_eq!(messages[1].role, "assistant");
+        assert_eq!(messages[1].content, "Un modo verbal [02:05]");
+        assert_eq!(
+            messages[1].citations.as_deref(),
+            Some(r#"[{"start":125.0}]"#)
+        );
+        assert_eq!(messages[1].provider.as_deref(), Some("ollama"));
+        assert_eq!(messages[1].model.as_deref(), Some("llama3.2:latest"));
+
+        // A message id can only be used once
+        assert!(ChatRepository::add_message(
+            &pool,
+            "chat-answer",
+            "m1",
+            "user",
+            "Again",
+            None,
+            None,
+            None
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clear_messages_only_clears_the_session() {
+        let pool = DatabaseManager::test_pool().await;
+        insert_meeting(&pool, "m1").await;
+        insert_meeting(&pool, "m2").await;
+        for (id, meeting_id) in [("chat-1", "m1"), ("chat-2", "m1"), ("chat-3", "m2")] {
+            ChatRepository::add_message(&pool, id, meeting_id, "user", "Hola", None, None, None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            ChatRepository::clear_messages(&pool, "m1").await.unwrap(),
+            2
+        );
+        assert!(ChatRepository::list_messages(&pool, "m1")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            ChatRepository::list_messages(&pool, "m2")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
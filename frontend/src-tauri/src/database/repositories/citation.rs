@@ -0,0 +1,62 @@
+use crate::database::models::SummaryCitation;
+use crate::summary::citations::NoteCitation;
+use chrono::Utc;
+use sqlx::{Connection, SqlitePool};
+use uuid::Uuid;
+
+pub struct CitationsRepository;
+
+impl CitationsRepository {
+    /// Replaces the stored citations of a session's summary with `citations`.
+    pub async fn replace_citations(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        citations: &[NoteCitation],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = pool.acquire().await?;
+        let mut transaction = conn.begin().await?;
+        let now = Utc::now();
+
+        sqlx::query("DELETE FROM summary_citations WHERE meeting_id = ?")
+            .bind(meeting_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for citation in citations {
+            sqlx::query(
+                r#"
+                INSERT INTO summary_citations
+                    (id, meeting_id, line_index, section, item_text, timestamp_label, audio_start_time, transcript_id, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(format!("citation-{}", Uuid::new_v4()))
+            .bind(meeting_id)
+            .bind(citation.line_index as i64)
+            .bind(&citation.section)
+            .bind(&citation.item_text)
+            .bind(&citation.timestamp_label)
+            .bind(citation.audio_start_time)
+            .bind(&citation.transcript_id)
+            .bind(now)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Lists the citations of a session's summary in note order.
+    pub async fn list_citations(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<SummaryCitation>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryCitation>(
+            "SELECT * FROM summary_citations WHERE meeting_id = ? ORDER BY line_index ASC, audio_start_time ASC",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,261 @@
+use crate::database::models::HomeworkItem;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::info as log_info;
+use uuid::Uuid;
+
+pub struct HomeworkRepository;
+
+impl HomeworkRepository {
+    /// Creates a homework item the user added by hand to a session.
+    pub async fn create_item(
+        pool: &SqlitePool,
+        source_meeting_id: &str,
+        description: &str,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<HomeworkItem, sqlx::Error> {
+        let id = format!("homework-{}", Uuid::new_v4());
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO homework_items (id, source_meeting_id, description, status, origin, due_date, created_at, updated_at)
+            VALUES (?, ?, ?, 'open', 'manual', ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(source_meeting_id)
+        .bind(description)
+        .bind(due_date)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(HomeworkItem {
+            id,
+            source_meeting_id: source_meeting_id.to_string(),
+            description: description.to_string(),
+            status: "open".to_string(),
+            origin: "manual".to_string(),
+            due_date,
+            reviewed_in_meeting_id: None,
+            reminder_sent_at: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Replaces the still-open extracted homework of a session with a freshly extracted list.
+    ///
+    /// Items added by hand are never touched. An extracted description that matches an
+    /// existing item of the session, ignoring case and spacing, keeps that item as it is,
+    /// so regenerating the notes neither duplicates homework nor resets due dates,
+    /// reminders or review state. Open extracted items missing from the new list are removed.
+    pub async fn replace_open_items_for_meeting(
+        pool: &SqlitePool,
+        source_meeting_id: &str,
+        descriptions: &[String],
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<usize, sqlx::Error> {
+        let mut transaction = pool.begin().await?;
+
+        let existing: Vec<(String, String, String, String)> = sqlx::query_as(
+            "SELECT id, description, status, origin FROM homework_items WHERE source_meeting_id = ?",
+        )
+        .bind(source_meeting_id)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let extracted: Vec<String> = descriptions
+            .iter()
+            .map(|d| normalize_description(d))
+            .collect();
+        let mut kept: Vec<String> = Vec::new();
+        for (id, description, status, origin) in &existing {
+            let description = normalize_description(description);
+            if status == "open" && origin == "extracted" && !extracted.contains(&description) {
+                sqlx::query("DELETE FROM homework_items WHERE id = ?")
+                    .bind(id)
+                    .execute(&mut *transaction)
+                    .await?;
+            } else {
+                kept.push(description);
+            }
+        }
+
+        let now = Utc::now();
+        let mut inserted = 0;
+        for (description, normalized) in descriptions.iter().zip(extracted) {
+            if kept.contains(&normalized) {
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO homework_items (id, source_meeting_id, description, status, origin, due_date, created_at, updated_at)
+                VALUES (?, ?, ?, 'open', 'extracted', ?, ?, ?)
+                "#,
+            )
+            .bind(format!("homework-{}", Uuid::new_v4()))
+            .bind(source_meeting_id)
+            .bind(description)
+            .bind(due_date)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *transaction)
+            .await?;
+            kept.push(normalized);
+            inserted += 1;
+        }
+
+        transaction.commit().await?;
+        log_info!(
+            "Stored {} new homework items for meeting_id: {}",
+            inserted,
+            source_meeting_id
+        );
+        Ok(inserted)
+    }
+
+    /// Lists homework, optionally filtered by source session and/or status.
+    pub async fn list_items(
+        pool: &SqlitePool,
+        source_meeting_id: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<Vec<HomeworkItem>, sqlx::Error> {
+        sqlx::query_as::<_, HomeworkItem>(
+            r#"
+            SELECT * FROM homework_items
+            WHERE (?1 IS NULL OR source_meeting_id = ?1)
+              AND (?2 IS NULL OR status = ?2)
+            ORDER BY due_date IS NULL, due_date ASC, created_at ASC
+            "#,
+        )
+        .bind(source_meeting_id)
+        .bind(status)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Lists homework that was still open when the given session took place.
+    ///
+    /// Only homework assigned in earlier sessions is returned, so regenerating the
+    /// notes of an old lesson never shows homework from later lessons.
+    pub async fn get_open_items_before_meeting(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<HomeworkItem>, sqlx::Error> {
+        sqlx::query_as::<_, HomeworkItem>(
+            r#"
+            SELECT h.* FROM homework_items h
+            JOIN meetings m ON m.id = h.source_meeting_id
+            WHERE h.source_meeting_id != ?1
+              AND (h.status = 'open' OR h.reviewed_in_meeting_id = ?1)
+              AND m.created_at < (SELECT created_at FROM meetings WHERE id = ?1)
+            ORDER BY m.created_at ASC, h.created_at ASC
+            "#,
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Updates the status of a homework item. Returns false if the item doesn't exist.
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: &str,
+        status: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE homework_items SET status = ?, updated_at = ? WHERE id = ?")
+            .bind(status)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Updates the due date of a homework item and re-arms its reminder.
+    pub async fn update_due_date(
+        pool: &SqlitePool,
+        id: &str,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE homework_items SET due_date = ?, reminder_sent_at = NULL, updated_at = ? WHERE id = ?",
+        )
+        .bind(due_date)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records that a later session's notes report the homework as reviewed.
+    pub async fn mark_reviewed(
+        pool: &SqlitePool,
+        ids: &[String],
+        meeting_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        for id in ids {
+            sqlx::query(
+                "UPDATE homework_items SET reviewed_in_meeting_id = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(meeting_id)
+            .bind(now)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Lists open homework due before `due_before` that hasn't been reminded about yet.
+    pub async fn get_items_needing_reminder(
+        pool: &SqlitePool,
+        due_before: DateTime<Utc>,
+    ) -> Result<Vec<HomeworkItem>, sqlx::Error> {
+        sqlx::query_as::<_, HomeworkItem>(
+            r#"
+            SELECT * FROM homework_items
+            WHERE status = 'open'
+              AND reminder_sent_at IS NULL
+              AND due_date IS NOT NULL
+              AND due_date <= ?
+            ORDER BY due_date ASC
+            "#,
+        )
+        .bind(due_before)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_reminder_sent(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE homework_items SET reminder_sent_at = ? WHERE id = ?")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_item(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM homework_items WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Lowercases a description and collapses its whitespace, for matching extracted homework
+fn normalize_description(description: &str) -> String {
+    description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
@@ -0,0 +1,46 @@
+use crate::database::models::LearnerProfile;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct LearnerProfileRepository;
+
+impl LearnerProfileRepository {
+    /// Returns the learner profile, or an empty profile if none was saved yet.
+    pub async fn get_profile(pool: &SqlitePool) -> Result<LearnerProfile, sqlx::Error> {
+        let profile = sqlx::query_as::<_, LearnerProfile>(
+            "SELECT target_language, native_language, learner_level, tutor_name FROM learner_profile WHERE id = '1'",
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(profile.unwrap_or_default())
+    }
+
+    pub async fn save_profile(
+        pool: &SqlitePool,
+        profile: &LearnerProfile,
+    ) -> Result<(), sqlx::Error> {
+        // Single-row table, same convention as the settings table
+        sqlx::query(
+            r#"
+            INSERT INTO learner_profile (id, target_language, native_language, learner_level, tutor_name, updated_at)
+            VALUES ('1', ?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(id) DO UPDATE SET
+                target_language = excluded.target_language,
+                native_language = excluded.native_language,
+                learner_level = excluded.learner_level,
+                tutor_name = excluded.tutor_name,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&profile.target_language)
+        .bind(&profile.native_language)
+        .bind(&profile.learner_level)
+        .bind(&profile.tutor_name)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
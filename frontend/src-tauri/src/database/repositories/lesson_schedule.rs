@@ -0,0 +1,68 @@
+use crate::database::models::ScheduledLesson;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub struct LessonScheduleRepository;
+
+impl LessonScheduleRepository {
+    pub async fn add_lesson(
+        pool: &SqlitePool,
+        scheduled_at: DateTime<Utc>,
+        title: Option<&str>,
+    ) -> Result<ScheduledLesson, sqlx::Error> {
+        let id = format!("lesson-{}", Uuid::new_v4());
+        let now = Utc::now();
+
+        sqlx::query(
+            "INSERT INTO lesson_schedule (id, title, scheduled_at, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(title)
+        .bind(scheduled_at)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(ScheduledLesson {
+            id,
+            title: title.map(|t| t.to_string()),
+            scheduled_at,
+            created_at: now,
+        })
+    }
+
+    /// Lists scheduled lessons at or after `from`, soonest first.
+    pub async fn list_upcoming(
+        pool: &SqlitePool,
+        from: DateTime<Utc>,
+    ) -> Result<Vec<ScheduledLesson>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduledLesson>(
+            "SELECT * FROM lesson_schedule WHERE scheduled_at >= ? ORDER BY scheduled_at ASC",
+        )
+        .bind(from)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns the first scheduled lesson strictly after `after`, if any.
+    pub async fn get_next_lesson_after(
+        pool: &SqlitePool,
+        after: DateTime<Utc>,
+    ) -> Result<Option<ScheduledLesson>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduledLesson>(
+            "SELECT * FROM lesson_schedule WHERE scheduled_at > ? ORDER BY scheduled_at ASC LIMIT 1",
+        )
+        .bind(after)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete_lesson(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM lesson_schedule WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
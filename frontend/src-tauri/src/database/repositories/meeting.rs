@@ -1,6 +1,6 @@
 use crate::api::{SessionDetails, SessionTranscript};
 use crate::database::models::{MeetingModel, Transcript};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::{Connection, Error as SqlxError, SqliteConnection, SqlitePool};
 use tracing::{error, info};
 
@@ -15,6 +15,47 @@ impl MeetingsRepository {
         Ok(meetings)
     }
 
+    /// Finds sessions with a transcript, newest first
+    ///
+    /// All given filters must match. `query` matches the title or transcript text
+    /// (case-insensitive); `tag` matches one of the session's tags.
+    pub async fn find_meetings(
+        pool: &SqlitePool,
+        created_after: Option<DateTime<Utc>>,
+        created_before: Option<DateTime<Utc>>,
+        tag: Option<&str>,
+        query: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<MeetingModel>, sqlx::Error> {
+        let pattern = query.map(|q| format!("%{}%", escape_like(&q.to_lowercase())));
+        sqlx::query_as::<_, MeetingModel>(
+            r#"
+            SELECT m.* FROM meetings m
+            WHERE (?1 IS NULL OR m.created_at >= ?1)
+              AND (?2 IS NULL OR m.created_at <= ?2)
+              AND (?3 IS NULL OR EXISTS (
+                  SELECT 1 FROM meeting_tags mt WHERE mt.meeting_id = m.id AND mt.tag = ?3
+              ))
+              AND (?4 IS NULL OR LOWER(m.title) LIKE ?4 ESCAPE '\' OR EXISTS (
+                  SELECT 1 FROM transcripts t WHERE t.meeting_id = m.id AND LOWER(t.transcript) LIKE ?4 ESCAPE '\'
+              ))
+              AND (
+                  EXISTS (SELECT 1 FROM transcripts t WHERE t.meeting_id = m.id)
+                  OR EXISTS (SELECT 1 FROM transcript_chunks tc WHERE tc.meeting_id = m.id)
+              )
+            ORDER BY m.created_at DESC
+            LIMIT ?5
+            "#,
+        )
+        .bind(created_after)
+        .bind(created_before)
+        .bind(tag)
+        .bind(pattern)
+        .bind(limit.unwrap_or(-1))
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn delete_meeting(pool: &SqlitePool, meeting_id: &str) -> Result<bool, SqlxError> {
         if meeting_id.trim().is_empty() {
             return Err(SqlxError::Protocol(
@@ -230,6 +271,13 @@ impl MeetingsRepository {
     }
 }
 
+/// Escapes the `LIKE` wildcards in user input, for patterns with `ESCAPE '\'`
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 async fn delete_meeting_with_transaction(
     transaction: &mut SqliteConnection,
     meeting_id: &str,
@@ -264,7 +312,37 @@ async fn delete_meeting_with_transaction(
         .execute(&mut *transaction)
         .await?;
 
-    // 4. Finally, delete the meeting
+    // 4. Delete homework assigned in this meeting
+    sqlx::query("DELETE FROM homework_items WHERE source_meeting_id = ?")
+        .bind(meeting_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // 5. Delete summary citations
+    sqlx::query("DELETE FROM summary_citations WHERE meeting_id = ?")
+        .bind(meeting_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // 6. Delete summary version history
+    sqlx::query("DELETE FROM summary_versions WHERE meeting_id = ?")
+        .bind(meeting_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // 7. Delete queued and finished summary jobs
+    sqlx::query("DELETE FROM summary_jobs WHERE meeting_id = ?")
+        .bind(meeting_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // 8. Delete session tags
+    sqlx::query("DELETE FROM meeting_tags WHERE meeting_id = ?")
+        .bind(meeting_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    // 9. Finally, delete the meeting
     let result = sqlx::query("DELETE FROM meetings WHERE id = ?")
         .bind(meeting_id)
         .execute(&mut *transaction)
@@ -0,0 +1,55 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct MeetingTagsRepository;
+
+impl MeetingTagsRepository {
+    /// Gets a session's tags in alphabetical order.
+    pub async fn get_tags(pool: &SqlitePool, meeting_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT tag FROM meeting_tags WHERE meeting_id = ? ORDER BY tag")
+                .bind(meeting_id)
+                .fetch_all(pool)
+                .await?;
+        Ok(rows.into_iter().map(|(tag,)| tag).collect())
+    }
+
+    /// Replaces a session's tags. Tags are trimmed; empty and duplicate tags
+    /// (ignoring case) are skipped.
+    pub async fn set_tags(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        tags: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = pool.begin().await?;
+        let now = Utc::now();
+
+        sqlx::query("DELETE FROM meeting_tags WHERE meeting_id = ?")
+            .bind(meeting_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            sqlx::query(
+                "INSERT OR IGNORE INTO meeting_tags (meeting_id, tag, created_at) VALUES (?, ?, ?)",
+            )
+            .bind(meeting_id)
+            .bind(tag)
+            .bind(now)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Lists all tags in use with the number of sessions carrying them.
+    pub async fn list_tags(pool: &SqlitePool) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT MIN(tag), COUNT(*) FROM meeting_tags GROUP BY tag COLLATE NOCASE ORDER BY MIN(tag)",
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
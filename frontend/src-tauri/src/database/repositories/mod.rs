@@ -1,5 +1,14 @@
+pub mod chat;
+pub mod citation;
+pub mod homework;
+pub mod learner_profile;
+pub mod lesson_schedule;
 pub mod meeting;
+pub mod meeting_tag;
 pub mod setting;
 pub mod summary;
+pub mod summary_batch;
+pub mod summary_job;
+pub mod summary_version;
 pub mod transcript;
 pub mod transcript_chunk;
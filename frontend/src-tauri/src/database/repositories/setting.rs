@@ -1,5 +1,8 @@
 use crate::database::models::{Setting, TranscriptSetting};
-use crate::summary::CustomOpenAIConfig;
+use crate::secrets::{delete_secret, get_secret, set_secret, SecretStoreError};
+use crate::summary::fallback::ProviderFallbackConfig;
+use crate::summary::redaction::RedactionConfig;
+use crate::summary::{CustomOpenAIConfig, SamplingConfig};
 use sqlx::SqlitePool;
 
 #[derive(serde::Deserialize, Debug)]
@@ -24,8 +27,75 @@ pub struct SaveTranscriptConfigRequest {
 
 pub struct SettingsRepository;
 
+/// Summary providers with an API key, and the `settings` column earlier
+/// versions saved it in
+const SUMMARY_API_KEY_COLUMNS: &[(&str, &str)] = &[
+    ("openai", "openaiApiKey"),
+    ("claude", "anthropicApiKey"),
+    ("ollama", "ollamaApiKey"),
+    ("groq", "groqApiKey"),
+    ("openrouter", "openRouterApiKey"),
+    ("gemini", "geminiApiKey"),
+];
+
+/// Transcript providers with an API key, and the `transcript_settings` column
+/// earlier versions saved it in
+const TRANSCRIPT_API_KEY_COLUMNS: &[(&str, &str)] = &[
+    ("localWhisper", "whisperApiKey"),
+    ("deepgram", "deepgramApiKey"),
+    ("elevenLabs", "elevenLabsApiKey"),
+    ("groq", "groqApiKey"),
+    ("openai", "openaiApiKey"),
+];
+
+/// Secret store name of the custom OpenAI endpoint's API key
+const CUSTOM_OPENAI_SECRET: &str = "summary.custom-openai";
+
+/// Secret store name of a summary provider's API key, `None` if it needs none
+fn summary_secret_name(provider: &str) -> std::result::Result<Option<String>, sqlx::Error> {
+    match provider {
+        "builtin-ai" => Ok(None), // No API key needed
+        _ => secret_name("summary", SUMMARY_API_KEY_COLUMNS, provider),
+    }
+}
+
+/// Secret store name of a transcript provider's API key, `None` if it needs none
+fn transcript_secret_name(provider: &str) -> std::result::Result<Option<String>, sqlx::Error> {
+    match provider {
+        "parakeet" => Ok(None), // Parakeet doesn't need an API key
+        _ => secret_name("transcript", TRANSCRIPT_API_KEY_COLUMNS, provider),
+    }
+}
+
+fn secret_name(
+    scope: &str,
+    columns: &[(&str, &str)],
+    provider: &str,
+) -> std::result::Result<Option<String>, sqlx::Error> {
+    if columns.iter().any(|(name, _)| *name == provider) {
+        Ok(Some(format!("{}.{}", scope, provider)))
+    } else {
+        Err(sqlx::Error::Protocol(format!("Invalid provider: {}", provider)))
+    }
+}
+
+fn secret_error(e: SecretStoreError) -> sqlx::Error {
+    sqlx::Error::Protocol(e.to_string())
+}
+
+/// A secret read for display, where a locked store reads as no secret, so the
+/// settings can be shown before the master password is entered
+fn unless_locked(
+    result: std::result::Result<Option<String>, SecretStoreError>,
+) -> std::result::Result<Option<String>, sqlx::Error> {
+    match result {
+        Err(SecretStoreError::Locked) => Ok(None),
+        result => result.map_err(secret_error),
+    }
+}
+
 // Transcript providers: localWhisper, deepgram, elevenLabs, groq, openai
-// Summary providers: openai, claude, ollama, groq, added openrouter
+// Summary providers: openai, claude, ollama, groq, added openrouter, gemini
 // NOTE: Handle data exclusion in the higher layer as this is database abstraction layer(using SELECT *)
 
 impl SettingsRepository {
@@ -72,37 +142,28 @@ impl SettingsRepository {
         provider: &str,
         api_key: &str,
     ) -> std::result::Result<(), sqlx::Error> {
-        // Custom OpenAI uses JSON config (customOpenAIConfig) instead of a separate API key column
+        // Custom OpenAI uses JSON config (customOpenAIConfig) instead of a separate API key
         if provider == "custom-openai" {
             return Err(sqlx::Error::Protocol(
                 "custom-openai provider should use save_custom_openai_config() instead of save_api_key()".into(),
             ));
         }
 
-        let api_key_column = match provider {
-            "openai" => "openaiApiKey",
-            "claude" => "anthropicApiKey",
-            "ollama" => "ollamaApiKey",
-            "groq" => "groqApiKey",
-            "openrouter" => "openRouterApiKey",
-            "builtin-ai" => return Ok(()), // No API key needed
-            _ => {
-                return Err(sqlx::Error::Protocol(
-                    format!("Invalid provider: {}", provider).into(),
-                ))
-            }
+        let Some(secret_name) = summary_secret_name(provider)? else {
+            return Ok(());
         };
+        set_secret(&secret_name, api_key).map_err(secret_error)?;
 
-        let query = format!(
+        // Callers expect the settings row to exist after saving a key
+        sqlx::query(
             r#"
-            INSERT INTO settings (id, provider, model, whisperModel, "{}")
-            VALUES ('1', 'openai', 'gpt-4o-2024-11-20', 'large-v3', $1)
-            ON CONFLICT(id) DO UPDATE SET
-                "{}" = $1
+            INSERT INTO settings (id, provider, model, whisperModel)
+            VALUES ('1', 'openai', 'gpt-4o-2024-11-20', 'large-v3')
+            ON CONFLICT(id) DO NOTHING
             "#,
-            api_key_column, api_key_column
-        );
-        sqlx::query(&query).bind(api_key).execute(pool).await?;
+        )
+        .execute(pool)
+        .await?;
 
         Ok(())
     }
@@ -117,26 +178,26 @@ impl SettingsRepository {
             return Ok(config.and_then(|c| c.api_key));
         }
 
-        let api_key_column = match provider {
-            "openai" => "openaiApiKey",
-            "ollama" => "ollamaApiKey",
-            "groq" => "groqApiKey",
-            "claude" => "anthropicApiKey",
-            "openrouter" => "openRouterApiKey",
-            "builtin-ai" => return Ok(None), // No API key needed
-            _ => {
-                return Err(sqlx::Error::Protocol(
-                    format!("Invalid provider: {}", provider).into(),
-                ))
-            }
-        };
+        match summary_secret_name(provider)? {
+            Some(secret_name) => get_secret(&secret_name).map_err(secret_error),
+            None => Ok(None),
+        }
+    }
 
-        let query = format!(
-            "SELECT {} FROM settings WHERE id = '1' LIMIT 1",
-            api_key_column
-        );
-        let api_key = sqlx::query_scalar(&query).fetch_optional(pool).await?;
-        Ok(api_key)
+    /// Like `get_api_key`, but a locked secret store reads as no key
+    pub async fn get_api_key_for_display(
+        pool: &SqlitePool,
+        provider: &str,
+    ) -> std::result::Result<Option<String>, sqlx::Error> {
+        if provider == "custom-openai" {
+            let config = Self::get_custom_openai_config(pool).await?;
+            return Ok(config.and_then(|c| c.api_key));
+        }
+
+        match summary_secret_name(provider)? {
+            Some(secret_name) => unless_locked(get_secret(&secret_name)),
+            None => Ok(None),
+        }
     }
 
     pub async fn get_transcript_config(
@@ -177,58 +238,41 @@ impl SettingsRepository {
         provider: &str,
         api_key: &str,
     ) -> std::result::Result<(), sqlx::Error> {
-        let api_key_column = match provider {
-            "localWhisper" => "whisperApiKey",
-            "parakeet" => return Ok(()), // Parakeet doesn't need an API key, return early
-            "deepgram" => "deepgramApiKey",
-            "elevenLabs" => "elevenLabsApiKey",
-            "groq" => "groqApiKey",
-            "openai" => "openaiApiKey",
-            _ => {
-                return Err(sqlx::Error::Protocol(
-                    format!("Invalid provider: {}", provider).into(),
-                ))
-            }
+        let Some(secret_name) = transcript_secret_name(provider)? else {
+            return Ok(());
         };
+        set_secret(&secret_name, api_key).map_err(secret_error)?;
 
-        let query = format!(
+        sqlx::query(
             r#"
-            INSERT INTO transcript_settings (id, provider, model, "{}")
-            VALUES ('1', 'parakeet', 'parakeet-tdt-0.6b-v3-int8', $1)
-            ON CONFLICT(id) DO UPDATE SET
-                "{}" = $1
+            INSERT INTO transcript_settings (id, provider, model)
+            VALUES ('1', 'parakeet', 'parakeet-tdt-0.6b-v3-int8')
+            ON CONFLICT(id) DO NOTHING
             "#,
-            api_key_column, api_key_column
-        );
-        sqlx::query(&query).bind(api_key).execute(pool).await?;
+        )
+        .execute(pool)
+        .await?;
 
         Ok(())
     }
 
     pub async fn get_transcript_api_key(
-        pool: &SqlitePool,
         provider: &str,
     ) -> std::result::Result<Option<String>, sqlx::Error> {
-        let api_key_column = match provider {
-            "localWhisper" => "whisperApiKey",
-            "parakeet" => return Ok(None), // Parakeet doesn't need an API key
-            "deepgram" => "deepgramApiKey",
-            "elevenLabs" => "elevenLabsApiKey",
-            "groq" => "groqApiKey",
-            "openai" => "openaiApiKey",
-            _ => {
-                return Err(sqlx::Error::Protocol(
-                    format!("Invalid provider: {}", provider).into(),
-                ))
-            }
-        };
+        match transcript_secret_name(provider)? {
+            Some(secret_name) => get_secret(&secret_name).map_err(secret_error),
+            None => Ok(None),
+        }
+    }
 
-        let query = format!(
-            "SELECT {} FROM transcript_settings WHERE id = '1' LIMIT 1",
-            api_key_column
-        );
-        let api_key = sqlx::query_scalar(&query).fetch_optional(pool).await?;
-        Ok(api_key)
+    /// Like `get_transcript_api_key`, but a locked secret store reads as no key
+    pub async fn get_transcript_api_key_for_display(
+        provider: &str,
+    ) -> std::result::Result<Option<String>, sqlx::Error> {
+        match transcript_secret_name(provider)? {
+            Some(secret_name) => unless_locked(get_secret(&secret_name)),
+            None => Ok(None),
+        }
     }
 
     pub async fn delete_api_key(
@@ -237,33 +281,80 @@ impl SettingsRepository {
     ) -> std::result::Result<(), sqlx::Error> {
         // Custom OpenAI uses JSON config - clear the entire config
         if provider == "custom-openai" {
+            delete_secret(CUSTOM_OPENAI_SECRET).map_err(secret_error)?;
             sqlx::query("UPDATE settings SET customOpenAIConfig = NULL WHERE id = '1'")
                 .execute(pool)
                 .await?;
             return Ok(());
         }
 
-        let api_key_column = match provider {
-            "openai" => "openaiApiKey",
-            "ollama" => "ollamaApiKey",
-            "groq" => "groqApiKey",
-            "claude" => "anthropicApiKey",
-            "openrouter" => "openRouterApiKey",
-            "builtin-ai" => return Ok(()), // No API key needed
-            _ => {
-                return Err(sqlx::Error::Protocol(
-                    format!("Invalid provider: {}", provider).into(),
+        if let Some(secret_name) = summary_secret_name(provider)? {
+            delete_secret(&secret_name).map_err(secret_error)?;
+        }
+        Ok(())
+    }
+
+    /// Moves API keys that earlier versions saved in plaintext columns to the
+    /// secret store
+    ///
+    /// Each key is cleared from the database once stored. While the secret
+    /// store is locked the keys stay put and this fails; it is run again on
+    /// unlock.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of keys moved
+    pub async fn migrate_api_keys_to_secret_store(
+        pool: &SqlitePool,
+    ) -> std::result::Result<usize, sqlx::Error> {
+        let mut moved = 0;
+        for (table, scope, columns) in [
+            ("settings", "summary", SUMMARY_API_KEY_COLUMNS),
+            ("transcript_settings", "transcript", TRANSCRIPT_API_KEY_COLUMNS),
+        ] {
+            for (provider, column) in columns {
+                let api_key = sqlx::query_scalar::<_, Option<String>>(&format!(
+                    "SELECT \"{}\" FROM {} WHERE id = '1' LIMIT 1",
+                    column, table
                 ))
+                .fetch_optional(pool)
+                .await?
+                .flatten()
+                .filter(|key| !key.trim().is_empty());
+
+                if let Some(api_key) = api_key {
+                    set_secret(&format!("{}.{}", scope, provider), &api_key)
+                        .map_err(secret_error)?;
+                    moved += 1;
+                }
+                sqlx::query(&format!(
+                    "UPDATE {} SET \"{}\" = NULL WHERE id = '1' AND \"{}\" IS NOT NULL",
+                    table, column, column
+                ))
+                .execute(pool)
+                .await?;
             }
-        };
+        }
 
-        let query = format!(
-            "UPDATE settings SET {} = NULL WHERE id = '1'",
-            api_key_column
-        );
-        sqlx::query(&query).execute(pool).await?;
+        let custom_config = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT customOpenAIConfig FROM settings WHERE id = '1' LIMIT 1",
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+        if let Some(mut config) = custom_config
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<CustomOpenAIConfig>(json).ok())
+        {
+            if let Some(api_key) = config.api_key.take() {
+                if !api_key.trim().is_empty() {
+                    set_secret(CUSTOM_OPENAI_SECRET, &api_key).map_err(secret_error)?;
+                    moved += 1;
+                }
+                Self::write_custom_openai_config(pool, &config).await?;
+            }
+        }
 
-        Ok(())
+        Ok(moved)
     }
 
     // ===== CUSTOM OPENAI CONFIG METHODS =====
@@ -296,11 +387,18 @@ impl SettingsRepository {
 
                 if let Some(json) = config_json {
                     // Parse JSON into CustomOpenAIConfig
-                    let config: CustomOpenAIConfig = serde_json::from_str(&json)
+                    let mut config: CustomOpenAIConfig = serde_json::from_str(&json)
                         .map_err(|e| sqlx::Error::Protocol(
                             format!("Invalid JSON in customOpenAIConfig: {}", e).into()
                         ))?;
 
+                    // The API key is kept in the secret store; a key still in the
+                    // JSON predates that and is moved by the key migration. While
+                    // the store is locked the config comes without its key.
+                    if let Some(api_key) = unless_locked(get_secret(CUSTOM_OPENAI_SECRET))? {
+                        config.api_key = Some(api_key);
+                    }
+
                     Ok(Some(config))
                 } else {
                     Ok(None)
@@ -310,7 +408,8 @@ impl SettingsRepository {
         }
     }
 
-    /// Saves the custom OpenAI configuration as JSON
+    /// Saves the custom OpenAI configuration as JSON, and its API key in the
+    /// secret store
     ///
     /// # Arguments
     /// * `pool` - Database connection pool
@@ -318,10 +417,28 @@ impl SettingsRepository {
     ///
     /// # Returns
     /// * `Ok(())` - Config saved successfully
-    /// * `Err(sqlx::Error)` - Database or JSON serialization error
+    /// * `Err(sqlx::Error)` - Database, secret store or JSON serialization error
     pub async fn save_custom_openai_config(
         pool: &SqlitePool,
         config: &CustomOpenAIConfig,
+    ) -> std::result::Result<(), sqlx::Error> {
+        match config.api_key.as_deref().filter(|key| !key.trim().is_empty()) {
+            Some(api_key) => set_secret(CUSTOM_OPENAI_SECRET, api_key),
+            None => delete_secret(CUSTOM_OPENAI_SECRET),
+        }
+        .map_err(secret_error)?;
+
+        let config = CustomOpenAIConfig {
+            api_key: None,
+            ..config.clone()
+        };
+        Self::write_custom_openai_config(pool, &config).await
+    }
+
+    /// Writes the custom OpenAI configuration JSON as is
+    async fn write_custom_openai_config(
+        pool: &SqlitePool,
+        config: &CustomOpenAIConfig,
     ) -> std::result::Result<(), sqlx::Error> {
         // Serialize config to JSON
         let config_json = serde_json::to_string(config)
@@ -345,4 +462,353 @@ impl SettingsRepository {
 
         Ok(())
     }
+
+    /// Gets the provider fallback configuration from JSON
+    ///
+    /// # Returns
+    /// * `Ok(Some(ProviderFallbackConfig))` - Config exists and is valid JSON
+    /// * `Ok(None)` - No config stored
+    /// * `Err(sqlx::Error)` - Database error
+    pub async fn get_provider_fallback_config(
+        pool: &SqlitePool,
+    ) -> std::result::Result<Option<ProviderFallbackConfig>, sqlx::Error> {
+        let row: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT providerFallbackConfig FROM settings WHERE id = '1' LIMIT 1")
+                .fetch_optional(pool)
+                .await?;
+
+        match row.and_then(|(json,)| json) {
+            Some(json) => serde_json::from_str(&json).map(Some).map_err(|e| {
+                sqlx::Error::Protocol(format!("Invalid JSON in providerFallbackConfig: {}", e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves the provider fallback configuration as JSON
+    ///
+    /// Only updates an existing settings row; the model config must be saved first.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - Config saved
+    /// * `Ok(false)` - No settings row exists yet
+    pub async fn save_provider_fallback_config(
+        pool: &SqlitePool,
+        config: &ProviderFallbackConfig,
+    ) -> std::result::Result<bool, sqlx::Error> {
+        let config_json = serde_json::to_string(config).map_err(|e| {
+            sqlx::Error::Protocol(format!("Failed to serialize config to JSON: {}", e))
+        })?;
+
+        let result = sqlx::query("UPDATE settings SET providerFallbackConfig = ? WHERE id = '1'")
+            .bind(config_json)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Gets the personal data redaction configuration from JSON
+    ///
+    /// # Returns
+    /// * `Ok(Some(RedactionConfig))` - Config exists and is valid JSON
+    /// * `Ok(None)` - No config stored
+    /// * `Err(sqlx::Error)` - Database error
+    pub async fn get_redaction_config(
+        pool: &SqlitePool,
+    ) -> std::result::Result<Option<RedactionConfig>, sqlx::Error> {
+        let row: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT redactionConfig FROM settings WHERE id = '1' LIMIT 1")
+                .fetch_optional(pool)
+                .await?;
+
+        match row.and_then(|(json,)| json) {
+            Some(json) => serde_json::from_str(&json).map(Some).map_err(|e| {
+                sqlx::Error::Protocol(format!("Invalid JSON in redactionConfig: {}", e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves the personal data redaction configuration as JSON
+    ///
+    /// Only updates an existing settings row; the model config must be saved first.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - Config saved
+    /// * `Ok(false)` - No settings row exists yet
+    pub async fn save_redaction_config(
+        pool: &SqlitePool,
+        config: &RedactionConfig,
+    ) -> std::result::Result<bool, sqlx::Error> {
+        let config_json = serde_json::to_string(config).map_err(|e| {
+            sqlx::Error::Protocol(format!("Failed to serialize config to JSON: {}", e))
+        })?;
+
+        let result = sqlx::query("UPDATE settings SET redactionConfig = ? WHERE id = '1'")
+            .bind(config_json)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Gets the sampling parameters for Gemini and Ollama from JSON
+    ///
+    /// # Returns
+    /// * `Ok(Some(SamplingConfig))` - Config exists and is valid JSON
+    /// * `Ok(None)` - No config stored
+    /// * `Err(sqlx::Error)` - Database error
+    pub async fn get_sampling_config(
+        pool: &SqlitePool,
+    ) -> std::result::Result<Option<SamplingConfig>, sqlx::Error> {
+        let row: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT samplingConfig FROM settings WHERE id = '1' LIMIT 1")
+                .fetch_optional(pool)
+                .await?;
+
+        match row.and_then(|(json,)| json) {
+            Some(json) => serde_json::from_str(&json).map(Some).map_err(|e| {
+                sqlx::Error::Protocol(format!("Invalid JSON in samplingConfig: {}", e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves the sampling parameters for Gemini and Ollama as JSON
+    ///
+    /// Only updates an existing settings row; the model config must be saved first.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - Config saved
+    /// * `Ok(false)` - No settings row exists yet
+    pub async fn save_sampling_config(
+        pool: &SqlitePool,
+        config: &SamplingConfig,
+    ) -> std::result::Result<bool, sqlx::Error> {
+        let config_json = serde_json::to_string(config).map_err(|e| {
+            sqlx::Error::Protocol(format!("Failed to serialize config to JSON: {}", e))
+        })?;
+
+        let result = sqlx::query("UPDATE settings SET samplingConfig = ? WHERE id = '1'")
+            .bind(config_json)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manager::DatabaseManager;
+    use crate::secrets::store::set_secret_store;
+    use crate::secrets::{SecretBackend, SecretStore, SecretStoreStatus};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// The secret store is global, so the tests replacing it run one at a time
+    static SECRET_STORE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// In-memory secret store that can be locked
+    #[derive(Default)]
+    struct MemoryStore {
+        secrets: Mutex<HashMap<String, String>>,
+        locked: bool,
+    }
+
+    impl MemoryStore {
+        fn install(locked: bool) -> Arc<Self> {
+            let store = Arc::new(Self {
+                locked,
+                ..Default::default()
+            });
+            set_secret_store(store.clone());
+            store
+        }
+
+        fn secret(&self, name: &str) -> Option<String> {
+            self.secrets.lock().unwrap().get(name).cloned()
+        }
+    }
+
+    impl SecretStore for MemoryStore {
+        fn status(&self) -> SecretStoreStatus {
+            SecretStoreStatus {
+                backend: SecretBackend::EncryptedFile,
+                unlocked: !self.locked,
+                has_master_password: true,
+            }
+        }
+
+        fn get(&self, name: &str) -> std::result::Result<Option<String>, SecretStoreError> {
+            if self.locked {
+                return Err(SecretStoreError::Locked);
+            }
+            Ok(self.secret(name))
+        }
+
+        fn set(&self, name: &str, value: &str) -> std::result::Result<(), SecretStoreError> {
+            if self.locked {
+                return Err(SecretStoreError::Locked);
+            }
+            self.secrets
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> std::result::Result<(), SecretStoreError> {
+            if self.locked {
+                return Err(SecretStoreError::Locked);
+            }
+            self.secrets.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    /// Settings as saved by versions that kept API keys in the database
+    async fn insert_legacy_keys(pool: &SqlitePool) {
+        sqlx::query(
+            "INSERT INTO settings (id, provider, model, whisperModel, openaiApiKey, customOpenAIConfig) \
+             VALUES ('1', 'openai', 'gpt-4o', 'large-v3', 'sk-legacy-openai', ?)",
+        )
+        .bind(r#"{"endpoint":"http://localhost:8000/v1","apiKey":"sk-legacy-custom","model":"local-model","maxTokens":null,"temperature":null,"topP":null}"#)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO transcript_settings (id, provider, model, deepgramApiKey) \
+             VALUES ('1', 'deepgram', 'nova-2', 'dg-legacy')",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn column(pool: &SqlitePool, table: &str, column: &str) -> Option<String> {
+        sqlx::query_scalar(&format!(
+            "SELECT \"{}\" FROM {} WHERE id = '1'",
+            column, table
+        ))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migration_moves_keys_and_clears_columns() {
+        let _guard = SECRET_STORE_LOCK.lock().await;
+        let store = MemoryStore::install(false);
+        let pool = DatabaseManager::test_pool().await;
+        insert_legacy_keys(&pool).await;
+
+        let moved = SettingsRepository::migrate_api_keys_to_secret_store(&pool)
+            .await
+            .unwrap();
+        assert_eq!(moved, 3);
+        assert_eq!(
+            store.secret("summary.openai").as_deref(),
+            Some("sk-legacy-openai")
+        );
+        assert_eq!(
+            store.secret("transcript.deepgram").as_deref(),
+            Some("dg-legacy")
+        );
+        assert_eq!(
+            store.secret(CUSTOM_OPENAI_SECRET).as_deref(),
+            Some("sk-legacy-custom")
+        );
+        assert_eq!(column(&pool, "settings", "openaiApiKey").await, None);
+        assert_eq!(
+            column(&pool, "transcript_settings", "deepgramApiKey").await,
+            None
+        );
+
+        // The custom endpoint's config stays, without its key
+        let json = column(&pool, "settings", "customOpenAIConfig")
+            .await
+            .unwrap();
+        let config: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(config["apiKey"].is_null(), "{}", json);
+        assert_eq!(config["endpoint"], "http://localhost:8000/v1");
+
+        // A second run has nothing left to move
+        assert_eq!(
+            SettingsRepository::migrate_api_keys_to_secret_store(&pool)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_keeps_keys_while_store_is_locked() {
+        let _guard = SECRET_STORE_LOCK.lock().await;
+        MemoryStore::install(true);
+        let pool = DatabaseManager::test_pool().await;
+        insert_legacy_keys(&pool).await;
+
+        assert!(SettingsRepository::migrate_api_keys_to_secret_store(&pool)
+            .await
+            .is_err());
+        assert_eq!(
+            column(&pool, "settings", "openaiApiKey").await.as_deref(),
+            Some("sk-legacy-openai")
+        );
+        assert_eq!(
+            column(&pool, "transcript_settings", "deepgramApiKey")
+                .await
+                .as_deref(),
+            Some("dg-legacy")
+        );
+        assert!(column(&pool, "settings", "customOpenAIConfig")
+            .await
+            .unwrap()
+            .contains("sk-legacy-custom"));
+    }
+
+    #[tokio::test]
+    async fn test_locked_store_reads_as_no_key_for_display() {
+        let _guard = SECRET_STORE_LOCK.lock().await;
+        MemoryStore::install(true);
+        let pool = DatabaseManager::test_pool().await;
+        SettingsRepository::write_custom_openai_config(
+            &pool,
+            &CustomOpenAIConfig {
+                endpoint: "http://localhost:8000/v1".to_string(),
+                api_key: None,
+                model: "local-model".to_string(),
+                max_tokens: None,
+                temperature: None,
+                top_p: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(SettingsRepository::get_api_key(&pool, "openai")
+            .await
+            .is_err());
+        assert_eq!(
+            SettingsRepository::get_api_key_for_display(&pool, "openai")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            SettingsRepository::get_transcript_api_key_for_display("deepgram")
+                .await
+                .unwrap(),
+            None
+        );
+        let config = SettingsRepository::get_custom_openai_config(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.model, "local-model");
+        assert_eq!(config.api_key, None);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::database::models::SummaryProcess;
+use crate::database::models::{PreviousSessionSummary, SummaryProcess};
 use chrono::Utc;
 use serde_json::Value;
 use sqlx::SqlitePool;
@@ -82,6 +82,58 @@ impl SummaryProcessesRepository {
         .await
     }
 
+    /// Retrieves the stored summaries of the sessions that took place before the given one.
+    ///
+    /// Sessions carry no tutor or language metadata, so candidates are simply the
+    /// `limit` most recent earlier sessions with a summary, most recent first.
+    pub async fn get_previous_session_summaries(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        limit: i64,
+    ) -> Result<Vec<PreviousSessionSummary>, sqlx::Error> {
+        sqlx::query_as::<_, PreviousSessionSummary>(
+            r#"
+            SELECT m.id AS meeting_id, m.title, m.created_at, p.result
+            FROM meetings m
+            JOIN summary_processes p ON p.meeting_id = m.id
+            WHERE m.id != ?1
+              AND p.result IS NOT NULL
+              AND m.created_at < (SELECT created_at FROM meetings WHERE id = ?1)
+            ORDER BY m.created_at DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(meeting_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Makes a stored summary version the current summary of a session.
+    pub async fn set_current_result(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        result: &str,
+        metadata: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let updated = sqlx::query(
+            r#"
+            UPDATE summary_processes
+            SET status = 'completed', result = ?, metadata = ?, updated_at = ?, error = NULL
+            WHERE meeting_id = ?
+            "#,
+        )
+        .bind(result)
+        .bind(metadata)
+        .bind(now)
+        .bind(meeting_id)
+        .execute(pool)
+        .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
     pub async fn create_or_reset_process(
         pool: &SqlitePool,
         meeting_id: &str,
@@ -124,15 +176,18 @@ impl SummaryProcessesRepository {
         result: Value, // Keep this as Value to handle both old and new formats if needed
         chunk_count: i64,
         processing_time: f64,
+        metadata: Value,
     ) -> Result<(), sqlx::Error> {
         let now = Utc::now();
         let result_str = serde_json::to_string(&result)
             .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize result: {}", e)))?;
+        let metadata_str = serde_json::to_string(&metadata)
+            .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize metadata: {}", e)))?;
 
         sqlx::query(
             r#"
             UPDATE summary_processes
-            SET status = 'completed', result = ?, updated_at = ?, end_time = ?, chunk_count = ?, processing_time = ?, error = NULL, result_backup = NULL, result_backup_timestamp = NULL
+            SET status = 'completed', result = ?, updated_at = ?, end_time = ?, chunk_count = ?, processing_time = ?, metadata = ?, error = NULL, result_backup = NULL, result_backup_timestamp = NULL
             WHERE meeting_id = ?
             "#
         )
@@ -141,6 +196,7 @@ impl SummaryProcessesRepository {
         .bind(now)
         .bind(chunk_count)
         .bind(processing_time)
+        .bind(metadata_str)
         .bind(meeting_id)
         .execute(pool)
         .await?;
@@ -218,4 +274,41 @@ impl SummaryProcessesRepository {
         );
         Ok(())
     }
+
+    /// Fails summary processes that are still pending but have no queued or running job
+    ///
+    /// This happens when the app quit while a summary was generated before the job
+    /// queue existed, or when the job was lost. The previous result is restored.
+    ///
+    /// # Returns
+    /// Number of processes marked as failed
+    pub async fn fail_orphaned_processes(
+        pool: &SqlitePool,
+        error: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            UPDATE summary_processes
+            SET
+                status = 'failed',
+                error = ?,
+                updated_at = ?,
+                end_time = ?,
+                result = COALESCE(result_backup, result),
+                result_backup = NULL,
+                result_backup_timestamp = NULL
+            WHERE status IN ('PENDING', 'processing')
+              AND meeting_id NOT IN (
+                  SELECT meeting_id FROM summary_jobs WHERE status IN ('queued', 'running')
+              )
+            "#,
+        )
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
 }
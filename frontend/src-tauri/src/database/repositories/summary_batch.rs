@@ -0,0 +1,99 @@
+use crate::database::models::SummaryBatch;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub struct SummaryBatchesRepository;
+
+impl SummaryBatchesRepository {
+    /// Creates a running batch; `selection` is the JSON of the filters used.
+    pub async fn create_batch(
+        pool: &SqlitePool,
+        provider: &str,
+        model: &str,
+        template_id: &str,
+        selection: &str,
+    ) -> Result<SummaryBatch, sqlx::Error> {
+        let now = Utc::now();
+        let id = format!("batch-{}", Uuid::new_v4());
+
+        sqlx::query_as::<_, SummaryBatch>(
+            r#"
+            INSERT INTO summary_batches
+                (id, status, provider, model, template_id, selection, total, skipped, created_at, updated_at)
+            VALUES (?1, 'running', ?2, ?3, ?4, ?5, 0, 0, ?6, ?6)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(provider)
+        .bind(model)
+        .bind(template_id)
+        .bind(selection)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Records how many sessions were queued and skipped.
+    pub async fn set_counts(
+        pool: &SqlitePool,
+        batch_id: &str,
+        total: i64,
+        skipped: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE summary_batches SET total = ?, skipped = ?, updated_at = ? WHERE id = ?")
+            .bind(total)
+            .bind(skipped)
+            .bind(Utc::now())
+            .bind(batch_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_batch(
+        pool: &SqlitePool,
+        batch_id: &str,
+    ) -> Result<Option<SummaryBatch>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryBatch>("SELECT * FROM summary_batches WHERE id = ?")
+            .bind(batch_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Lists the most recent batches, newest first.
+    pub async fn list_batches(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<SummaryBatch>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryBatch>(
+            "SELECT * FROM summary_batches ORDER BY created_at DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Marks a running batch as `completed` or `cancelled`.
+    ///
+    /// # Returns
+    /// Whether the batch was still running
+    pub async fn finish_batch(
+        pool: &SqlitePool,
+        batch_id: &str,
+        status: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE summary_batches SET status = ?, finished_at = ?, updated_at = ? WHERE id = ? AND status = 'running'",
+        )
+        .bind(status)
+        .bind(now)
+        .bind(now)
+        .bind(batch_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
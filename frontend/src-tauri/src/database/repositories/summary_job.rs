@@ -0,0 +1,249 @@
+use crate::database::models::SummaryJob;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Parameters of a summary generation request
+#[derive(Debug)]
+pub struct NewSummaryJob<'a> {
+    pub meeting_id: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub template_id: &'a str,
+    pub custom_prompt: &'a str,
+    pub previous_session_count: i64,
+    pub max_attempts: i64,
+    /// Batch the job belongs to, if it was queued by a batch regeneration
+    pub batch_id: Option<&'a str>,
+}
+
+pub struct SummaryJobsRepository;
+
+impl SummaryJobsRepository {
+    /// Queues a job, replacing a job for the same session that hasn't started yet.
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        job: NewSummaryJob<'_>,
+    ) -> Result<SummaryJob, sqlx::Error> {
+        let mut transaction = pool.begin().await?;
+        let now = Utc::now();
+        let id = format!("job-{}", Uuid::new_v4());
+
+        sqlx::query("DELETE FROM summary_jobs WHERE meeting_id = ? AND status = 'queued'")
+            .bind(job.meeting_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let created = sqlx::query_as::<_, SummaryJob>(
+            r#"
+            INSERT INTO summary_jobs
+                (id, meeting_id, status, provider, model, template_id, custom_prompt,
+                 previous_session_count, attempts, max_attempts, next_attempt_at, created_at, updated_at,
+                 batch_id)
+            VALUES (?1, ?2, 'queued', ?3, ?4, ?5, ?6, ?7, 0, ?8, ?9, ?9, ?9, ?10)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(job.meeting_id)
+        .bind(job.provider)
+        .bind(job.model)
+        .bind(job.template_id)
+        .bind(job.custom_prompt)
+        .bind(job.previous_session_count)
+        .bind(job.max_attempts.max(1))
+        .bind(now)
+        .bind(job.batch_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(created)
+    }
+
+    /// Gets the queued or running job of a session, if any.
+    pub async fn get_active_job(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Option<SummaryJob>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryJob>(
+            "SELECT * FROM summary_jobs WHERE meeting_id = ? AND status IN ('queued', 'running') ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(meeting_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Lists running jobs followed by queued jobs in the order they will run.
+    pub async fn list_active_jobs(pool: &SqlitePool) -> Result<Vec<SummaryJob>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryJob>(
+            r#"
+            SELECT * FROM summary_jobs
+            WHERE status IN ('queued', 'running')
+            ORDER BY CASE status WHEN 'running' THEN 0 ELSE 1 END, created_at
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Marks the oldest job that is due as running and counts the attempt.
+    pub async fn claim_next_job(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SummaryJob>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryJob>(
+            r#"
+            UPDATE summary_jobs
+            SET status = 'running', attempts = attempts + 1, started_at = ?1, updated_at = ?1
+            WHERE id = (
+                SELECT id FROM summary_jobs
+                WHERE status = 'queued' AND next_attempt_at <= ?1
+                ORDER BY created_at
+                LIMIT 1
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Marks a job as `done`, `failed` or `cancelled`.
+    pub async fn finish_job(
+        pool: &SqlitePool,
+        job_id: &str,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE summary_jobs SET status = ?, last_error = COALESCE(?, last_error), finished_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(status)
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(job_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Puts a failed job back in the queue, to run again at `next_attempt_at`.
+    pub async fn schedule_retry(
+        pool: &SqlitePool,
+        job_id: &str,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE summary_jobs SET status = 'queued', last_error = ?, next_attempt_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(Utc::now())
+        .bind(job_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Cancels the jobs of a session that haven't started yet.
+    ///
+    /// # Returns
+    /// Number of cancelled jobs
+    pub async fn cancel_queued_jobs(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE summary_jobs SET status = 'cancelled', finished_at = ?, updated_at = ? WHERE meeting_id = ? AND status = 'queued'",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(meeting_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Cancels the jobs of a batch that haven't started yet.
+    ///
+    /// # Returns
+    /// Sessions of the cancelled jobs
+    pub async fn cancel_queued_batch_jobs(
+        pool: &SqlitePool,
+        batch_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query_scalar(
+            "UPDATE summary_jobs SET status = 'cancelled', finished_at = ?, updated_at = ? WHERE batch_id = ? AND status = 'queued' RETURNING meeting_id",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Puts jobs that were running when the app quit back in the queue.
+    ///
+    /// The interrupted attempt is not counted against the job's retry limit.
+    pub async fn requeue_interrupted_jobs(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            UPDATE summary_jobs
+            SET status = 'queued', attempts = MAX(attempts - 1, 0), next_attempt_at = ?1, updated_at = ?1
+            WHERE status = 'running'
+            "#,
+        )
+        .bind(now)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes finished jobs last updated before `before`.
+    pub async fn delete_finished_jobs(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM summary_jobs WHERE status IN ('done', 'failed', 'cancelled') AND updated_at < ?",
+        )
+        .bind(before)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Counts the jobs of a batch by status.
+    pub async fn count_batch_jobs(
+        pool: &SqlitePool,
+        batch_id: &str,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT status, COUNT(*) FROM summary_jobs WHERE batch_id = ? GROUP BY status",
+        )
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Lists the jobs of a batch that are running.
+    pub async fn list_running_batch_jobs(
+        pool: &SqlitePool,
+        batch_id: &str,
+    ) -> Result<Vec<SummaryJob>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryJob>(
+            "SELECT * FROM summary_jobs WHERE batch_id = ? AND status = 'running'",
+        )
+        .bind(batch_id)
+        .fetch_all(pool)
+        .await
+    }
+}
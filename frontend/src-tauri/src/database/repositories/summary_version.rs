@@ -0,0 +1,143 @@
+use crate::database::models::SummaryVersion;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Details of a summary version to record
+#[derive(Debug, Default)]
+pub struct NewSummaryVersion<'a> {
+    pub source: &'a str,
+    pub restored_from: Option<i64>,
+    pub provider: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub template_id: Option<&'a str>,
+    pub template_version: Option<i64>,
+    pub prompt_hash: Option<&'a str>,
+    pub result: &'a str,
+    pub metadata: Option<&'a str>,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+pub struct SummaryVersionsRepository;
+
+impl SummaryVersionsRepository {
+    /// Records a new version with the next version number, then deletes the oldest
+    /// versions so that at most `keep` remain for the session.
+    pub async fn add_version(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        version: NewSummaryVersion<'_>,
+        keep: usize,
+    ) -> Result<SummaryVersion, sqlx::Error> {
+        let mut transaction = pool.begin().await?;
+        let now = Utc::now();
+        let id = format!("version-{}", Uuid::new_v4());
+
+        let (version_number,): (i64,) = sqlx::query_as(
+            "SELECT COALESCE(MAX(version_number), 0) + 1 FROM summary_versions WHERE meeting_id = ?",
+        )
+        .bind(meeting_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO summary_versions
+                (id, meeting_id, version_number, source, restored_from, provider, model, template_id,
+                 template_version, prompt_hash, result, metadata, started_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(meeting_id)
+        .bind(version_number)
+        .bind(version.source)
+        .bind(version.restored_from)
+        .bind(version.provider)
+        .bind(version.model)
+        .bind(version.template_id)
+        .bind(version.template_version)
+        .bind(version.prompt_hash)
+        .bind(version.result)
+        .bind(version.metadata)
+        .bind(version.started_at)
+        .bind(now)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM summary_versions
+            WHERE meeting_id = ?1
+              AND version_number NOT IN (
+                  SELECT version_number FROM summary_versions
+                  WHERE meeting_id = ?1
+                  ORDER BY version_number DESC
+                  LIMIT ?2
+              )
+            "#,
+        )
+        .bind(meeting_id)
+        .bind(keep.max(1) as i64)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(SummaryVersion {
+            id,
+            meeting_id: meeting_id.to_string(),
+            version_number,
+            source: version.source.to_string(),
+            restored_from: version.restored_from,
+            provider: version.provider.map(|s| s.to_string()),
+            model: version.model.map(|s| s.to_string()),
+            template_id: version.template_id.map(|s| s.to_string()),
+            template_version: version.template_version,
+            prompt_hash: version.prompt_hash.map(|s| s.to_string()),
+            result: version.result.to_string(),
+            metadata: version.metadata.map(|s| s.to_string()),
+            started_at: version.started_at,
+            created_at: now,
+        })
+    }
+
+    /// Lists all versions of a session's summary, newest first.
+    pub async fn list_versions(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<SummaryVersion>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryVersion>(
+            "SELECT * FROM summary_versions WHERE meeting_id = ? ORDER BY version_number DESC",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn get_version(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        version_number: i64,
+    ) -> Result<Option<SummaryVersion>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryVersion>(
+            "SELECT * FROM summary_versions WHERE meeting_id = ? AND version_number = ?",
+        )
+        .bind(meeting_id)
+        .bind(version_number)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn get_latest_version(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Option<SummaryVersion>, sqlx::Error> {
+        sqlx::query_as::<_, SummaryVersion>(
+            "SELECT * FROM summary_versions WHERE meeting_id = ? ORDER BY version_number DESC LIMIT 1",
+        )
+        .bind(meeting_id)
+        .fetch_optional(pool)
+        .await
+    }
+}
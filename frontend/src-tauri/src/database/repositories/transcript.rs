@@ -1,4 +1,5 @@
 use crate::api::{TranscriptSearchResult, TranscriptSegment};
+use crate::database::models::Transcript;
 use chrono::Utc;
 use sqlx::{Connection, Error as SqlxError, SqlitePool};
 use tracing::{error, info};
@@ -120,6 +121,78 @@ impl TranscriptsRepository {
         Ok(results)
     }
 
+    /// Returns a session's transcript as plain text, one segment per line, in recording order
+    pub async fn get_full_transcript_text(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<String, SqlxError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT transcript FROM transcripts WHERE meeting_id = ? ORDER BY COALESCE(audio_start_time, 0), timestamp",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(text,)| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// Returns all of a session's transcript segments, in the order of
+    /// `get_full_transcript_text`
+    pub async fn get_ordered_transcripts(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<Transcript>, SqlxError> {
+        sqlx::query_as::<_, Transcript>(
+            "SELECT * FROM transcripts
+             WHERE meeting_id = ?
+             ORDER BY COALESCE(audio_start_time, 0), timestamp",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns a session's transcript segments that have recording timestamps,
+    /// in recording order
+    pub async fn get_timed_transcripts(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<Vec<Transcript>, SqlxError> {
+        sqlx::query_as::<_, Transcript>(
+            "SELECT * FROM transcripts
+             WHERE meeting_id = ? AND audio_start_time IS NOT NULL
+             ORDER BY audio_start_time ASC",
+        )
+        .bind(meeting_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Counts a session's transcript segments per audio source
+    ///
+    /// # Returns
+    /// Tuple of (mic_segments, system_segments). Segments without a recorded
+    /// source are not counted.
+    pub async fn count_segments_by_speaker(
+        pool: &SqlitePool,
+        meeting_id: &str,
+    ) -> Result<(i64, i64), SqlxError> {
+        sqlx::query_as::<_, (i64, i64)>(
+            "SELECT
+                COALESCE(SUM(CASE WHEN speaker = 'mic' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN speaker = 'system' THEN 1 ELSE 0 END), 0)
+             FROM transcripts WHERE meeting_id = ?",
+        )
+        .bind(meeting_id)
+        .fetch_one(pool)
+        .await
+    }
+
     /// Helper function to extract a snippet of text around the first match of a query.
     fn get_match_context(transcript: &str, query: &str) -> String {
         let transcript_lower = transcript.to_lowercase();
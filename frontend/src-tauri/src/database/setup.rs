@@ -1,12 +1,21 @@
-use log::info;
+use log::{info, warn};
 use tauri::{AppHandle, Emitter, Manager};
 
+use super::maintenance;
 use super::manager::DatabaseManager;
+use super::repositories::setting::SettingsRepository;
+use crate::encryption::vault::vault;
 use crate::state::AppState;
 
 /// Initialize database on app startup
 /// Handles first launch detection and conditional initialization
 pub async fn initialize_database_on_startup(app: &AppHandle) -> Result<(), String> {
+    // An encrypted database is opened once the user unlocks encryption
+    if vault().is_enabled() && !vault().is_unlocked() {
+        info!("Encryption is locked - database initialization waits for unlock");
+        return Ok(());
+    }
+
     // Check if this is the first launch (no database exists yet)
     let is_first_launch = DatabaseManager::is_first_launch(app)
         .await
@@ -30,8 +39,18 @@ pub async fn initialize_database_on_startup(app: &AppHandle) -> Result<(), Strin
             .await
             .map_err(|e| format!("Failed to initialize database manager: {}", e))?;
 
+        // Keys saved in plaintext columns by earlier versions move to the secret store
+        match SettingsRepository::migrate_api_keys_to_secret_store(db_manager.pool()).await {
+            Ok(0) => {}
+            Ok(moved) => info!("Moved {} API key(s) to the secret store", moved),
+            Err(e) => warn!("API keys stay in the database until the secret store is unlocked: {}", e),
+        }
+
+        let pool = db_manager.pool().clone();
         app.manage(AppState { db_manager });
         info!("Database initialized successfully");
+
+        maintenance::run_startup_integrity_check(app, pool);
     }
 
     Ok(())
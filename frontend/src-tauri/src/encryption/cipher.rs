@@ -0,0 +1,107 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+/// Start of all encrypted data, authenticated along with it
+pub const MAGIC: &[u8; 8] = b"UCLENC01";
+
+pub const KEY_LEN: usize = 32;
+
+const NONCE_LEN: usize = 24;
+
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CipherError {
+    #[error("Data is not encrypted")]
+    NotEncrypted,
+    #[error("Encrypted data is truncated")]
+    Truncated,
+    #[error("Decryption failed: wrong key or damaged data")]
+    Decryption,
+    #[error("Encryption failed")]
+    Encryption,
+}
+
+/// Whether the data was produced by `seal`
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypts data with XChaCha20-Poly1305 under a random nonce
+///
+/// The result is `MAGIC`, the nonce and the ciphertext with its tag.
+pub fn seal(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, CipherError> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: MAGIC,
+            },
+        )
+        .map_err(|_| CipherError::Encryption)?;
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts data produced by `seal`
+pub fn open(key: &[u8; KEY_LEN], sealed: &[u8]) -> Result<Vec<u8>, CipherError> {
+    let body = sealed
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or(CipherError::NotEncrypted)?;
+    if body.len() < NONCE_LEN + TAG_LEN {
+        return Err(CipherError::Truncated);
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: MAGIC,
+            },
+        )
+        .map_err(|_| CipherError::Decryption)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = [7u8; KEY_LEN];
+        let sealed = seal(&key, b"Student: I goed to school").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed
+            .windows(b"goed".len())
+            .any(|window| window == b"goed"));
+        assert_eq!(open(&key, &sealed).unwrap(), b"Student: I goed to school");
+
+        // Nonces are random, so the same data never encrypts the same way
+        assert_ne!(seal(&key, b"same").unwrap(), seal(&key, b"same").unwrap());
+        assert_eq!(open(&key, &seal(&key, b"").unwrap()).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_key_and_damaged_data() {
+        let key = [7u8; KEY_LEN];
+        let mut sealed = seal(&key, b"lesson audio").unwrap();
+        assert_eq!(open(&[8u8; KEY_LEN], &sealed), Err(CipherError::Decryption));
+        assert_eq!(open(&key, b"plain text"), Err(CipherError::NotEncrypted));
+        assert_eq!(
+            open(&key, &sealed[..MAGIC.len() + 10]),
+            Err(CipherError::Truncated)
+        );
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert_eq!(open(&key, &sealed), Err(CipherError::Decryption));
+    }
+}
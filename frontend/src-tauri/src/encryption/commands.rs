@@ -0,0 +1,121 @@
+use crate::audio::recording_preferences::{
+    get_default_recordings_folder, load_recording_preferences,
+};
+use crate::database::repositories::meeting::MeetingsRepository;
+use crate::encryption::files::{convert_session_folders, remove_plain_copies};
+use crate::encryption::vault::{vault, EncryptionStatus};
+use crate::state::AppState;
+use log::{error as log_error, info as log_info};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+pub async fn api_get_encryption_status() -> Result<EncryptionStatus, String> {
+    Ok(vault().status())
+}
+
+/// Turns encryption on
+///
+/// New session files are encrypted right away; the database and existing
+/// sessions are encrypted at the next launch, after unlocking.
+#[tauri::command]
+pub async fn api_enable_encryption(passphrase: String) -> Result<(), String> {
+    log_info!("api_enable_encryption called");
+    vault().enable(&passphrase)
+}
+
+/// Unlocks encryption, then opens the database and finishes any pending
+/// conversion of session files
+#[tauri::command]
+pub async fn api_unlock_encryption(app: AppHandle, passphrase: String) -> Result<(), String> {
+    log_info!("api_unlock_encryption called");
+    vault().unlock(&passphrase)?;
+
+    if app.try_state::<AppState>().is_none() {
+        crate::database::setup::initialize_database_on_startup(&app).await?;
+    }
+
+    let folders = session_folders(&app).await;
+    let decrypt = vault().is_disable_pending();
+    tauri::async_runtime::spawn_blocking(move || convert_session_folders(&folders, !decrypt))
+        .await
+        .map_err(|e| format!("Failed to convert session files: {}", e))?;
+
+    if decrypt {
+        vault().finish_disable()?;
+        log_info!("Encryption turned off");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn api_change_encryption_passphrase(
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    log_info!("api_change_encryption_passphrase called");
+    vault().change_passphrase(&current_passphrase, &new_passphrase)
+}
+
+/// Turns encryption off
+///
+/// The database and sessions are decrypted at the next launch, after
+/// unlocking one last time.
+#[tauri::command]
+pub async fn api_disable_encryption(passphrase: String) -> Result<(), String> {
+    log_info!("api_disable_encryption called");
+    vault().request_disable(&passphrase)
+}
+
+/// Deletes the decrypted checkpoint copies that earlier versions left in
+/// session folders when the app crashed while merging a recording
+pub async fn remove_leftover_plain_copies(app: &AppHandle) {
+    let folders = session_folders(app).await;
+    let removed = tauri::async_runtime::spawn_blocking(move || {
+        folders
+            .iter()
+            .map(|folder| remove_plain_copies(&folder.join(".checkpoints")))
+            .sum::<usize>()
+    })
+    .await;
+    match removed {
+        Ok(0) => {}
+        Ok(removed) => log_info!("Removed {} leftover decrypted checkpoint(s)", removed),
+        Err(e) => log_error!("Failed to remove leftover decrypted checkpoints: {}", e),
+    }
+}
+
+/// Folders that can hold session files: the recordings folders and the
+/// folders of sessions in the database
+async fn session_folders(app: &AppHandle) -> Vec<PathBuf> {
+    let mut roots = vec![get_default_recordings_folder()];
+    match load_recording_preferences(app).await {
+        Ok(preferences) => roots.push(preferences.save_folder),
+        Err(e) => log_error!("Failed to load recording preferences: {}", e),
+    }
+
+    let mut folders: BTreeSet<PathBuf> = roots
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+
+    if let Some(state) = app.try_state::<AppState>() {
+        match MeetingsRepository::get_meetings(state.db_manager.pool()).await {
+            Ok(meetings) => folders.extend(
+                meetings
+                    .into_iter()
+                    .filter_map(|meeting| meeting.folder_path)
+                    .map(PathBuf::from)
+                    .filter(|path| path.is_dir()),
+            ),
+            Err(e) => log_error!("Failed to list sessions: {}", e),
+        }
+    }
+    folders.into_iter().collect()
+}
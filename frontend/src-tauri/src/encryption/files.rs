@@ -0,0 +1,208 @@
+use crate::encryption::cipher;
+use crate::encryption::vault::vault;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Extensions of the session files that are encrypted (audio, checkpoints,
+/// transcripts and metadata)
+const SESSION_FILE_EXTENSIONS: &[&str] = &["mp4", "m4a", "wav", "json", "txt"];
+
+fn locked() -> io::Error {
+    io::Error::other("Encryption is locked")
+}
+
+/// `path` with `suffix` appended to its file name
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Writes a file, encrypted when encryption is on
+///
+/// # Errors
+/// Besides I/O errors, when encryption is on but locked
+pub fn write(path: &Path, data: &[u8]) -> io::Result<()> {
+    match vault().sealing_keys().map_err(io::Error::other)? {
+        Some(keys) => fs::write(
+            path,
+            cipher::seal(keys.file_key(), data).map_err(io::Error::other)?,
+        ),
+        None => fs::write(path, data),
+    }
+}
+
+/// Reads a file, decrypting it if it is encrypted
+///
+/// # Errors
+/// Besides I/O errors, when the file is encrypted and encryption is locked
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    if !cipher::is_encrypted(&data) {
+        return Ok(data);
+    }
+    let keys = vault().keys().ok_or_else(locked)?;
+    cipher::open(keys.file_key(), &data).map_err(io::Error::other)
+}
+
+/// Whether a file starts with the header of encrypted data
+pub fn is_encrypted_file(path: &Path) -> io::Result<bool> {
+    let mut header = [0u8; cipher::MAGIC.len()];
+    match File::open(path)?.read_exact(&mut header) {
+        Ok(()) => Ok(cipher::is_encrypted(&header)),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Encrypts a file written in plaintext (e.g. by FFmpeg) when encryption is on
+///
+/// Returns whether the file was encrypted.
+pub fn seal_in_place(path: &Path) -> io::Result<bool> {
+    let Some(keys) = vault().sealing_keys().map_err(io::Error::other)? else {
+        return Ok(false);
+    };
+    let data = fs::read(path)?;
+    if cipher::is_encrypted(&data) {
+        return Ok(false);
+    }
+    replace(
+        path,
+        &cipher::seal(keys.file_key(), &data).map_err(io::Error::other)?,
+    )?;
+    Ok(true)
+}
+
+/// Decrypts an encrypted file in place
+///
+/// Returns whether the file was decrypted.
+pub fn open_in_place(path: &Path) -> io::Result<bool> {
+    let data = fs::read(path)?;
+    if !cipher::is_encrypted(&data) {
+        return Ok(false);
+    }
+    let keys = vault().keys().ok_or_else(locked)?;
+    replace(
+        path,
+        &cipher::open(keys.file_key(), &data).map_err(io::Error::other)?,
+    )?;
+    Ok(true)
+}
+
+/// Deletes the `.plain` copies of checkpoints that earlier versions decrypted
+/// to disk for FFmpeg and left behind when the app crashed
+///
+/// Returns the number of deleted files.
+pub fn remove_plain_copies(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("plain") {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => warn!("Failed to remove decrypted copy {}: {}", path.display(), e),
+        }
+    }
+    if removed > 0 {
+        info!(
+            "Removed {} leftover decrypted file(s) from {}",
+            removed,
+            dir.display()
+        );
+    }
+    removed
+}
+
+/// Replaces a file through a temp file, so a crash never leaves it half-written
+fn replace(path: &Path, data: &[u8]) -> io::Result<()> {
+    let temp_path = with_suffix(path, ".tmp");
+    fs::write(&temp_path, data)?;
+    fs::rename(&temp_path, path)
+}
+
+/// Session files in a session folder and its checkpoints
+fn session_files(folder: &Path) -> Vec<PathBuf> {
+    [folder.to_path_buf(), folder.join(".checkpoints")]
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        SESSION_FILE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+        })
+        .collect()
+}
+
+/// Encrypts (or decrypts) the files of session folders
+///
+/// Files already in the wanted state are skipped, so an interrupted run can
+/// simply be repeated. Returns the number of files converted.
+pub fn convert_session_folders(folders: &[PathBuf], encrypt: bool) -> usize {
+    let mut converted = 0;
+    for folder in folders {
+        for path in session_files(folder) {
+            let result = if encrypt {
+                seal_in_place(&path)
+            } else {
+                open_in_place(&path)
+            };
+            match result {
+                Ok(true) => converted += 1,
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "Failed to {} {}: {}",
+                    if encrypt { "encrypt" } else { "decrypt" },
+                    path.display(),
+                    e
+                ),
+            }
+        }
+    }
+    info!(
+        "{} {} session file(s) in {} folder(s)",
+        if encrypt { "Encrypted" } else { "Decrypted" },
+        converted,
+        folders.len()
+    );
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_plain_copies_keeps_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("audio_chunk_000.mp4"), b"sealed").unwrap();
+        fs::write(dir.path().join("audio_chunk_000.mp4.plain"), b"audio").unwrap();
+        fs::write(dir.path().join("audio_chunk_001.mp4.plain"), b"audio").unwrap();
+
+        assert_eq!(remove_plain_copies(dir.path()), 2);
+        let remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(remaining, vec![OsString::from("audio_chunk_000.mp4")]);
+
+        // A missing checkpoints folder is nothing to clean up
+        assert_eq!(remove_plain_copies(&dir.path().join(".checkpoints")), 0);
+    }
+}
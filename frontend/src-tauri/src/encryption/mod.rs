@@ -0,0 +1,18 @@
+/// Encryption module - passphrase encryption at rest
+///
+/// This module contains:
+/// - XChaCha20-Poly1305 encryption of session files (audio, checkpoints,
+///   transcripts and metadata)
+/// - The vault holding the data keys, wrapped with an Argon2id key derived
+///   from the user's passphrase
+/// - Reading and writing session files through the vault
+/// - Tauri commands to enable, unlock and disable encryption and change the
+///   passphrase
+///
+/// The database is encrypted with SQLCipher under a key from the vault, see
+/// `database::manager`.
+
+pub mod cipher;
+pub mod commands;
+pub mod files;
+pub mod vault;
@@ -0,0 +1,515 @@
+use crate::encryption::cipher::{self, KEY_LEN};
+use argon2::{Algorithm, Argon2, Params, Version};
+use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+use zeroize::Zeroize;
+
+/// Encryption settings; encryption is on while this file exists
+const CONFIG_FILE: &str = "encryption.json";
+
+const CONFIG_VERSION: u32 = 1;
+
+const SALT_LEN: usize = 16;
+
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+static VAULT: Lazy<Vault> = Lazy::new(Vault::default);
+
+/// Argon2id cost, stored with the salt so it can be raised later
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptionConfig {
+    version: u32,
+    kdf: KdfParams,
+    /// Argon2 salt (hex)
+    salt: String,
+    /// Data keys sealed with the passphrase key (hex); a wrong passphrase fails to open them
+    wrapped_keys: String,
+    /// Set when the user turned encryption off; the data is decrypted at the next unlock
+    #[serde(default)]
+    disable_pending: bool,
+}
+
+/// Keys of the encrypted data, wiped from memory when dropped
+///
+/// The keys are random and wrapped with a key derived from the passphrase,
+/// so changing the passphrase doesn't re-encrypt any data.
+pub struct Keys {
+    file: [u8; KEY_LEN],
+    database: [u8; KEY_LEN],
+}
+
+impl Keys {
+    fn generate() -> Self {
+        let mut keys = Self {
+            file: [0; KEY_LEN],
+            database: [0; KEY_LEN],
+        };
+        OsRng.fill_bytes(&mut keys.file);
+        OsRng.fill_bytes(&mut keys.database);
+        keys
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 2 * KEY_LEN {
+            return None;
+        }
+        let mut keys = Self {
+            file: [0; KEY_LEN],
+            database: [0; KEY_LEN],
+        };
+        keys.file.copy_from_slice(&bytes[..KEY_LEN]);
+        keys.database.copy_from_slice(&bytes[KEY_LEN..]);
+        Some(keys)
+    }
+
+    /// Key of the session files
+    pub fn file_key(&self) -> &[u8; KEY_LEN] {
+        &self.file
+    }
+
+    /// SQLCipher raw key as an SQL string literal, for `PRAGMA key` and `ATTACH … KEY`
+    pub fn database_key_sql(&self) -> String {
+        format!("'x''{}'''", to_hex(&self.database))
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        self.file.zeroize();
+        self.database.zeroize();
+    }
+}
+
+/// Encryption state reported to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub disable_pending: bool,
+}
+
+#[derive(Default)]
+struct VaultState {
+    dir: Option<PathBuf>,
+    /// Loaded settings, or why they couldn't be loaded
+    config: Option<Result<EncryptionConfig, String>>,
+    keys: Option<Arc<Keys>>,
+}
+
+/// Holds the encryption settings and, once unlocked, the data keys
+#[derive(Default)]
+pub struct Vault {
+    state: Mutex<VaultState>,
+}
+
+impl Vault {
+    /// Loads the settings from `dir`, locked
+    ///
+    /// Settings that exist but can't be read keep encryption on, so nothing
+    /// is written in plaintext until they are fixed.
+    pub fn init(&self, dir: &Path) {
+        let config = match fs::read_to_string(dir.join(CONFIG_FILE)) {
+            Ok(json) => Some(serde_json::from_str(&json).map_err(|e| {
+                error!("Invalid encryption settings: {}", e);
+                format!("Encryption settings are damaged: {}", e)
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                error!("Failed to read encryption settings: {}", e);
+                Some(Err(format!("Failed to read encryption settings: {}", e)))
+            }
+        };
+        let enabled = config.is_some();
+        *self.state.lock().unwrap() = VaultState {
+            dir: Some(dir.to_path_buf()),
+            config,
+            keys: None,
+        };
+        info!("Encryption initialized (enabled: {})", enabled);
+    }
+
+    pub fn status(&self) -> EncryptionStatus {
+        let state = self.state.lock().unwrap();
+        EncryptionStatus {
+            enabled: state.config.is_some(),
+            unlocked: state.keys.is_some(),
+            disable_pending: matches!(&state.config, Some(Ok(config)) if config.disable_pending),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.status().enabled
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.status().unlocked
+    }
+
+    pub fn is_disable_pending(&self) -> bool {
+        self.status().disable_pending
+    }
+
+    /// Keys for decrypting data, if unlocked
+    pub fn keys(&self) -> Option<Arc<Keys>> {
+        self.state.lock().unwrap().keys.clone()
+    }
+
+    /// Keys new data is encrypted with
+    ///
+    /// `None` when encryption is off or being turned off.
+    ///
+    /// # Errors
+    /// When encryption is on but locked, or its settings are damaged
+    pub fn sealing_keys(&self) -> Result<Option<Arc<Keys>>, String> {
+        let state = self.state.lock().unwrap();
+        match &state.config {
+            None => Ok(None),
+            Some(Err(e)) => Err(e.clone()),
+            Some(Ok(config)) if config.disable_pending => Ok(None),
+            Some(Ok(_)) => state
+                .keys
+                .clone()
+                .map(Some)
+                .ok_or_else(|| "Encryption is locked".to_string()),
+        }
+    }
+
+    /// Unlocks the data keys with the passphrase
+    pub fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        let config = self
+            .config()?
+            .ok_or_else(|| "Encryption is not enabled".to_string())?;
+        let keys = unwrap_keys(&config, passphrase)?;
+        self.state.lock().unwrap().keys = Some(Arc::new(keys));
+        info!("Encryption unlocked");
+        Ok(())
+    }
+
+    /// Turns encryption on with new data keys, unlocked
+    ///
+    /// New session files are encrypted right away; the database and existing
+    /// sessions are encrypted at the next unlock. Turning encryption back on
+    /// while it is being turned off just cancels that, with the old passphrase.
+    pub fn enable(&self, passphrase: &str) -> Result<(), String> {
+        self.enable_with(passphrase, KdfParams::default())
+    }
+
+    fn enable_with(&self, passphrase: &str, kdf: KdfParams) -> Result<(), String> {
+        match self.config()? {
+            Some(mut config) if config.disable_pending => {
+                let keys = unwrap_keys(&config, passphrase)?;
+                config.disable_pending = false;
+                self.save(config)?;
+                self.state.lock().unwrap().keys = Some(Arc::new(keys));
+                info!("Cancelled turning encryption off");
+                return Ok(());
+            }
+            Some(_) => return Err("Encryption is already enabled".to_string()),
+            None => {}
+        }
+        validate_passphrase(passphrase)?;
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let keys = Keys::generate();
+        let config = EncryptionConfig {
+            version: CONFIG_VERSION,
+            kdf,
+            salt: to_hex(&salt),
+            wrapped_keys: wrap_keys(&keys, passphrase, &kdf, &salt)?,
+            disable_pending: false,
+        };
+        self.save(config)?;
+        self.state.lock().unwrap().keys = Some(Arc::new(keys));
+        info!("Encryption enabled");
+        Ok(())
+    }
+
+    /// Re-wraps the data keys with a new passphrase
+    pub fn change_passphrase(&self, current: &str, new: &str) -> Result<(), String> {
+        validate_passphrase(new)?;
+        let mut config = self
+            .config()?
+            .ok_or_else(|| "Encryption is not enabled".to_string())?;
+        let keys = unwrap_keys(&config, current)?;
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        config.salt = to_hex(&salt);
+        config.wrapped_keys = wrap_keys(&keys, new, &config.kdf, &salt)?;
+        self.save(config)?;
+        self.state.lock().unwrap().keys = Some(Arc::new(keys));
+        info!("Encryption passphrase changed");
+        Ok(())
+    }
+
+    /// Marks encryption to be turned off at the next unlock
+    ///
+    /// The database can only be decrypted before it is opened, so the data is
+    /// decrypted at the next launch; until then new files are written in plaintext.
+    pub fn request_disable(&self, passphrase: &str) -> Result<(), String> {
+        let mut config = self
+            .config()?
+            .ok_or_else(|| "Encryption is not enabled".to_string())?;
+        let keys = unwrap_keys(&config, passphrase)?;
+        config.disable_pending = true;
+        self.save(config)?;
+        self.state.lock().unwrap().keys = Some(Arc::new(keys));
+        info!("Encryption will be turned off at the next launch");
+        Ok(())
+    }
+
+    /// Removes the settings and keys once all data is decrypted
+    pub fn finish_disable(&self) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        let dir = state
+            .dir
+            .clone()
+            .ok_or_else(|| "Encryption is not initialized".to_string())?;
+        match fs::remove_file(dir.join(CONFIG_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Failed to remove encryption settings: {}", e));
+            }
+            _ => {}
+        }
+        state.config = None;
+        state.keys = None;
+        info!("Encryption disabled");
+        Ok(())
+    }
+
+    fn config(&self) -> Result<Option<EncryptionConfig>, String> {
+        match &self.state.lock().unwrap().config {
+            None => Ok(None),
+            Some(Ok(config)) => Ok(Some(config.clone())),
+            Some(Err(e)) => Err(e.clone()),
+        }
+    }
+
+    fn save(&self, config: EncryptionConfig) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        let dir = state
+            .dir
+            .clone()
+            .ok_or_else(|| "Encryption is not initialized".to_string())?;
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize encryption settings: {}", e))?;
+        let temp_path = dir.join(format!(".{}.tmp", CONFIG_FILE));
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&temp_path, json))
+            .and_then(|_| fs::rename(&temp_path, dir.join(CONFIG_FILE)))
+            .map_err(|e| format!("Failed to save encryption settings: {}", e))?;
+        state.config = Some(Ok(config));
+        Ok(())
+    }
+}
+
+/// The vault holding the keys of all encrypted data
+pub fn vault() -> &'static Vault {
+    &VAULT
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_CHARS
+        ));
+    }
+    Ok(())
+}
+
+/// Derives the key that wraps the data keys with Argon2id
+pub(crate) fn derive_key(
+    passphrase: &str,
+    kdf: &KdfParams,
+    salt: &[u8],
+) -> Result<[u8; KEY_LEN], String> {
+    let params = Params::new(
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn wrap_keys(
+    keys: &Keys,
+    passphrase: &str,
+    kdf: &KdfParams,
+    salt: &[u8],
+) -> Result<String, String> {
+    let mut wrapping_key = derive_key(passphrase, kdf, salt)?;
+    let mut bytes = [keys.file, keys.database].concat();
+    let sealed = cipher::seal(&wrapping_key, &bytes);
+    wrapping_key.zeroize();
+    bytes.zeroize();
+    sealed
+        .map(|sealed| to_hex(&sealed))
+        .map_err(|e| format!("Failed to wrap encryption keys: {}", e))
+}
+
+fn unwrap_keys(config: &EncryptionConfig, passphrase: &str) -> Result<Keys, String> {
+    let damaged = || "Encryption settings are damaged".to_string();
+    let salt = from_hex(&config.salt).ok_or_else(damaged)?;
+    let wrapped = from_hex(&config.wrapped_keys).ok_or_else(damaged)?;
+
+    let mut wrapping_key = derive_key(passphrase, &config.kdf, &salt)?;
+    let opened = cipher::open(&wrapping_key, &wrapped);
+    wrapping_key.zeroize();
+    let mut bytes = opened.map_err(|_| "Wrong passphrase".to_string())?;
+    let keys = Keys::from_bytes(&bytes).ok_or_else(damaged);
+    bytes.zeroize();
+    keys
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap Argon2 cost, so the tests stay fast
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn vault_in(dir: &Path) -> Vault {
+        let vault = Vault::default();
+        vault.init(dir);
+        vault
+    }
+
+    #[test]
+    fn test_enable_and_unlock() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault_in(dir.path());
+        assert!(vault.sealing_keys().unwrap().is_none());
+        assert!(vault.enable_with("short", TEST_KDF).is_err());
+
+        vault.enable_with("correct horse", TEST_KDF).unwrap();
+        let sealed = cipher::seal(vault.keys().unwrap().file_key(), b"transcript").unwrap();
+
+        // Locked after a restart until the passphrase is given
+        let restarted = vault_in(dir.path());
+        assert_eq!(
+            restarted.status(),
+            EncryptionStatus {
+                enabled: true,
+                unlocked: false,
+                disable_pending: false
+            }
+        );
+        assert_eq!(
+            restarted.sealing_keys().err().as_deref(),
+            Some("Encryption is locked")
+        );
+        assert_eq!(
+            restarted.unlock("wrong horse").unwrap_err(),
+            "Wrong passphrase"
+        );
+        restarted.unlock("correct horse").unwrap();
+        let keys = restarted.sealing_keys().unwrap().unwrap();
+        assert_eq!(
+            cipher::open(keys.file_key(), &sealed).unwrap(),
+            b"transcript"
+        );
+        assert!(keys.database_key_sql().starts_with("'x''"));
+    }
+
+    #[test]
+    fn test_change_passphrase_keeps_data_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault_in(dir.path());
+        vault.enable_with("first passphrase", TEST_KDF).unwrap();
+        let sealed = cipher::seal(vault.keys().unwrap().file_key(), b"audio").unwrap();
+
+        assert!(vault
+            .change_passphrase("wrong passphrase", "second passphrase")
+            .is_err());
+        vault
+            .change_passphrase("first passphrase", "second passphrase")
+            .unwrap();
+
+        let restarted = vault_in(dir.path());
+        assert!(restarted.unlock("first passphrase").is_err());
+        restarted.unlock("second passphrase").unwrap();
+        assert_eq!(
+            cipher::open(restarted.keys().unwrap().file_key(), &sealed).unwrap(),
+            b"audio"
+        );
+    }
+
+    #[test]
+    fn test_disable() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = vault_in(dir.path());
+        vault.enable_with("correct horse", TEST_KDF).unwrap();
+        vault.request_disable("correct horse").unwrap();
+
+        // Existing data can still be read, new data is written in plaintext
+        let restarted = vault_in(dir.path());
+        assert!(restarted.is_disable_pending());
+        restarted.unlock("correct horse").unwrap();
+        assert!(restarted.keys().is_some());
+        assert!(restarted.sealing_keys().unwrap().is_none());
+
+        restarted.finish_disable().unwrap();
+        assert!(!vault_in(dir.path()).is_enabled());
+    }
+
+    #[test]
+    fn test_damaged_settings_keep_encryption_on() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CONFIG_FILE), "{ not json").unwrap();
+        let vault = vault_in(dir.path());
+        assert!(vault.is_enabled());
+        assert!(vault.sealing_keys().is_err());
+        assert!(vault.unlock("correct horse").is_err());
+    }
+}
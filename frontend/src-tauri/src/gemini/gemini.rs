@@ -0,0 +1,217 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+use crate::database::repositories::setting::SettingsRepository;
+use crate::network::{self, Channel};
+use crate::secrets::redact;
+use crate::state::AppState;
+use crate::summary::llm_client::GEMINI_API_BASE;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeminiModel {
+    /// Model id as used in requests (e.g. "gemini-2.0-flash")
+    pub id: String,
+    pub name: String,
+    pub context_length: Option<u32>,
+    pub output_token_limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiApiModel {
+    name: String,
+    display_name: Option<String>,
+    input_token_limit: Option<u32>,
+    output_token_limit: Option<u32>,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModelsResponse {
+    #[serde(default)]
+    models: Vec<GeminiApiModel>,
+    next_page_token: Option<String>,
+}
+
+/// Lists the models of the Gemini API at `api_base` that can generate text
+pub async fn list_models(
+    client: &Client,
+    api_base: &str,
+    api_key: &str,
+) -> Result<Vec<GeminiModel>, String> {
+    let url = format!("{}/models", api_base.trim_end_matches('/'));
+    network::check(&url, Channel::ModelList)?;
+    let mut models = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    loop {
+        let mut request = client
+            .get(&url)
+            .header("x-goog-api-key", api_key)
+            .query(&[("pageSize", "1000")]);
+        if let Some(token) = &page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to make HTTP request: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP request failed with status {}: {}", status, body));
+        }
+
+        let page: GeminiModelsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
+
+        models.extend(
+            page.models
+                .into_iter()
+                .filter(|m| m.supported_generation_methods.iter().any(|g| g == "generateContent"))
+                .map(|m| {
+                    let id = m.name.trim_start_matches("models/").to_string();
+                    GeminiModel {
+                        name: m.display_name.unwrap_or_else(|| id.clone()),
+                        id,
+                        context_length: m.input_token_limit,
+                        output_token_limit: m.output_token_limit,
+                    }
+                }),
+        );
+
+        match page.next_page_token.filter(|t| !t.is_empty()) {
+            Some(token) => page_token = Some(token),
+            None => break,
+        }
+    }
+
+    Ok(models)
+}
+
+/// Lists Gemini models, using `api_key` or else the saved Gemini API key
+#[command]
+pub async fn get_gemini_models(
+    state: tauri::State<'_, AppState>,
+    api_key: Option<String>,
+) -> Result<Vec<GeminiModel>, String> {
+    let api_key = match api_key.filter(|k| !k.trim().is_empty()) {
+        Some(key) => {
+            // Stored keys are registered when read; one typed into the form isn't stored yet
+            redact::register(&key);
+            key
+        }
+        None => SettingsRepository::get_api_key(state.db_manager.pool(), "gemini")
+            .await
+            .map_err(|e| format!("Failed to retrieve Gemini API key: {}", e))?
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| "Gemini API key not configured".to_string())?,
+    };
+
+    list_models(&Client::new(), GEMINI_API_BASE, &api_key).await
+}
+
+/// Single-request HTTP server standing in for the Gemini API in tests
+#[cfg(test)]
+pub(crate) mod mock_server {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Serves one request with the given status line ("200 OK"), content type and body.
+    /// Returns the base URL and a handle resolving to the raw request received.
+    pub async fn serve_once(
+        status: &'static str,
+        content_type: &'static str,
+        body: String,
+    ) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+
+            // Read the headers, then as much body as Content-Length announces
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| {
+                            let (name, value) = l.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        (base_url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_models_keeps_text_models() {
+        let body = r#"{"models":[
+            {"name":"models/gemini-2.0-flash","displayName":"Gemini 2.0 Flash","inputTokenLimit":1048576,"outputTokenLimit":8192,"supportedGenerationMethods":["generateContent","countTokens"]},
+            {"name":"models/text-embedding-004","displayName":"Text Embedding 004","supportedGenerationMethods":["embedContent"]}
+        ]}"#;
+        let (base_url, request) =
+            mock_server::serve_once("200 OK", "application/json", body.to_string()).await;
+
+        let models = list_models(&Client::new(), &base_url, "test-key").await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "gemini-2.0-flash");
+        assert_eq!(models[0].name, "Gemini 2.0 Flash");
+        assert_eq!(models[0].context_length, Some(1048576));
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("GET /models?pageSize=1000 "));
+        assert!(request.to_lowercase().contains("x-goog-api-key: test-key"));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_reports_invalid_key() {
+        let body = r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","status":"INVALID_ARGUMENT"}}"#;
+        let (base_url, _request) =
+            mock_server::serve_once("400 Bad Request", "application/json", body.to_string()).await;
+
+        let error = list_models(&Client::new(), &base_url, "bad-key").await.unwrap_err();
+        assert!(error.contains("400"));
+        assert!(error.contains("API key not valid"));
+    }
+}
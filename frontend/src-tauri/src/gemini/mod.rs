@@ -0,0 +1,3 @@
+pub mod gemini;
+
+pub use gemini::*;
@@ -0,0 +1,179 @@
+use crate::database::models::{HomeworkItem, ScheduledLesson};
+use crate::database::repositories::{
+    homework::HomeworkRepository, lesson_schedule::LessonScheduleRepository,
+};
+use crate::homework::HomeworkStatus;
+use crate::state::AppState;
+use chrono::{DateTime, Utc};
+use log::{error as log_error, info as log_info};
+use tauri::{AppHandle, Runtime};
+
+/// Parses an RFC 3339 timestamp coming from the frontend
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| format!("Invalid date '{}': {}", value, e))
+}
+
+/// Lists homework, optionally filtered by source session and status
+#[tauri::command]
+pub async fn api_list_homework<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: Option<String>,
+    status: Option<String>,
+) -> Result<Vec<HomeworkItem>, String> {
+    log_info!(
+        "api_list_homework called for meeting_id: {:?}, status: {:?}",
+        meeting_id,
+        status
+    );
+
+    let status = status
+        .map(|s| s.parse::<HomeworkStatus>().map(|s| s.as_str()))
+        .transpose()?;
+
+    HomeworkRepository::list_items(state.db_manager.pool(), meeting_id.as_deref(), status)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to list homework: {}", e);
+            format!("Failed to list homework: {}", e)
+        })
+}
+
+/// Adds a homework item to a session manually
+///
+/// If no due date is given, the homework is due at the next scheduled lesson.
+#[tauri::command]
+pub async fn api_add_homework<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    description: String,
+    due_date: Option<String>,
+) -> Result<HomeworkItem, String> {
+    log_info!("api_add_homework called for meeting_id: {}", meeting_id);
+
+    if description.trim().is_empty() {
+        return Err("Homework description cannot be empty".to_string());
+    }
+
+    let pool = state.db_manager.pool();
+    let due_date = match due_date {
+        Some(value) => Some(parse_datetime(&value)?),
+        None => LessonScheduleRepository::get_next_lesson_after(pool, Utc::now())
+            .await
+            .map_err(|e| format!("Failed to look up next scheduled lesson: {}", e))?
+            .map(|lesson| lesson.scheduled_at),
+    };
+
+    HomeworkRepository::create_item(pool, &meeting_id, description.trim(), due_date)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to add homework for {}: {}", meeting_id, e);
+            format!("Failed to add homework: {}", e)
+        })
+}
+
+/// Sets the status of a homework item (open, done or skipped)
+#[tauri::command]
+pub async fn api_update_homework_status<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    homework_id: String,
+    status: String,
+) -> Result<(), String> {
+    log_info!(
+        "api_update_homework_status called for {}: {}",
+        homework_id,
+        status
+    );
+
+    let status: HomeworkStatus = status.parse()?;
+
+    match HomeworkRepository::update_status(state.db_manager.pool(), &homework_id, status.as_str())
+        .await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Homework not found: {}", homework_id)),
+        Err(e) => Err(format!("Failed to update homework status: {}", e)),
+    }
+}
+
+/// Changes or clears the due date of a homework item
+#[tauri::command]
+pub async fn api_update_homework_due_date<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    homework_id: String,
+    due_date: Option<String>,
+) -> Result<(), String> {
+    log_info!("api_update_homework_due_date called for {}", homework_id);
+
+    let due_date = due_date.map(|value| parse_datetime(&value)).transpose()?;
+
+    match HomeworkRepository::update_due_date(state.db_manager.pool(), &homework_id, due_date).await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Homework not found: {}", homework_id)),
+        Err(e) => Err(format!("Failed to update homework due date: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub async fn api_delete_homework<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    homework_id: String,
+) -> Result<(), String> {
+    log_info!("api_delete_homework called for {}", homework_id);
+
+    match HomeworkRepository::delete_item(state.db_manager.pool(), &homework_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Homework not found: {}", homework_id)),
+        Err(e) => Err(format!("Failed to delete homework: {}", e)),
+    }
+}
+
+/// Adds an upcoming lesson to the schedule (used as the default homework due date)
+#[tauri::command]
+pub async fn api_add_scheduled_lesson<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    scheduled_at: String,
+    title: Option<String>,
+) -> Result<ScheduledLesson, String> {
+    log_info!("api_add_scheduled_lesson called for {}", scheduled_at);
+
+    let scheduled_at = parse_datetime(&scheduled_at)?;
+
+    LessonScheduleRepository::add_lesson(state.db_manager.pool(), scheduled_at, title.as_deref())
+        .await
+        .map_err(|e| format!("Failed to add scheduled lesson: {}", e))
+}
+
+/// Lists upcoming scheduled lessons, soonest first
+#[tauri::command]
+pub async fn api_list_scheduled_lessons<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ScheduledLesson>, String> {
+    LessonScheduleRepository::list_upcoming(state.db_manager.pool(), Utc::now())
+        .await
+        .map_err(|e| format!("Failed to list scheduled lessons: {}", e))
+}
+
+#[tauri::command]
+pub async fn api_delete_scheduled_lesson<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    lesson_id: String,
+) -> Result<(), String> {
+    log_info!("api_delete_scheduled_lesson called for {}", lesson_id);
+
+    match LessonScheduleRepository::delete_lesson(state.db_manager.pool(), &lesson_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Scheduled lesson not found: {}", lesson_id)),
+        Err(e) => Err(format!("Failed to delete scheduled lesson: {}", e)),
+    }
+}
@@ -0,0 +1,256 @@
+use crate::database::models::HomeworkItem;
+use crate::summary::markdown::{list_item_text, section_heading_title, table_data_cells};
+use crate::summary::templates::Template;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Title of the template section the homework is extracted from
+pub const HOMEWORK_SECTION_TITLE: &str = "Homework";
+
+/// Title of the section added to the notes when earlier homework is still open
+pub const HOMEWORK_REVIEW_SECTION_TITLE: &str = "Homework Review";
+
+// Matches review lines such as "[H2] Reviewed: ..." or "[H1] - Not reviewed"
+static HOMEWORK_REVIEW_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\[H(\d+)\]\s*[:\-–]?\s*(not reviewed|reviewed)").unwrap()
+});
+
+/// Extracts the list items of a named section from generated markdown
+///
+/// # Arguments
+/// * `markdown` - Summary markdown
+/// * `section_title` - Section title to look for (case-insensitive)
+///
+/// # Returns
+/// The list items of the section, without markers, or the first cell of each
+/// table row. Placeholder items such as "None noted in this section." are skipped.
+pub fn extract_section_items(markdown: &str, section_title: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut in_section = false;
+    let lines: Vec<&str> = markdown.lines().collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(title) = section_heading_title(line) {
+            in_section = title.eq_ignore_ascii_case(section_title);
+            continue;
+        }
+
+        if !in_section {
+            continue;
+        }
+
+        let text = match table_data_cells(line, lines.get(i + 1).copied()) {
+            Some(cells) => cells.into_iter().next().unwrap_or_default(),
+            None => match list_item_text(line) {
+                Some(text) => text.to_string(),
+                None => continue,
+            },
+        };
+        if text.is_empty() || text.to_lowercase().starts_with("none noted") {
+            continue;
+        }
+        items.push(text);
+    }
+
+    items
+}
+
+/// Title of the homework section in a resolved template, such as "Homework" or
+/// "Homework (Spanish)"
+///
+/// # Returns
+/// None if the template has no homework section, or its condition dropped it
+pub fn homework_section_title(template: &Template) -> Option<&str> {
+    template
+        .sections
+        .iter()
+        .map(|section| section.title.as_str())
+        .find(|title| {
+            let title = title.to_lowercase();
+            title.starts_with(&HOMEWORK_SECTION_TITLE.to_lowercase())
+                && !title.eq_ignore_ascii_case(HOMEWORK_REVIEW_SECTION_TITLE)
+        })
+}
+
+/// Extracts homework items from the homework section of generated notes
+///
+/// # Arguments
+/// * `markdown` - Summary markdown
+/// * `template` - The template the notes were generated with, resolved for the session
+pub fn extract_homework_items(markdown: &str, template: &Template) -> Vec<String> {
+    homework_section_title(template)
+        .map(|title| extract_section_items(markdown, title))
+        .unwrap_or_default()
+}
+
+/// Builds the `<open_homework>` prompt context listing homework from earlier sessions
+///
+/// Items are labelled `[H1]`, `[H2]`, ... in the given order so the review section of
+/// the generated notes can be matched back to them with `parse_reviewed_homework`.
+///
+/// # Returns
+/// None if there is no open homework
+pub fn build_open_homework_context(items: &[HomeworkItem]) -> Option<String> {
+    if items.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<String> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let mut line = format!(
+                "- [H{}] {} (assigned {}",
+                i + 1,
+                item.description,
+                item.created_at.format("%Y-%m-%d")
+            );
+            if let Some(due) = item.due_date {
+                line.push_str(&format!(", due {}", due.format("%Y-%m-%d")));
+            }
+            line.push(')');
+            line
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+/// Instruction for the "Homework Review" section, appended to the template instructions
+pub fn homework_review_instruction() -> String {
+    format!(
+        "- **For the '{}' section:** For every item in `<open_homework>`, write one list item that starts with its label followed by `Reviewed` if the homework was checked or discussed in this session, or `Not reviewed` otherwise, then a short note on how it went (e.g. `- [H1] Reviewed: student completed the exercises, two mistakes with articles`).\n",
+        HOMEWORK_REVIEW_SECTION_TITLE
+    )
+}
+
+/// Returns the IDs of open homework the generated notes report as reviewed
+///
+/// # Arguments
+/// * `markdown` - Generated summary markdown
+/// * `items` - The homework items passed to `build_open_homework_context`, in the same order
+pub fn parse_reviewed_homework(markdown: &str, items: &[HomeworkItem]) -> Vec<String> {
+    let mut reviewed = Vec::new();
+
+    for line in extract_section_items(markdown, HOMEWORK_REVIEW_SECTION_TITLE) {
+        if let Some(captures) = HOMEWORK_REVIEW_REGEX.captures(&line) {
+            let is_reviewed = captures[2].eq_ignore_ascii_case("reviewed");
+            let index = captures[1].parse::<usize>().unwrap_or(0);
+            if is_reviewed && index >= 1 {
+                if let Some(item) = items.get(index - 1) {
+                    if !reviewed.contains(&item.id) {
+                        reviewed.push(item.id.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    reviewed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::templates::TemplateSection;
+    use chrono::Utc;
+
+    fn item(id: &str, description: &str) -> HomeworkItem {
+        HomeworkItem {
+            id: id.to_string(),
+            source_meeting_id: "meeting-1".to_string(),
+            description: description.to_string(),
+            status: "open".to_string(),
+            origin: "extracted".to_string(),
+            due_date: None,
+            reviewed_in_meeting_id: None,
+            reminder_sent_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn template(titles: &[&str]) -> Template {
+        Template {
+            name: "Lesson".to_string(),
+            description: "Lesson notes".to_string(),
+            version: 1,
+            sections: titles
+                .iter()
+                .map(|title| TemplateSection {
+                    title: title.to_string(),
+                    instruction: "Notes".to_string(),
+                    format: "list".to_string(),
+                    item_format: None,
+                    example_item_format: None,
+                    condition: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_extract_homework_from_bold_sections() {
+        let markdown = "**Key Phrases**\n\n- ¿Qué tal?\n\n**Homework**\n\n- Write 10 sentences using the past tense\n1. Read chapter 3\n- [ ] Record a voice note\n\n**Progress Notes**\n\nGood progress.";
+        let items = extract_homework_items(markdown, &template(&["Key Phrases", "Homework"]));
+        assert_eq!(
+            items,
+            vec![
+                "Write 10 sentences using the past tense",
+                "Read chapter 3",
+                "Record a voice note"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_homework_from_table_rows() {
+        let markdown = "**Homework**\n\n| **Task** | **Due** |\n| --- | --- |\n| **Write 10 sentences in the past tense** | Friday |\n| Read chapter 3 | Monday |\n\n**Progress Notes**\n\n| Not | homework |";
+        assert_eq!(
+            extract_homework_items(markdown, &template(&["Homework"])),
+            vec!["Write 10 sentences in the past tense", "Read chapter 3"]
+        );
+    }
+
+    #[test]
+    fn test_extract_homework_from_headings_skips_placeholder() {
+        let markdown = "## Homework\n\n- None noted in this section.\n\n## Progress Notes\n- Not homework";
+        assert!(extract_homework_items(markdown, &template(&["Homework"])).is_empty());
+    }
+
+    #[test]
+    fn test_extract_homework_uses_resolved_section_title() {
+        let markdown =
+            "**Homework (Spanish)**\n\n- Conjugate ser\n\n**Homework**\n\n- Stale heading";
+        assert_eq!(
+            extract_homework_items(markdown, &template(&["Homework (Spanish)"])),
+            vec!["Conjugate ser"]
+        );
+        // A template whose homework section was dropped by its condition has no homework
+        assert!(extract_homework_items(markdown, &template(&["Summary"])).is_empty());
+    }
+
+    #[test]
+    fn test_review_section_is_not_homework() {
+        let markdown = "**Homework Review**\n\n- [H1] Reviewed: done\n\n**Homework**\n\n- New task";
+        assert_eq!(
+            extract_homework_items(markdown, &template(&["Homework"])),
+            vec!["New task"]
+        );
+    }
+
+    #[test]
+    fn test_parse_reviewed_homework() {
+        let items = vec![item("a", "Verb drills"), item("b", "Essay"), item("c", "Podcast")];
+        let markdown = "**Homework Review**\n\n- [H1] Reviewed: all correct\n- [H2] Not reviewed\n- [H3] - reviewed briefly\n- [H9] Reviewed\n";
+        assert_eq!(parse_reviewed_homework(markdown, &items), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_build_open_homework_context() {
+        assert!(build_open_homework_context(&[]).is_none());
+
+        let context = build_open_homework_context(&[item("a", "Verb drills")]).unwrap();
+        assert!(context.starts_with("- [H1] Verb drills (assigned "));
+    }
+}
@@ -0,0 +1,197 @@
+/// Homework module - tracks homework carried between lessons
+///
+/// This module contains:
+/// - Extraction of homework from the homework section of generated notes
+/// - Prompt context so the next session's notes report whether homework was reviewed
+/// - Reminder notifications for homework that is due soon
+/// - Tauri commands for managing homework and the lesson schedule
+
+pub mod commands;
+pub mod extractor;
+pub mod reminders;
+
+pub use extractor::{
+    build_open_homework_context, extract_homework_items, homework_review_instruction,
+    parse_reviewed_homework,
+};
+pub use reminders::start_homework_reminder_loop;
+
+use crate::database::models::HomeworkItem;
+use crate::database::repositories::{
+    homework::HomeworkRepository, lesson_schedule::LessonScheduleRepository,
+    meeting::MeetingsRepository,
+};
+use crate::summary::templates::Template;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+use tracing::{error, info};
+
+/// Homework status enumeration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HomeworkStatus {
+    Open,
+    Done,
+    Skipped,
+}
+
+impl FromStr for HomeworkStatus {
+    type Err = String;
+
+    /// Parse status from string (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(Self::Open),
+            "done" => Ok(Self::Done),
+            "skipped" => Ok(Self::Skipped),
+            _ => Err(format!("Invalid homework status: {}", s)),
+        }
+    }
+}
+
+impl HomeworkStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Done => "done",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// Updates homework tracking after a session summary has been generated
+///
+/// - Stores the homework listed in the new notes, due at the next scheduled lesson
+///   after the session
+/// - Records which earlier homework the notes report as reviewed
+///
+/// Failures are logged and never fail the summary itself.
+///
+/// # Arguments
+/// * `pool` - SQLx connection pool
+/// * `meeting_id` - Session the notes were generated for
+/// * `markdown` - Generated notes
+/// * `open_homework` - Homework that was passed to the prompt via `build_open_homework_context`
+/// * `template` - The template the notes were generated with, resolved for the session
+pub async fn sync_homework_from_summary(
+    pool: &SqlitePool,
+    meeting_id: &str,
+    markdown: &str,
+    open_homework: &[HomeworkItem],
+    template: &Template,
+) {
+    let reviewed = parse_reviewed_homework(markdown, open_homework);
+    if !reviewed.is_empty() {
+        info!(
+            "Notes for {} report {} homework item(s) as reviewed",
+            meeting_id,
+            reviewed.len()
+        );
+        if let Err(e) = HomeworkRepository::mark_reviewed(pool, &reviewed, meeting_id).await {
+            error!("Failed to mark homework as reviewed for {}: {}", meeting_id, e);
+        }
+    }
+
+    let descriptions = extract_homework_items(markdown, template);
+
+    // Homework is due at the next lesson scheduled after the session took place
+    let session_time = match MeetingsRepository::get_meeting_metadata(pool, meeting_id).await {
+        Ok(Some(meeting)) => meeting.created_at.0,
+        _ => Utc::now(),
+    };
+    let due_date = match LessonScheduleRepository::get_next_lesson_after(pool, session_time).await {
+        Ok(lesson) => lesson.map(|l| l.scheduled_at),
+        Err(e) => {
+            error!("Failed to look up next scheduled lesson: {}", e);
+            None
+        }
+    };
+
+    if let Err(e) =
+        HomeworkRepository::replace_open_items_for_meeting(pool, meeting_id, &descriptions, due_date)
+            .await
+    {
+        error!("Failed to store homework for {}: {}", meeting_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manager::DatabaseManager;
+    use crate::summary::templates::TemplateSection;
+    use chrono::{Duration, TimeZone};
+
+    fn template() -> Template {
+        Template {
+            name: "Lesson".to_string(),
+            description: "Lesson notes".to_string(),
+            version: 1,
+            sections: vec![TemplateSection {
+                title: "Homework".to_string(),
+                instruction: "Homework".to_string(),
+                format: "list".to_string(),
+                item_format: None,
+                example_item_format: None,
+                condition: None,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regenerating_notes_keeps_manual_and_edited_homework() {
+        let pool = DatabaseManager::test_pool().await;
+        let session_time = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        sqlx::query(
+            "INSERT INTO meetings (id, title, created_at, updated_at) VALUES ('m1', 'Lesson 1', ?1, ?1)",
+        )
+        .bind(session_time)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let notes = "**Homework**\n\n- Read chapter 3\n- Write 10 sentences";
+        sync_homework_from_summary(&pool, "m1", notes, &[], &template()).await;
+        HomeworkRepository::create_item(&pool, "m1", "Practice rolling Rs", None)
+            .await
+            .unwrap();
+        let items = HomeworkRepository::list_items(&pool, Some("m1"), None)
+            .await
+            .unwrap();
+        let reading = items
+            .iter()
+            .find(|item| item.description == "Read chapter 3")
+            .unwrap();
+        let due_date = session_time + Duration::days(3);
+        HomeworkRepository::update_due_date(&pool, &reading.id, Some(due_date))
+            .await
+            .unwrap();
+        HomeworkRepository::mark_reminder_sent(&pool, &reading.id)
+            .await
+            .unwrap();
+
+        // Regenerated notes drop one item, reword another's spacing and add a new one
+        let notes = "**Homework**\n\n- read  chapter 3\n- Listen to a podcast";
+        sync_homework_from_summary(&pool, "m1", notes, &[], &template()).await;
+
+        let items = HomeworkRepository::list_items(&pool, Some("m1"), None)
+            .await
+            .unwrap();
+        let mut descriptions: Vec<(&str, &str)> = items
+            .iter()
+            .map(|item| (item.description.as_str(), item.origin.as_str()))
+            .collect();
+        descriptions.sort();
+        assert_eq!(
+            descriptions,
+            [
+                ("Listen to a podcast", "extracted"),
+                ("Practice rolling Rs", "manual"),
+                ("Read chapter 3", "extracted"),
+            ]
+        );
+        let reading_after = items.iter().find(|item| item.id == reading.id).unwrap();
+        assert_eq!(reading_after.due_date, Some(due_date));
+        assert!(reading_after.reminder_sent_at.is_some());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::database::repositories::homework::HomeworkRepository;
+use crate::notifications::commands::NotificationManagerState;
+use crate::state::AppState;
+use chrono::Utc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Wry};
+use tracing::{error, info, warn};
+
+/// How often open homework is checked for upcoming due dates
+const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Homework due within this many hours triggers a reminder
+const REMINDER_LEAD_TIME_HOURS: i64 = 24;
+
+/// Starts the background task that reminds the user about homework due soon
+///
+/// Each open homework item is reminded about once, when it is due within
+/// `REMINDER_LEAD_TIME_HOURS` (or already overdue). Changing the due date re-arms the reminder.
+pub fn start_homework_reminder_loop(app: AppHandle<Wry>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match send_due_homework_reminders(&app).await {
+                Ok(0) => {}
+                Ok(count) => info!("Sent {} homework reminder(s)", count),
+                Err(e) => warn!("Homework reminder check failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Shows a notification for each open homework item that is due soon
+///
+/// # Returns
+/// Number of reminders shown
+async fn send_due_homework_reminders(app: &AppHandle<Wry>) -> Result<usize, String> {
+    // The database isn't available until first-launch setup has completed
+    let pool = match app.try_state::<AppState>() {
+        Some(state) => state.db_manager.pool().clone(),
+        None => return Ok(0),
+    };
+
+    let due_before = Utc::now() + chrono::Duration::hours(REMINDER_LEAD_TIME_HOURS);
+    let items = HomeworkRepository::get_items_needing_reminder(&pool, due_before)
+        .await
+        .map_err(|e| format!("Failed to query homework: {}", e))?;
+
+    if items.is_empty() {
+        return Ok(0);
+    }
+
+    let manager_state = app.state::<NotificationManagerState<Wry>>();
+    let manager_lock = manager_state.read().await;
+    let manager = match manager_lock.as_ref() {
+        Some(manager) => manager,
+        None => return Err("Notification manager not initialized".to_string()),
+    };
+
+    let mut sent = 0;
+    for item in items {
+        if let Err(e) = manager
+            .show_homework_reminder(&item.description, item.due_date)
+            .await
+        {
+            error!("Failed to show homework reminder for {}: {}", item.id, e);
+            continue;
+        }
+
+        if let Err(e) = HomeworkRepository::mark_reminder_sent(&pool, &item.id).await {
+            error!("Failed to mark homework reminder as sent for {}: {}", item.id, e);
+        }
+        sent += 1;
+    }
+
+    Ok(sent)
+}
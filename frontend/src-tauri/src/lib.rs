@@ -38,13 +38,19 @@ pub(crate) use perf_trace;
 pub mod analytics;
 pub mod api;
 pub mod audio;
+pub mod chat;
 pub mod console_utils;
 pub mod database;
+pub mod encryption;
+pub mod gemini;
+pub mod homework;
+pub mod network;
 pub mod notifications;
 pub mod ollama;
 pub mod onboarding;
 pub mod openrouter;
 pub mod parakeet_engine;
+pub mod secrets;
 pub mod state;
 pub mod summary;
 pub mod tray;
@@ -217,12 +223,14 @@ fn get_transcription_status() -> TranscriptionStatus {
 
 #[tauri::command]
 fn read_audio_file(file_path: String) -> Result<Vec<u8>, String> {
-    match std::fs::read(&file_path) {
+    match encryption::files::read(std::path::Path::new(&file_path)) {
         Ok(data) => Ok(data),
         Err(e) => Err(format!("Failed to read audio file: {}", e)),
     }
 }
 
+/// Saves a transcript to a file, encrypted like the session files when
+/// encryption is on
 #[tauri::command]
 async fn save_transcript(file_path: String, content: String) -> Result<(), String> {
     log_info!("Saving transcript to: {}", file_path);
@@ -235,8 +243,8 @@ async fn save_transcript(file_path: String, content: String) -> Result<(), Strin
         }
     }
 
-    // Write content to file
-    std::fs::write(&file_path, content)
+    // Encrypted like the session files when encryption is on
+    encryption::files::write(std::path::Path::new(&file_path), content.as_bytes())
         .map_err(|e| format!("Failed to write transcript: {}", e))?;
 
     log_info!("Transcript saved successfully");
@@ -423,6 +431,17 @@ pub fn run() {
         .setup(|_app| {
             log::info!("Application setup complete");
 
+            // Load the local-only and encryption settings before anything
+            // can connect or touch the database
+            match _app.path().app_data_dir() {
+                Ok(app_data_dir) => {
+                    network::gate::gate().init(&app_data_dir);
+                    encryption::vault::vault().init(&app_data_dir);
+                    secrets::store::init(&app_data_dir);
+                }
+                Err(e) => log::error!("Failed to resolve app data dir for the network gate, encryption and secrets: {}", e),
+            }
+
             // Initialize system tray
             if let Err(e) = tray::create_tray(_app.handle()) {
                 log::error!("Failed to create system tray: {}", e);
@@ -502,6 +521,21 @@ pub fn run() {
             })
             .expect("Failed to initialize database");
 
+            // Decrypted checkpoints left behind by a crash of an earlier version
+            let app_for_cleanup = _app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                encryption::commands::remove_leftover_plain_copies(&app_for_cleanup).await;
+            });
+
+            // Start homework reminder notifications (waits for the database on first launch)
+            homework::start_homework_reminder_loop(_app.handle().clone());
+
+            // Start scheduled database backups and maintenance
+            database::maintenance::start_database_maintenance_loop(_app.handle().clone());
+
+            // Run queued summary jobs, resuming those interrupted by the last shutdown
+            summary::job_queue::start_summary_job_queue(_app.handle().clone());
+
             // Initialize bundled templates directory for dynamic template discovery
             log::info!("Initializing bundled templates directory...");
             if let Ok(resource_path) = _app.handle().path().resource_dir() {
@@ -637,6 +671,9 @@ pub fn run() {
             api::api_get_session_metadata,
             api::api_get_session_transcripts,
             api::api_save_session_title,
+            api::api_get_session_tags,
+            api::api_save_session_tags,
+            api::api_list_session_tags,
             api::api_save_transcript,
             api::open_session_folder,
             api::test_backend_connection,
@@ -645,16 +682,74 @@ pub fn run() {
             // Custom OpenAI commands
             api::api_save_custom_openai_config,
             api::api_get_custom_openai_config,
+            api::api_save_provider_fallback_config,
+            api::api_get_provider_fallback_config,
+            api::api_save_redaction_config,
+            api::api_get_redaction_config,
+            api::api_save_sampling_config,
+            api::api_get_sampling_config,
             api::api_test_custom_openai_connection,
             // Summary commands
             summary::api_process_transcript,
             summary::api_get_summary,
             summary::api_save_session_summary,
             summary::api_cancel_summary,
+            summary::api_get_summary_citations,
+            summary::api_get_summary_queue,
+            summary::api_preview_summary_batch,
+            summary::api_start_summary_batch,
+            summary::api_get_summary_batch,
+            summary::api_list_summary_batches,
+            summary::api_cancel_summary_batch,
+            summary::api_list_summary_versions,
+            summary::api_get_summary_version,
+            summary::api_diff_summary_versions,
+            summary::api_restore_summary_version,
             // Template commands
             summary::api_list_templates,
             summary::api_get_template_details,
             summary::api_validate_template,
+            summary::api_get_template_json,
+            summary::api_create_template,
+            summary::api_update_template,
+            summary::api_delete_template,
+            summary::api_duplicate_template,
+            summary::api_import_template,
+            summary::api_export_template,
+            summary::api_list_template_variables,
+            summary::api_get_learner_profile,
+            summary::api_save_learner_profile,
+            // Homework commands
+            homework::commands::api_list_homework,
+            homework::commands::api_add_homework,
+            homework::commands::api_update_homework_status,
+            homework::commands::api_update_homework_due_date,
+            homework::commands::api_delete_homework,
+            homework::commands::api_add_scheduled_lesson,
+            homework::commands::api_list_scheduled_lessons,
+            homework::commands::api_delete_scheduled_lesson,
+            // Network commands
+            network::commands::api_get_local_only_mode,
+            network::commands::api_set_local_only_mode,
+            network::commands::api_get_network_audit_log,
+            network::commands::api_clear_network_audit_log,
+            network::updater::api_check_for_update,
+            network::updater::api_install_update,
+            // Encryption commands
+            encryption::commands::api_get_encryption_status,
+            encryption::commands::api_enable_encryption,
+            encryption::commands::api_unlock_encryption,
+            encryption::commands::api_change_encryption_passphrase,
+            encryption::commands::api_disable_encryption,
+            // Secret store commands
+            secrets::commands::api_get_secret_store_status,
+            secrets::commands::api_unlock_secret_store,
+            secrets::commands::api_change_secret_store_master_password,
+            // Session chat commands
+            chat::commands::api_chat_send_message,
+            chat::commands::api_chat_list_messages,
+            chat::commands::api_chat_clear,
+            chat::commands::api_chat_cancel,
             // Built-in AI commands
             summary::summary_engine::builtin_ai_list_models,
             summary::summary_engine::builtin_ai_get_model_info,
@@ -664,7 +759,9 @@ pub fn run() {
             summary::summary_engine::builtin_ai_is_model_ready,
             summary::summary_engine::builtin_ai_get_available_summary_model,
             summary::summary_engine::builtin_ai_get_recommended_model,
+            summary::summary_engine::builtin_ai_add_local_model,
             openrouter::get_openrouter_models,
+            gemini::get_gemini_models,
             audio::recording_preferences::get_recording_preferences,
             audio::recording_preferences::set_recording_preferences,
             audio::recording_preferences::get_default_recordings_folder_path,
@@ -715,6 +812,16 @@ pub fn run() {
             database::commands::get_database_directory,
             database::commands::open_database_folder,
             whisper_engine::commands::open_models_folder,
+            // Database backup commands
+            database::commands::api_list_database_backups,
+            database::commands::api_create_database_backup,
+            database::commands::api_restore_database_backup,
+            database::commands::api_delete_database_backup,
+            database::commands::api_get_database_maintenance_settings,
+            database::commands::api_save_database_maintenance_settings,
+            database::commands::api_get_database_integrity_report,
+            database::commands::api_check_database_integrity,
+            database::commands::api_run_database_maintenance,
             // Onboarding commands
             onboarding::get_onboarding_status,
             onboarding::save_onboarding_status_cmd,
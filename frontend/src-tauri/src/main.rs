@@ -4,11 +4,11 @@
 )]
 
 use log;
-use env_logger;
 
 fn main() {
     std::env::set_var("RUST_LOG", "info");
-    env_logger::init();
+    // Keeps API keys out of the logs
+    app_lib::secrets::redact::init_logger();
 
     // Async logger will be initialized lazily when first needed (after Tauri runtime starts)
     log::info!("Starting application...");
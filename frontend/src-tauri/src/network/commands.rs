@@ -0,0 +1,34 @@
+use crate::network::gate::{self, BlockedConnection};
+use log::info as log_info;
+
+/// Entries returned by `api_get_network_audit_log` unless a limit is given
+const DEFAULT_AUDIT_LOG_LIMIT: usize = 200;
+
+/// Whether local-only mode is on
+#[tauri::command]
+pub async fn api_get_local_only_mode() -> Result<bool, String> {
+    Ok(gate::gate().is_local_only())
+}
+
+/// Turns local-only mode on or off
+///
+/// While it is on, only loopback hosts (e.g. Ollama on localhost) can be reached.
+#[tauri::command]
+pub async fn api_set_local_only_mode(enabled: bool) -> Result<(), String> {
+    log_info!("api_set_local_only_mode called: {}", enabled);
+    gate::gate().set_local_only(enabled)
+}
+
+/// Lists connections blocked by local-only mode, newest first
+#[tauri::command]
+pub async fn api_get_network_audit_log(
+    limit: Option<usize>,
+) -> Result<Vec<BlockedConnection>, String> {
+    Ok(gate::gate().audit_log(limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT)))
+}
+
+#[tauri::command]
+pub async fn api_clear_network_audit_log() -> Result<(), String> {
+    log_info!("api_clear_network_audit_log called");
+    gate::gate().clear_audit_log()
+}
@@ -0,0 +1,350 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::{error, info, warn};
+use url::{Host, Url};
+
+/// Local-only setting, read synchronously at startup so it applies before anything connects
+const POLICY_FILE: &str = "network_policy.json";
+
+/// Blocked connections, one JSON object per line
+const AUDIT_LOG_FILE: &str = "network_audit.jsonl";
+
+/// Previous audit log, kept when the current one is rotated
+const AUDIT_LOG_ROTATED_FILE: &str = "network_audit.1.jsonl";
+
+/// Size at which the audit log is rotated
+const MAX_AUDIT_LOG_BYTES: u64 = 512 * 1024;
+
+static GATE: Lazy<NetworkGate> = Lazy::new(NetworkGate::default);
+
+/// What an outbound connection is for, as recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    /// Summary and chat requests to LLM providers
+    LlmProvider,
+    /// Model lists of LLM providers (OpenRouter, Gemini, Ollama)
+    ModelList,
+    /// Whisper, Parakeet and built-in summary model downloads
+    ModelDownload,
+    Analytics,
+    /// The app server used by `api::make_api_request`
+    AppServer,
+    Updater,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LlmProvider => "llm_provider",
+            Self::ModelList => "model_list",
+            Self::ModelDownload => "model_download",
+            Self::Analytics => "analytics",
+            Self::AppServer => "app_server",
+            Self::Updater => "updater",
+        }
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error of a connection the gate didn't allow
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NetworkError {
+    #[error("Blocked by local-only mode: {channel} connection to {host}")]
+    Blocked { channel: Channel, host: String },
+}
+
+impl From<NetworkError> for String {
+    fn from(error: NetworkError) -> Self {
+        error.to_string()
+    }
+}
+
+/// A connection blocked by local-only mode
+///
+/// Only the host and path are recorded: query strings may carry API keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockedConnection {
+    pub timestamp: DateTime<Utc>,
+    pub channel: Channel,
+    pub host: String,
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkPolicy {
+    #[serde(default)]
+    local_only: bool,
+}
+
+/// Central check for all outbound HTTP requests
+///
+/// In local-only mode only loopback hosts may be reached; every other
+/// connection fails with `NetworkError::Blocked` and is written to the audit log.
+#[derive(Default)]
+pub struct NetworkGate {
+    local_only: AtomicBool,
+    /// Directory of the policy file and audit log, set by `init`
+    dir: Mutex<Option<PathBuf>>,
+}
+
+impl NetworkGate {
+    /// Loads the policy from `dir`
+    ///
+    /// A policy file that exists but can't be read turns local-only mode on,
+    /// so a damaged file never lets traffic out.
+    pub fn init(&self, dir: &Path) {
+        let local_only = match fs::read_to_string(dir.join(POLICY_FILE)) {
+            Ok(json) => match serde_json::from_str::<NetworkPolicy>(&json) {
+                Ok(policy) => policy.local_only,
+                Err(e) => {
+                    error!("Invalid network policy, enabling local-only mode: {}", e);
+                    true
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => {
+                error!(
+                    "Failed to read network policy, enabling local-only mode: {}",
+                    e
+                );
+                true
+            }
+        };
+        self.local_only.store(local_only, Ordering::SeqCst);
+        *self.dir.lock().unwrap() = Some(dir.to_path_buf());
+        info!("Network gate initialized (local-only: {})", local_only);
+    }
+
+    pub fn is_local_only(&self) -> bool {
+        self.local_only.load(Ordering::SeqCst)
+    }
+
+    /// Turns local-only mode on or off and saves the setting
+    ///
+    /// The mode changes even if it can't be saved, which is reported as an error.
+    pub fn set_local_only(&self, local_only: bool) -> Result<(), String> {
+        self.local_only.store(local_only, Ordering::SeqCst);
+        info!(
+            "Local-only mode {}",
+            if local_only { "enabled" } else { "disabled" }
+        );
+
+        let dir = self.dir.lock().unwrap().clone();
+        let dir = dir.ok_or_else(|| "Network gate is not initialized".to_string())?;
+        let json = serde_json::to_string(&NetworkPolicy { local_only })
+            .map_err(|e| format!("Failed to serialize network policy: {}", e))?;
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join(POLICY_FILE), json))
+            .map_err(|e| format!("Failed to save network policy: {}", e))
+    }
+
+    /// Checks whether a request to `url` may be sent
+    ///
+    /// # Errors
+    /// `NetworkError::Blocked` in local-only mode if the host isn't a loopback
+    /// address (unparseable URLs are blocked too)
+    pub fn check(&self, url: &str, channel: Channel) -> Result<(), NetworkError> {
+        if !self.is_local_only() || is_loopback_url(url) {
+            return Ok(());
+        }
+
+        let (host, path) = match Url::parse(url.trim()) {
+            Ok(parsed) => (
+                parsed.host_str().unwrap_or_default().to_string(),
+                parsed.path().to_string(),
+            ),
+            Err(_) => ("invalid URL".to_string(), String::new()),
+        };
+        warn!(
+            "Local-only mode blocked a {} connection to {}",
+            channel, host
+        );
+        self.record(&BlockedConnection {
+            timestamp: Utc::now(),
+            channel,
+            host: host.clone(),
+            path,
+        });
+        Err(NetworkError::Blocked { channel, host })
+    }
+
+    /// Blocked connections, newest first
+    pub fn audit_log(&self, limit: usize) -> Vec<BlockedConnection> {
+        let Some(dir) = self.dir.lock().unwrap().clone() else {
+            return Vec::new();
+        };
+        let mut entries: Vec<BlockedConnection> = [AUDIT_LOG_ROTATED_FILE, AUDIT_LOG_FILE]
+            .iter()
+            .filter_map(|file| fs::read_to_string(dir.join(file)).ok())
+            .flat_map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        entries.reverse();
+        entries.truncate(limit);
+        entries
+    }
+
+    /// Deletes the audit log
+    pub fn clear_audit_log(&self) -> Result<(), String> {
+        let Some(dir) = self.dir.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        for file in [AUDIT_LOG_FILE, AUDIT_LOG_ROTATED_FILE] {
+            match fs::remove_file(dir.join(file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Failed to clear network audit log: {}", e));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&self, entry: &BlockedConnection) {
+        // Held while writing, so concurrent entries don't interleave
+        let guard = self.dir.lock().unwrap();
+        let Some(dir) = guard.as_ref() else {
+            return;
+        };
+        let path = dir.join(AUDIT_LOG_FILE);
+        if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_AUDIT_LOG_BYTES) {
+            if let Err(e) = fs::rename(&path, dir.join(AUDIT_LOG_ROTATED_FILE)) {
+                warn!("Failed to rotate network audit log: {}", e);
+            }
+        }
+        let result = serde_json::to_string(entry)
+            .map_err(std::io::Error::other)
+            .and_then(|line| {
+                let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+                writeln!(file, "{}", line)
+            });
+        if let Err(e) = result {
+            error!("Failed to write network audit log: {}", e);
+        }
+    }
+}
+
+/// The gate every outbound request goes through
+pub fn gate() -> &'static NetworkGate {
+    &GATE
+}
+
+/// Checks a request with the global gate, see `NetworkGate::check`
+pub fn check(url: &str, channel: Channel) -> Result<(), NetworkError> {
+    GATE.check(url, channel)
+}
+
+/// Whether the URL points at this machine (localhost, 127.0.0.0/8 or ::1)
+pub fn is_loopback_url(url: &str) -> bool {
+    match Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host().map(|host| host.to_owned()))
+    {
+        Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate_in(dir: &Path) -> NetworkGate {
+        let gate = NetworkGate::default();
+        gate.init(dir);
+        gate
+    }
+
+    #[test]
+    fn test_blocks_only_remote_hosts_in_local_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate_in(dir.path());
+        assert!(gate
+            .check(
+                "https://api.openai.com/v1/chat/completions",
+                Channel::LlmProvider
+            )
+            .is_ok());
+
+        gate.set_local_only(true).unwrap();
+        assert!(gate
+            .check("http://localhost:11434/api/chat", Channel::LlmProvider)
+            .is_ok());
+        assert!(gate
+            .check(
+                "http://127.0.0.1:8000/v1/chat/completions",
+                Channel::LlmProvider
+            )
+            .is_ok());
+        assert!(gate
+            .check("http://[::1]:11434/api/tags", Channel::ModelList)
+            .is_ok());
+        assert_eq!(
+            gate.check(
+                "https://api.openai.com/v1/chat/completions",
+                Channel::LlmProvider
+            ),
+            Err(NetworkError::Blocked {
+                channel: Channel::LlmProvider,
+                host: "api.openai.com".to_string()
+            })
+        );
+        assert!(gate
+            .check("http://localhost.evil.com/", Channel::Analytics)
+            .is_err());
+        assert!(gate.check("not a url", Channel::AppServer).is_err());
+
+        // The setting survives a restart
+        assert!(gate_in(dir.path()).is_local_only());
+    }
+
+    #[test]
+    fn test_audit_log_records_blocked_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = gate_in(dir.path());
+        gate.set_local_only(true).unwrap();
+        let _ = gate.check(
+            "https://generativelanguage.googleapis.com/v1beta/models?key=secret",
+            Channel::ModelList,
+        );
+        let _ = gate.check("https://us.i.posthog.com/capture/", Channel::Analytics);
+        let _ = gate.check("http://localhost:11434/api/chat", Channel::LlmProvider);
+
+        let log = gate.audit_log(10);
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].channel, Channel::Analytics);
+        assert_eq!(log[1].host, "generativelanguage.googleapis.com");
+        assert_eq!(log[1].path, "/v1beta/models");
+        let raw = fs::read_to_string(dir.path().join(AUDIT_LOG_FILE)).unwrap();
+        assert!(!raw.contains("secret"));
+
+        gate.clear_audit_log().unwrap();
+        assert!(gate.audit_log(10).is_empty());
+    }
+
+    #[test]
+    fn test_damaged_policy_enables_local_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(POLICY_FILE), "{ not json").unwrap();
+        assert!(gate_in(dir.path()).is_local_only());
+    }
+}
@@ -0,0 +1,14 @@
+/// Network module - controls what leaves the machine
+///
+/// This module contains:
+/// - The gate every outbound HTTP request goes through, which blocks all
+///   non-loopback hosts in local-only mode
+/// - An audit log of the blocked connections
+/// - Update checks and installation through the gate
+/// - Tauri commands for the local-only setting and the audit log
+
+pub mod commands;
+pub mod gate;
+pub mod updater;
+
+pub use gate::{check, is_loopback_url, BlockedConnection, Channel, NetworkError};
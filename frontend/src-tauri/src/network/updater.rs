@@ -0,0 +1,133 @@
+use crate::network::gate::{self, Channel};
+use log::{error as log_error, info as log_info};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// Event emitted with an `UpdateDownloadProgress` while an update is downloaded
+pub const UPDATE_DOWNLOAD_PROGRESS_EVENT: &str = "update-download-progress";
+
+/// Update found by the last check, installed by `api_install_update`
+static PENDING_UPDATE: Lazy<Mutex<Option<Update>>> = Lazy::new(|| Mutex::new(None));
+
+/// An available update
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub date: Option<String>,
+    pub body: Option<String>,
+}
+
+/// Download progress, in the shape of the updater plugin's JavaScript events
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data")]
+pub enum UpdateDownloadProgress {
+    Started {
+        #[serde(rename = "contentLength")]
+        content_length: Option<u64>,
+    },
+    Progress {
+        #[serde(rename = "chunkLength")]
+        chunk_length: usize,
+    },
+    Finished,
+}
+
+/// Update endpoints from the updater plugin configuration
+fn updater_endpoints<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|config| config.get("endpoints"))
+        .and_then(|endpoints| endpoints.as_array())
+        .map(|endpoints| {
+            endpoints
+                .iter()
+                .filter_map(|endpoint| endpoint.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks for an app update
+///
+/// Updates are checked here rather than from the frontend, so the request
+/// goes through the network gate.
+#[tauri::command]
+pub async fn api_check_for_update<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<UpdateInfo>, String> {
+    for endpoint in updater_endpoints(&app) {
+        gate::check(&endpoint, Channel::Updater)?;
+    }
+
+    let updater = app
+        .updater()
+        .map_err(|e| format!("Failed to create updater: {}", e))?;
+    let update = updater.check().await.map_err(|e| {
+        log_error!("Failed to check for updates: {}", e);
+        format!("Failed to check for updates: {}", e)
+    })?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        date: update.date.map(|date| date.to_string()),
+        body: update.body.clone(),
+    });
+    if let Some(info) = &info {
+        log_info!(
+            "Update available: {} -> {}",
+            info.current_version,
+            info.version
+        );
+    }
+    *PENDING_UPDATE.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// Downloads and installs the update found by `api_check_for_update`
+///
+/// Progress is emitted as `update-download-progress` events. The frontend
+/// restarts the app afterwards.
+#[tauri::command]
+pub async fn api_install_update<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let update = PENDING_UPDATE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No update available, check for updates first".to_string())?;
+    gate::check(update.download_url.as_str(), Channel::Updater)?;
+
+    log_info!("Installing update {}", update.version);
+    let emit = |progress: UpdateDownloadProgress| {
+        if let Err(e) = app.emit(UPDATE_DOWNLOAD_PROGRESS_EVENT, progress) {
+            log_error!("Failed to emit {}: {}", UPDATE_DOWNLOAD_PROGRESS_EVENT, e);
+        }
+    };
+    let mut started = false;
+    update
+        .download_and_install(
+            |chunk_length, content_length| {
+                if !started {
+                    started = true;
+                    emit(UpdateDownloadProgress::Started { content_length });
+                }
+                emit(UpdateDownloadProgress::Progress { chunk_length });
+            },
+            || emit(UpdateDownloadProgress::Finished),
+        )
+        .await
+        .map_err(|e| {
+            log_error!("Failed to install update: {}", e);
+            format!("Failed to install update: {}", e)
+        })?;
+
+    *PENDING_UPDATE.lock().unwrap() = None;
+    Ok(())
+}
@@ -172,6 +172,21 @@ impl<R: Runtime> NotificationManager<R> {
         self.show_notification(notification).await
     }
 
+    /// Show a homework reminder notification
+    pub async fn show_homework_reminder(
+        &self,
+        description: &str,
+        due_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<()> {
+        let settings = self.settings.read().await;
+        if !settings.notification_preferences.show_homework_reminders {
+            return Ok(());
+        }
+
+        let notification = Notification::homework_reminder(description, due_date);
+        self.show_notification(notification).await
+    }
+
     /// Show a system error notification
     pub async fn show_system_error(&self, error: String) -> Result<()> {
         let settings = self.settings.read().await;
@@ -299,6 +314,7 @@ impl<R: Runtime> NotificationManager<R> {
             NotificationType::RecordingResumed => settings.notification_preferences.show_recording_resumed,
             NotificationType::TranscriptionComplete => settings.notification_preferences.show_transcription_complete,
             NotificationType::SessionReminder(_) => settings.notification_preferences.show_session_reminders,
+            NotificationType::HomeworkReminder => settings.notification_preferences.show_homework_reminders,
             NotificationType::SystemError(_) => settings.notification_preferences.show_system_errors,
             NotificationType::Test => true, // Always show test notifications
         }
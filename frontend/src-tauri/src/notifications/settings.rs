@@ -55,6 +55,10 @@ pub struct NotificationPreferences {
     /// Show session reminder notifications
     pub show_session_reminders: bool,
 
+    /// Show reminders for homework due before the next lesson
+    #[serde(default = "default_true")]
+    pub show_homework_reminders: bool,
+
     /// Show system error notifications
     pub show_system_errors: bool,
 
@@ -62,6 +66,10 @@ pub struct NotificationPreferences {
     pub session_reminder_minutes: Vec<u64>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl Default for NotificationSettings {
     fn default() -> Self {
         Self {
@@ -87,6 +95,7 @@ impl Default for NotificationPreferences {
             show_recording_resumed: true,
             show_transcription_complete: true,
             show_session_reminders: true,
+            show_homework_reminders: true,
             show_system_errors: true,
             session_reminder_minutes: vec![15, 5], // 15 minutes and 5 minutes before
         }
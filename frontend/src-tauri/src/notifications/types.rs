@@ -21,6 +21,7 @@ pub enum NotificationType {
     RecordingResumed,
     TranscriptionComplete,
     SessionReminder(u64), // Duration in minutes
+    HomeworkReminder,
     SystemError(String),
     Test, // For testing notifications
 }
@@ -187,6 +188,22 @@ impl Notification {
         .with_timeout(NotificationTimeout::Seconds(10))
     }
 
+    pub fn homework_reminder(description: &str, due_date: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        let body = match due_date {
+            Some(due) if due <= chrono::Utc::now() => format!("Homework overdue: {}", description),
+            Some(due) => format!(
+                "Homework due {}: {}",
+                due.with_timezone(&chrono::Local).format("%a %H:%M"),
+                description
+            ),
+            None => format!("Homework open: {}", description),
+        };
+
+        Notification::new("Uchitil Live", body, NotificationType::HomeworkReminder)
+            .with_priority(NotificationPriority::Normal)
+            .with_timeout(NotificationTimeout::Seconds(10))
+    }
+
     pub fn system_error(error: impl Into<String>) -> Self {
         let error_string = error.into();
         Notification::new(
@@ -4,6 +4,7 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
+use crate::network::{self, Channel};
 use regex::Regex;
 use once_cell::sync::Lazy;
 
@@ -144,6 +145,7 @@ async fn fetch_model_info(
     let client = Client::new();
     let base_url = endpoint.unwrap_or("http://localhost:11434");
     let url = format!("{}/api/show", base_url);
+    network::check(&url, Channel::ModelList)?;
 
     let payload = serde_json::json!({
         "name": model_name,
@@ -9,6 +9,7 @@ use tokio::sync::RwLock;
 use futures_util::StreamExt;
 use once_cell::sync::Lazy;
 use crate::ollama::metadata::ModelMetadataCache;
+use crate::network::{self, Channel};
 
 // Global set to track models currently being downloaded
 static DOWNLOADING_MODELS: Lazy<Arc<RwLock<HashSet<String>>>> = Lazy::new(|| {
@@ -162,6 +163,7 @@ async fn get_models_via_http_async(endpoint: Option<&str>) -> Result<Vec<OllamaM
     let client = Client::new();
     let base_url = endpoint.unwrap_or("http://localhost:11434");
     let url = format!("{}/api/tags", base_url);
+    network::check(&url, Channel::ModelList)?;
 
     let response = client
         .get(&url)
@@ -271,6 +273,11 @@ pub async fn pull_ollama_model<R: Runtime>(
         }
     }
 
+    network::check(
+        endpoint.as_deref().unwrap_or("http://localhost:11434"),
+        Channel::ModelDownload,
+    )?;
+
     // Mark model as downloading
     {
         let mut downloading = DOWNLOADING_MODELS.write().await;
@@ -440,6 +447,7 @@ pub async fn delete_ollama_model(
     let client = Client::new();
     let base_url = endpoint.as_deref().unwrap_or("http://localhost:11434");
     let url = format!("{}/api/delete", base_url);
+    network::check(&url, Channel::ModelList)?;
 
     let payload = serde_json::json!({
         "name": model_name
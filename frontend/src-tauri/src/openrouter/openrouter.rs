@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use reqwest::blocking::Client;
+use crate::network::{self, Channel};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenRouterModel {
@@ -33,6 +34,8 @@ struct Pricing {
     completion: Option<String>,
 }
 
+const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
+
 #[derive(Debug, Deserialize)]
 struct OpenRouterResponse {
     data: Vec<OpenRouterApiModel>,
@@ -40,9 +43,10 @@ struct OpenRouterResponse {
 
 #[command]
 pub fn get_openrouter_models() -> Result<Vec<OpenRouterModel>, String> {
+    network::check(OPENROUTER_MODELS_URL, Channel::ModelList)?;
     let client = Client::new();
     let response = client
-        .get("https://openrouter.ai/api/v1/models")
+        .get(OPENROUTER_MODELS_URL)
         .send()
         .map_err(|e| format!("Failed to make HTTP request: {}", e))?;
 
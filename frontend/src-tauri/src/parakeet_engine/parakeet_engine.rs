@@ -1,3 +1,4 @@
+use crate::network::{self, Channel};
 use crate::parakeet_engine::model::ParakeetModel;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
@@ -582,14 +583,6 @@ impl ParakeetEngine {
             }
         };
 
-        // Update model status to downloading
-        {
-            let mut models = self.available_models.write().await;
-            if let Some(model) = models.get_mut(model_name) {
-                model.status = ModelStatus::Downloading { progress: 0 };
-            }
-        }
-
         // HuggingFace base URL for Parakeet models (version-specific)
         let base_url = if model_name.contains("-v2-") {
             "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v2-onnx/resolve/main"
@@ -598,6 +591,19 @@ impl ParakeetEngine {
             "https://meetily.towardsgeneralintelligence.com/models/parakeet-tdt-0.6b-v3-onnx"
         };
 
+        if let Err(e) = network::check(base_url, Channel::ModelDownload) {
+            self.active_downloads.write().await.remove(model_name);
+            return Err(e.into());
+        }
+
+        // Update model status to downloading
+        {
+            let mut models = self.available_models.write().await;
+            if let Some(model) = models.get_mut(model_name) {
+                model.status = ModelStatus::Downloading { progress: 0 };
+            }
+        }
+
         // Determine which files to download based on quantization
         let files_to_download = match model_info.quantization {
             QuantizationType::Int8 => vec![
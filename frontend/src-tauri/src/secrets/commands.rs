@@ -0,0 +1,43 @@
+use crate::database::repositories::setting::SettingsRepository;
+use crate::secrets::store::{secret_store, SecretStoreStatus};
+use crate::state::AppState;
+use log::{error as log_error, info as log_info};
+use tauri::{AppHandle, Manager, Runtime};
+
+#[tauri::command]
+pub async fn api_get_secret_store_status() -> Result<SecretStoreStatus, String> {
+    Ok(secret_store().map_err(|e| e.to_string())?.status())
+}
+
+/// Unlocks the encrypted file store, setting the master password on first
+/// use, then moves any API keys still in the database into it
+#[tauri::command]
+pub async fn api_unlock_secret_store<R: Runtime>(
+    app: AppHandle<R>,
+    master_password: String,
+) -> Result<(), String> {
+    log_info!("api_unlock_secret_store called");
+    secret_store()
+        .and_then(|store| store.unlock(&master_password))
+        .map_err(|e| e.to_string())?;
+
+    if let Some(state) = app.try_state::<AppState>() {
+        match SettingsRepository::migrate_api_keys_to_secret_store(state.db_manager.pool()).await {
+            Ok(0) => {}
+            Ok(moved) => log_info!("Moved {} API key(s) to the secret store", moved),
+            Err(e) => log_error!("Failed to move API keys to the secret store: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn api_change_secret_store_master_password(
+    current_password: String,
+    new_password: String,
+) -> Result<(), String> {
+    log_info!("api_change_secret_store_master_password called");
+    secret_store()
+        .and_then(|store| store.change_master_password(&current_password, &new_password))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,301 @@
+use crate::encryption::cipher::{self, KEY_LEN};
+use crate::encryption::vault::{derive_key, from_hex, to_hex, KdfParams, MIN_PASSPHRASE_CHARS};
+use crate::secrets::store::{SecretBackend, SecretStore, SecretStoreError, SecretStoreStatus};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zeroize::Zeroize;
+
+const SECRETS_FILE: &str = "secrets.json";
+
+const FILE_VERSION: u32 = 1;
+
+const SALT_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretsFile {
+    version: u32,
+    kdf: KdfParams,
+    /// Argon2 salt (hex)
+    salt: String,
+    /// The secrets as a JSON object, sealed with the master password key (hex)
+    secrets: String,
+}
+
+/// The decrypted secrets and the key to save them with, wiped when dropped
+struct Unlocked {
+    key: [u8; KEY_LEN],
+    kdf: KdfParams,
+    salt: Vec<u8>,
+    secrets: BTreeMap<String, String>,
+}
+
+impl Drop for Unlocked {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        for value in self.secrets.values_mut() {
+            value.zeroize();
+        }
+    }
+}
+
+/// Secrets in a file encrypted with a key derived from a master password,
+/// for systems without an OS keyring
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    kdf: KdfParams,
+    state: Mutex<Option<Unlocked>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(dir: &Path) -> Self {
+        Self::with_kdf(dir, KdfParams::default())
+    }
+
+    fn with_kdf(dir: &Path, kdf: KdfParams) -> Self {
+        Self {
+            path: dir.join(SECRETS_FILE),
+            kdf,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Whether secrets were saved to a file in `dir`
+    pub fn exists_in(dir: &Path) -> bool {
+        dir.join(SECRETS_FILE).exists()
+    }
+
+    fn read_file(&self) -> Result<Option<SecretsFile>, SecretStoreError> {
+        match fs::read(&self.path) {
+            Ok(json) => serde_json::from_slice(&json)
+                .map(Some)
+                .map_err(|e| SecretStoreError::Damaged(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn open(file: &SecretsFile, master_password: &str) -> Result<Unlocked, SecretStoreError> {
+        let damaged = |what: &str| SecretStoreError::Damaged(format!("invalid {}", what));
+        let salt = from_hex(&file.salt).ok_or_else(|| damaged("salt"))?;
+        let sealed = from_hex(&file.secrets).ok_or_else(|| damaged("secrets"))?;
+
+        let key =
+            derive_key(master_password, &file.kdf, &salt).map_err(SecretStoreError::Damaged)?;
+        let mut unlocked = Unlocked {
+            key,
+            kdf: file.kdf,
+            salt,
+            secrets: BTreeMap::new(),
+        };
+        let mut json =
+            cipher::open(&unlocked.key, &sealed).map_err(|_| SecretStoreError::WrongPassword)?;
+        let secrets = serde_json::from_slice(&json);
+        json.zeroize();
+        unlocked.secrets = secrets.map_err(|_| damaged("secrets"))?;
+        Ok(unlocked)
+    }
+
+    /// A new key for `master_password` under a fresh salt
+    fn new_unlocked(
+        &self,
+        master_password: &str,
+        secrets: BTreeMap<String, String>,
+    ) -> Result<Unlocked, SecretStoreError> {
+        if master_password.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(SecretStoreError::InvalidPassword(format!(
+                "Master password must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            )));
+        }
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key =
+            derive_key(master_password, &self.kdf, &salt).map_err(SecretStoreError::Damaged)?;
+        Ok(Unlocked {
+            key,
+            kdf: self.kdf,
+            salt,
+            secrets,
+        })
+    }
+
+    fn save(&self, unlocked: &Unlocked) -> Result<(), SecretStoreError> {
+        let mut json = serde_json::to_vec(&unlocked.secrets)
+            .map_err(|e| SecretStoreError::Damaged(e.to_string()))?;
+        let sealed = cipher::seal(&unlocked.key, &json);
+        json.zeroize();
+        let file = SecretsFile {
+            version: FILE_VERSION,
+            kdf: unlocked.kdf,
+            salt: to_hex(&unlocked.salt),
+            secrets: to_hex(&sealed.map_err(|e| SecretStoreError::Damaged(e.to_string()))?),
+        };
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| SecretStoreError::Damaged(e.to_string()))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Applies `change` to the secrets and saves them
+    fn update(
+        &self,
+        change: impl FnOnce(&mut BTreeMap<String, String>),
+    ) -> Result<(), SecretStoreError> {
+        let mut state = self.state.lock().unwrap();
+        let unlocked = state.as_mut().ok_or(SecretStoreError::Locked)?;
+        let previous = unlocked.secrets.clone();
+        change(&mut unlocked.secrets);
+        if let Err(e) = self.save(unlocked) {
+            unlocked.secrets = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn status(&self) -> SecretStoreStatus {
+        SecretStoreStatus {
+            backend: SecretBackend::EncryptedFile,
+            unlocked: self.state.lock().unwrap().is_some(),
+            has_master_password: self.path.exists(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError> {
+        let state = self.state.lock().unwrap();
+        let unlocked = state.as_ref().ok_or(SecretStoreError::Locked)?;
+        Ok(unlocked.secrets.get(name).cloned())
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretStoreError> {
+        self.update(|secrets| {
+            secrets.insert(name.to_string(), value.to_string());
+        })
+    }
+
+    fn delete(&self, name: &str) -> Result<(), SecretStoreError> {
+        self.update(|secrets| {
+            secrets.remove(name);
+        })
+    }
+
+    fn unlock(&self, master_password: &str) -> Result<(), SecretStoreError> {
+        let unlocked = match self.read_file()? {
+            Some(file) => Self::open(&file, master_password)?,
+            None => {
+                let unlocked = self.new_unlocked(master_password, BTreeMap::new())?;
+                self.save(&unlocked)?;
+                unlocked
+            }
+        };
+        *self.state.lock().unwrap() = Some(unlocked);
+        Ok(())
+    }
+
+    fn change_master_password(&self, current: &str, new: &str) -> Result<(), SecretStoreError> {
+        let file = self.read_file()?.ok_or_else(|| {
+            SecretStoreError::InvalidPassword("No master password is set".to_string())
+        })?;
+        let current = Self::open(&file, current)?;
+        let unlocked = self.new_unlocked(new, current.secrets.clone())?;
+        self.save(&unlocked)?;
+        *self.state.lock().unwrap() = Some(unlocked);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap Argon2 cost, so the tests stay fast
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_secrets_survive_restart_behind_master_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileStore::with_kdf(dir.path(), TEST_KDF);
+        assert!(!store.status().has_master_password);
+        assert!(matches!(
+            store.get("summary.openai"),
+            Err(SecretStoreError::Locked)
+        ));
+        assert!(matches!(
+            store.unlock("short"),
+            Err(SecretStoreError::InvalidPassword(_))
+        ));
+
+        store.unlock("correct horse").unwrap();
+        store.set("summary.openai", "sk-file-store-test").unwrap();
+        store
+            .set("transcript.deepgram", "dg-file-store-test")
+            .unwrap();
+        store.delete("transcript.deepgram").unwrap();
+        store.delete("summary.missing").unwrap();
+
+        let raw = fs::read_to_string(dir.path().join(SECRETS_FILE)).unwrap();
+        assert!(!raw.contains("sk-file-store-test"));
+
+        let restarted = EncryptedFileStore::with_kdf(dir.path(), TEST_KDF);
+        assert_eq!(
+            restarted.status(),
+            SecretStoreStatus {
+                backend: SecretBackend::EncryptedFile,
+                unlocked: false,
+                has_master_password: true,
+            }
+        );
+        assert!(matches!(
+            restarted.unlock("wrong horse"),
+            Err(SecretStoreError::WrongPassword)
+        ));
+        restarted.unlock("correct horse").unwrap();
+        assert_eq!(
+            restarted.get("summary.openai").unwrap().as_deref(),
+            Some("sk-file-store-test")
+        );
+        assert_eq!(restarted.get("transcript.deepgram").unwrap(), None);
+    }
+
+    #[test]
+    fn test_change_master_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileStore::with_kdf(dir.path(), TEST_KDF);
+        store.unlock("correct horse").unwrap();
+        store
+            .set("summary.claude", "sk-ant-file-store-test")
+            .unwrap();
+
+        assert!(matches!(
+            store.change_master_password("wrong horse", "battery staple"),
+            Err(SecretStoreError::WrongPassword)
+        ));
+        store
+            .change_master_password("correct horse", "battery staple")
+            .unwrap();
+
+        let restarted = EncryptedFileStore::with_kdf(dir.path(), TEST_KDF);
+        assert!(restarted.unlock("correct horse").is_err());
+        restarted.unlock("battery staple").unwrap();
+        assert_eq!(
+            restarted.get("summary.claude").unwrap().as_deref(),
+            Some("sk-ant-file-store-test")
+        );
+    }
+}
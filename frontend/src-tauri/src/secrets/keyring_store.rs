@@ -0,0 +1,61 @@
+use crate::secrets::store::{SecretBackend, SecretStore, SecretStoreError, SecretStoreStatus};
+use keyring::{Entry, Error as KeyringError};
+use tracing::warn;
+
+/// Service the secrets are filed under in the OS keyring
+const SERVICE: &str = "com.uchitil-live.app";
+
+/// Entry looked up to check that the keyring works
+const PROBE_NAME: &str = "availability-check";
+
+/// Secrets in the OS keyring (macOS Keychain, Windows Credential Manager,
+/// Secret Service on Linux)
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// Whether the OS keyring can be used, e.g. a Secret Service is running
+    pub fn is_available() -> bool {
+        match Entry::new(SERVICE, PROBE_NAME).and_then(|entry| entry.get_password()) {
+            Ok(_) | Err(KeyringError::NoEntry) => true,
+            Err(e) => {
+                warn!("OS keyring is not available: {}", e);
+                false
+            }
+        }
+    }
+
+    fn entry(name: &str) -> Result<Entry, SecretStoreError> {
+        Entry::new(SERVICE, name).map_err(|e| SecretStoreError::Keyring(e.to_string()))
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn status(&self) -> SecretStoreStatus {
+        SecretStoreStatus {
+            backend: SecretBackend::Keyring,
+            unlocked: true,
+            has_master_password: true,
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(KeyringError::NoEntry) => Ok(None),
+            Err(e) => Err(SecretStoreError::Keyring(e.to_string())),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretStoreError> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| SecretStoreError::Keyring(e.to_string()))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), SecretStoreError> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+            Err(e) => Err(SecretStoreError::Keyring(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+/// Secrets module - API keys and other credentials outside the database
+///
+/// This module contains:
+/// - The `SecretStore` trait and the store chosen at startup
+/// - An OS keyring backend, used when the system has one
+/// - An encrypted file backend behind a master password, for systems without
+/// - Removal of known secret values from log messages
+/// - Tauri commands for the store status and master password
+
+pub mod commands;
+pub mod file_store;
+pub mod keyring_store;
+pub mod redact;
+pub mod store;
+
+pub use store::{
+    delete_secret, get_secret, secret_store, set_secret, SecretBackend, SecretStore,
+    SecretStoreError, SecretStoreStatus,
+};
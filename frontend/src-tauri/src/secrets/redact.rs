@@ -0,0 +1,78 @@
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::RwLock;
+
+/// Shown in place of a secret
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Shorter values are too likely to match ordinary text
+const MIN_SECRET_LEN: usize = 8;
+
+/// Secret values seen by this process
+static KNOWN_SECRETS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Marks a value as secret, so `scrub` removes it from text
+pub fn register(value: &str) {
+    let value = value.trim();
+    if value.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut known = KNOWN_SECRETS.write().unwrap();
+    if !known.iter().any(|known| known == value) {
+        known.push(value.to_string());
+        // Longest first, so a secret containing another is removed whole
+        known.sort_by_key(|known| std::cmp::Reverse(known.len()));
+    }
+}
+
+/// Replaces every known secret in `text` with `REDACTED`
+pub fn scrub(text: &str) -> Cow<'_, str> {
+    let known = KNOWN_SECRETS.read().unwrap();
+    let mut scrubbed = Cow::Borrowed(text);
+    for secret in known.iter() {
+        if scrubbed.contains(secret.as_str()) {
+            scrubbed = Cow::Owned(scrubbed.replace(secret.as_str(), REDACTED));
+        }
+    }
+    scrubbed
+}
+
+/// Installs the logger, scrubbing known secrets from every message
+pub fn init_logger() {
+    env_logger::Builder::from_default_env()
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "[{} {:<5} {}] {}",
+                buf.timestamp(),
+                record.level(),
+                record.target(),
+                scrub(&record.args().to_string())
+            )
+        })
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_removes_registered_secrets() {
+        register("sk-test-0123456789abcdef");
+        register("sk-test-0123456789abcdef-longer");
+        register("short");
+
+        assert_eq!(
+            scrub("Request failed: invalid key sk-test-0123456789abcdef-longer (401)"),
+            "Request failed: invalid key [REDACTED] (401)"
+        );
+        assert_eq!(
+            scrub("GET /models?key=sk-test-0123456789abcdef"),
+            "GET /models?key=[REDACTED]"
+        );
+        assert_eq!(scrub("a short message"), "a short message");
+        assert!(matches!(scrub("nothing secret here"), Cow::Borrowed(_)));
+    }
+}
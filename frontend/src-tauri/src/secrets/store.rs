@@ -0,0 +1,119 @@
+use crate::secrets::file_store::EncryptedFileStore;
+use crate::secrets::keyring_store::KeyringStore;
+use crate::secrets::redact;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+static SECRET_STORE: Lazy<RwLock<Option<Arc<dyn SecretStore>>>> = Lazy::new(|| RwLock::new(None));
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretStoreError {
+    #[error("Secret store is not initialized")]
+    NotInitialized,
+    #[error("Secret store is locked, enter the master password in settings")]
+    Locked,
+    #[error("Wrong master password")]
+    WrongPassword,
+    #[error("{0}")]
+    InvalidPassword(String),
+    #[error("Secret store does not support this")]
+    Unsupported,
+    #[error("Secret store is damaged: {0}")]
+    Damaged(String),
+    #[error("OS keyring error: {0}")]
+    Keyring(String),
+    #[error("Secret store I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretBackend {
+    Keyring,
+    EncryptedFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStoreStatus {
+    pub backend: SecretBackend,
+    pub unlocked: bool,
+    /// Whether a master password was set (always true for the keyring)
+    pub has_master_password: bool,
+}
+
+/// Storage for API keys and other credentials, outside the database
+///
+/// Secrets are addressed by name, e.g. `summary.openai`.
+pub trait SecretStore: Send + Sync {
+    fn status(&self) -> SecretStoreStatus;
+
+    fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError>;
+
+    fn set(&self, name: &str, value: &str) -> Result<(), SecretStoreError>;
+
+    /// Removes a secret; removing a missing secret is not an error
+    fn delete(&self, name: &str) -> Result<(), SecretStoreError>;
+
+    /// Unlocks the store, setting the master password on first use
+    fn unlock(&self, _master_password: &str) -> Result<(), SecretStoreError> {
+        Ok(())
+    }
+
+    fn change_master_password(&self, _current: &str, _new: &str) -> Result<(), SecretStoreError> {
+        Err(SecretStoreError::Unsupported)
+    }
+}
+
+/// Picks the backend: the OS keyring when available, else an encrypted file
+/// in `dir`
+///
+/// Once secrets were saved to the file it stays in use, as they can't be
+/// moved to the keyring without the master password.
+pub fn init(dir: &Path) {
+    let store: Arc<dyn SecretStore> =
+        if !EncryptedFileStore::exists_in(dir) && KeyringStore::is_available() {
+            Arc::new(KeyringStore)
+        } else {
+            Arc::new(EncryptedFileStore::new(dir))
+        };
+    info!("Using secret store: {:?}", store.status().backend);
+    *SECRET_STORE.write().unwrap() = Some(store);
+}
+
+/// The secret store chosen at startup
+pub fn secret_store() -> Result<Arc<dyn SecretStore>, SecretStoreError> {
+    SECRET_STORE
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or(SecretStoreError::NotInitialized)
+}
+
+/// Replaces the store chosen at startup
+#[cfg(test)]
+pub(crate) fn set_secret_store(store: Arc<dyn SecretStore>) {
+    *SECRET_STORE.write().unwrap() = Some(store);
+}
+
+/// Reads a secret, marking its value for removal from logs
+pub fn get_secret(name: &str) -> Result<Option<String>, SecretStoreError> {
+    let value = secret_store()?.get(name)?;
+    if let Some(value) = &value {
+        redact::register(value);
+    }
+    Ok(value)
+}
+
+/// Saves a secret, marking its value for removal from logs
+pub fn set_secret(name: &str, value: &str) -> Result<(), SecretStoreError> {
+    redact::register(value);
+    secret_store()?.set(name, value)
+}
+
+pub fn delete_secret(name: &str) -> Result<(), SecretStoreError> {
+    secret_store()?.delete(name)
+}
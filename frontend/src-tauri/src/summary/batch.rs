@@ -0,0 +1,574 @@
+use crate::database::models::SummaryBatch;
+use crate::database::repositories::{
+    meeting::MeetingsRepository,
+    summary::SummaryProcessesRepository,
+    summary_batch::SummaryBatchesRepository,
+    summary_job::{NewSummaryJob, SummaryJobsRepository},
+    transcript::TranscriptsRepository,
+    transcript_chunk::TranscriptChunksRepository,
+};
+use crate::summary::job_queue;
+use crate::summary::service::SummaryService;
+use crate::summary::versions;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{info, warn};
+
+/// Event emitted with a `BatchProgress` whenever a job of a batch finishes
+pub const BATCH_PROGRESS_EVENT: &str = "summary-batch-progress";
+
+/// Filters selecting the sessions of a batch; all given filters must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSelection {
+    /// Only sessions created at or after this time
+    pub start_date: Option<DateTime<Utc>>,
+    /// Only sessions created at or before this time
+    pub end_date: Option<DateTime<Utc>>,
+    /// Only sessions with this tag (case-insensitive)
+    pub tag: Option<String>,
+    /// Only sessions whose title or transcript contains this text
+    pub query: Option<String>,
+    /// At most this many sessions, most recent first
+    pub limit: Option<i64>,
+}
+
+impl BatchSelection {
+    fn tag(&self) -> Option<&str> {
+        self.tag.as_deref().map(str::trim).filter(|t| !t.is_empty())
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.query
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+    }
+
+    /// Rejects selections without any filter, so a batch never regenerates every session by accident
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.tag().is_none()
+            && self.query().is_none()
+            && self.limit.is_none()
+        {
+            return Err("Select sessions by date range, tag, search query or a limit".to_string());
+        }
+        if self.limit.is_some_and(|limit| limit <= 0) {
+            return Err("Limit must be greater than 0".to_string());
+        }
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if start > end {
+                return Err("Start date must be before end date".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the sessions of a batch are regenerated
+#[derive(Debug, Clone, Copy)]
+pub struct BatchSettings<'a> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub template_id: &'a str,
+    /// Context added to the prompt of every session
+    pub custom_prompt: &'a str,
+    /// Number of earlier sessions whose notes are passed as context
+    pub previous_session_count: i64,
+}
+
+/// A session selected for a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSession {
+    pub meeting_id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Progress of a batch, counted from its jobs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    /// `running`, `completed` or `cancelled`
+    pub status: String,
+    /// Sessions queued by the batch
+    pub total: i64,
+    /// Selected sessions that couldn't be queued (no transcript, or already generating)
+    pub skipped: i64,
+    pub queued: i64,
+    pub running: i64,
+    /// Sessions regenerated successfully
+    pub done: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+}
+
+impl BatchProgress {
+    fn from_counts(batch: &SummaryBatch, counts: Vec<(String, i64)>) -> BatchProgress {
+        let mut progress = BatchProgress {
+            batch_id: batch.id.clone(),
+            status: batch.status.clone(),
+            total: batch.total,
+            skipped: batch.skipped,
+            ..Default::default()
+        };
+        for (status, count) in counts {
+            match status.as_str() {
+                "queued" => progress.queued += count,
+                "running" => progress.running += count,
+                "done" => progress.done += count,
+                "failed" => progress.failed += count,
+                "cancelled" => progress.cancelled += count,
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    /// Whether every job of the batch has finished
+    pub fn is_finished(&self) -> bool {
+        self.queued == 0 && self.running == 0
+    }
+}
+
+/// Lists the sessions a selection matches, most recent first
+pub async fn select_sessions(
+    pool: &SqlitePool,
+    selection: &BatchSelection,
+) -> Result<Vec<BatchSession>, String> {
+    selection.validate()?;
+    let meetings = MeetingsRepository::find_meetings(
+        pool,
+        selection.start_date,
+        selection.end_date,
+        selection.tag(),
+        selection.query(),
+        selection.limit,
+    )
+    .await
+    .map_err(|e| format!("Failed to select sessions: {}", e))?;
+
+    Ok(meetings
+        .into_iter()
+        .map(|m| BatchSession {
+            meeting_id: m.id,
+            title: m.title,
+            created_at: m.created_at.0,
+        })
+        .collect())
+}
+
+/// Gets the progress of a batch
+pub async fn batch_progress(pool: &SqlitePool, batch_id: &str) -> Result<BatchProgress, String> {
+    let batch = SummaryBatchesRepository::get_batch(pool, batch_id)
+        .await
+        .map_err(|e| format!("Failed to load batch: {}", e))?
+        .ok_or_else(|| format!("Batch {} not found", batch_id))?;
+    let counts = SummaryJobsRepository::count_batch_jobs(pool, batch_id)
+        .await
+        .map_err(|e| format!("Failed to count batch jobs: {}", e))?;
+    Ok(BatchProgress::from_counts(&batch, counts))
+}
+
+/// Regenerates the summaries of all selected sessions with the given settings
+///
+/// For each session the current summary is recorded in the version history
+/// first, then a summary job is queued. Sessions without a transcript or with a
+/// summary being generated right now are skipped.
+pub async fn start_batch<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    selection: &BatchSelection,
+    settings: BatchSettings<'_>,
+) -> Result<BatchProgress, String> {
+    let sessions = select_sessions(pool, selection).await?;
+    if sessions.is_empty() {
+        return Err("No sessions match the selection".to_string());
+    }
+
+    let selection_json = serde_json::to_string(selection).unwrap_or_default();
+    let batch = SummaryBatchesRepository::create_batch(
+        pool,
+        settings.provider,
+        settings.model,
+        settings.template_id,
+        &selection_json,
+    )
+    .await
+    .map_err(|e| format!("Failed to create batch: {}", e))?;
+    info!(
+        "Starting batch {} over {} session(s) with {} / {} / {}",
+        batch.id,
+        sessions.len(),
+        settings.provider,
+        settings.model,
+        settings.template_id
+    );
+
+    let mut total = 0;
+    let mut skipped = 0;
+    for session in &sessions {
+        match queue_session(app, pool, &batch.id, &session.meeting_id, settings).await {
+            Ok(()) => total += 1,
+            Err(e) => {
+                warn!(
+                    "Skipping {} in batch {}: {}",
+                    session.meeting_id, batch.id, e
+                );
+                skipped += 1;
+            }
+        }
+    }
+
+    SummaryBatchesRepository::set_counts(pool, &batch.id, total, skipped)
+        .await
+        .map_err(|e| format!("Failed to update batch: {}", e))?;
+    if total == 0 {
+        let _ = SummaryBatchesRepository::finish_batch(pool, &batch.id, "completed").await;
+    }
+
+    let progress = batch_progress(pool, &batch.id).await?;
+    emit_progress(app, &progress);
+    Ok(progress)
+}
+
+/// Snapshots a session's current summary and queues its regeneration
+async fn queue_session<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    batch_id: &str,
+    meeting_id: &str,
+    settings: BatchSettings<'_>,
+) -> Result<(), String> {
+    job_queue::ensure_not_running(pool, meeting_id).await?;
+
+    // Reuse the transcript of the last generation, or build it from the segments
+    let text = match TranscriptChunksRepository::get_transcript_text(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load transcript: {}", e))?
+    {
+        Some(text) if !text.trim().is_empty() => text,
+        _ => TranscriptsRepository::get_full_transcript_text(pool, meeting_id)
+            .await
+            .map_err(|e| format!("Failed to load transcript: {}", e))?,
+    };
+    if text.trim().is_empty() {
+        return Err("no transcript".to_string());
+    }
+
+    versions::snapshot_current_result(pool, meeting_id).await?;
+
+    SummaryProcessesRepository::create_or_reset_process(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to initialize process: {}", e))?;
+    TranscriptChunksRepository::save_transcript_data(
+        pool,
+        meeting_id,
+        &text,
+        settings.provider,
+        settings.model,
+        40000,
+        1000,
+    )
+    .await
+    .map_err(|e| format!("Failed to save transcript data: {}", e))?;
+
+    job_queue::enqueue_summary(
+        app,
+        pool,
+        NewSummaryJob {
+            meeting_id,
+            provider: settings.provider,
+            model: settings.model,
+            template_id: settings.template_id,
+            custom_prompt: settings.custom_prompt,
+            previous_session_count: settings.previous_session_count,
+            max_attempts: job_queue::MAX_ATTEMPTS,
+            batch_id: Some(batch_id),
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Cancels a batch: queued jobs are dropped and running ones are cancelled
+pub async fn cancel_batch<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    batch_id: &str,
+) -> Result<BatchProgress, String> {
+    let finished = SummaryBatchesRepository::finish_batch(pool, batch_id, "cancelled")
+        .await
+        .map_err(|e| format!("Failed to cancel batch: {}", e))?;
+    if !finished {
+        return Err("Batch is not running".to_string());
+    }
+
+    // Only the batch's own jobs: a session may have been queued again outside the batch
+    let mut cancelled = job_queue::cancel_queued_batch(app, pool, batch_id).await?;
+    let running = SummaryJobsRepository::list_running_batch_jobs(pool, batch_id)
+        .await
+        .map_err(|e| format!("Failed to load batch jobs: {}", e))?;
+    for job in running {
+        if SummaryService::cancel_summary(&job.meeting_id) {
+            cancelled.push(job.meeting_id);
+        }
+    }
+    for meeting_id in &cancelled {
+        if let Err(e) = SummaryProcessesRepository::update_process_cancelled(pool, meeting_id).await
+        {
+            warn!(
+                "Failed to update DB status to cancelled for {}: {}",
+                meeting_id, e
+            );
+        }
+    }
+    info!(
+        "Cancelled batch {} ({} active job(s))",
+        batch_id,
+        cancelled.len()
+    );
+
+    let progress = batch_progress(pool, batch_id).await?;
+    emit_progress(app, &progress);
+    Ok(progress)
+}
+
+/// Reports progress after a batch job finished and completes the batch after its last job
+pub async fn on_batch_job_finished<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    batch_id: &str,
+) {
+    let mut progress = match batch_progress(pool, batch_id).await {
+        Ok(progress) => progress,
+        Err(e) => {
+            warn!("{}", e);
+            return;
+        }
+    };
+
+    if progress.is_finished() && progress.status == "running" {
+        match SummaryBatchesRepository::finish_batch(pool, batch_id, "completed").await {
+            Ok(_) => {
+                progress.status = "completed".to_string();
+                info!(
+                    "Batch {} completed: {} done, {} failed, {} cancelled, {} skipped",
+                    batch_id, progress.done, progress.failed, progress.cancelled, progress.skipped
+                );
+            }
+            Err(e) => warn!("Failed to complete batch {}: {}", batch_id, e),
+        }
+    }
+
+    emit_progress(app, &progress);
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, progress: &BatchProgress) {
+    if let Err(e) = app.emit(BATCH_PROGRESS_EVENT, progress) {
+        warn!("Failed to emit {}: {}", BATCH_PROGRESS_EVENT, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manager::DatabaseManager;
+    use crate::database::repositories::meeting_tag::MeetingTagsRepository;
+    use chrono::TimeZone;
+
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 10, 0, 0).unwrap()
+    }
+
+    async fn insert_session(
+        pool: &SqlitePool,
+        id: &str,
+        title: &str,
+        day: u32,
+        transcript: Option<&str>,
+    ) {
+        sqlx::query(
+            "INSERT INTO meetings (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        )
+        .bind(id)
+        .bind(title)
+        .bind(self::day(day))
+        .execute(pool)
+        .await
+        .unwrap();
+        if let Some(transcript) = transcript {
+            sqlx::query("INSERT INTO transcripts (id, meeting_id, transcript, timestamp) VALUES (?1, ?2, ?3, ?4)")
+                .bind(format!("{}-t", id))
+                .bind(id)
+                .bind(transcript)
+                .bind(self::day(day))
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn selected(pool: &SqlitePool, selection: BatchSelection) -> Vec<String> {
+        select_sessions(pool, &selection)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|session| session.meeting_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_select_sessions_by_date_tag_and_query() {
+        let pool = DatabaseManager::test_pool().await;
+        insert_session(&pool, "m1", "Lesson 1", 1, Some("Hola, ¿qué tal?")).await;
+        insert_session(&pool, "m2", "Lesson 2", 5, Some("El pretérito indefinido")).await;
+        insert_session(
+            &pool,
+            "m3",
+            "Subjuntivo review",
+            9,
+            Some("Ojalá que llueva"),
+        )
+        .await;
+        // Sessions without a transcript can't be regenerated
+        insert_session(&pool, "m4", "Lesson 4", 7, None).await;
+        MeetingTagsRepository::set_tags(&pool, "m1", &["Spanish".to_string()])
+            .await
+            .unwrap();
+        MeetingTagsRepository::set_tags(&pool, "m3", &["spanish".to_string(), "B1".to_string()])
+            .await
+            .unwrap();
+
+        let by_date = BatchSelection {
+            start_date: Some(day(2)),
+            end_date: Some(day(9)),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, by_date).await, ["m3", "m2"]);
+
+        let by_tag = BatchSelection {
+            tag: Some(" SPANISH ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, by_tag).await, ["m3", "m1"]);
+
+        // Matches titles and transcripts, ignoring case
+        let by_query = BatchSelection {
+            query: Some("Pretérito".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, by_query).await, ["m2"]);
+        let by_title = BatchSelection {
+            query: Some("subjuntivo".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, by_title).await, ["m3"]);
+
+        let combined = BatchSelection {
+            end_date: Some(day(8)),
+            tag: Some("spanish".to_string()),
+            limit: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, combined).await, ["m1"]);
+
+        let limited = BatchSelection {
+            limit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, limited).await, ["m3", "m2"]);
+    }
+
+    #[tokio::test]
+    async fn test_select_sessions_query_matches_wildcards_literally() {
+        let pool = DatabaseManager::test_pool().await;
+        insert_session(&pool, "m1", "Quiz", 1, Some("Scored 100% on the quiz")).await;
+        insert_session(
+            &pool,
+            "m2",
+            "Quiz retake",
+            2,
+            Some("Scored 1000 on the quiz"),
+        )
+        .await;
+        insert_session(&pool, "m3", "Verbs", 3, Some("ser_estar drills")).await;
+        insert_session(&pool, "m4", "Verbs again", 4, Some("ser y estar")).await;
+
+        let percent = BatchSelection {
+            query: Some("100%".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, percent).await, ["m1"]);
+        let underscore = BatchSelection {
+            query: Some("ser_".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pool, underscore).await, ["m3"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_batch_jobs_keeps_other_jobs() {
+        let pool = DatabaseManager::test_pool().await;
+        for (id, batch_id) in [
+            ("m1", Some("batch-a")),
+            ("m2", Some("batch-b")),
+            ("m3", None),
+        ] {
+            insert_session(&pool, id, id, 1, Some("Hola")).await;
+            SummaryJobsRepository::enqueue(
+                &pool,
+                NewSummaryJob {
+                    meeting_id: id,
+                    provider: "ollama",
+                    model: "llama3.2:latest",
+                    template_id: "standard_meeting",
+                    custom_prompt: "",
+                    previous_session_count: 0,
+                    max_attempts: 3,
+                    batch_id,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let cancelled = SummaryJobsRepository::cancel_queued_batch_jobs(&pool, "batch-a")
+            .await
+            .unwrap();
+        assert_eq!(cancelled, ["m1"]);
+        for (id, status) in [("m1", None), ("m2", Some("queued")), ("m3", Some("queued"))] {
+            let job = SummaryJobsRepository::get_active_job(&pool, id)
+                .await
+                .unwrap();
+            assert_eq!(job.map(|job| job.status).as_deref(), status);
+        }
+    }
+
+    #[test]
+    fn test_selection_requires_a_filter() {
+        assert!(BatchSelection::default().validate().is_err());
+        assert!(BatchSelection {
+            tag: Some("  ".to_string()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(BatchSelection {
+            limit: Some(50),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+        assert!(BatchSelection {
+            start_date: Some(Utc::now()),
+            end_date: Some(Utc::now() - chrono::Duration::days(1)),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
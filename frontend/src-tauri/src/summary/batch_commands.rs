@@ -0,0 +1,96 @@
+use crate::database::models::SummaryBatch;
+use crate::database::repositories::summary_batch::SummaryBatchesRepository;
+use crate::state::AppState;
+use crate::summary::batch::{self, BatchProgress, BatchSelection, BatchSession, BatchSettings};
+use log::{error as log_error, info as log_info};
+use tauri::{AppHandle, Runtime};
+
+/// Number of batches returned by `api_list_summary_batches`
+const LISTED_BATCHES: i64 = 20;
+
+/// Lists the sessions a batch with this selection would regenerate
+#[tauri::command]
+pub async fn api_preview_summary_batch<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    selection: BatchSelection,
+) -> Result<Vec<BatchSession>, String> {
+    batch::select_sessions(state.db_manager.pool(), &selection).await
+}
+
+/// Regenerates the summaries of all selected sessions
+///
+/// Current summaries are kept in the version history. Progress is reported with
+/// the `summary-batch-progress` event.
+///
+/// # Arguments
+/// * `selection` - Date range, tag, search query and/or limit selecting the sessions
+/// * `model` - LLM provider name (e.g., "ollama", "openai")
+/// * `model_name` - Specific model
+/// * `template_id` - Template the notes are regenerated with
+/// * `custom_prompt` - Context added to the prompt of every session
+/// * `previous_sessions` - Number of earlier sessions whose notes are passed as context
+#[tauri::command]
+pub async fn api_start_summary_batch<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    selection: BatchSelection,
+    model: String,
+    model_name: String,
+    template_id: String,
+    custom_prompt: Option<String>,
+    previous_sessions: Option<u32>,
+) -> Result<BatchProgress, String> {
+    log_info!(
+        "api_start_summary_batch called with {:?}, model: {}/{}, template: {}",
+        selection,
+        model,
+        model_name,
+        template_id
+    );
+
+    let settings = BatchSettings {
+        provider: &model,
+        model: &model_name,
+        template_id: &template_id,
+        custom_prompt: custom_prompt.as_deref().unwrap_or(""),
+        previous_session_count: previous_sessions.unwrap_or(0) as i64,
+    };
+    batch::start_batch(&app, state.db_manager.pool(), &selection, settings)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to start summary batch: {}", e);
+            e
+        })
+}
+
+#[tauri::command]
+pub async fn api_get_summary_batch<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    batch_id: String,
+) -> Result<BatchProgress, String> {
+    batch::batch_progress(state.db_manager.pool(), &batch_id).await
+}
+
+/// Lists recent batches, newest first
+#[tauri::command]
+pub async fn api_list_summary_batches<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SummaryBatch>, String> {
+    SummaryBatchesRepository::list_batches(state.db_manager.pool(), LISTED_BATCHES)
+        .await
+        .map_err(|e| format!("Failed to list batches: {}", e))
+}
+
+/// Cancels a batch: sessions not started yet are dropped, running ones are cancelled
+#[tauri::command]
+pub async fn api_cancel_summary_batch<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    batch_id: String,
+) -> Result<BatchProgress, String> {
+    log_info!("api_cancel_summary_batch called for batch_id: {}", batch_id);
+    batch::cancel_batch(&app, state.db_manager.pool(), &batch_id).await
+}
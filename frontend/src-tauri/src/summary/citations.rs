@@ -0,0 +1,381 @@
+use crate::database::models::Transcript;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Matches `[mm:ss]` and `[h:mm:ss]` timestamp markers
+static TIMESTAMP_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[(?:(\d{1,2}):)?(\d{1,3}):([0-5]\d)\]").unwrap());
+
+/// Matches a markdown list marker at the start of a line (`- `, `* `, `1. `)
+static LIST_MARKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+").unwrap());
+
+/// Cited times may be off by this many seconds, since markers are rounded down
+const CITATION_TOLERANCE_SECS: f64 = 1.0;
+
+/// A transcript segment with its position in the recording
+#[derive(Debug, Clone)]
+pub struct TimedSegment {
+    pub id: String,
+    pub start: f64,
+    pub end: Option<f64>,
+    pub text: String,
+}
+
+impl TimedSegment {
+    /// Converts stored transcripts, skipping segments without recording timestamps
+    pub fn from_transcripts(transcripts: Vec<Transcript>) -> Vec<TimedSegment> {
+        let mut segments: Vec<TimedSegment> = transcripts
+            .into_iter()
+            .filter_map(|t| {
+                let start = t.audio_start_time?;
+                let end = t.audio_end_time.or(t.duration.map(|d| start + d));
+                Some(TimedSegment {
+                    id: t.id,
+                    start,
+                    end,
+                    text: t.transcript,
+                })
+            })
+            .collect();
+        segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+        segments
+    }
+}
+
+/// A note item linked to the point in the recording it cites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteCitation {
+    /// Section the item belongs to, if any
+    pub section: Option<String>,
+    /// Zero-based line of the item in the notes markdown
+    pub line_index: usize,
+    /// Item text without list marker and timestamp markers
+    pub item_text: String,
+    /// Timestamp as written in the notes (e.g. "12:05")
+    pub timestamp_label: String,
+    /// Start of the cited segment, in seconds from the start of the recording
+    pub audio_start_time: f64,
+    /// Cited transcript segment
+    pub transcript_id: String,
+}
+
+/// Outcome of validating the citations in generated notes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CitationReport {
+    pub citations: Vec<NoteCitation>,
+    /// Cited timestamps that matched no transcript segment (removed from the notes)
+    pub invalid_timestamps: Vec<String>,
+    /// List items without any citation
+    pub uncited_items: usize,
+}
+
+/// Formats seconds from the start of the recording as `mm:ss` (or `h:mm:ss` past an hour)
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+fn parse_timestamp(caps: &regex::Captures) -> f64 {
+    let hours: u64 = caps.get(1).map_or(0, |h| h.as_str().parse().unwrap_or(0));
+    let minutes: u64 = caps[2].parse().unwrap_or(0);
+    let secs: u64 = caps[3].parse().unwrap_or(0);
+    (hours * 3600 + minutes * 60 + secs) as f64
+}
+
+/// Transcript text sent to the LLM, with the position of each transcript segment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptText {
+    pub text: String,
+    /// Byte offsets in `text` at which segments start, ascending (empty if unknown)
+    pub segment_starts: Vec<usize>,
+}
+
+impl TranscriptText {
+    /// Joins segments with newlines, one line per segment
+    pub fn from_segments<I: IntoIterator<Item = String>>(segments: I) -> Self {
+        let mut transcript = Self::default();
+        for segment in segments {
+            if !transcript.segment_starts.is_empty() {
+                transcript.text.push('\n');
+            }
+            transcript.segment_starts.push(transcript.text.len());
+            transcript.text.push_str(&segment);
+        }
+        transcript
+    }
+
+    /// Text whose segments aren't known, e.g. a transcript passed in by the frontend
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            segment_starts: Vec::new(),
+        }
+    }
+
+    /// Segments of the text, without the newlines joining them (the whole text if unsegmented)
+    pub fn segments(&self) -> Vec<&str> {
+        if self.segment_starts.is_empty() {
+            return vec![self.text.as_str()];
+        }
+        // Each segment ends before the newline preceding the next one
+        let ends = self.segment_starts[1..]
+            .iter()
+            .map(|&next| next - 1)
+            .chain([self.text.len()]);
+        self.segment_starts
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| &self.text[start..end])
+            .collect()
+    }
+
+    /// Replaces the text of each segment, keeping the segment boundaries
+    pub fn map_segments(&self, mut f: impl FnMut(&str) -> String) -> Self {
+        if self.segment_starts.is_empty() {
+            return Self::plain(f(&self.text));
+        }
+        Self::from_segments(self.segments().into_iter().map(f))
+    }
+}
+
+/// Builds the transcript sent to the LLM, one `[mm:ss] text` line per segment
+///
+/// Segments without recording timestamps are kept as plain lines, so no speech
+/// is lost from the notes; they just can't be cited.
+pub fn build_timestamped_transcript(transcripts: &[Transcript]) -> TranscriptText {
+    TranscriptText::from_segments(
+        transcripts
+            .iter()
+            .filter(|t| !t.transcript.trim().is_empty())
+            .map(|t| match t.audio_start_time {
+                Some(start) => format!("[{}] {}", format_timestamp(start), t.transcript.trim()),
+                None => t.transcript.trim().to_string(),
+            }),
+    )
+}
+
+/// Removes all timestamp markers, e.g. before notes are reused as prompt context
+pub fn strip_citations(markdown: &str) -> String {
+    let stripped = TIMESTAMP_REGEX.replace_all(markdown, "");
+    stripped
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Instruction added to the final prompt when the transcript carries timestamps
+pub fn citation_instruction() -> &'static str {
+    "- **Citations:** The transcript lines start with `[mm:ss]` timestamps. End every list item with the timestamp of the transcript line that supports it, copied exactly (e.g. `- Corrected 'I have went' to 'I went' [12:05]`). Only cite timestamps that appear in the transcript.\n"
+}
+
+/// Instruction added to chunk prompts so timestamps survive multi-level summarization
+pub fn chunk_citation_instruction() -> &'static str {
+    " Keep the `[mm:ss]` timestamp of the transcript line each point comes from."
+}
+
+/// Finds the segment a cited time refers to
+fn find_segment(segments: &[TimedSegment], seconds: f64) -> Option<&TimedSegment> {
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(i, segment)| {
+            let end = segment
+                .end
+                .or_else(|| segments.get(i + 1).map(|next| next.start))
+                .unwrap_or(segment.start);
+            segment.start.floor() <= seconds + CITATION_TOLERANCE_SECS
+                && seconds <= end.max(segment.start) + CITATION_TOLERANCE_SECS
+        })
+        .min_by(|(_, a), (_, b)| {
+            (a.start - seconds).abs().total_cmp(&(b.start - seconds).abs())
+        })
+        .map(|(_, segment)| segment)
+}
+
+/// Section title of a `**Title**` or `#`-heading line
+fn section_title(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    if let Some(title) = trimmed.strip_prefix("**").and_then(|t| t.strip_suffix("**")) {
+        return Some(title.trim().to_string());
+    }
+    if trimmed.starts_with('#') {
+        return Some(trimmed.trim_start_matches('#').trim().to_string());
+    }
+    None
+}
+
+/// Parses the citations in generated notes and validates them against the transcript
+///
+/// Citations that match no segment are removed from the notes, so every
+/// remaining timestamp can be followed to the audio.
+///
+/// # Returns
+/// Tuple of (notes with invalid citations removed, report)
+pub fn validate_citations(markdown: &str, segments: &[TimedSegment]) -> (String, CitationReport) {
+    let mut report = CitationReport::default();
+    let mut section: Option<String> = None;
+    let mut lines = Vec::new();
+
+    for (line_index, line) in markdown.lines().enumerate() {
+        if let Some(title) = section_title(line) {
+            section = Some(title);
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let is_list_item = LIST_MARKER_REGEX.is_match(line);
+        let item_text = LIST_MARKER_REGEX
+            .replace(&strip_citations(line), "")
+            .trim()
+            .to_string();
+
+        let mut cited = false;
+        let mut cleaned = line.to_string();
+        for caps in TIMESTAMP_REGEX.captures_iter(line) {
+            let marker = &caps[0];
+            let label = marker.trim_matches(|c| c == '[' || c == ']').to_string();
+            match find_segment(segments, parse_timestamp(&caps)) {
+                Some(segment) => {
+                    cited = true;
+                    report.citations.push(NoteCitation {
+                        section: section.clone(),
+                        line_index,
+                        item_text: item_text.clone(),
+                        timestamp_label: label,
+                        audio_start_time: segment.start,
+                        transcript_id: segment.id.clone(),
+                    });
+                }
+                None => {
+                    let with_space = format!(" {}", marker);
+                    cleaned = if cleaned.contains(&with_space) {
+                        cleaned.replacen(&with_space, "", 1)
+                    } else {
+                        cleaned.replacen(marker, "", 1)
+                    };
+                    report.invalid_timestamps.push(label);
+                }
+            }
+        }
+
+        if is_list_item && !cited && !item_text.is_empty() {
+            report.uncited_items += 1;
+        }
+
+        lines.push(cleaned.trim_end().to_string());
+    }
+
+    (lines.join("\n"), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(id: &str, start: f64, end: f64, text: &str) -> TimedSegment {
+        TimedSegment {
+            id: id.to_string(),
+            start,
+            end: Some(end),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0.0), "00:00");
+        assert_eq!(format_timestamp(725.9), "12:05");
+        assert_eq!(format_timestamp(3725.0), "1:02:05");
+    }
+
+    fn transcript(id: &str, start: Option<f64>, text: &str) -> Transcript {
+        Transcript {
+            id: id.to_string(),
+            meeting_id: "m".to_string(),
+            transcript: text.to_string(),
+            timestamp: String::new(),
+            summary: None,
+            action_items: None,
+            key_points: None,
+            audio_start_time: start,
+            audio_end_time: None,
+            duration: None,
+        }
+    }
+
+    #[test]
+    fn test_build_timestamped_transcript() {
+        let transcripts = vec![
+            transcript("a", Some(0.0), "Hello!"),
+            transcript("b", None, "Recovered without timestamps"),
+            transcript("c", Some(65.2), " I have went to the shop. "),
+            transcript("d", Some(70.0), "  "),
+        ];
+        let transcript = build_timestamped_transcript(&transcripts);
+        assert_eq!(
+            transcript.text,
+            "[00:00] Hello!\nRecovered without timestamps\n[01:05] I have went to the shop."
+        );
+        assert_eq!(transcript.segment_starts, vec![0, 15, 44]);
+        assert_eq!(TimedSegment::from_transcripts(transcripts).len(), 3);
+
+        // Segments keep their boundaries when their text changes length
+        let redacted = transcript.map_segments(|segment| segment.replace("Hello", "[NAME_1]"));
+        assert_eq!(
+            redacted.segments(),
+            vec![
+                "[00:00] [NAME_1]!",
+                "Recovered without timestamps",
+                "[01:05] I have went to the shop."
+            ]
+        );
+        assert_eq!(redacted.segment_starts, vec![0, 18, 47]);
+        assert_eq!(
+            TranscriptText::plain("hola".to_string()).map_segments(str::to_uppercase),
+            TranscriptText::plain("HOLA".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_citations() {
+        let segments = vec![
+            segment("a", 0.0, 4.0, "Hello!"),
+            segment("b", 65.2, 70.0, "I have went to the shop."),
+        ];
+        let markdown = "**Corrections**\n\n- Corrected 'I have went' to 'I went' [01:05]\n- Invented item [42:00]\n- No citation";
+
+        let (cleaned, report) = validate_citations(markdown, &segments);
+
+        assert_eq!(report.citations.len(), 1);
+        let citation = &report.citations[0];
+        assert_eq!(citation.transcript_id, "b");
+        assert_eq!(citation.section.as_deref(), Some("Corrections"));
+        assert_eq!(citation.line_index, 2);
+        assert_eq!(citation.item_text, "Corrected 'I have went' to 'I went'");
+        assert_eq!(report.invalid_timestamps, vec!["42:00".to_string()]);
+        assert_eq!(report.uncited_items, 2);
+        assert!(cleaned.contains("- Invented item\n"));
+        assert!(cleaned.contains("[01:05]"));
+    }
+
+    #[test]
+    fn test_citation_within_segment() {
+        let segments = vec![segment("a", 60.0, 90.0, "A long explanation")];
+        let (_, report) = validate_citations("- Explained the subjunctive [01:20]", &segments);
+        assert_eq!(report.citations.len(), 1);
+        assert_eq!(report.citations[0].audio_start_time, 60.0);
+    }
+
+    #[test]
+    fn test_strip_citations() {
+        assert_eq!(strip_citations("- Past tense [01:05]\n- Vocabulary"), "- Past tense\n- Vocabulary");
+    }
+}
@@ -1,8 +1,13 @@
+use crate::database::models::SummaryCitation;
 use crate::database::repositories::{
-    meeting::MeetingsRepository, summary::SummaryProcessesRepository,
+    citation::CitationsRepository,
+    meeting::MeetingsRepository,
+    summary::SummaryProcessesRepository,
+    summary_job::NewSummaryJob,
     transcript_chunk::TranscriptChunksRepository,
 };
 use crate::state::AppState;
+use crate::summary::job_queue::{self, QueuedJobInfo};
 use crate::summary::service::SummaryService;
 use log::{error as log_error, info as log_info, warn as log_warn};
 use serde::{Deserialize, Serialize};
@@ -18,6 +23,8 @@ pub struct SummaryResponse {
     pub end: Option<String>,
     pub data: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// Generation details (template version, verification report)
+    pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -99,6 +106,11 @@ pub async fn api_get_summary<R: Runtime>(
                 None
             };
 
+            let metadata = process
+                .metadata
+                .as_deref()
+                .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok());
+
             // Fetch session title from database
             let session_name = match MeetingsRepository::get_meeting(pool, &meeting_id).await {
                 Ok(Some(meeting_details)) => {
@@ -123,6 +135,7 @@ pub async fn api_get_summary<R: Runtime>(
                 end: process.end_time.map(|t| t.to_rfc3339()),
                 data,
                 error,
+                metadata,
             };
 
             log_info!(
@@ -151,6 +164,7 @@ pub async fn api_get_summary<R: Runtime>(
                 end: None,
                 data: None,
                 error: None,
+                metadata: None,
             })
         }
         Err(e) => {
@@ -162,7 +176,10 @@ pub async fn api_get_summary<R: Runtime>(
 
 /// Processes transcript and generates summary (Native SQLx implementation)
 ///
-/// Spawns a background task and returns immediately with process_id
+/// Queues a summary job and returns immediately with process_id. Jobs survive
+/// restarts and are retried on transient provider errors.
+/// `previous_sessions` adds the notes of that many earlier sessions as context
+/// and a "Progress since last session" section to the output.
 #[tauri::command]
 pub async fn api_process_transcript<R: Runtime>(
     app: AppHandle<R>,
@@ -175,6 +192,7 @@ pub async fn api_process_transcript<R: Runtime>(
     _overlap: Option<i32>,
     custom_prompt: Option<String>,
     template_id: Option<String>,
+    previous_sessions: Option<u32>,
     _auth_token: Option<String>,
 ) -> Result<ProcessTranscriptResponse, String> {
     use uuid::Uuid;
@@ -187,6 +205,10 @@ pub async fn api_process_transcript<R: Runtime>(
     );
 
     let pool = state.db_manager.pool().clone();
+
+    // Fail before resetting the current summary if it is being generated right now
+    job_queue::ensure_not_running(&pool, &m_id).await?;
+
     let final_prompt = custom_prompt.unwrap_or_else(|| "".to_string());
     let final_template_id = template_id.unwrap_or_else(|| "daily_standup".to_string());
 
@@ -215,44 +237,78 @@ pub async fn api_process_transcript<R: Runtime>(
 
     log_info!("✓ Transcript chunks saved for meeting_id: {}", &m_id);
 
-    // Spawn background task for actual processing
-    let meeting_id_clone = m_id.clone();
-    tauri::async_runtime::spawn(async move {
-        SummaryService::process_transcript_background(
-            app,
-            pool,
-            meeting_id_clone.clone(),
-            text,
-            model,
-            model_name,
-            final_prompt,
-            final_template_id,
-        )
-        .await;
-    });
-
-    log_info!("🚀 Background task spawned for meeting_id: {}", &m_id);
+    // Queue the generation; the job queue runs it in the background
+    job_queue::enqueue_summary(
+        &app,
+        &pool,
+        NewSummaryJob {
+            meeting_id: &m_id,
+            provider: &model,
+            model: &model_name,
+            template_id: &final_template_id,
+            custom_prompt: &final_prompt,
+            previous_session_count: previous_sessions.unwrap_or(0) as i64,
+            max_attempts: job_queue::MAX_ATTEMPTS,
+            batch_id: None,
+        },
+    )
+    .await?;
+
+    log_info!("🚀 Summary job queued for meeting_id: {}", &m_id);
 
     Ok(ProcessTranscriptResponse {
-        message: "Summary generation started".to_string(),
+        message: "Summary generation queued".to_string(),
         process_id: m_id,
     })
 }
 
-/// Cancels an ongoing summary generation process
+/// Gets the running and queued summary jobs with their queue positions
+///
+/// The same list is sent with the `summary-queue-updated` event whenever it changes.
+#[tauri::command]
+pub async fn api_get_summary_queue<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<QueuedJobInfo>, String> {
+    job_queue::queue_snapshot(state.db_manager.pool()).await
+}
+
+/// Gets the transcript citations of a session's summary
+///
+/// Each citation links a note item to the transcript segment and audio position
+/// it cites, so the UI can jump to the recording.
+#[tauri::command]
+pub async fn api_get_summary_citations<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+) -> Result<Vec<SummaryCitation>, String> {
+    log_info!("api_get_summary_citations called for meeting_id: {}", meeting_id);
+
+    CitationsRepository::list_citations(state.db_manager.pool(), &meeting_id)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to load citations for {}: {}", meeting_id, e);
+            format!("Failed to load summary citations: {}", e)
+        })
+}
+
+/// Cancels an ongoing or queued summary generation process
 ///
 /// This command triggers the cancellation token for the specified session,
-/// stopping the summary generation gracefully.
+/// stopping the summary generation gracefully. A job that hasn't started yet
+/// (or is waiting to be retried) is removed from the queue.
 #[tauri::command]
 pub async fn api_cancel_summary<R: Runtime>(
-    _app: AppHandle<R>,
+    app: AppHandle<R>,
     state: tauri::State<'_, AppState>,
     meeting_id: String,
 ) -> Result<serde_json::Value, String> {
     log_info!("api_cancel_summary called for meeting_id: {}", meeting_id);
 
-    // Trigger cancellation via the service
-    let cancelled = SummaryService::cancel_summary(&meeting_id);
+    // Trigger cancellation via the service, or drop the job from the queue
+    let cancelled = SummaryService::cancel_summary(&meeting_id)
+        || job_queue::cancel_queued_summary(&app, state.db_manager.pool(), &meeting_id).await?;
 
     if cancelled {
         // Update database status to cancelled
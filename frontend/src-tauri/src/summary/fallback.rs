@@ -0,0 +1,157 @@
+use crate::summary::llm_client::{LLMProvider, LlmError};
+use serde::{Deserialize, Serialize};
+
+/// A provider and model to try when the ones before it are unreachable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackProvider {
+    /// Provider name as accepted by `LLMProvider::from_str` (e.g. "ollama", "builtin-ai")
+    pub provider: String,
+    pub model: String,
+}
+
+/// Fallback settings for summary generation
+/// Stored as JSON in the database (settings.providerFallbackConfig)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderFallbackConfig {
+    /// Tried in order after the selected provider fails with a connectivity,
+    /// rate-limit or timeout error
+    #[serde(default)]
+    pub chain: Vec<FallbackProvider>,
+
+    /// Providers that must never receive transcripts, neither as the selected
+    /// provider nor as a fallback
+    #[serde(default)]
+    pub never_send_providers: Vec<String>,
+}
+
+impl ProviderFallbackConfig {
+    /// Checks that all provider names are known
+    pub fn validate(&self) -> Result<(), String> {
+        for entry in &self.chain {
+            LLMProvider::from_str(&entry.provider)?;
+            if entry.model.trim().is_empty() {
+                return Err(format!("No model set for fallback provider {}", entry.provider));
+            }
+        }
+        for provider in &self.never_send_providers {
+            LLMProvider::from_str(provider)?;
+        }
+        Ok(())
+    }
+
+    /// Whether transcripts may be sent to this provider
+    pub fn allows(&self, provider: &LLMProvider) -> bool {
+        !self
+            .never_send_providers
+            .iter()
+            .any(|blocked| LLMProvider::from_str(blocked).ok().as_ref() == Some(provider))
+    }
+
+    /// Builds the ordered list of (provider, model) pairs to try for a summary
+    ///
+    /// The selected provider comes first, followed by the fallback chain.
+    /// Duplicates and providers marked as "never send" are left out.
+    ///
+    /// # Errors
+    /// If the selected provider itself is marked as "never send"
+    pub fn candidates(&self, provider: &str, model: &str) -> Result<Vec<FallbackProvider>, String> {
+        let selected = LLMProvider::from_str(provider)?;
+        if !self.allows(&selected) {
+            return Err(format!(
+                "Transcripts may not be sent to {} (marked as \"never send\" in the fallback settings)",
+                provider
+            ));
+        }
+
+        let mut candidates = vec![FallbackProvider {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }];
+        for entry in &self.chain {
+            let Ok(fallback) = LLMProvider::from_str(&entry.provider) else {
+                continue;
+            };
+            let duplicate = candidates.iter().any(|c| {
+                LLMProvider::from_str(&c.provider).ok().as_ref() == Some(&fallback)
+                    && c.model == entry.model
+            });
+            if self.allows(&fallback) && !duplicate {
+                candidates.push(entry.clone());
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+/// Whether a failed LLM request should be retried with the next provider
+///
+/// Only errors that say nothing about the request itself qualify: the provider
+/// couldn't be reached (or local-only mode blocked it), rejected the request
+/// for rate limiting, or timed out. Server errors are left to the job queue's
+/// retries with the same provider (see `job_queue::is_transient_error`).
+pub fn is_fallback_error(error: &LlmError) -> bool {
+    match error {
+        LlmError::Blocked(_) | LlmError::Connect(_) | LlmError::Timeout => true,
+        LlmError::Http { status, .. } => status.as_u16() == 429,
+        LlmError::Cancelled | LlmError::Interrupted(_) | LlmError::Other(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{Channel, NetworkError};
+
+    fn entry(provider: &str, model: &str) -> FallbackProvider {
+        FallbackProvider {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_candidates_follow_chain_without_blocked_providers() {
+        let config = ProviderFallbackConfig {
+            chain: vec![
+                entry("builtin-ai", "gemma3:1b"),
+                entry("ollama", "llama3.2:latest"),
+                entry("groq", "llama-3.3-70b"),
+                entry("custom-openai", "local-model"),
+            ],
+            never_send_providers: vec!["groq".to_string()],
+        };
+
+        let candidates = config.candidates("ollama", "llama3.2:latest").unwrap();
+        let providers: Vec<&str> = candidates.iter().map(|c| c.provider.as_str()).collect();
+        assert_eq!(providers, vec!["ollama", "builtin-ai", "custom-openai"]);
+
+        assert!(config.candidates("Groq", "llama-3.3-70b").is_err());
+    }
+
+    #[test]
+    fn test_is_fallback_error() {
+        let http = |status: u16| LlmError::Http {
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            body: "error".to_string(),
+        };
+        assert!(is_fallback_error(&LlmError::Connect(
+            "error sending request for url (http://localhost:11434/api/chat)".to_string()
+        )));
+        assert!(is_fallback_error(&http(429)));
+        assert!(is_fallback_error(&LlmError::Timeout));
+        assert!(is_fallback_error(&LlmError::Blocked(
+            NetworkError::Blocked {
+                channel: Channel::LlmProvider,
+                host: "api.openai.com".to_string(),
+            }
+        )));
+        assert!(!is_fallback_error(&http(401)));
+        assert!(!is_fallback_error(&http(503)));
+        assert!(!is_fallback_error(&LlmError::Cancelled));
+        // A message that merely mentions a status code isn't a rate limit
+        assert!(!is_fallback_error(&LlmError::Other(
+            "Ollama truncated the prompt: only 429 of ~5000 tokens were read".to_string()
+        )));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::database::models::SummaryJob;
+use crate::database::repositories::{
+    summary::SummaryProcessesRepository,
+    summary_job::{NewSummaryJob, SummaryJobsRepository},
+    transcript_chunk::TranscriptChunksRepository,
+};
+use crate::state::AppState;
+use crate::summary::batch;
+use crate::summary::llm_client::LlmError;
+use crate::summary::service::{SummaryOutcome, SummaryService};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Wry};
+use tokio::sync::{Notify, Semaphore};
+use tracing::{error, info, warn};
+
+/// Maximum number of summaries generated at the same time
+pub const MAX_CONCURRENT_JOBS: usize = 2;
+
+/// Attempts per job, including the first one
+pub const MAX_ATTEMPTS: i64 = 3;
+
+/// Delay before the first retry; doubled for every further attempt
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+
+/// Upper bound for the delay between retries
+const RETRY_MAX_DELAY_SECS: i64 = 10 * 60;
+
+/// How often the queue is checked for jobs whose retry delay has passed
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Finished jobs are deleted after this many days
+const FINISHED_JOB_RETENTION_DAYS: i64 = 7;
+
+/// Event emitted with the current `Vec<QueuedJobInfo>` whenever the queue changes
+pub const QUEUE_UPDATED_EVENT: &str = "summary-queue-updated";
+
+/// Wakes the dispatcher when a job is queued or finished
+static QUEUE_NOTIFY: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// A queued or running job as shown in the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJobInfo {
+    pub job_id: String,
+    pub meeting_id: String,
+    /// `queued` or `running`
+    pub status: String,
+    /// 1-based position among queued jobs (0 while running)
+    pub position: usize,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    /// Earliest time a queued job runs (later than now while waiting to retry)
+    pub next_attempt_at: DateTime<Utc>,
+    /// Error of the previous attempt, if it is being retried
+    pub last_error: Option<String>,
+}
+
+/// Whether a provider error is likely transient (network, rate limit, server error)
+pub fn is_transient_error(error: &LlmError) -> bool {
+    match error {
+        LlmError::Connect(_) | LlmError::Timeout | LlmError::Interrupted(_) => true,
+        LlmError::Http { status, .. } => {
+            status.as_u16() == 408 || status.as_u16() == 429 || status.is_server_error()
+        }
+        LlmError::Cancelled | LlmError::Blocked(_) | LlmError::Other(_) => false,
+    }
+}
+
+/// Delay before retrying a job that failed on its `attempt`-th attempt (1-based)
+pub fn retry_delay(attempt: i64) -> chrono::Duration {
+    let exponent = (attempt - 1).clamp(0, 16) as u32;
+    let seconds = RETRY_BASE_DELAY_SECS
+        .saturating_mul(2_i64.pow(exponent))
+        .min(RETRY_MAX_DELAY_SECS);
+    chrono::Duration::seconds(seconds)
+}
+
+/// Numbers the active jobs: running jobs get position 0, queued jobs 1, 2, ...
+fn queue_positions(jobs: Vec<SummaryJob>) -> Vec<QueuedJobInfo> {
+    let mut position = 0;
+    jobs.into_iter()
+        .map(|job| {
+            let job_position = if job.status == "running" {
+                0
+            } else {
+                position += 1;
+                position
+            };
+            QueuedJobInfo {
+                job_id: job.id,
+                meeting_id: job.meeting_id,
+                status: job.status,
+                position: job_position,
+                attempts: job.attempts,
+                max_attempts: job.max_attempts,
+                next_attempt_at: job.next_attempt_at,
+                last_error: job.last_error,
+            }
+        })
+        .collect()
+}
+
+/// Lists the running and queued jobs with their queue positions
+pub async fn queue_snapshot(pool: &SqlitePool) -> Result<Vec<QueuedJobInfo>, String> {
+    SummaryJobsRepository::list_active_jobs(pool)
+        .await
+        .map(queue_positions)
+        .map_err(|e| format!("Failed to load summary queue: {}", e))
+}
+
+/// Sends the current queue to the frontend
+async fn emit_queue_updated<R: Runtime>(app: &AppHandle<R>, pool: &SqlitePool) {
+    match queue_snapshot(pool).await {
+        Ok(jobs) => {
+            if let Err(e) = app.emit(QUEUE_UPDATED_EVENT, &jobs) {
+                warn!("Failed to emit {}: {}", QUEUE_UPDATED_EVENT, e);
+            }
+        }
+        Err(e) => warn!("{}", e),
+    }
+}
+
+/// Queues a summary generation request
+///
+/// A request for a session that is already queued replaces the queued one.
+/// Fails if the summary of the session is being generated right now.
+pub async fn enqueue_summary<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    job: NewSummaryJob<'_>,
+) -> Result<SummaryJob, String> {
+    ensure_not_running(pool, job.meeting_id).await?;
+
+    let queued = SummaryJobsRepository::enqueue(pool, job)
+        .await
+        .map_err(|e| format!("Failed to queue summary: {}", e))?;
+    info!("Queued summary job {} for meeting_id: {}", queued.id, queued.meeting_id);
+
+    QUEUE_NOTIFY.notify_one();
+    emit_queue_updated(app, pool).await;
+    Ok(queued)
+}
+
+/// Fails if the summary of the session is being generated right now
+pub async fn ensure_not_running(pool: &SqlitePool, meeting_id: &str) -> Result<(), String> {
+    let active = SummaryJobsRepository::get_active_job(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to check summary queue: {}", e))?;
+    if active.is_some_and(|active| active.status == "running") {
+        return Err("A summary is already being generated for this session".to_string());
+    }
+    Ok(())
+}
+
+/// Cancels a session's job that hasn't started yet
+///
+/// # Returns
+/// Whether a queued job was cancelled
+pub async fn cancel_queued_summary<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    meeting_id: &str,
+) -> Result<bool, String> {
+    let cancelled = SummaryJobsRepository::cancel_queued_jobs(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to cancel queued summary: {}", e))?;
+    if cancelled > 0 {
+        info!("Cancelled queued summary job for meeting_id: {}", meeting_id);
+        emit_queue_updated(app, pool).await;
+    }
+    Ok(cancelled > 0)
+}
+
+/// Cancels the jobs of a batch that haven't started yet
+///
+/// # Returns
+/// Sessions of the cancelled jobs
+pub async fn cancel_queued_batch<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    batch_id: &str,
+) -> Result<Vec<String>, String> {
+    let cancelled = SummaryJobsRepository::cancel_queued_batch_jobs(pool, batch_id)
+        .await
+        .map_err(|e| format!("Failed to cancel queued batch jobs: {}", e))?;
+    if !cancelled.is_empty() {
+        info!("Cancelled {} queued job(s) of batch {}", cancelled.len(), batch_id);
+        emit_queue_updated(app, pool).await;
+    }
+    Ok(cancelled)
+}
+
+/// Starts the background task that runs queued summary jobs
+///
+/// Runs at most `MAX_CONCURRENT_JOBS` jobs at a time, oldest first. Jobs that were
+/// running when the app quit are queued again on startup.
+pub fn start_summary_job_queue(app: AppHandle<Wry>) {
+    tauri::async_runtime::spawn(async move {
+        // The database isn't available until first-launch setup has completed
+        let pool = loop {
+            if let Some(state) = app.try_state::<AppState>() {
+                break state.db_manager.pool().clone();
+            }
+            tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+        };
+
+        recover_jobs(&pool).await;
+        emit_queue_updated(&app, &pool).await;
+
+        let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS));
+        loop {
+            while let Ok(permit) = permits.clone().try_acquire_owned() {
+                let job = match SummaryJobsRepository::claim_next_job(&pool, Utc::now()).await {
+                    Ok(Some(job)) => job,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Failed to claim summary job: {}", e);
+                        break;
+                    }
+                };
+
+                emit_queue_updated(&app, &pool).await;
+                let app = app.clone();
+                let pool = pool.clone();
+                tauri::async_runtime::spawn(async move {
+                    let batch_id = job.batch_id.clone();
+                    run_job(&app, &pool, job).await;
+                    drop(permit);
+                    if let Some(batch_id) = batch_id {
+                        batch::on_batch_job_finished(&app, &pool, &batch_id).await;
+                    }
+                    QUEUE_NOTIFY.notify_one();
+                    emit_queue_updated(&app, &pool).await;
+                });
+            }
+
+            tokio::select! {
+                _ = QUEUE_NOTIFY.notified() => {}
+                _ = tokio::time::sleep(QUEUE_POLL_INTERVAL) => {}
+            }
+        }
+    });
+}
+
+/// Re-queues interrupted jobs, fails summaries that lost their job and deletes old jobs
+async fn recover_jobs(pool: &SqlitePool) {
+    match SummaryJobsRepository::requeue_interrupted_jobs(pool).await {
+        Ok(0) => {}
+        Ok(count) => info!("Re-queued {} interrupted summary job(s)", count),
+        Err(e) => error!("Failed to re-queue interrupted summary jobs: {}", e),
+    }
+
+    match SummaryProcessesRepository::fail_orphaned_processes(
+        pool,
+        "Summary generation was interrupted. Please try again.",
+    )
+    .await
+    {
+        Ok(0) => {}
+        Ok(count) => warn!("Marked {} interrupted summary process(es) as failed", count),
+        Err(e) => error!("Failed to clean up interrupted summary processes: {}", e),
+    }
+
+    let cutoff = Utc::now() - chrono::Duration::days(FINISHED_JOB_RETENTION_DAYS);
+    if let Err(e) = SummaryJobsRepository::delete_finished_jobs(pool, cutoff).await {
+        warn!("Failed to delete finished summary jobs: {}", e);
+    }
+}
+
+/// Runs one attempt of a claimed job and records the outcome
+async fn run_job(app: &AppHandle<Wry>, pool: &SqlitePool, job: SummaryJob) {
+    info!(
+        "Running summary job {} for meeting_id: {} (attempt {}/{})",
+        job.id, job.meeting_id, job.attempts, job.max_attempts
+    );
+
+    let text = match TranscriptChunksRepository::get_transcript_text(pool, &job.meeting_id).await {
+        Ok(Some(text)) => text,
+        Ok(None) => {
+            let outcome = SummaryService::update_process_failed(
+                pool,
+                &job.meeting_id,
+                "No transcript found for this session",
+            )
+            .await;
+            finish_job(pool, &job, outcome).await;
+            return;
+        }
+        Err(e) => {
+            let outcome = SummaryService::update_process_failed(
+                pool,
+                &job.meeting_id,
+                &format!("Failed to load transcript: {}", e),
+            )
+            .await;
+            finish_job(pool, &job, outcome).await;
+            return;
+        }
+    };
+
+    let outcome = SummaryService::process_transcript_background(
+        app.clone(),
+        pool.clone(),
+        job.meeting_id.clone(),
+        text,
+        job.provider.clone(),
+        job.model.clone(),
+        job.custom_prompt.clone(),
+        job.template_id.clone(),
+        job.previous_session_count.max(0) as usize,
+        job.attempts < job.max_attempts,
+    )
+    .await;
+
+    finish_job(pool, &job, outcome).await;
+}
+
+/// Stores the outcome of an attempt, scheduling a retry for transient errors
+async fn finish_job(pool: &SqlitePool, job: &SummaryJob, outcome: SummaryOutcome) {
+    let result = match &outcome {
+        SummaryOutcome::Completed => {
+            SummaryJobsRepository::finish_job(pool, &job.id, "done", None).await
+        }
+        SummaryOutcome::Cancelled => {
+            SummaryJobsRepository::finish_job(pool, &job.id, "cancelled", None).await
+        }
+        SummaryOutcome::Failed(error) => {
+            SummaryJobsRepository::finish_job(pool, &job.id, "failed", Some(error)).await
+        }
+        SummaryOutcome::Retryable(error) => {
+            let delay = retry_delay(job.attempts);
+            info!(
+                "Retrying summary job {} in {}s after transient error",
+                job.id,
+                delay.num_seconds()
+            );
+            SummaryJobsRepository::schedule_retry(pool, &job.id, error, Utc::now() + delay).await
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Failed to update summary job {}: {}", job.id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient_error() {
+        let http = |status: u16| LlmError::Http {
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            body: "error".to_string(),
+        };
+        assert!(is_transient_error(&http(429)));
+        assert!(is_transient_error(&http(529)));
+        assert!(is_transient_error(&http(503)));
+        assert!(is_transient_error(&LlmError::Timeout));
+        assert!(is_transient_error(&LlmError::Connect(
+            "connection refused".to_string()
+        )));
+        assert!(!is_transient_error(&http(401)));
+        assert!(!is_transient_error(&http(404)));
+        assert!(!is_transient_error(&LlmError::Cancelled));
+        // Error messages that merely mention a status code or a timeout don't count
+        assert!(!is_transient_error(&LlmError::Other(
+            "Invalid model name: gpt-4-500k-timeout".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(3).num_seconds(), 120);
+        assert_eq!(retry_delay(10).num_seconds(), RETRY_MAX_DELAY_SECS);
+    }
+}
@@ -1,12 +1,28 @@
-use reqwest::{header, Client};
-use serde::{Deserialize, Serialize};
+use reqwest::{header, Client, StatusCode};
+use crate::network::{self, Channel, NetworkError};
+use crate::summary::streaming::{
+    parse_gemini_finish_reason, parse_ollama_stats, parse_stream_line, LineBuffer, OllamaStats,
+    ProgressCallback, StreamAccumulator, StreamEvent,
+};
+use crate::summary::processor::rough_token_count;
+use serde::Serialize;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
 
 const REQUEST_TIMEOUT_DURATION: Duration = Duration::from_secs(300);
 
+/// How long Ollama keeps the model loaded after a request, so the chunks of a
+/// long transcript don't each reload it
+const OLLAMA_KEEP_ALIVE: &str = "10m";
+
+/// Base URL of the Gemini API
+pub const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Output limit requested from Gemini when no max tokens are configured
+const GEMINI_MAX_OUTPUT_TOKENS: u32 = 8192;
+
 // Generic structure for OpenAI-compatible API chat messages
 #[derive(Debug, Serialize)]
 pub struct ChatMessage {
@@ -25,22 +41,7 @@ pub struct ChatRequest {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
-}
-
-// Generic structure for OpenAI-compatible API chat responses
-#[derive(Deserialize, Debug)]
-pub struct ChatResponse {
-    pub choices: Vec<Choice>,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct Choice {
-    pub message: MessageContent,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct MessageContent {
-    pub content: String,
+    pub stream: bool,
 }
 
 // Claude-specific request structure
@@ -50,24 +51,123 @@ pub struct ClaudeRequest {
     pub max_tokens: u32,
     pub system: String,
     pub messages: Vec<ChatMessage>,
+    pub stream: bool,
 }
 
-// Claude-specific response structure
-#[derive(Deserialize, Debug)]
-pub struct ClaudeChatResponse {
-    pub content: Vec<ClaudeChatContent>,
+// Ollama native chat request (`/api/chat`), streamed as one JSON object per line
+#[derive(Debug, Serialize)]
+pub struct OllamaChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub stream: bool,
+    pub options: OllamaOptions,
+    pub keep_alive: String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ClaudeChatContent {
+// Ollama model options; without `num_ctx` Ollama uses its own default context
+// window and silently drops the start of longer prompts
+#[derive(Debug, Serialize)]
+pub struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum tokens to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+}
+
+// Gemini `generateContent` request; the system prompt goes into `systemInstruction`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiRequest {
+    pub system_instruction: GeminiContent,
+    pub contents: Vec<GeminiContent>,
+    pub generation_config: GeminiGenerationConfig,
+}
+
+// Gemini generation parameters; unset sampling values use the model's defaults
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiGenerationConfig {
+    pub max_output_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeminiContent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeminiPart {
     pub text: String,
 }
 
+/// Why an LLM request failed, so callers can decide whether to retry it or
+/// move on to another provider without parsing the message
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LlmError {
+    #[error("Summary generation was cancelled")]
+    Cancelled,
+
+    /// Local-only mode doesn't allow connections to the provider
+    #[error(transparent)]
+    Blocked(#[from] NetworkError),
+
+    /// The provider couldn't be reached (connection refused, DNS failure, ...)
+    #[error("Failed to send request to LLM: {0}")]
+    Connect(String),
+
+    /// The request or the response stream timed out
+    #[error("LLM request timed out after {} seconds", REQUEST_TIMEOUT_DURATION.as_secs())]
+    Timeout,
+
+    /// The provider answered with an error status
+    #[error("LLM API request failed ({status}): {body}")]
+    Http { status: StatusCode, body: String },
+
+    /// The connection broke off while the response was streamed
+    #[error("Failed to read LLM response stream: {0}")]
+    Interrupted(String),
+
+    /// Any other failure: configuration, an error reported in the stream, an
+    /// empty or truncated response
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<String> for LlmError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<reqwest::Error> for LlmError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else if e.is_connect() {
+            Self::Connect(e.to_string())
+        } else {
+            Self::Other(format!("Failed to send request to LLM: {}", e))
+        }
+    }
+}
+
 /// LLM Provider enumeration for multi-provider support
 #[derive(Debug, Clone, PartialEq)]
 pub enum LLMProvider {
     OpenAI,
     Claude,
+    Gemini,
     Groq,
     Ollama,
     OpenRouter,
@@ -81,6 +181,7 @@ impl LLMProvider {
         match s.to_lowercase().as_str() {
             "openai" => Ok(Self::OpenAI),
             "claude" => Ok(Self::Claude),
+            "gemini" => Ok(Self::Gemini),
             "groq" => Ok(Self::Groq),
             "ollama" => Ok(Self::Ollama),
             "openrouter" => Ok(Self::OpenRouter),
@@ -93,6 +194,10 @@ impl LLMProvider {
 
 /// Generates a summary using the specified LLM provider
 ///
+/// The response is streamed: server-sent events for Claude, Gemini and the
+/// OpenAI-compatible providers, NDJSON for Ollama and a token stream from the
+/// built-in sidecar. The partial text is passed to `on_progress` as it arrives.
+///
 /// # Arguments
 /// * `client` - Reqwest HTTP client (reused for performance)
 /// * `provider` - The LLM provider to use
@@ -101,15 +206,18 @@ impl LLMProvider {
 /// * `system_prompt` - System instructions for the LLM
 /// * `user_prompt` - User query/content to process
 /// * `ollama_endpoint` - Optional custom Ollama endpoint (defaults to localhost:11434)
+/// * `ollama_num_ctx` - Optional context window to request from Ollama (the model's context size, `None` if unknown)
 /// * `custom_openai_endpoint` - Optional custom OpenAI-compatible endpoint
-/// * `max_tokens` - Optional max tokens (for CustomOpenAI provider)
-/// * `temperature` - Optional temperature (for CustomOpenAI provider)
-/// * `top_p` - Optional top_p (for CustomOpenAI provider)
+/// * `max_tokens` - Optional max tokens (for CustomOpenAI, Gemini and Ollama providers)
+/// * `temperature` - Optional temperature (for CustomOpenAI, Gemini and Ollama providers)
+/// * `top_p` - Optional top_p (for CustomOpenAI, Gemini and Ollama providers)
 /// * `app_data_dir` - Optional app data directory (for BuiltInAI provider)
-/// * `cancellation_token` - Optional token to cancel the request
+/// * `output_schema` - Optional JSON schema the output must match (BuiltInAI provider, which then returns JSON)
+/// * `cancellation_token` - Optional token to cancel the request, also while streaming
+/// * `on_progress` - Optional callback receiving the partial output
 ///
 /// # Returns
-/// The generated summary text or an error message
+/// The generated summary text or why the request failed
 pub async fn generate_summary(
     client: &Client,
     provider: &LLMProvider,
@@ -118,17 +226,20 @@ pub async fn generate_summary(
     system_prompt: &str,
     user_prompt: &str,
     ollama_endpoint: Option<&str>,
+    ollama_num_ctx: Option<usize>,
     custom_openai_endpoint: Option<&str>,
     max_tokens: Option<u32>,
     temperature: Option<f32>,
     top_p: Option<f32>,
     app_data_dir: Option<&PathBuf>,
+    output_schema: Option<&serde_json::Value>,
     cancellation_token: Option<&CancellationToken>,
-) -> Result<String, String> {
+    on_progress: Option<ProgressCallback<'_>>,
+) -> Result<String, LlmError> {
     // Check if cancelled before starting
     if let Some(token) = cancellation_token {
         if token.is_cancelled() {
-            return Err("Summary generation was cancelled".to_string());
+            return Err(LlmError::Cancelled);
         }
     }
 
@@ -142,10 +253,58 @@ pub async fn generate_summary(
             model_name,
             system_prompt,
             user_prompt,
+            output_schema,
             cancellation_token,
+            on_progress,
         )
         .await
-        .map_err(|e| e.to_string());
+        .map_err(|e| {
+            if cancellation_token.is_some_and(|token| token.is_cancelled()) {
+                LlmError::Cancelled
+            } else {
+                LlmError::Other(e.to_string())
+            }
+        });
+    }
+
+    // Gemini has its own request format and authentication
+    if provider == &LLMProvider::Gemini {
+        return generate_gemini(
+            client,
+            GEMINI_API_BASE,
+            model_name,
+            api_key,
+            system_prompt,
+            user_prompt,
+            GeminiGenerationConfig {
+                max_output_tokens: max_tokens.unwrap_or(GEMINI_MAX_OUTPUT_TOKENS),
+                temperature,
+                top_p,
+            },
+            cancellation_token,
+            on_progress,
+        )
+        .await;
+    }
+
+    // Ollama uses its native chat API, which accepts the context window
+    if provider == &LLMProvider::Ollama {
+        return generate_ollama(
+            client,
+            ollama_endpoint.unwrap_or("http://localhost:11434"),
+            model_name,
+            system_prompt,
+            user_prompt,
+            OllamaOptions {
+                num_ctx: ollama_num_ctx,
+                temperature,
+                top_p,
+                num_predict: max_tokens,
+            },
+            cancellation_token,
+            on_progress,
+        )
+        .await;
     }
 
     let (api_url, mut headers) = match provider {
@@ -161,15 +320,6 @@ pub async fn generate_summary(
             "https://openrouter.ai/api/v1/chat/completions".to_string(),
             header::HeaderMap::new(),
         ),
-        LLMProvider::Ollama => {
-            let host = ollama_endpoint
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "http://localhost:11434".to_string());
-            (
-                format!("{}/v1/chat/completions", host),
-                header::HeaderMap::new(),
-            )
-        }
         LLMProvider::CustomOpenAI => {
             let endpoint = custom_openai_endpoint
                 .ok_or_else(|| "Custom OpenAI endpoint not configured".to_string())?;
@@ -194,9 +344,9 @@ pub async fn generate_summary(
             );
             ("https://api.anthropic.com/v1/messages".to_string(), header_map)
         }
-        LLMProvider::BuiltInAI => {
-            // This case is handled earlier with early returns
-            unreachable!("BuiltInAI is handled before this match statement")
+        LLMProvider::BuiltInAI | LLMProvider::Gemini | LLMProvider::Ollama => {
+            // These cases are handled earlier with early returns
+            unreachable!("BuiltInAI, Gemini and Ollama are handled before this match statement")
         }
     };
 
@@ -216,120 +366,348 @@ pub async fn generate_summary(
             .map_err(|_| "Invalid content type".to_string())?,
     );
 
-    // Build request body based on provider
-    let request_body = if provider != &LLMProvider::Claude {
-        // For CustomOpenAI, apply optional parameters if provided
-        let (max_tokens_val, temperature_val, top_p_val) = if provider == &LLMProvider::CustomOpenAI {
-            (max_tokens, temperature, top_p)
-        } else {
-            (None, None, None)
-        };
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system_prompt.to_string(),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: user_prompt.to_string(),
+        },
+    ];
 
-        serde_json::json!(ChatRequest {
-            model: model_name.to_string(),
-            messages: vec![
-                ChatMessage {
-                    role: "system".to_string(),
-                    content: system_prompt.to_string(),
-                },
-                ChatMessage {
-                    role: "user".to_string(),
-                    content: user_prompt.to_string(),
-                }
-            ],
-            max_tokens: max_tokens_val,
-            temperature: temperature_val,
-            top_p: top_p_val,
-        })
-    } else {
-        serde_json::json!(ClaudeRequest {
+    // Build request body based on provider
+    let request_body = match provider {
+        LLMProvider::Claude => serde_json::json!(ClaudeRequest {
             system: system_prompt.to_string(),
             model: model_name.to_string(),
             max_tokens: 2048,
             messages: vec![ChatMessage {
                 role: "user".to_string(),
                 content: user_prompt.to_string(),
-            }]
-        })
+            }],
+            stream: true,
+        }),
+        _ => {
+            // For CustomOpenAI, apply optional parameters if provided
+            let (max_tokens_val, temperature_val, top_p_val) =
+                if provider == &LLMProvider::CustomOpenAI {
+                    (max_tokens, temperature, top_p)
+                } else {
+                    (None, None, None)
+                };
+
+            serde_json::json!(ChatRequest {
+                model: model_name.to_string(),
+                messages,
+                max_tokens: max_tokens_val,
+                temperature: temperature_val,
+                top_p: top_p_val,
+                stream: true,
+            })
+        }
     };
 
     info!("🐞 LLM Request to {}: model={}", provider_name(provider), model_name);
 
+    stream_response(
+        client,
+        provider,
+        &api_url,
+        headers,
+        &request_body,
+        cancellation_token,
+        on_progress,
+    )
+    .await
+    .map(|(text, _)| text)
+}
+
+/// Generates text with the Gemini `streamGenerateContent` API at `api_base`
+#[allow(clippy::too_many_arguments)]
+async fn generate_gemini(
+    client: &Client,
+    api_base: &str,
+    model_name: &str,
+    api_key: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+    generation_config: GeminiGenerationConfig,
+    cancellation_token: Option<&CancellationToken>,
+    on_progress: Option<ProgressCallback<'_>>,
+) -> Result<String, LlmError> {
+    // Model ids from the model list are prefixed with "models/"
+    let model = model_name.trim_start_matches("models/");
+    let api_url = format!(
+        "{}/models/{}:streamGenerateContent?alt=sse",
+        api_base.trim_end_matches('/'),
+        model
+    );
+
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        "x-goog-api-key",
+        api_key
+            .parse()
+            .map_err(|_| "Invalid API key format".to_string())?,
+    );
+    headers.insert(
+        header::CONTENT_TYPE,
+        "application/json"
+            .parse()
+            .map_err(|_| "Invalid content type".to_string())?,
+    );
+
+    let max_output_tokens = generation_config.max_output_tokens;
+    let request_body = serde_json::json!(GeminiRequest {
+        system_instruction: GeminiContent {
+            role: None,
+            parts: vec![GeminiPart {
+                text: system_prompt.to_string(),
+            }],
+        },
+        contents: vec![GeminiContent {
+            role: Some("user".to_string()),
+            parts: vec![GeminiPart {
+                text: user_prompt.to_string(),
+            }],
+        }],
+        generation_config,
+    });
+
+    info!("🐞 LLM Request to Gemini: model={}", model);
+
+    let (text, final_line) = stream_response(
+        client,
+        &LLMProvider::Gemini,
+        &api_url,
+        headers,
+        &request_body,
+        cancellation_token,
+        on_progress,
+    )
+    .await?;
+
+    // A response cut off at the output limit would be stored as if it were complete
+    let finish_reason = final_line.as_deref().and_then(parse_gemini_finish_reason);
+    if finish_reason.as_deref() == Some("MAX_TOKENS") {
+        return Err(LlmError::Other(format!(
+            "Gemini stopped at the limit of {} output tokens; the response is truncated",
+            max_output_tokens
+        )));
+    }
+    Ok(text)
+}
+
+/// Generates text with the Ollama `/api/chat` API at `host`
+///
+/// A prompt that doesn't fit into the context window is truncated by Ollama
+/// without an error, so the token counts of the final line are checked and
+/// truncation is reported as an error.
+#[allow(clippy::too_many_arguments)]
+async fn generate_ollama(
+    client: &Client,
+    host: &str,
+    model_name: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+    options: OllamaOptions,
+    cancellation_token: Option<&CancellationToken>,
+    on_progress: Option<ProgressCallback<'_>>,
+) -> Result<String, LlmError> {
+    let api_url = format!("{}/api/chat", host.trim_end_matches('/'));
+
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        "application/json"
+            .parse()
+            .map_err(|_| "Invalid content type".to_string())?,
+    );
+
+    let num_ctx = options.num_ctx;
+    let request_body = serde_json::json!(OllamaChatRequest {
+        model: model_name.to_string(),
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: user_prompt.to_string(),
+            },
+        ],
+        stream: true,
+        options,
+        keep_alive: OLLAMA_KEEP_ALIVE.to_string(),
+    });
+
+    info!(
+        "🐞 LLM Request to Ollama: model={}, num_ctx={:?}",
+        model_name, num_ctx
+    );
+
+    let (text, final_line) = stream_response(
+        client,
+        &LLMProvider::Ollama,
+        &api_url,
+        headers,
+        &request_body,
+        cancellation_token,
+        on_progress,
+    )
+    .await?;
+
+    if let Some(stats) = final_line.as_deref().and_then(parse_ollama_stats) {
+        let estimated_prompt_tokens =
+            rough_token_count(system_prompt) + rough_token_count(user_prompt);
+        if let Some(message) = ollama_truncation(&stats, estimated_prompt_tokens, num_ctx) {
+            return Err(LlmError::Other(message));
+        }
+        if stats.done_reason.as_deref() == Some("length") {
+            warn!(
+                "Ollama stopped at the length limit after {:?} tokens; the response may be incomplete",
+                stats.eval_count
+            );
+        }
+    }
+    Ok(text)
+}
+
+/// Describes how Ollama truncated the prompt, if the token counts show it did
+///
+/// Ollama cuts a prompt that exceeds `num_ctx` down to fit, so a prompt that
+/// filled the whole window, or one estimated to be larger than the window of
+/// which fewer tokens were evaluated, was truncated.
+fn ollama_truncation(
+    stats: &OllamaStats,
+    estimated_prompt_tokens: usize,
+    num_ctx: Option<usize>,
+) -> Option<String> {
+    let evaluated = stats.prompt_eval_count?;
+    let num_ctx = num_ctx?;
+    let truncated = evaluated >= num_ctx
+        || (estimated_prompt_tokens > num_ctx && evaluated < estimated_prompt_tokens);
+    truncated.then(|| {
+        format!(
+            "Ollama truncated the prompt: only {} of ~{} tokens fit into the context window of {} tokens",
+            evaluated, estimated_prompt_tokens, num_ctx
+        )
+    })
+}
+
+/// Sends a streaming request and collects the generated text
+///
+/// Returns the text together with the last line of the stream, if any: the
+/// final Ollama line, or the Gemini chunk with the finish reason.
+async fn stream_response(
+    client: &Client,
+    provider: &LLMProvider,
+    api_url: &str,
+    headers: header::HeaderMap,
+    request_body: &serde_json::Value,
+    cancellation_token: Option<&CancellationToken>,
+    on_progress: Option<ProgressCallback<'_>>,
+) -> Result<(String, Option<String>), LlmError> {
+    network::check(api_url, Channel::LlmProvider)?;
+
     // Send request with timeout and cancellation support
     let request_future = client
         .post(api_url)
         .headers(headers)
-        .json(&request_body)
+        .json(request_body)
         .timeout(REQUEST_TIMEOUT_DURATION)
         .send();
 
     // Use tokio::select to race between cancellation and request completion
-    let response = if let Some(token) = cancellation_token {
+    let mut response = if let Some(token) = cancellation_token {
         tokio::select! {
-            result = request_future => {
-                result.map_err(|e| {
-                    if e.is_timeout() {
-                        format!("LLM request timed out after 60 seconds")
-                    } else {
-                        format!("Failed to send request to LLM: {}", e)
-                    }
-                })?
-            }
+            result = request_future => result?,
             _ = token.cancelled() => {
-                return Err("Summary generation was cancelled".to_string());
+                return Err(LlmError::Cancelled);
             }
         }
     } else {
-        request_future.await.map_err(|e| {
-            if e.is_timeout() {
-                format!("LLM request timed out after 60 seconds")
-            } else {
-                format!("Failed to send request to LLM: {}", e)
-            }
-        })?
+        request_future.await?
     };
 
-    if !response.status().is_success() {
+    // Include the status, so rate limiting (429) can be told apart from other failures
+    let status = response.status();
+    if !status.is_success() {
         let error_body = response
             .text()
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("LLM API request failed: {}", error_body));
+        return Err(LlmError::Http {
+            status,
+            body: error_body,
+        });
     }
 
-    // Parse response based on provider
-    if provider == &LLMProvider::Claude {
-        let chat_response = response
-            .json::<ClaudeChatResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+    // Read the streamed response, racing every chunk against cancellation
+    let mut lines = LineBuffer::default();
+    let mut output = StreamAccumulator::new(on_progress);
+    let mut done = false;
+    let mut final_line = None;
+    while !done {
+        let chunk = if let Some(token) = cancellation_token {
+            tokio::select! {
+                chunk = response.chunk() => chunk,
+                _ = token.cancelled() => {
+                    info!("LLM stream from {} cancelled", provider_name(provider));
+                    return Err(LlmError::Cancelled);
+                }
+            }
+        } else {
+            response.chunk().await
+        };
 
-        info!("🐞 LLM Response received from Claude");
+        let chunk = chunk.map_err(|e| {
+            if e.is_timeout() {
+                LlmError::Timeout
+            } else {
+                LlmError::Interrupted(e.to_string())
+            }
+        })?;
+        let new_lines = match chunk {
+            Some(chunk) => lines.push(&chunk),
+            None => {
+                done = true;
+                lines.finish().into_iter().collect()
+            }
+        };
+        for line in new_lines {
+            match parse_stream_line(provider, &line) {
+                Some(StreamEvent::Text(text)) => output.push(&text),
+                Some(StreamEvent::Done) => done = true,
+                Some(StreamEvent::Error(message)) => {
+                    return Err(LlmError::Other(format!(
+                        "LLM stream from {} failed: {}",
+                        provider_name(provider),
+                        message
+                    )));
+                }
+                None => {}
+            }
+            if !line.trim().is_empty() {
+                final_line = Some(line);
+            }
+            if done {
+                break;
+            }
+        }
+    }
 
-        let content = chat_response
-            .content
-            .get(0)
-            .ok_or("No content in LLM response")?
-            .text
-            .trim();
-        Ok(content.to_string())
-    } else {
-        let chat_response = response
-            .json::<ChatResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
-
-        info!("🐞 LLM Response received from {}", provider_name(provider));
-
-        let content = chat_response
-            .choices
-            .get(0)
-            .ok_or("No content in LLM response")?
-            .message
-            .content
-            .trim();
-        Ok(content.to_string())
+    info!("🐞 LLM Response received from {}", provider_name(provider));
+
+    let content = output.finish();
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(LlmError::Other("No content in LLM response".to_string()));
     }
+    Ok((content.to_string(), final_line))
 }
 
 /// Helper function to get provider name for logging
@@ -337,6 +715,7 @@ fn provider_name(provider: &LLMProvider) -> &str {
     match provider {
         LLMProvider::OpenAI => "OpenAI",
         LLMProvider::Claude => "Claude",
+        LLMProvider::Gemini => "Gemini",
         LLMProvider::Groq => "Groq",
         LLMProvider::Ollama => "Ollama",
         LLMProvider::BuiltInAI => "Built-in AI",
@@ -344,3 +723,241 @@ fn provider_name(provider: &LLMProvider) -> &str {
         LLMProvider::CustomOpenAI => "Custom OpenAI",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gemini::mock_server::serve_once;
+
+    #[tokio::test]
+    async fn test_gemini_generation_against_mock_server() {
+        let body = [
+            r##"data: {"candidates":[{"content":{"parts":[{"text":"# Lesson 4"}],"role":"model"},"index":0}]}"##,
+            r#"data: {"candidates":[{"content":{"parts":[{"text":"\n\n**Summary**"}],"role":"model"},"finishReason":"STOP","index":0}]}"#,
+        ]
+        .join("\r\n\r\n")
+            + "\r\n\r\n";
+        let (base_url, request) = serve_once("200 OK", "text/event-stream", body).await;
+
+        let text = generate_gemini(
+            &Client::new(),
+            &base_url,
+            "models/gemini-2.0-flash",
+            "test-key",
+            "You write lesson notes.",
+            "Transcript: hola",
+            GeminiGenerationConfig {
+                max_output_tokens: 4096,
+                temperature: Some(0.2),
+                top_p: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "# Lesson 4\n\n**Summary**");
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /models/gemini-2.0-flash:streamGenerateContent?alt=sse "));
+        assert!(request.to_lowercase().contains("x-goog-api-key: test-key"));
+        assert!(!request.to_lowercase().contains("authorization:"));
+
+        let json_body: serde_json::Value =
+            serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(
+            json_body["systemInstruction"]["parts"][0]["text"],
+            "You write lesson notes."
+        );
+        assert_eq!(json_body["contents"][0]["role"], "user");
+        assert_eq!(json_body["contents"][0]["parts"][0]["text"], "Transcript: hola");
+        assert_eq!(
+            json_body["generationConfig"],
+            serde_json::json!({"maxOutputTokens": 4096, "temperature": 0.2f32})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_max_tokens_is_reported_as_truncation() {
+        let body = [
+            r##"data: {"candidates":[{"content":{"parts":[{"text":"# Lesson 4"}],"role":"model"},"index":0}]}"##,
+            r#"data: {"candidates":[{"content":{"parts":[{"text":"\n\n**Summ"}],"role":"model"},"finishReason":"MAX_TOKENS","index":0}]}"#,
+        ]
+        .join("\r\n\r\n")
+            + "\r\n\r\n";
+        let (base_url, _request) = serve_once("200 OK", "text/event-stream", body).await;
+
+        let error = generate_gemini(
+            &Client::new(),
+            &base_url,
+            "gemini-2.0-flash",
+            "test-key",
+            "system",
+            "user",
+            GeminiGenerationConfig {
+                max_output_tokens: 16,
+                temperature: None,
+                top_p: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Gemini stopped at the limit of 16 output tokens; the response is truncated"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_safety_block_is_reported() {
+        let body = "data: {\"promptFeedback\":{\"blockReason\":\"SAFETY\"}}\r\n\r\n".to_string();
+        let (base_url, _request) = serve_once("200 OK", "text/event-stream", body).await;
+
+        let error = generate_gemini(
+            &Client::new(),
+            &base_url,
+            "gemini-2.0-flash",
+            "test-key",
+            "system",
+            "user",
+            GeminiGenerationConfig {
+                max_output_tokens: GEMINI_MAX_OUTPUT_TOKENS,
+                temperature: None,
+                top_p: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "LLM stream from Gemini failed: Prompt blocked by Gemini safety filters (SAFETY)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_http_error_includes_status() {
+        let body = r#"{"error":{"code":404,"message":"models/gemini-0 is not found","status":"NOT_FOUND"}}"#;
+        let (base_url, _request) =
+            serve_once("404 Not Found", "application/json", body.to_string()).await;
+
+        let error = generate_gemini(
+            &Client::new(),
+            &base_url,
+            "gemini-0",
+            "test-key",
+            "system",
+            "user",
+            GeminiGenerationConfig {
+                max_output_tokens: GEMINI_MAX_OUTPUT_TOKENS,
+                temperature: None,
+                top_p: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&error, LlmError::Http { status, .. } if *status == StatusCode::NOT_FOUND)
+        );
+        assert!(error
+            .to_string()
+            .starts_with("LLM API request failed (404 Not Found)"));
+        assert!(error.to_string().contains("is not found"));
+    }
+
+    #[tokio::test]
+    async fn test_ollama_generation_sends_context_window() {
+        let body = [
+            r##"{"model":"llama3.2","message":{"role":"assistant","content":"# Lesson"},"done":false}"##,
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":" 4"},"done":false}"#,
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":12,"eval_count":2}"#,
+        ]
+        .join("\n")
+            + "\n";
+        let (base_url, request) = serve_once("200 OK", "application/x-ndjson", body).await;
+
+        let text = generate_ollama(
+            &Client::new(),
+            &base_url,
+            "llama3.2",
+            "You write lesson notes.",
+            "Transcript: hola",
+            OllamaOptions {
+                num_ctx: Some(8192),
+                temperature: Some(0.3),
+                top_p: None,
+                num_predict: Some(1024),
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "# Lesson 4");
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /api/chat "));
+        let json_body: serde_json::Value =
+            serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(json_body["options"]["num_ctx"], 8192);
+        assert_eq!(json_body["options"]["num_predict"], 1024);
+        assert!(json_body["options"].get("top_p").is_none());
+        assert_eq!(json_body["keep_alive"], OLLAMA_KEEP_ALIVE);
+        assert_eq!(json_body["messages"][0]["role"], "system");
+        assert_eq!(json_body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_ollama_reports_truncated_prompt() {
+        let body = [
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":"Notes"},"done":false}"#,
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":2048,"eval_count":1}"#,
+        ]
+        .join("\n");
+        let (base_url, _request) = serve_once("200 OK", "application/x-ndjson", body).await;
+
+        let error = generate_ollama(
+            &Client::new(),
+            &base_url,
+            "llama3.2",
+            "system",
+            &"hola ".repeat(2000),
+            OllamaOptions {
+                num_ctx: Some(2048),
+                temperature: None,
+                top_p: None,
+                num_predict: None,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Ollama truncated the prompt: only 2048 of ~"));
+    }
+
+    #[test]
+    fn test_ollama_truncation() {
+        let stats = |evaluated| OllamaStats {
+            prompt_eval_count: Some(evaluated),
+            ..Default::default()
+        };
+        // Fits into the window
+        assert_eq!(ollama_truncation(&stats(1500), 1600, Some(4096)), None);
+        // Cached prefix tokens aren't counted as evaluated
+        assert_eq!(ollama_truncation(&stats(200), 1600, Some(4096)), None);
+        // Filled the whole window
+        assert!(ollama_truncation(&stats(4096), 3900, Some(4096)).is_some());
+        // Older Ollama versions keep only part of an oversized prompt
+        assert!(ollama_truncation(&stats(2100), 6000, Some(4096)).is_some());
+        // Without a requested window there is nothing to compare against
+        assert_eq!(ollama_truncation(&stats(4096), 6000, None), None);
+    }
+}
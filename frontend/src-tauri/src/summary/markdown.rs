@@ -0,0 +1,131 @@
+/// Returns the section title if the line is a markdown heading (`## Title`) or a
+/// bold section line (`**Title**`) as produced by `Template::to_markdown_structure`
+pub fn section_heading_title(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+
+    let title = if trimmed.starts_with('#') {
+        trimmed.trim_start_matches('#').trim()
+    } else if trimmed.len() > 4 && trimmed.starts_with("**") && trimmed.ends_with("**") {
+        trimmed[2..trimmed.len() - 2].trim()
+    } else {
+        return None;
+    };
+
+    Some(title.trim_end_matches(':').trim())
+}
+
+/// Strips list markers (`-`, `*`, `+`, `1.`) and task checkboxes from a list item line
+pub fn list_item_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+
+    let rest = if let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        trimmed[digits..]
+            .strip_prefix(". ")
+            .or_else(|| trimmed[digits..].strip_prefix(") "))?
+    };
+
+    let rest = rest
+        .strip_prefix("[ ] ")
+        .or_else(|| rest.strip_prefix("[x] "))
+        .or_else(|| rest.strip_prefix("[X] "))
+        .unwrap_or(rest);
+
+    Some(rest.trim())
+}
+
+/// Cells of a table row (`| a | b |`), trimmed and without the outer pipes
+fn table_cells(line: &str) -> Option<Vec<&str>> {
+    let inner = line.trim().strip_prefix('|')?;
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    Some(inner.split('|').map(str::trim).collect())
+}
+
+/// Whether a table row is the `| --- | :---: |` line below the header
+fn is_table_separator(cells: &[&str]) -> bool {
+    cells.iter().all(|cell| {
+        !cell.is_empty() && cell.contains('-') && cell.chars().all(|c| matches!(c, '-' | ':'))
+    })
+}
+
+/// Column names of a table skeleton such as a section's `item_format`
+/// (`| **Word** | **Translation** |` followed by `| --- | --- |`), with bold and code markup removed
+///
+/// # Returns
+/// None if `text` doesn't start with a header row and a separator row
+pub fn table_column_names(text: &str) -> Option<Vec<String>> {
+    let mut lines = text.trim().lines();
+    let header = table_cells(lines.next()?)?;
+    if !is_table_separator(&table_cells(lines.next()?)?) {
+        return None;
+    }
+    Some(header.into_iter().map(plain_cell).collect())
+}
+
+/// Cells of a table data row, with bold and code markup removed
+///
+/// # Arguments
+/// * `line` - The line to parse
+/// * `next_line` - The line after it, to recognize the header row above the separator
+///
+/// # Returns
+/// None for lines that aren't tables, header rows and separator rows
+pub fn table_data_cells(line: &str, next_line: Option<&str>) -> Option<Vec<String>> {
+    let cells = table_cells(line)?;
+    if is_table_separator(&cells)
+        || next_line
+            .and_then(table_cells)
+            .is_some_and(|next| is_table_separator(&next))
+    {
+        return None;
+    }
+    Some(cells.into_iter().map(plain_cell).collect())
+}
+
+/// A table cell with bold and code markup removed
+fn plain_cell(cell: &str) -> String {
+    cell.replace(['*', '`'], "").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_data_cells_skip_header_and_separator() {
+        let table = "| **Word/Phrase** | **Translation/Definition** |\n| --- | :---: |\n| **el mercado** | the market |\n|`la tienda`|the shop";
+        let lines: Vec<&str> = table.lines().collect();
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| table_data_cells(line, lines.get(i + 1).copied()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["el mercado".to_string(), "the market".to_string()],
+                vec!["la tienda".to_string(), "the shop".to_string()],
+            ]
+        );
+        assert_eq!(table_data_cells("- not a table", None), None);
+    }
+
+    #[test]
+    fn test_table_column_names() {
+        assert_eq!(
+            table_column_names("| **Word/Phrase** | **Translation** |\n| --- | --- |"),
+            Some(vec!["Word/Phrase".to_string(), "Translation".to_string()])
+        );
+        assert_eq!(table_column_names("| **Word/Phrase** | **Translation** |"), None);
+        assert_eq!(table_column_names("- **Word**: translation"), None);
+    }
+}
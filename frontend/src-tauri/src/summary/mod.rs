@@ -1,9 +1,20 @@
 /// Summary module - handles all session summary generation functionality
 ///
 /// This module contains:
-/// - LLM client for communicating with various AI providers (OpenAI, Claude, Groq, Ollama, OpenRouter, CustomOpenAI)
+/// - LLM client for communicating with various AI providers (OpenAI, Claude, Gemini, Groq, Ollama, OpenRouter, CustomOpenAI)
 /// - Processor for chunking transcripts and generating summaries
+/// - Markdown helpers for reading sections, list items and table rows of notes
+/// - Token counting with the model's vocabulary or a per-script estimate
+/// - Streaming of LLM output with `summary-progress` events
+/// - Structured (JSON) notes for the built-in model, rendered back to markdown
 /// - Service layer for orchestrating summary generation
+/// - Provider fallback chains for unreachable or rate-limited providers
+/// - Redaction of personal data before transcripts are sent to cloud providers
+/// - Persistent job queue running summaries with retries and restart recovery
+/// - Batch regeneration of many sessions with a new template or model
+/// - Previous-session context for describing progress between lessons
+/// - Transcript timestamp citations linking note items to the audio
+/// - Version history of generated summaries with diff and restore
 /// - Templates for structured session summary generation
 /// - Tauri commands for frontend integration
 
@@ -30,31 +41,103 @@ pub struct CustomOpenAIConfig {
     pub top_p: Option<f32>,
 }
 
+/// Sampling parameters for providers without a configuration of their own (Gemini, Ollama)
+/// Stored as JSON in the database (settings.samplingConfig); unset values use the provider's defaults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingConfig {
+    /// Maximum tokens for completion (optional)
+    pub max_tokens: Option<u32>,
+    /// Temperature parameter (0.0-2.0, optional)
+    pub temperature: Option<f32>,
+    /// Top-P sampling parameter (0.0-1.0, optional)
+    pub top_p: Option<f32>,
+}
+
+impl SamplingConfig {
+    /// Checks that the parameters are in the ranges the providers accept
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(temp) = self.temperature {
+            if !(0.0..=2.0).contains(&temp) {
+                return Err("Temperature must be between 0.0 and 2.0".to_string());
+            }
+        }
+        if let Some(top) = self.top_p {
+            if !(0.0..=1.0).contains(&top) {
+                return Err("Top P must be between 0.0 and 1.0".to_string());
+            }
+        }
+        if self.max_tokens == Some(0) {
+            return Err("Max tokens must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub mod batch;
+pub mod batch_commands;
+pub mod citations;
 pub mod commands;
+pub mod fallback;
+pub mod job_queue;
 pub mod llm_client;
+pub mod markdown;
 pub mod processor;
+pub mod redaction;
 pub mod service;
+pub mod session_context;
+pub mod streaming;
+pub mod structured_output;
 pub mod summary_engine;
 pub mod template_commands;
 pub mod templates;
+pub mod tokenizer;
+pub mod version_commands;
+pub mod versions;
 
 // Re-export Tauri commands (with their generated __cmd__ variants)
 pub use commands::{
-    __cmd__api_cancel_summary, __cmd__api_get_summary, __cmd__api_process_transcript,
+    __cmd__api_cancel_summary, __cmd__api_get_summary, __cmd__api_get_summary_citations,
+    __cmd__api_get_summary_queue, __cmd__api_process_transcript,
     __cmd__api_save_session_summary, api_cancel_summary, api_get_summary,
-    api_process_transcript, api_save_session_summary,
+    api_get_summary_citations, api_get_summary_queue, api_process_transcript,
+    api_save_session_summary,
 };
 
 // Re-export template commands
 pub use template_commands::{
-    __cmd__api_get_template_details, __cmd__api_list_templates, __cmd__api_validate_template,
-    api_get_template_details, api_list_templates, api_validate_template,
+    __cmd__api_create_template, __cmd__api_delete_template, __cmd__api_duplicate_template,
+    __cmd__api_export_template, __cmd__api_get_learner_profile, __cmd__api_get_template_details,
+    __cmd__api_get_template_json, __cmd__api_import_template, __cmd__api_list_template_variables,
+    __cmd__api_list_templates, __cmd__api_save_learner_profile, __cmd__api_update_template,
+    __cmd__api_validate_template, api_create_template, api_delete_template,
+    api_duplicate_template, api_export_template, api_get_learner_profile,
+    api_get_template_details, api_get_template_json, api_import_template,
+    api_list_template_variables, api_list_templates, api_save_learner_profile,
+    api_update_template, api_validate_template,
+};
+
+// Re-export batch regeneration commands
+pub use batch_commands::{
+    __cmd__api_cancel_summary_batch, __cmd__api_get_summary_batch,
+    __cmd__api_list_summary_batches, __cmd__api_preview_summary_batch,
+    __cmd__api_start_summary_batch, api_cancel_summary_batch, api_get_summary_batch,
+    api_list_summary_batches, api_preview_summary_batch, api_start_summary_batch,
+};
+
+// Re-export summary version commands
+pub use version_commands::{
+    __cmd__api_diff_summary_versions, __cmd__api_get_summary_version,
+    __cmd__api_list_summary_versions, __cmd__api_restore_summary_version,
+    api_diff_summary_versions, api_get_summary_version, api_list_summary_versions,
+    api_restore_summary_version,
 };
 
 // Re-export commonly used items
 pub use llm_client::LLMProvider;
 pub use processor::{
     chunk_text, clean_llm_markdown_output, extract_session_name_from_markdown,
-    generate_session_summary, rough_token_count,
+    generate_session_summary, rough_token_count, verify_summary_against_transcript,
+    VerificationReport,
 };
-pub use service::SummaryService;
+pub use service::{SummaryOutcome, SummaryService};
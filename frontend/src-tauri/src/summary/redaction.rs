@@ -0,0 +1,449 @@
+use crate::network::is_loopback_url;
+use crate::summary::llm_client::LLMProvider;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Written and spoken ("maria at gmail dot com") email addresses
+static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b|\b(?:[a-z0-9._-]+\s+dot\s+)*[a-z0-9._-]+\s+at\s+[a-z0-9-]+(?:\s+dot\s+[a-z]{2,}){1,2}\b",
+    )
+    .unwrap()
+});
+
+/// Phone numbers: digit groups with optional country code, area code in
+/// parentheses and separators; only matches with enough digits are redacted
+static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)[\s.-]?)?\b\d{2,4}(?:[\s.-]?\d{2,4}){1,5}\b")
+        .unwrap()
+});
+
+/// Street addresses in the formats of the most common lesson languages
+static ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        // 221 Baker Street, 12 rue de la Paix
+        r"\b\d{1,5},?\s+(?:(?:\p{Lu}[\w'-]*\s+){1,3}(?i:street|st|avenue|ave|road|rd|boulevard|blvd|lane|ln|drive|dr|court|ct|way|place|pl|terrace|close)\b\.?",
+        r"|(?i:rue|avenue|boulevard|place|chemin|allée)\s+(?:(?:de|du|des|la|le|l')\s*)*\p{Lu}[\w'-]*)",
+        // Calle Mayor 5, Via Roma 10, Rua Augusta 25
+        r"|\b(?i:calle|avenida|avda\.|plaza|paseo|via|viale|piazza|rua|travessa)\s+(?:(?:de|del|la|los|las|da|do|di|della)\s+)*(?:\p{Lu}[\w'-]*\s+){1,3},?\s*\d{1,5}\b",
+        // Hauptstraße 12, Lindenweg 3a, Altstadtring 7; "ring" only after a stem of three
+        // letters not ending in "e", so "Spring 2024" or "Offering 2" aren't addresses
+        r"|\b\p{Lu}[\w-]+(?:straße|strasse|str\.|weg|gasse|allee|platz|[\w&&[^e]]ring)\s+\d{1,4}[a-z]?\b",
+    ))
+    .unwrap()
+});
+
+/// Placeholders as written by `Redactor`, also when the model dropped the brackets
+static PLACEHOLDER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[?\b(NAME|EMAIL|PHONE|ADDRESS)_(\d+)\b\]?").unwrap());
+
+/// Digits a phone number needs, so years, prices and page numbers stay
+const MIN_PHONE_DIGITS: usize = 9;
+const MAX_PHONE_DIGITS: usize = 15;
+
+/// Personal data redaction for cloud providers
+/// Stored as JSON in the database (settings.redactionConfig)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionConfig {
+    /// Replace personal data before transcripts are sent to a cloud provider
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Names of people to redact (the learner, family, classmates); the tutor
+    /// name of the learner profile is always included
+    #[serde(default)]
+    pub names: Vec<String>,
+
+    /// Words that are never redacted, even if a pattern matches them
+    #[serde(default)]
+    pub allowed_terms: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            names: Vec::new(),
+            allowed_terms: Vec::new(),
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Checks that no name is blank
+    pub fn validate(&self) -> Result<(), String> {
+        if self.names.iter().any(|name| name.trim().is_empty()) {
+            return Err("Names to redact cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Kind of personal data, used as placeholder label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PiiKind {
+    Name,
+    Email,
+    Phone,
+    Address,
+}
+
+impl PiiKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "NAME",
+            Self::Email => "EMAIL",
+            Self::Phone => "PHONE",
+            Self::Address => "ADDRESS",
+        }
+    }
+
+    fn report_key(&self) -> &'static str {
+        match self {
+            Self::Name => "names",
+            Self::Email => "emails",
+            Self::Phone => "phones",
+            Self::Address => "addresses",
+        }
+    }
+}
+
+/// What was redacted, without the redacted values
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RedactionReport {
+    /// Replaced occurrences by kind
+    pub replaced: BTreeMap<&'static str, usize>,
+    /// Distinct values by kind
+    pub distinct: BTreeMap<&'static str, usize>,
+}
+
+impl RedactionReport {
+    /// Number of replaced occurrences
+    pub fn total(&self) -> usize {
+        self.replaced.values().sum()
+    }
+}
+
+impl std::fmt::Display for RedactionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.replaced.is_empty() {
+            return write!(f, "nothing redacted");
+        }
+        let parts: Vec<String> = self
+            .replaced
+            .iter()
+            .map(|(kind, count)| {
+                format!(
+                    "{} {} ({} distinct)",
+                    count,
+                    kind,
+                    self.distinct.get(kind).unwrap_or(&0)
+                )
+            })
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Replaces personal data with placeholders and puts it back into the model's output
+///
+/// A value gets the same placeholder (`[NAME_1]`, `[EMAIL_2]`, ...) in all text
+/// redacted by one redactor, so the chunks and prompts of a session refer to
+/// the same person consistently.
+pub struct Redactor {
+    names: Option<Regex>,
+    allowed_terms: HashSet<String>,
+    /// Placeholder by kind and normalized value
+    placeholders: HashMap<(PiiKind, String), String>,
+    /// Original value by placeholder
+    originals: HashMap<String, String>,
+    report: RedactionReport,
+}
+
+impl Redactor {
+    /// Creates a redactor for the configured names and `extra_names` (e.g. from the learner profile)
+    pub fn new(config: &RedactionConfig, extra_names: &[String]) -> Self {
+        let mut names: Vec<&str> = config
+            .names
+            .iter()
+            .chain(extra_names)
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .collect();
+        // Longest first, so "Maria Garcia" wins over "Maria"
+        names.sort_by_key(|name| std::cmp::Reverse(name.chars().count()));
+        names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+        let names = (!names.is_empty()).then(|| {
+            let alternatives: Vec<String> = names.iter().map(|name| name_pattern(name)).collect();
+            Regex::new(&format!("(?i){}", alternatives.join("|"))).unwrap()
+        });
+
+        Self {
+            names,
+            allowed_terms: config
+                .allowed_terms
+                .iter()
+                .map(|term| term.trim().to_lowercase())
+                .collect(),
+            placeholders: HashMap::new(),
+            originals: HashMap::new(),
+            report: RedactionReport::default(),
+        }
+    }
+
+    /// Replaces emails, addresses, phone numbers and names with placeholders
+    pub fn redact(&mut self, text: &str) -> String {
+        let text = self.replace(&EMAIL_REGEX, PiiKind::Email, text);
+        let text = self.replace(&ADDRESS_REGEX, PiiKind::Address, &text);
+        let text = self.replace(&PHONE_REGEX, PiiKind::Phone, &text);
+        match self.names.clone() {
+            Some(names) => self.replace(&names, PiiKind::Name, &text),
+            None => text,
+        }
+    }
+
+    /// Puts the original values back in place of the placeholders
+    pub fn restore(&self, text: &str) -> String {
+        PLACEHOLDER_REGEX
+            .replace_all(text, |caps: &Captures| {
+                let placeholder = format!("[{}_{}]", &caps[1], &caps[2]);
+                match self.originals.get(&placeholder) {
+                    Some(original) => original.clone(),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    pub fn report(&self) -> &RedactionReport {
+        &self.report
+    }
+
+    fn replace(&mut self, regex: &Regex, kind: PiiKind, text: &str) -> String {
+        regex
+            .replace_all(text, |caps: &Captures| {
+                let value = &caps[0];
+                if self.allowed_terms.contains(&value.to_lowercase()) {
+                    return value.to_string();
+                }
+                if kind == PiiKind::Phone {
+                    let digits = value.chars().filter(char::is_ascii_digit).count();
+                    if !(MIN_PHONE_DIGITS..=MAX_PHONE_DIGITS).contains(&digits) {
+                        return value.to_string();
+                    }
+                }
+                self.placeholder(kind, value)
+            })
+            .into_owned()
+    }
+
+    fn placeholder(&mut self, kind: PiiKind, value: &str) -> String {
+        let normalized = match kind {
+            PiiKind::Phone => value.chars().filter(char::is_ascii_digit).collect(),
+            _ => value
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase(),
+        };
+        *self.report.replaced.entry(kind.report_key()).or_default() += 1;
+
+        if let Some(placeholder) = self.placeholders.get(&(kind, normalized.clone())) {
+            return placeholder.clone();
+        }
+        let distinct = self.report.distinct.entry(kind.report_key()).or_default();
+        *distinct += 1;
+        let placeholder = format!("[{}_{}]", kind.label(), distinct);
+        self.placeholders
+            .insert((kind, normalized), placeholder.clone());
+        self.originals
+            .insert(placeholder.clone(), value.to_string());
+        placeholder
+    }
+}
+
+/// Pattern for a configured name, matching it as a whole word
+///
+/// Scripts written without spaces have no word boundaries around names, so
+/// the boundary is only required next to letters of spaced scripts.
+fn name_pattern(name: &str) -> String {
+    let spaced = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() && !is_unspaced_script(c));
+    format!(
+        "{}{}{}",
+        if spaced(name.chars().next()) {
+            r"\b"
+        } else {
+            ""
+        },
+        regex::escape(name),
+        if spaced(name.chars().next_back()) {
+            r"\b"
+        } else {
+            ""
+        },
+    )
+}
+
+/// Han, Kana, Hangul syllables and Thai/Lao/Khmer, where words aren't separated by spaces
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0EFF | 0x1780..=0x17FF | 0x3040..=0x30FF | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF)
+}
+
+/// Whether a provider runs on this machine, so transcripts never leave it
+///
+/// Ollama and custom OpenAI-compatible servers count as local only when their
+/// endpoint is a loopback address (Ollama defaults to localhost).
+pub fn is_local_provider(provider: &LLMProvider, endpoint: Option<&str>) -> bool {
+    match provider {
+        LLMProvider::BuiltInAI => true,
+        LLMProvider::Ollama => endpoint.map_or(true, is_loopback_url),
+        LLMProvider::CustomOpenAI => endpoint.is_some_and(is_loopback_url),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(names: &[&str]) -> Redactor {
+        let config = RedactionConfig {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            ..Default::default()
+        };
+        Redactor::new(&config, &[])
+    }
+
+    #[test]
+    fn test_redacts_contact_details() {
+        let mut redactor = redactor(&[]);
+        let text = "[00:12] Student: My email is maria.garcia@example.com and my number is +34 612 345 678. \
+                    [00:20] Student: I live at 221 Baker Street, or write to maria dot garcia at gmail dot com.";
+        let redacted = redactor.redact(text);
+        for secret in [
+            "maria.garcia@example.com",
+            "612 345 678",
+            "221 Baker Street",
+            "gmail dot com",
+        ] {
+            assert!(!redacted.contains(secret), "{} in {}", secret, redacted);
+        }
+        assert!(
+            redacted.contains("[EMAIL_1]")
+                && redacted.contains("[PHONE_1]")
+                && redacted.contains("[ADDRESS_1]")
+        );
+        // Timestamps are kept, so citations still work
+        assert!(redacted.starts_with("[00:12] Student:") && redacted.contains("[00:20]"));
+        assert_eq!(redactor.report().replaced["emails"], 2);
+    }
+
+    #[test]
+    fn test_keeps_numbers_that_are_not_phones() {
+        let mut redactor = redactor(&[]);
+        let text =
+            "Open page 104, exercise 3b. We met in 2019-2020 and it costs 1 500 euros at 10:30.";
+        assert_eq!(redactor.redact(text), text);
+        assert_eq!(redactor.report().total(), 0);
+    }
+
+    #[test]
+    fn test_redacts_addresses_in_other_languages() {
+        let mut redactor = redactor(&[]);
+        for address in [
+            "Calle Mayor 5",
+            "12 rue de la Paix",
+            "Hauptstraße 12",
+            "Altstadtring 7",
+            "Via Roma 10",
+        ] {
+            let redacted = redactor.redact(&format!("Vivo en {}, cerca del centro.", address));
+            assert!(!redacted.contains(address), "{}", redacted);
+        }
+    }
+
+    #[test]
+    fn test_keeps_words_that_only_end_like_street_names() {
+        let mut redactor = redactor(&[]);
+        let text = "In Spring 2024 we studied a lot. Bring 3 examples. Offering 2 options, String 5 words.";
+        assert_eq!(redactor.redact(text), text);
+        assert_eq!(redactor.report().total(), 0);
+    }
+
+    #[test]
+    fn test_names_get_stable_placeholders() {
+        let mut redactor = redactor(&["Maria Garcia", "Maria", "王芳"]);
+        let first = redactor.redact("Maria Garcia said hello. Then MARIA asked about Mariana.");
+        let second = redactor.redact("Chunk two: Maria again. 我叫王芳。");
+        assert_eq!(
+            first,
+            "[NAME_1] said hello. Then [NAME_2] asked about Mariana."
+        );
+        assert_eq!(second, "Chunk two: [NAME_2] again. 我叫[NAME_3]。");
+        assert_eq!(redactor.report().distinct["names"], 3);
+        assert_eq!(redactor.report().replaced["names"], 4);
+    }
+
+    #[test]
+    fn test_restores_placeholders_in_summary() {
+        let mut redactor = redactor(&["Maria"]);
+        redactor.redact("Maria: call me at 0612 345 678");
+        let summary = "- NAME_1 practised phone numbers ([PHONE_1]) [00:12]\n- [NAME_9] is unknown";
+        assert_eq!(
+            redactor.restore(summary),
+            "- Maria practised phone numbers (0612 345 678) [00:12]\n- [NAME_9] is unknown"
+        );
+    }
+
+    #[test]
+    fn test_allowed_terms_are_kept() {
+        let config = RedactionConfig {
+            names: vec!["Paris".to_string()],
+            allowed_terms: vec!["paris".to_string()],
+            ..Default::default()
+        };
+        let mut redactor = Redactor::new(&config, &[]);
+        assert_eq!(
+            redactor.redact("We talked about Paris."),
+            "We talked about Paris."
+        );
+    }
+
+    #[test]
+    fn test_local_providers() {
+        assert!(is_local_provider(&LLMProvider::BuiltInAI, None));
+        assert!(is_local_provider(&LLMProvider::Ollama, None));
+        assert!(is_local_provider(
+            &LLMProvider::Ollama,
+            Some("http://127.0.0.1:11434")
+        ));
+        assert!(!is_local_provider(
+            &LLMProvider::Ollama,
+            Some("http://gpu-box.lan:11434")
+        ));
+        assert!(is_local_provider(
+            &LLMProvider::CustomOpenAI,
+            Some("http://localhost:8000/v1")
+        ));
+        assert!(is_local_provider(
+            &LLMProvider::CustomOpenAI,
+            Some("http://[::1]:8000/v1")
+        ));
+        assert!(!is_local_provider(
+            &LLMProvider::CustomOpenAI,
+            Some("https://api.together.xyz/v1")
+        ));
+        assert!(!is_local_provider(&LLMProvider::OpenAI, None));
+        assert!(!is_local_provider(&LLMProvider::Claude, None));
+    }
+}
@@ -1,14 +1,32 @@
 use crate::database::repositories::{
+    homework::HomeworkRepository, learner_profile::LearnerProfileRepository,
     meeting::MeetingsRepository, setting::SettingsRepository, summary::SummaryProcessesRepository,
+    citation::CitationsRepository,
+    summary_version::{NewSummaryVersion, SummaryVersionsRepository},
+    transcript::TranscriptsRepository,
 };
-use crate::summary::llm_client::LLMProvider;
-use crate::summary::processor::{extract_session_name_from_markdown, generate_session_summary};
+use crate::homework;
+use crate::summary::citations::{self, TimedSegment, TranscriptText};
+use crate::summary::fallback::{self, FallbackProvider, ProviderFallbackConfig};
+use crate::summary::job_queue;
+use crate::summary::llm_client::{LLMProvider, LlmError};
+use crate::summary::processor::{
+    extract_session_name_from_markdown, generate_session_summary, notes_section_titles,
+    verify_summary_against_transcript,
+};
+use crate::summary::redaction::{self, RedactionConfig, RedactionReport, Redactor};
+use crate::summary::session_context::{self, PreviousSessionNotes};
+use crate::summary::streaming::StreamProgress;
+use crate::summary::templates::{self, Template, TemplateContext};
+use crate::summary::versions;
+use crate::summary::SamplingConfig;
 use crate::ollama::metadata::ModelMetadataCache;
+use serde::Serialize;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use once_cell::sync::Lazy;
@@ -22,12 +40,105 @@ static METADATA_CACHE: Lazy<ModelMetadataCache> = Lazy::new(|| {
 static CANCELLATION_REGISTRY: Lazy<Arc<Mutex<HashMap<String, CancellationToken>>>> =
     Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
 
+/// Event emitted with a `SummaryProgress` while the LLM output is streamed
+pub const SUMMARY_PROGRESS_EVENT: &str = "summary-progress";
+
+/// Partial output of the LLM request currently generating a session's summary
+#[derive(Debug, Serialize)]
+pub struct SummaryProgress<'a> {
+    pub meeting_id: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    #[serde(flatten)]
+    pub progress: &'a StreamProgress,
+}
+
+/// How a summary generation attempt ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum SummaryOutcome {
+    Completed,
+    Cancelled,
+    /// The process was marked as failed
+    Failed(String),
+    /// A transient provider error; the process was left pending so it can be retried
+    Retryable(String),
+}
+
+/// Provider-specific settings needed to call a model
+pub(crate) struct ProviderSettings {
+    pub(crate) provider: LLMProvider,
+    pub(crate) api_key: String,
+    pub(crate) ollama_endpoint: Option<String>,
+    /// Context window requested from Ollama (the model's context size, `None` if it couldn't be fetched)
+    pub(crate) ollama_num_ctx: Option<usize>,
+    pub(crate) custom_openai_endpoint: Option<String>,
+    pub(crate) max_tokens: Option<u32>,
+    pub(crate) temperature: Option<f32>,
+    pub(crate) top_p: Option<f32>,
+    /// Token limit for single-pass processing
+    pub(crate) token_threshold: usize,
+}
+
+/// Summary inputs with personal data replaced, for providers outside this machine
+struct RedactedInputs {
+    redactor: Redactor,
+    text: TranscriptText,
+    custom_prompt: String,
+    homework_context: Option<String>,
+    previous_sessions: Vec<PreviousSessionNotes>,
+    template: Template,
+}
+
+impl RedactedInputs {
+    fn new(
+        mut redactor: Redactor,
+        text: &TranscriptText,
+        custom_prompt: &str,
+        homework_context: Option<&str>,
+        previous_sessions: &[PreviousSessionNotes],
+        template: &Template,
+    ) -> Self {
+        // Segment by segment, so the segment boundaries stay known
+        let text = text.map_segments(|segment| redactor.redact(segment));
+        let custom_prompt = redactor.redact(custom_prompt);
+        let homework_context = homework_context.map(|context| redactor.redact(context));
+        let previous_sessions = previous_sessions
+            .iter()
+            .map(|notes| PreviousSessionNotes {
+                title: redactor.redact(&notes.title),
+                date: notes.date,
+                markdown: redactor.redact(&notes.markdown),
+            })
+            .collect();
+        // The resolved template carries profile values such as the tutor's name
+        let mut template = template.clone();
+        for section in &mut template.sections {
+            section.title = redactor.redact(&section.title);
+            section.instruction = redactor.redact(&section.instruction);
+            for format in [&mut section.item_format, &mut section.example_item_format]
+                .into_iter()
+                .flatten()
+            {
+                *format = redactor.redact(format);
+            }
+        }
+        Self {
+            redactor,
+            text,
+            custom_prompt,
+            homework_context,
+            previous_sessions,
+            template,
+        }
+    }
+}
+
 /// Summary service - handles all summary generation logic
 pub struct SummaryService;
 
 impl SummaryService {
     /// Registers a new cancellation token for a session
-    fn register_cancellation_token(meeting_id: &str) -> CancellationToken {
+    pub(crate) fn register_cancellation_token(meeting_id: &str) -> CancellationToken {
         let token = CancellationToken::new();
         if let Ok(mut registry) = CANCELLATION_REGISTRY.lock() {
             registry.insert(meeting_id.to_string(), token.clone());
@@ -50,7 +161,7 @@ impl SummaryService {
     }
 
     /// Cleans up the cancellation token after processing completes
-    fn cleanup_cancellation_token(meeting_id: &str) {
+    pub(crate) fn cleanup_cancellation_token(meeting_id: &str) {
         if let Ok(mut registry) = CANCELLATION_REGISTRY.lock() {
             if registry.remove(meeting_id).is_some() {
                 info!("Cleaned up cancellation token for session: {}", meeting_id);
@@ -60,11 +171,11 @@ impl SummaryService {
 
     /// Processes transcript in the background and generates summary
     ///
-    /// This function is designed to be spawned as an async task and does not block
-    /// the main thread. It updates the database with progress and results.
+    /// This function is run by the summary job queue and does not block the main
+    /// thread. It updates the database with progress and results.
     ///
     /// # Arguments
-    /// * `_app` - Tauri app handle (for future use)
+    /// * `_app` - Tauri app handle (for app data paths and `summary-progress` events)
     /// * `pool` - SQLx connection pool
     /// * `meeting_id` - Unique identifier for the session
     /// * `text` - Full transcript text
@@ -72,6 +183,8 @@ impl SummaryService {
     /// * `model_name` - Specific model (e.g., "gpt-4", "llama3.2:latest")
     /// * `custom_prompt` - Optional user-provided context
     /// * `template_id` - Template identifier (e.g., "daily_standup", "standard_meeting" etc.)
+    /// * `previous_session_count` - Number of earlier sessions whose notes are added as context (0 disables)
+    /// * `allow_retry` - Leave the process pending on transient provider errors, so the caller can retry
     pub async fn process_transcript_background<R: tauri::Runtime>(
         _app: AppHandle<R>,
         pool: SqlitePool,
@@ -81,7 +194,9 @@ impl SummaryService {
         model_name: String,
         custom_prompt: String,
         template_id: String,
-    ) {
+        previous_session_count: usize,
+        allow_retry: bool,
+    ) -> SummaryOutcome {
         let start_time = Instant::now();
         info!(
             "Starting background processing for session id: {}",
@@ -91,38 +206,439 @@ impl SummaryService {
         // Register cancellation token for this session
         let cancellation_token = Self::register_cancellation_token(&meeting_id);
 
-        // Parse provider
-        let provider = match LLMProvider::from_str(&model_provider) {
-            Ok(p) => p,
+        // The selected provider first, then the configured fallbacks
+        let fallback_config = match SettingsRepository::get_provider_fallback_config(&pool).await {
+            Ok(config) => config.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to load provider fallback config: {}, using none", e);
+                ProviderFallbackConfig::default()
+            }
+        };
+        let candidates = match fallback_config.candidates(&model_provider, &model_name) {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                Self::cleanup_cancellation_token(&meeting_id);
+                return Self::update_process_failed(&pool, &meeting_id, &e).await;
+            }
+        };
+
+        // Get app data directory for BuiltInAI provider
+        let app_data_dir = _app.path().app_data_dir().ok();
+
+        // Open homework from earlier sessions, so the notes can say whether it was reviewed
+        let open_homework =
+            match HomeworkRepository::get_open_items_before_meeting(&pool, &meeting_id).await {
+                Ok(items) => items,
+                Err(e) => {
+                    warn!("Failed to load open homework for {}: {}", meeting_id, e);
+                    Vec::new()
+                }
+            };
+        let homework_context = homework::build_open_homework_context(&open_homework);
+
+        // Notes of earlier sessions, so the summary can describe progress since then
+        let previous_sessions = if previous_session_count > 0 {
+            Self::load_previous_session_notes(&pool, &meeting_id, previous_session_count).await
+        } else {
+            Vec::new()
+        };
+
+        // Resolved once, so the prompt, the JSON schema and the homework extraction
+        // all see the same sections
+        let template = match templates::get_template(&template_id) {
+            Ok(template) => {
+                Self::resolve_template(
+                    &pool,
+                    &meeting_id,
+                    &template,
+                    !previous_sessions.is_empty(),
+                    homework_context.is_some(),
+                )
+                .await
+            }
             Err(e) => {
-                Self::update_process_failed(&pool, &meeting_id, &e).await;
-                return;
+                Self::cleanup_cancellation_token(&meeting_id);
+                let e = format!("Failed to load template '{}': {}", template_id, e);
+                return Self::update_process_failed(&pool, &meeting_id, &e).await;
             }
         };
 
+        // Annotate the transcript with [mm:ss] markers when the recording timestamps are known,
+        // so each note item can cite where it happened
+        let transcripts = match TranscriptsRepository::get_ordered_transcripts(&pool, &meeting_id).await {
+            Ok(transcripts) => transcripts,
+            Err(e) => {
+                warn!("Failed to load transcript timestamps for {}: {}", meeting_id, e);
+                Vec::new()
+            }
+        };
+        let timed_segments = TimedSegment::from_transcripts(transcripts.clone());
+        let cite_timestamps = !timed_segments.is_empty();
+        let transcript = if cite_timestamps {
+            info!(
+                "Using timestamped transcript ({} of {} segments timed) for meeting_id: {}",
+                timed_segments.len(),
+                transcripts.len(),
+                meeting_id
+            );
+            citations::build_timestamped_transcript(&transcripts)
+        } else {
+            TranscriptText::plain(text)
+        };
+
+        // Personal data is replaced with placeholders for providers outside this machine
+        let mut redactor = Self::load_redactor(&pool).await;
+        let mut redacted: Option<RedactedInputs> = None;
+        let mut redaction_report: Option<RedactionReport> = None;
+
+        // Generate summary, moving on to the next provider while the current one
+        // can't be reached, is rate limited or times out
+        let client = reqwest::Client::new();
+        let mut fallback_errors: Vec<String> = Vec::new();
+        let mut result = Err(LlmError::Other("No provider available".to_string()));
+        let mut used = candidates[0].clone();
+        for (index, candidate) in candidates.iter().enumerate() {
+            let settings = match Self::load_provider_settings(&pool, candidate).await {
+                Ok(settings) => settings,
+                // A misconfigured selected provider fails the summary; a misconfigured fallback is skipped
+                Err(e) if index == 0 => {
+                    Self::cleanup_cancellation_token(&meeting_id);
+                    return Self::update_process_failed(&pool, &meeting_id, &e).await;
+                }
+                Err(e) => {
+                    warn!("Skipping fallback provider {}: {}", candidate.provider, e);
+                    fallback_errors.push(format!("{}/{}: {}", candidate.provider, candidate.model, e));
+                    continue;
+                }
+            };
+
+            let endpoint = settings
+                .ollama_endpoint
+                .as_deref()
+                .or(settings.custom_openai_endpoint.as_deref());
+            let is_local = redaction::is_local_provider(&settings.provider, endpoint);
+            if !is_local {
+                if let Some(redactor) = redactor.take() {
+                    redacted = Some(RedactedInputs::new(
+                        redactor,
+                        &transcript,
+                        &custom_prompt,
+                        homework_context.as_deref(),
+                        &previous_sessions,
+                        &template,
+                    ));
+                }
+            }
+            let inputs = redacted.as_ref().filter(|_| !is_local);
+
+            used = candidate.clone();
+            let on_progress = |progress: &StreamProgress| {
+                // Show the streamed notes with the original values
+                let restored;
+                let progress = match inputs {
+                    Some(inputs) => {
+                        restored = StreamProgress {
+                            text: inputs.redactor.restore(&progress.text),
+                            ..progress.clone()
+                        };
+                        &restored
+                    }
+                    None => progress,
+                };
+                let event = SummaryProgress {
+                    meeting_id: &meeting_id,
+                    provider: &candidate.provider,
+                    model: &candidate.model,
+                    progress,
+                };
+                if let Err(e) = _app.emit(SUMMARY_PROGRESS_EVENT, &event) {
+                    warn!("Failed to emit {}: {}", SUMMARY_PROGRESS_EVENT, e);
+                }
+            };
+            result = generate_session_summary(
+                &client,
+                &settings.provider,
+                &candidate.model,
+                &settings.api_key,
+                inputs.map_or(&transcript, |inputs| &inputs.text),
+                cite_timestamps,
+                inputs.map_or(&custom_prompt, |inputs| &inputs.custom_prompt),
+                inputs.map_or(&template, |inputs| &inputs.template),
+                inputs.map_or(homework_context.as_deref(), |inputs| {
+                    inputs.homework_context.as_deref()
+                }),
+                inputs.map_or(&previous_sessions, |inputs| &inputs.previous_sessions),
+                settings.token_threshold,
+                settings.ollama_endpoint.as_deref(),
+                settings.ollama_num_ctx,
+                settings.custom_openai_endpoint.as_deref(),
+                settings.max_tokens,
+                settings.temperature,
+                settings.top_p,
+                app_data_dir.as_ref(),
+                Some(&cancellation_token),
+                Some(&on_progress),
+            )
+            .await;
+            if let (Some(inputs), Ok((markdown, _, _))) = (inputs, result.as_mut()) {
+                *markdown = inputs.redactor.restore(markdown);
+            }
+            redaction_report = inputs.map(|inputs| inputs.redactor.report().clone());
+
+            match &result {
+                Err(e) if fallback::is_fallback_error(e) => {
+                    warn!(
+                        "{}/{} failed for meeting_id {}: {}",
+                        candidate.provider, candidate.model, meeting_id, e
+                    );
+                    fallback_errors.push(format!("{}/{}: {}", candidate.provider, candidate.model, e));
+                }
+                _ => break,
+            }
+        }
+        if used != candidates[0] && result.is_ok() {
+            info!(
+                "Summary for meeting_id {} generated by fallback provider {}/{}",
+                meeting_id, used.provider, used.model
+            );
+        }
+        if let Some(report) = &redaction_report {
+            info!(
+                "Redaction report for meeting_id {} sent to {}: {} replaced ({})",
+                meeting_id,
+                used.provider,
+                report.total(),
+                report
+            );
+        }
+
+        let duration = start_time.elapsed().as_secs_f64();
+
+        // Clean up cancellation token regardless of outcome
+        Self::cleanup_cancellation_token(&meeting_id);
+
+        match result {
+            Ok((mut final_markdown, num_chunks, prompt_hash)) => {
+                if num_chunks == 0 && final_markdown.is_empty() {
+                    return Self::update_process_failed(
+                        &pool,
+                        &meeting_id,
+                        "Summary generation failed: No content was processed.",
+                    )
+                    .await;
+                }
+
+                info!(
+                    "✓ Successfully processed {} chunks for meeting_id: {}. Duration: {:.2}s",
+                    num_chunks, meeting_id, duration
+                );
+                info!("final markdown is {}", &final_markdown);
+
+                // Extract and update session name if present
+                if let Some(name) = extract_session_name_from_markdown(&final_markdown) {
+                    if !name.is_empty() {
+                        info!(
+                            "Updating session name to '{}' for meeting_id: {}",
+                            name, meeting_id
+                        );
+                        if let Err(e) =
+                            MeetingsRepository::update_meeting_title(&pool, &meeting_id, &name).await
+                        {
+                            error!("Failed to update session name for {}: {}", meeting_id, e);
+                        }
+
+                        // Strip the title line from markdown
+                        info!("Stripping title from final_markdown");
+                        if let Some(hash_pos) = final_markdown.find('#') {
+                            // Find end of first line after '#'
+                            let body_start =
+                                if let Some(line_end) = final_markdown[hash_pos..].find('\n') {
+                                    hash_pos + line_end
+                                } else {
+                                    final_markdown.len() // No newline, whole string is title
+                                };
+
+                            final_markdown = final_markdown[body_start..].trim_start().to_string();
+                        } else {
+                            // No '#' found, clear the string
+                            final_markdown.clear();
+                        }
+                    }
+                }
+
+                // Check vocabulary, corrections and quotes against the transcript,
+                // dropping items the model made up
+                let (verified_markdown, verification_report) =
+                    verify_summary_against_transcript(
+                        &final_markdown,
+                        &transcript.text,
+                        &notes_section_titles(&template),
+                    );
+                final_markdown = verified_markdown;
+
+                // Validate the [mm:ss] citations against the real segments, dropping invented ones
+                let citation_report = if timed_segments.is_empty() {
+                    None
+                } else {
+                    let (validated_markdown, report) =
+                        citations::validate_citations(&final_markdown, &timed_segments);
+                    if !report.invalid_timestamps.is_empty() {
+                        warn!(
+                            "Removed {} citation(s) matching no transcript segment for {}: {:?}",
+                            report.invalid_timestamps.len(),
+                            meeting_id,
+                            report.invalid_timestamps
+                        );
+                    }
+                    final_markdown = validated_markdown;
+                    Some(report)
+                };
+
+                // Create result JSON with markdown only (summary_json will be added on first edit)
+                let result_json = serde_json::json!({
+                    "markdown": final_markdown,
+                });
+
+                // Record which template version and provider the notes were generated with
+                let template_version = template.version;
+                let metadata = serde_json::json!({
+                    "template_id": template_id,
+                    "template_version": template_version,
+                    "verification": verification_report,
+                    "provider": used.provider,
+                    "model": used.model,
+                    "requested_provider": model_provider,
+                    "requested_model": model_name,
+                    "fallback_errors": fallback_errors,
+                    "redaction": redaction_report,
+                });
+
+                let result_str = result_json.to_string();
+                let metadata_str = metadata.to_string();
+
+                // Update database with completed status
+                if let Err(e) = SummaryProcessesRepository::update_process_completed(
+                    &pool,
+                    &meeting_id,
+                    result_json,
+                    num_chunks,
+                    duration,
+                    metadata,
+                )
+                .await
+                {
+                    error!(
+                        "Failed to save completed process for {}: {}",
+                        meeting_id, e
+                    );
+                } else {
+                    info!(
+                        "Summary saved successfully for meeting_id: {}",
+                        meeting_id
+                    );
+                }
+
+                // Keep every generation in the version history
+                let started_at = chrono::Utc::now()
+                    - chrono::Duration::milliseconds((duration * 1000.0) as i64);
+                if let Err(e) = SummaryVersionsRepository::add_version(
+                    &pool,
+                    &meeting_id,
+                    NewSummaryVersion {
+                        source: "generated",
+                        restored_from: None,
+                        provider: Some(&used.provider),
+                        model: Some(&used.model),
+                        template_id: Some(&template_id),
+                        template_version: Some(template_version as i64),
+                        prompt_hash: Some(&prompt_hash),
+                        result: &result_str,
+                        metadata: Some(&metadata_str),
+                        started_at: Some(started_at),
+                    },
+                    versions::MAX_VERSIONS_PER_SESSION,
+                )
+                .await
+                {
+                    error!("Failed to record summary version for {}: {}", meeting_id, e);
+                }
+
+                // Replace the citations of the previous summary (none without timestamps)
+                let note_citations = citation_report
+                    .map(|report| {
+                        info!(
+                            "Storing {} citation(s) for meeting_id: {} ({} uncited item(s))",
+                            report.citations.len(),
+                            meeting_id,
+                            report.uncited_items
+                        );
+                        report.citations
+                    })
+                    .unwrap_or_default();
+                if let Err(e) =
+                    CitationsRepository::replace_citations(&pool, &meeting_id, &note_citations).await
+                {
+                    error!("Failed to store citations for {}: {}", meeting_id, e);
+                }
+
+                homework::sync_homework_from_summary(
+                    &pool,
+                    &meeting_id,
+                    &citations::strip_citations(&final_markdown),
+                    &open_homework,
+                    &template,
+                )
+                .await;
+
+                SummaryOutcome::Completed
+            }
+            Err(LlmError::Cancelled) => {
+                info!("Summary generation was cancelled for meeting_id: {}", meeting_id);
+                if let Err(db_err) = SummaryProcessesRepository::update_process_cancelled(&pool, &meeting_id).await {
+                    error!("Failed to update DB status to cancelled for {}: {}", meeting_id, db_err);
+                }
+                SummaryOutcome::Cancelled
+            }
+            Err(e) if allow_retry && job_queue::is_transient_error(&e) => {
+                // Leave the process pending; the job queue schedules another attempt
+                warn!("Transient error for meeting_id {}: {}", meeting_id, e);
+                SummaryOutcome::Retryable(e.to_string())
+            }
+            Err(e) => Self::update_process_failed(&pool, &meeting_id, &e.to_string()).await,
+        }
+    }
+
+    /// Loads what is needed to call a provider: API key, endpoints, sampling parameters and context size
+    ///
+    /// # Errors
+    /// If the provider is unknown or not configured (missing API key or endpoint config)
+    pub(crate) async fn load_provider_settings(
+        pool: &SqlitePool,
+        candidate: &FallbackProvider,
+    ) -> Result<ProviderSettings, String> {
+        let model_provider = &candidate.provider;
+        let model_name = &candidate.model;
+        let provider = LLMProvider::from_str(model_provider)?;
+
         // Validate and setup api_key, Flexible for Ollama, BuiltInAI, and CustomOpenAI
         let api_key = if provider == LLMProvider::Ollama || provider == LLMProvider::BuiltInAI || provider == LLMProvider::CustomOpenAI {
             // These providers don't require API keys from the standard database column
             String::new()
         } else {
-            match SettingsRepository::get_api_key(&pool, &model_provider).await {
+            match SettingsRepository::get_api_key(pool, model_provider).await {
                 Ok(Some(key)) if !key.is_empty() => key,
                 Ok(None) | Ok(Some(_)) => {
-                    let err_msg = format!("API key not found for {}", &model_provider);
-                    Self::update_process_failed(&pool, &meeting_id, &err_msg).await;
-                    return;
+                    return Err(format!("API key not found for {}", model_provider));
                 }
                 Err(e) => {
-                    let err_msg = format!("Failed to retrieve API key for {}: {}", &model_provider, e);
-                    Self::update_process_failed(&pool, &meeting_id, &err_msg).await;
-                    return;
+                    return Err(format!("Failed to retrieve API key for {}: {}", model_provider, e));
                 }
             }
         };
 
         // Get Ollama endpoint if provider is Ollama
         let ollama_endpoint = if provider == LLMProvider::Ollama {
-            match SettingsRepository::get_model_config(&pool).await {
+            match SettingsRepository::get_model_config(pool).await {
                 Ok(Some(config)) => config.ollama_endpoint,
                 Ok(None) => None,
                 Err(e) => {
@@ -137,7 +653,7 @@ impl SummaryService {
         // Get CustomOpenAI config if provider is CustomOpenAI
         let (custom_openai_endpoint, custom_openai_api_key, custom_openai_max_tokens, custom_openai_temperature, custom_openai_top_p) =
             if provider == LLMProvider::CustomOpenAI {
-                match SettingsRepository::get_custom_openai_config(&pool).await {
+                match SettingsRepository::get_custom_openai_config(pool).await {
                     Ok(Some(config)) => {
                         info!("✓ Using custom OpenAI endpoint: {}", config.endpoint);
                         (
@@ -149,20 +665,29 @@ impl SummaryService {
                         )
                     }
                     Ok(None) => {
-                        let err_msg = "Custom OpenAI provider selected but no configuration found";
-                        Self::update_process_failed(&pool, &meeting_id, err_msg).await;
-                        return;
+                        return Err("Custom OpenAI provider selected but no configuration found".to_string());
                     }
                     Err(e) => {
-                        let err_msg = format!("Failed to retrieve custom OpenAI config: {}", e);
-                        Self::update_process_failed(&pool, &meeting_id, &err_msg).await;
-                        return;
+                        return Err(format!("Failed to retrieve custom OpenAI config: {}", e));
                     }
                 }
             } else {
                 (None, None, None, None, None)
             };
 
+        // Gemini and Ollama take the shared sampling parameters
+        let sampling = if provider == LLMProvider::Gemini || provider == LLMProvider::Ollama {
+            match SettingsRepository::get_sampling_config(pool).await {
+                Ok(config) => config.unwrap_or_default(),
+                Err(e) => {
+                    warn!("Failed to load sampling config: {}, using provider defaults", e);
+                    SamplingConfig::default()
+                }
+            }
+        } else {
+            SamplingConfig::default()
+        };
+
         // For CustomOpenAI, use its API key (if any) instead of the empty string
         let final_api_key = if provider == LLMProvider::CustomOpenAI {
             custom_openai_api_key.unwrap_or_default()
@@ -170,9 +695,12 @@ impl SummaryService {
             api_key
         };
 
+        // Context window reported by Ollama for the model, if it could be fetched
+        let mut ollama_num_ctx = None;
+
         // Dynamically fetch context size based on provider and model
         let token_threshold = if provider == LLMProvider::Ollama {
-            match METADATA_CACHE.get_or_fetch(&model_name, ollama_endpoint.as_deref()).await {
+            match METADATA_CACHE.get_or_fetch(model_name, ollama_endpoint.as_deref()).await {
                 Ok(metadata) => {
                     // Reserve 300 tokens for prompt overhead
                     let optimal = metadata.context_size.saturating_sub(300);
@@ -180,6 +708,7 @@ impl SummaryService {
                         "✓ Using dynamic context for {}: {} tokens (chunk size: {})",
                         model_name, metadata.context_size, optimal
                     );
+                    ollama_num_ctx = Some(metadata.context_size);
                     optimal
                 }
                 Err(e) => {
@@ -193,7 +722,7 @@ impl SummaryService {
         } else if provider == LLMProvider::BuiltInAI {
             // Get model's context size from registry
             use crate::summary::summary_engine::models;
-            let model = models::get_model_by_name(&model_name)
+            let model = models::get_model_by_name(model_name)
                 .ok_or_else(|| format!("Unknown model: {}", model_name));
 
             match model {
@@ -216,123 +745,164 @@ impl SummaryService {
             100000  // Effectively unlimited for single-pass processing
         };
 
-        // Get app data directory for BuiltInAI provider
-        let app_data_dir = _app.path().app_data_dir().ok();
-
-        // Generate summary
-        let client = reqwest::Client::new();
-        let result = generate_session_summary(
-            &client,
-            &provider,
-            &model_name,
-            &final_api_key,
-            &text,
-            &custom_prompt,
-            &template_id,
+        Ok(ProviderSettings {
+            provider,
+            api_key: final_api_key,
+            ollama_endpoint,
+            ollama_num_ctx,
+            custom_openai_endpoint,
+            max_tokens: custom_openai_max_tokens.or(sampling.max_tokens),
+            temperature: custom_openai_temperature.or(sampling.temperature),
+            top_p: custom_openai_top_p.or(sampling.top_p),
             token_threshold,
-            ollama_endpoint.as_deref(),
-            custom_openai_endpoint.as_deref(),
-            custom_openai_max_tokens,
-            custom_openai_temperature,
-            custom_openai_top_p,
-            app_data_dir.as_ref(),
-            Some(&cancellation_token),
-        )
-        .await;
-
-        let duration = start_time.elapsed().as_secs_f64();
-
-        // Clean up cancellation token regardless of outcome
-        Self::cleanup_cancellation_token(&meeting_id);
-
-        match result {
-            Ok((mut final_markdown, num_chunks)) => {
-                if num_chunks == 0 && final_markdown.is_empty() {
-                    Self::update_process_failed(
-                        &pool,
-                        &meeting_id,
-                        "Summary generation failed: No content was processed.",
-                    )
-                    .await;
-                    return;
-                }
+        })
+    }
 
+    /// Loads the notes of up to `count` earlier sessions, most recent first
+    ///
+    /// Sessions without usable markdown are skipped. Failures are logged and
+    /// result in an empty list, so the summary is generated without this context.
+    async fn load_previous_session_notes(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        count: usize,
+    ) -> Vec<PreviousSessionNotes> {
+        match SummaryProcessesRepository::get_previous_session_summaries(pool, meeting_id, count as i64)
+            .await
+        {
+            Ok(summaries) => {
+                let notes: Vec<PreviousSessionNotes> = summaries
+                    .into_iter()
+                    .filter_map(|summary| {
+                        session_context::markdown_from_result(&summary.result).map(|markdown| {
+                            PreviousSessionNotes {
+                                title: summary.title,
+                                date: summary.created_at,
+                                markdown: citations::strip_citations(&markdown),
+                            }
+                        })
+                    })
+                    .collect();
                 info!(
-                    "✓ Successfully processed {} chunks for meeting_id: {}. Duration: {:.2}s",
-                    num_chunks, meeting_id, duration
+                    "Loaded notes of {} previous session(s) for {}",
+                    notes.len(),
+                    meeting_id
                 );
-                info!("final markdown is {}", &final_markdown);
+                notes
+            }
+            Err(e) => {
+                warn!("Failed to load previous sessions for {}: {}", meeting_id, e);
+                Vec::new()
+            }
+        }
+    }
 
-                // Extract and update session name if present
-                if let Some(name) = extract_session_name_from_markdown(&final_markdown) {
-                    if !name.is_empty() {
-                        info!(
-                            "Updating session name to '{}' for meeting_id: {}",
-                            name, meeting_id
-                        );
-                        if let Err(e) =
-                            MeetingsRepository::update_meeting_title(&pool, &meeting_id, &name).await
-                        {
-                            error!("Failed to update session name for {}: {}", meeting_id, e);
-                        }
+    /// Creates the redactor for the configured names and the tutor name of the
+    /// learner profile, or `None` if redaction is turned off
+    ///
+    /// If the configuration can't be read, the defaults (redaction on) are used.
+    pub(crate) async fn load_redactor(pool: &SqlitePool) -> Option<Redactor> {
+        let config = match SettingsRepository::get_redaction_config(pool).await {
+            Ok(config) => config.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to load redaction config: {}, using defaults", e);
+                RedactionConfig::default()
+            }
+        };
+        if !config.enabled {
+            info!("Personal data redaction is turned off");
+            return None;
+        }
 
-                        // Strip the title line from markdown
-                        info!("Stripping title from final_markdown");
-                        if let Some(hash_pos) = final_markdown.find('#') {
-                            // Find end of first line after '#'
-                            let body_start =
-                                if let Some(line_end) = final_markdown[hash_pos..].find('\n') {
-                                    hash_pos + line_end
-                                } else {
-                                    final_markdown.len() // No newline, whole string is title
-                                };
+        let tutor_name = match LearnerProfileRepository::get_profile(pool).await {
+            Ok(profile) => profile.tutor_name,
+            Err(e) => {
+                warn!("Failed to load learner profile for redaction: {}", e);
+                None
+            }
+        };
+        Some(Redactor::new(&config, tutor_name.as_slice()))
+    }
 
-                            final_markdown = final_markdown[body_start..].trim_start().to_string();
-                        } else {
-                            // No '#' found, clear the string
-                            final_markdown.clear();
-                        }
-                    }
+    /// Resolves a template's variables and conditional sections for a session
+    ///
+    /// # Arguments
+    /// * `pool` - SQLx connection pool
+    /// * `meeting_id` - Session the template is resolved for
+    /// * `template` - Template as loaded, with `{{variables}}` and conditions
+    /// * `has_previous_sessions` - Notes of earlier sessions are passed as context
+    /// * `has_open_homework` - Open homework from earlier sessions is passed as context
+    pub(crate) async fn resolve_template(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        template: &Template,
+        has_previous_sessions: bool,
+        has_open_homework: bool,
+    ) -> Template {
+        // Learner profile and session details for template variables and conditional sections
+        let mut context = Self::load_template_context(pool, meeting_id).await;
+        context.set_condition("has_previous_sessions", has_previous_sessions);
+        context.set_condition("has_open_homework", has_open_homework);
+        template.resolve(&context)
+    }
+
+    /// Resolves a template for previewing it against a session, assuming
+    /// previous-session notes are included whenever earlier notes exist
+    pub(crate) async fn resolve_template_preview(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        template: &Template,
+    ) -> Template {
+        let has_previous_sessions =
+            !Self::load_previous_session_notes(pool, meeting_id, 1).await.is_empty();
+        let has_open_homework =
+            match HomeworkRepository::get_open_items_before_meeting(pool, meeting_id).await {
+                Ok(items) => !items.is_empty(),
+                Err(e) => {
+                    warn!("Failed to load open homework for {}: {}", meeting_id, e);
+                    false
                 }
+            };
+        Self::resolve_template(pool, meeting_id, template, has_previous_sessions, has_open_homework)
+            .await
+    }
 
-                // Create result JSON with markdown only (summary_json will be added on first edit)
-                let result_json = serde_json::json!({
-                    "markdown": final_markdown,
-                });
+    /// Builds the template variables and conditions for a session
+    ///
+    /// Values that can't be loaded are left unset, so the template falls back to
+    /// its defaults rather than failing the summary.
+    async fn load_template_context(pool: &SqlitePool, meeting_id: &str) -> TemplateContext {
+        let mut context = TemplateContext::default();
 
-                // Update database with completed status
-                if let Err(e) = SummaryProcessesRepository::update_process_completed(
-                    &pool,
-                    &meeting_id,
-                    result_json,
-                    num_chunks,
-                    duration,
-                )
-                .await
-                {
-                    error!(
-                        "Failed to save completed process for {}: {}",
-                        meeting_id, e
-                    );
-                } else {
-                    info!(
-                        "Summary saved successfully for meeting_id: {}",
-                        meeting_id
-                    );
-                }
+        match LearnerProfileRepository::get_profile(pool).await {
+            Ok(profile) => {
+                context.set_variable("target_language", profile.target_language.as_deref());
+                context.set_variable("native_language", profile.native_language.as_deref());
+                context.set_variable("learner_level", profile.learner_level.as_deref());
+                context.set_variable("tutor_name", profile.tutor_name.as_deref());
             }
-            Err(e) => {
-                // Check if error is due to cancellation
-                if e.contains("cancelled") {
-                    info!("Summary generation was cancelled for meeting_id: {}", meeting_id);
-                    if let Err(db_err) = SummaryProcessesRepository::update_process_cancelled(&pool, &meeting_id).await {
-                        error!("Failed to update DB status to cancelled for {}: {}", meeting_id, db_err);
-                    }
-                } else {
-                    Self::update_process_failed(&pool, &meeting_id, &e).await;
-                }
+            Err(e) => warn!("Failed to load learner profile: {}", e),
+        }
+
+        match MeetingsRepository::get_meeting_metadata(pool, meeting_id).await {
+            Ok(Some(meeting)) => {
+                context.set_variable("session_title", Some(&meeting.title));
+                let date = meeting.created_at.0.format("%Y-%m-%d").to_string();
+                context.set_variable("session_date", Some(&date));
             }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to load session details for {}: {}", meeting_id, e),
         }
+
+        match TranscriptsRepository::count_segments_by_speaker(pool, meeting_id).await {
+            Ok((mic_segments, system_segments)) => {
+                context.set_condition("has_mic_speech", mic_segments > 0);
+                context.set_condition("has_system_speech", system_segments > 0);
+            }
+            Err(e) => warn!("Failed to count transcript sources for {}: {}", meeting_id, e),
+        }
+
+        context
     }
 
     /// Updates the summary process status to failed with error message
@@ -341,7 +911,11 @@ impl SummaryService {
     /// * `pool` - SQLx connection pool
     /// * `meeting_id` - Session identifier
     /// * `error_msg` - Error message to store
-    async fn update_process_failed(pool: &SqlitePool, meeting_id: &str, error_msg: &str) {
+    pub(crate) async fn update_process_failed(
+        pool: &SqlitePool,
+        meeting_id: &str,
+        error_msg: &str,
+    ) -> SummaryOutcome {
         error!(
             "Processing failed for meeting_id {}: {}",
             meeting_id, error_msg
@@ -354,5 +928,6 @@ impl SummaryService {
                 meeting_id, e
             );
         }
+        SummaryOutcome::Failed(error_msg.to_string())
     }
 }
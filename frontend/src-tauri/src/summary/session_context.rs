@@ -0,0 +1,150 @@
+use crate::summary::processor::rough_token_count;
+use chrono::{DateTime, Utc};
+
+/// Title of the section added to the notes when previous sessions are available
+pub const PROGRESS_SECTION_TITLE: &str = "Progress since last session";
+
+/// Below this budget the previous-session context is dropped entirely,
+/// since a few lines of truncated notes are more confusing than helpful
+const MIN_CONTEXT_TOKENS: usize = 150;
+
+/// Upper bound for the previous-session context, even for large-context providers
+pub const MAX_CONTEXT_TOKENS: usize = 3000;
+
+/// Notes of an earlier session, used as context for the current summary
+#[derive(Debug, Clone)]
+pub struct PreviousSessionNotes {
+    pub title: String,
+    pub date: DateTime<Utc>,
+    pub markdown: String,
+}
+
+/// Extracts the markdown from a stored summary result (`{ "markdown": "...", ... }`)
+pub fn markdown_from_result(result_json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(result_json).ok()?;
+    let markdown = value.get("markdown")?.as_str()?.trim();
+    if markdown.is_empty() {
+        None
+    } else {
+        Some(markdown.to_string())
+    }
+}
+
+/// Compacts session notes: drops blank lines and empty-section placeholders,
+/// then cuts at a line boundary once `max_tokens` is reached
+fn compact_notes(markdown: &str, max_tokens: usize) -> String {
+    let mut compacted = String::new();
+
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() || line.contains("None noted in this section") {
+            continue;
+        }
+
+        if rough_token_count(&compacted) + rough_token_count(line) > max_tokens {
+            compacted.push_str("[...]\n");
+            break;
+        }
+
+        compacted.push_str(line);
+        compacted.push('\n');
+    }
+
+    compacted.trim_end().to_string()
+}
+
+/// Builds the `<previous_sessions>` prompt context from earlier session notes
+///
+/// The token budget is shared evenly between sessions so that the most recent
+/// lesson never crowds out the others.
+///
+/// # Arguments
+/// * `sessions` - Earlier sessions, most recent first
+/// * `token_budget` - Maximum tokens the context may use
+///
+/// # Returns
+/// None if there are no sessions or the budget is too small to be useful
+pub fn build_previous_sessions_context(
+    sessions: &[PreviousSessionNotes],
+    token_budget: usize,
+) -> Option<String> {
+    let token_budget = token_budget.min(MAX_CONTEXT_TOKENS);
+    if sessions.is_empty() || token_budget < MIN_CONTEXT_TOKENS {
+        return None;
+    }
+
+    // Reserve a little room per session for the wrapping tags
+    let per_session = (token_budget / sessions.len()).saturating_sub(30);
+    if per_session < MIN_CONTEXT_TOKENS / 2 {
+        return None;
+    }
+
+    let blocks: Vec<String> = sessions
+        .iter()
+        .map(|session| {
+            format!(
+                "<session date=\"{}\" title=\"{}\">\n{}\n</session>",
+                session.date.format("%Y-%m-%d"),
+                session.title.replace('"', "'"),
+                compact_notes(&session.markdown, per_session)
+            )
+        })
+        .collect();
+
+    Some(blocks.join("\n"))
+}
+
+/// Instruction for the progress section, appended to the template instructions
+pub fn progress_section_instruction() -> String {
+    format!(
+        "- **For the '{}' section:** Compare this session with the notes in `<previous_sessions>`. Mention topics continued from an earlier lesson (with its date), skills that improved, and mistakes that keep recurring. Only state progress that is supported by both the previous notes and the current transcript, and never copy content from `<previous_sessions>` into other sections.\n",
+        PROGRESS_SECTION_TITLE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(title: &str, markdown: &str) -> PreviousSessionNotes {
+        PreviousSessionNotes {
+            title: title.to_string(),
+            date: Utc::now(),
+            markdown: markdown.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_from_result() {
+        assert_eq!(
+            markdown_from_result(r#"{"markdown": "**Grammar Points**\n- Past tense"}"#),
+            Some("**Grammar Points**\n- Past tense".to_string())
+        );
+        assert_eq!(markdown_from_result(r#"{"markdown": "  "}"#), None);
+        assert_eq!(markdown_from_result("not json"), None);
+    }
+
+    #[test]
+    fn test_compact_notes_drops_placeholders() {
+        let notes = "**Grammar Points**\n\n- Past tense\n\n**Homework**\n\nNone noted in this section.\n";
+        assert_eq!(compact_notes(notes, 1000), "**Grammar Points**\n- Past tense\n**Homework**");
+    }
+
+    #[test]
+    fn test_context_respects_budget() {
+        let long_notes = "- A practised sentence about the weather\n".repeat(200);
+        let sessions = vec![session("Lesson 2", &long_notes), session("Lesson 1", &long_notes)];
+
+        let context = build_previous_sessions_context(&sessions, 1000).unwrap();
+        assert!(rough_token_count(&context) <= 1000);
+        assert!(context.contains("title=\"Lesson 2\""));
+        assert!(context.contains("title=\"Lesson 1\""));
+    }
+
+    #[test]
+    fn test_context_skipped_when_budget_too_small() {
+        let sessions = vec![session("Lesson 1", "- Past tense")];
+        assert!(build_previous_sessions_context(&sessions, 50).is_none());
+        assert!(build_previous_sessions_context(&[], 1000).is_none());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::summary::llm_client::LLMProvider;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two progress reports, so long outputs don't flood the frontend
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Partial output of a streamed generation
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StreamProgress {
+    /// Text generated so far
+    pub text: String,
+    /// Tokens generated so far (streamed chunks for HTTP providers, which send about one token each)
+    pub tokens: usize,
+    /// Generation speed since the first token
+    pub tokens_per_second: f64,
+    /// Share of the prompt evaluated so far (0.0 to 1.0), only reported by the built-in model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_progress: Option<f64>,
+    /// Share of the prompt taken from the KV cache (0.0 to 1.0), only reported by the built-in model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_hit_ratio: Option<f64>,
+}
+
+/// Receives the partial output while a response is streamed
+pub type ProgressCallback<'a> = &'a (dyn Fn(&StreamProgress) + Send + Sync);
+
+/// Collects streamed text and reports progress at most every `PROGRESS_INTERVAL`
+pub struct StreamAccumulator<'a> {
+    progress: StreamProgress,
+    first_token_at: Option<Instant>,
+    last_report: Option<Instant>,
+    unreported: bool,
+    /// Token count and speed come from the generator instead of being estimated
+    generator_stats: bool,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl<'a> StreamAccumulator<'a> {
+    pub fn new(on_progress: Option<ProgressCallback<'a>>) -> Self {
+        Self {
+            progress: StreamProgress::default(),
+            first_token_at: None,
+            last_report: None,
+            unreported: false,
+            generator_stats: false,
+            on_progress,
+        }
+    }
+
+    /// Appends one streamed piece of text
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let first_token_at = *self.first_token_at.get_or_insert(now);
+
+        self.progress.text.push_str(text);
+        if !self.generator_stats {
+            self.progress.tokens += 1;
+            let elapsed = now.duration_since(first_token_at).as_secs_f64();
+            self.progress.tokens_per_second = if elapsed > 0.0 {
+                self.progress.tokens as f64 / elapsed
+            } else {
+                0.0
+            };
+        }
+        self.changed(now);
+    }
+
+    /// Records how much of the prompt has been evaluated, and how much of it came from the KV cache
+    pub fn set_prompt_progress(&mut self, processed: usize, cached: usize, total: usize) {
+        if total > 0 {
+            self.progress.prompt_progress = Some((processed as f64 / total as f64).min(1.0));
+            self.progress.prompt_cache_hit_ratio = Some((cached as f64 / total as f64).min(1.0));
+            self.changed(Instant::now());
+        }
+    }
+
+    /// Replaces the estimated token count and speed with the generator's own figures
+    pub fn set_generation_stats(&mut self, tokens: usize, tokens_per_second: f64) {
+        self.generator_stats = true;
+        self.progress.tokens = tokens;
+        self.progress.tokens_per_second = tokens_per_second;
+        self.changed(Instant::now());
+    }
+
+    /// Text received so far
+    pub fn text(&self) -> &str {
+        &self.progress.text
+    }
+
+    /// Reports the last progress and returns the complete text
+    pub fn finish(mut self) -> String {
+        if self.unreported {
+            self.report(Instant::now());
+        }
+        self.progress.text
+    }
+
+    fn changed(&mut self, now: Instant) {
+        self.unreported = true;
+        if self
+            .last_report
+            .map_or(true, |last| now.duration_since(last) >= PROGRESS_INTERVAL)
+        {
+            self.report(now);
+        }
+    }
+
+    fn report(&mut self, now: Instant) {
+        if let Some(on_progress) = self.on_progress {
+            on_progress(&self.progress);
+        }
+        self.last_report = Some(now);
+        self.unreported = false;
+    }
+}
+
+/// Splits a byte stream into lines
+///
+/// Chunks may end in the middle of a line or of a UTF-8 character, so bytes are
+/// only decoded once their line is complete.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Adds a chunk and returns the lines it completed, without line endings
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            lines.push(
+                String::from_utf8_lossy(&line)
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string(),
+            );
+        }
+        lines
+    }
+
+    /// Returns the last line if the stream didn't end with a line break
+    pub fn finish(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let line = String::from_utf8_lossy(&self.buffer).trim_end().to_string();
+        self.buffer.clear();
+        Some(line)
+    }
+}
+
+/// Meaningful content of one line of a streamed response
+#[derive(Debug, PartialEq)]
+pub enum StreamEvent {
+    /// Generated text
+    Text(String),
+    /// The provider finished the response
+    Done,
+    /// The provider reported an error in the middle of the stream
+    Error(String),
+}
+
+/// Parses a line of a streamed response from the given provider
+///
+/// Claude, Gemini and the OpenAI-compatible providers send server-sent events,
+/// Ollama sends one JSON object per line. Returns `None` for lines without
+/// content (keep-alives, event names, role announcements).
+pub fn parse_stream_line(provider: &LLMProvider, line: &str) -> Option<StreamEvent> {
+    match provider {
+        LLMProvider::Claude => parse_claude_event(line),
+        LLMProvider::Gemini => parse_gemini_event(line),
+        LLMProvider::Ollama => parse_ollama_line(line),
+        _ => parse_openai_event(line),
+    }
+}
+
+/// Payload of a server-sent `data:` line
+fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim)
+}
+
+/// Message of an `{"error": ...}` object (either a string or `{"message": ...}`)
+fn error_message(value: &serde_json::Value) -> Option<String> {
+    let error = value.get("error")?;
+    if error.is_null() {
+        return None;
+    }
+    Some(
+        error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .or_else(|| error.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
+/// OpenAI-compatible chunk: `data: {"choices":[{"delta":{"content":"..."}}]}`, ended by `data: [DONE]`
+fn parse_openai_event(line: &str) -> Option<StreamEvent> {
+    let data = sse_data(line)?;
+    if data == "[DONE]" {
+        return Some(StreamEvent::Done);
+    }
+    let value: serde_json::Value = serde_json::from_str(data).ok()?;
+    if let Some(message) = error_message(&value) {
+        return Some(StreamEvent::Error(message));
+    }
+    value
+        .pointer("/choices/0/delta/content")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(|c| StreamEvent::Text(c.to_string()))
+}
+
+/// Claude event: text arrives in `content_block_delta` events, `message_stop` ends the message
+fn parse_claude_event(line: &str) -> Option<StreamEvent> {
+    let value: serde_json::Value = serde_json::from_str(sse_data(line)?).ok()?;
+    match value.get("type").and_then(|t| t.as_str())? {
+        "content_block_delta" => value
+            .pointer("/delta/text")
+            .and_then(|t| t.as_str())
+            .filter(|t| !t.is_empty())
+            .map(|t| StreamEvent::Text(t.to_string())),
+        "message_stop" => Some(StreamEvent::Done),
+        "error" => Some(StreamEvent::Error(
+            error_message(&value).unwrap_or_else(|| "Unknown error".to_string()),
+        )),
+        _ => None,
+    }
+}
+
+/// Gemini finish reasons meaning the response was withheld rather than completed
+const GEMINI_BLOCK_REASONS: &[&str] = &[
+    "SAFETY",
+    "RECITATION",
+    "BLOCKLIST",
+    "PROHIBITED_CONTENT",
+    "SPII",
+    "IMAGE_SAFETY",
+];
+
+/// Gemini `streamGenerateContent` chunk:
+/// `data: {"candidates":[{"content":{"parts":[{"text":"..."}]},"finishReason":"STOP"}]}`
+///
+/// The stream ends when the connection closes. A blocked prompt or response
+/// (`promptFeedback.blockReason` or a safety `finishReason`) is reported as an error.
+fn parse_gemini_event(line: &str) -> Option<StreamEvent> {
+    let value: serde_json::Value = serde_json::from_str(sse_data(line)?).ok()?;
+    if let Some(message) = error_message(&value) {
+        return Some(StreamEvent::Error(message));
+    }
+    if let Some(reason) = value.pointer("/promptFeedback/blockReason").and_then(|r| r.as_str()) {
+        return Some(StreamEvent::Error(format!(
+            "Prompt blocked by Gemini safety filters ({})",
+            reason
+        )));
+    }
+
+    let candidate = value.pointer("/candidates/0")?;
+    if let Some(reason) = candidate.get("finishReason").and_then(|r| r.as_str()) {
+        if GEMINI_BLOCK_REASONS.contains(&reason) {
+            return Some(StreamEvent::Error(format!(
+                "Response blocked by Gemini safety filters ({})",
+                reason
+            )));
+        }
+    }
+    let text: String = candidate
+        .pointer("/content/parts")
+        .and_then(|p| p.as_array())?
+        .iter()
+        .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+        .collect();
+    (!text.is_empty()).then_some(StreamEvent::Text(text))
+}
+
+/// Ollama `/api/chat` line: `{"message":{"content":"..."},"done":false}`
+fn parse_ollama_line(line: &str) -> Option<StreamEvent> {
+    let value: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    if let Some(message) = error_message(&value) {
+        return Some(StreamEvent::Error(message));
+    }
+    if value.get("done").and_then(|d| d.as_bool()) == Some(true) {
+        return Some(StreamEvent::Done);
+    }
+    value
+        .pointer("/message/content")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(|c| StreamEvent::Text(c.to_string()))
+}
+
+/// Token counts from the final line of an Ollama `/api/chat` stream
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct OllamaStats {
+    /// Prompt tokens evaluated (after Ollama truncated the prompt to the context window)
+    pub prompt_eval_count: Option<usize>,
+    /// Tokens generated
+    pub eval_count: Option<usize>,
+    /// Why generation stopped: "stop", or "length" when a token limit was reached
+    pub done_reason: Option<String>,
+}
+
+/// Parses the token counts of the final (`"done":true`) line of an Ollama stream
+pub fn parse_ollama_stats(line: &str) -> Option<OllamaStats> {
+    serde_json::from_str(line.trim()).ok()
+}
+
+/// Finish reason of a Gemini chunk: "STOP", or "MAX_TOKENS" when the output limit was reached
+pub fn parse_gemini_finish_reason(line: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(sse_data(line)?).ok()?;
+    value
+        .pointer("/candidates/0/finishReason")
+        .and_then(|r| r.as_str())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_line_buffer_joins_split_lines_and_characters() {
+        let mut lines = LineBuffer::default();
+        let text = "data: {\"text\":\"é\"}\r\ndata: [DONE]\n";
+        let bytes = text.as_bytes();
+        // Split inside the two-byte "é"
+        let split = text.find('é').unwrap() + 1;
+
+        assert!(lines.push(&bytes[..split]).is_empty());
+        assert_eq!(
+            lines.push(&bytes[split..]),
+            vec!["data: {\"text\":\"é\"}".to_string(), "data: [DONE]".to_string()]
+        );
+        assert_eq!(lines.finish(), None);
+
+        lines.push(b"{\"done\":true}");
+        assert_eq!(lines.finish(), Some("{\"done\":true}".to_string()));
+    }
+
+    #[test]
+    fn test_parse_openai_stream() {
+        let provider = LLMProvider::Groq;
+        assert_eq!(
+            parse_stream_line(&provider, r#"data: {"choices":[{"delta":{"content":"Hola"}}]}"#),
+            Some(StreamEvent::Text("Hola".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line(&provider, r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#),
+            None
+        );
+        assert_eq!(parse_stream_line(&provider, ": keep-alive"), None);
+        assert_eq!(parse_stream_line(&provider, "data: [DONE]"), Some(StreamEvent::Done));
+        assert_eq!(
+            parse_stream_line(&provider, r#"data: {"error":{"message":"overloaded"}}"#),
+            Some(StreamEvent::Error("overloaded".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_claude_stream() {
+        let provider = LLMProvider::Claude;
+        assert_eq!(parse_stream_line(&provider, "event: content_block_delta"), None);
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"**Vocabulary**"}}"#
+            ),
+            Some(StreamEvent::Text("**Vocabulary**".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line(&provider, r#"data: {"type":"message_stop"}"#),
+            Some(StreamEvent::Done)
+        );
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            ),
+            Some(StreamEvent::Error("Overloaded".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_gemini_stream() {
+        let provider = LLMProvider::Gemini;
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"data: {"candidates":[{"content":{"parts":[{"text":"**Sum"},{"text":"mary**"}],"role":"model"},"index":0}]}"#
+            ),
+            Some(StreamEvent::Text("**Summary**".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"data: {"candidates":[{"content":{"parts":[{"text":""}],"role":"model"},"finishReason":"STOP"}],"usageMetadata":{"totalTokenCount":12}}"#
+            ),
+            None
+        );
+        assert_eq!(
+            parse_stream_line(&provider, r#"data: {"promptFeedback":{"blockReason":"SAFETY"}}"#),
+            Some(StreamEvent::Error("Prompt blocked by Gemini safety filters (SAFETY)".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"data: {"candidates":[{"finishReason":"RECITATION","index":0}]}"#
+            ),
+            Some(StreamEvent::Error("Response blocked by Gemini safety filters (RECITATION)".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_ollama_stream() {
+        let provider = LLMProvider::Ollama;
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"{"model":"llama3.2","message":{"role":"assistant","content":" mercado"},"done":false}"#
+            ),
+            Some(StreamEvent::Text(" mercado".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line(
+                &provider,
+                r#"{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true,"eval_count":42}"#
+            ),
+            Some(StreamEvent::Done)
+        );
+        assert_eq!(
+            parse_stream_line(&provider, r#"{"error":"model not found"}"#),
+            Some(StreamEvent::Error("model not found".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_ollama_stats() {
+        let stats = parse_ollama_stats(
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","total_duration":4883583458,"prompt_eval_count":2048,"eval_count":298}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            stats,
+            OllamaStats {
+                prompt_eval_count: Some(2048),
+                eval_count: Some(298),
+                done_reason: Some("stop".to_string()),
+            }
+        );
+        assert_eq!(parse_ollama_stats(r#"{"done":true}"#), Some(OllamaStats::default()));
+    }
+
+    #[test]
+    fn test_accumulator_reports_final_progress() {
+        let reports: Mutex<Vec<StreamProgress>> = Mutex::new(Vec::new());
+        let on_progress = |progress: &StreamProgress| reports.lock().unwrap().push(progress.clone());
+
+        let mut stream = StreamAccumulator::new(Some(&on_progress));
+        stream.push("Hola");
+        stream.push("");
+        stream.push(" mundo");
+        assert_eq!(stream.text(), "Hola mundo");
+        assert_eq!(stream.finish(), "Hola mundo");
+
+        let reports = reports.lock().unwrap();
+        // The first token is reported right away, the rest when the stream finishes
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].text, "Hola");
+        assert_eq!(reports[1].text, "Hola mundo");
+        assert_eq!(reports[1].tokens, 2);
+        assert_eq!(reports[1].prompt_progress, None);
+    }
+
+    #[test]
+    fn test_accumulator_prefers_generator_stats() {
+        let mut stream = StreamAccumulator::new(None);
+        stream.set_prompt_progress(512, 1024, 2048);
+        assert_eq!(stream.progress.prompt_progress, Some(0.25));
+        assert_eq!(stream.progress.prompt_cache_hit_ratio, Some(0.5));
+
+        stream.set_generation_stats(16, 12.5);
+        stream.push(" mercado");
+        assert_eq!(stream.progress.tokens, 16);
+        assert_eq!(stream.progress.tokens_per_second, 12.5);
+    }
+}
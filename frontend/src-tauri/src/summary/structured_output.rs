@@ -0,0 +1,278 @@
+use crate::summary::markdown::table_column_names;
+use crate::summary::templates::TemplateSection;
+use serde_json::{json, Map, Value};
+
+/// What a section of the structured notes holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionContent {
+    Text,
+    /// An array of items
+    List,
+    /// An array of rows, each an object with one text per column
+    Table(Vec<String>),
+}
+
+/// A section the structured notes must contain
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSection {
+    pub title: String,
+    pub content: SectionContent,
+}
+
+impl OutputSection {
+    pub fn new(title: &str, format: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            content: if format == "list" {
+                SectionContent::List
+            } else {
+                SectionContent::Text
+            },
+        }
+    }
+
+    /// A template section; list sections whose item format is a table become tables
+    pub fn from_template_section(section: &TemplateSection) -> Self {
+        let columns = section
+            .item_format
+            .as_ref()
+            .or(section.example_item_format.as_ref())
+            .and_then(|format| table_column_names(format))
+            .filter(|columns| columns.iter().all(|column| !column.is_empty()));
+        match columns {
+            Some(columns) if section.format == "list" => Self {
+                title: section.title.clone(),
+                content: SectionContent::Table(columns),
+            },
+            _ => Self::new(&section.title, &section.format),
+        }
+    }
+}
+
+/// JSON schema for notes with the given sections:
+/// `{"title": "...", "sections": {"<section title>": "..." | ["...", ...] | [{"<column>": "..."}, ...]}}`
+///
+/// Section and column order is kept in the `required` lists.
+pub fn notes_schema(sections: &[OutputSection]) -> Value {
+    let mut properties = Map::new();
+    for section in sections {
+        let schema = match &section.content {
+            SectionContent::Text => json!({"type": "string"}),
+            SectionContent::List => json!({"type": "array", "items": {"type": "string"}}),
+            SectionContent::Table(columns) => {
+                let cells: Map<String, Value> = columns
+                    .iter()
+                    .map(|column| (column.clone(), json!({"type": "string"})))
+                    .collect();
+                json!({
+                    "type": "array",
+                    "items": {"type": "object", "properties": cells, "required": columns}
+                })
+            }
+        };
+        properties.insert(section.title.clone(), schema);
+    }
+    let required: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+
+    json!({
+        "type": "object",
+        "properties": {
+            "title": {"type": "string"},
+            "sections": {
+                "type": "object",
+                "properties": properties,
+                "required": required
+            }
+        },
+        "required": ["title", "sections"]
+    })
+}
+
+/// Prompt addition asking for the notes as JSON instead of markdown
+pub fn json_output_instruction(schema: &Value) -> String {
+    format!(
+        "\n\nRespond with a single JSON object instead of Markdown, matching this JSON schema:\n<schema>\n{}\n</schema>\nPut the session title in `title` and each section's content under its exact title in `sections`. Use one array entry per item for list sections (without a leading \"- \") and one object per row for table sections. Markdown formatting inside the texts is allowed.",
+        schema
+    )
+}
+
+/// Renders structured notes as the markdown layout of `Template::to_markdown_structure`
+///
+/// # Errors
+/// If the output isn't a complete JSON object, e.g. because generation stopped at the token limit
+pub fn notes_markdown_from_json(
+    output: &str,
+    sections: &[OutputSection],
+) -> Result<String, String> {
+    let notes: Value = serde_json::from_str(output.trim()).map_err(|e| {
+        format!("Structured notes are incomplete or invalid JSON (output may have hit the token limit): {}", e)
+    })?;
+
+    let title = notes
+        .get("title")
+        .and_then(|t| t.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Session Notes");
+    let mut markdown = format!("# {}\n\n", title);
+
+    let values = notes.get("sections");
+    for section in sections {
+        markdown.push_str(&format!("**{}**\n\n", section.title));
+
+        let content = match (values.and_then(|v| v.get(&section.title)), &section.content) {
+            (Some(Value::Array(rows)), SectionContent::Table(columns)) => {
+                table_markdown(rows, columns)
+            }
+            (Some(Value::Array(items)), _) => items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .map(list_item)
+                .filter(|item| !item.is_empty())
+                .map(|item| format!("- {}", item))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            (Some(Value::String(text)), _) => text.trim().to_string(),
+            _ => String::new(),
+        };
+        if content.is_empty() {
+            markdown.push_str("None noted in this section.\n\n");
+        } else {
+            markdown.push_str(&content);
+            markdown.push_str("\n\n");
+        }
+    }
+
+    Ok(markdown.trim_end().to_string() + "\n")
+}
+
+/// An array item without a list marker the model may have added
+fn list_item(item: &str) -> &str {
+    let item = item.trim();
+    item.strip_prefix("- ")
+        .or_else(|| item.strip_prefix("* "))
+        .unwrap_or(item)
+        .trim()
+}
+
+/// Renders table rows under the header of the template's item format
+///
+/// # Returns
+/// An empty string if no row has any text
+fn table_markdown(rows: &[Value], columns: &[String]) -> String {
+    let cell = |text: &str| text.trim().replace('|', "\\|").replace('\n', " ");
+    let rows: Vec<String> = rows
+        .iter()
+        .filter_map(|row| {
+            let cells: Vec<String> = match row {
+                Value::Object(cells) => columns
+                    .iter()
+                    .map(|column| cell(cells.get(column).and_then(|v| v.as_str()).unwrap_or("")))
+                    .collect(),
+                // A row given as plain text goes in the first column
+                Value::String(text) => std::iter::once(cell(list_item(text)))
+                    .chain(columns.iter().skip(1).map(|_| String::new()))
+                    .collect(),
+                _ => return None,
+            };
+            cells
+                .iter()
+                .any(|cell| !cell.is_empty())
+                .then(|| format!("| {} |", cells.join(" | ")))
+        })
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let header: Vec<String> = columns
+        .iter()
+        .map(|column| format!("**{}**", column))
+        .collect();
+    let separator = vec!["---"; columns.len()];
+    format!(
+        "| {} |\n| {} |\n{}",
+        header.join(" | "),
+        separator.join(" | "),
+        rows.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections() -> Vec<OutputSection> {
+        vec![
+            OutputSection::new("Summary", "paragraph"),
+            OutputSection::new("Vocabulary", "list"),
+            OutputSection::new("Homework", "list"),
+        ]
+    }
+
+    #[test]
+    fn test_schema_keeps_section_order() {
+        let schema = notes_schema(&sections());
+        assert_eq!(
+            schema["properties"]["sections"]["required"],
+            json!(["Summary", "Vocabulary", "Homework"])
+        );
+        assert_eq!(
+            schema["properties"]["sections"]["properties"]["Vocabulary"]["type"],
+            "array"
+        );
+    }
+
+    #[test]
+    fn test_markdown_from_json() {
+        let output = r#"{"title": "Lesson 4: At the market", "sections": {"Summary": "Practised ordering food.", "Vocabulary": ["- el mercado", "**la manzana** (apple)", " "], "Homework": []}}"#;
+
+        let markdown = notes_markdown_from_json(output, &sections()).unwrap();
+        assert_eq!(
+            markdown,
+            "# Lesson 4: At the market\n\n**Summary**\n\nPractised ordering food.\n\n**Vocabulary**\n\n- el mercado\n- **la manzana** (apple)\n\n**Homework**\n\nNone noted in this section.\n"
+        );
+
+        // Cut off at the token limit
+        assert!(notes_markdown_from_json(
+            r#"{"title": "Lesson 4", "sections": {"Summary": "Pract"#,
+            &sections()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_table_section_renders_rows() {
+        let section = TemplateSection {
+            title: "Vocabulary".to_string(),
+            instruction: "New words".to_string(),
+            format: "list".to_string(),
+            item_format: Some("| **Word/Phrase** | **Translation** |\n| --- | --- |".to_string()),
+            example_item_format: None,
+            condition: None,
+        };
+        let sections = vec![OutputSection::from_template_section(&section)];
+        assert_eq!(
+            sections[0].content,
+            SectionContent::Table(vec!["Word/Phrase".to_string(), "Translation".to_string()])
+        );
+
+        let schema = notes_schema(&sections);
+        let rows = &schema["properties"]["sections"]["properties"]["Vocabulary"];
+        assert_eq!(
+            rows["items"]["required"],
+            json!(["Word/Phrase", "Translation"])
+        );
+
+        let output = r#"{"title": "Lesson 5", "sections": {"Vocabulary": [{"Word/Phrase": "el mercado", "Translation": "the market"}, {"Word/Phrase": "sí | no", "Translation": ""}, "- la tienda", {"Word/Phrase": " ", "Translation": ""}]}}"#;
+        assert_eq!(
+            notes_markdown_from_json(output, &sections).unwrap(),
+            "# Lesson 5\n\n**Vocabulary**\n\n| **Word/Phrase** | **Translation** |\n| --- | --- |\n| el mercado | the market |\n| sí \\| no |  |\n| la tienda |  |\n"
+        );
+
+        let empty = r#"{"title": "Lesson 5", "sections": {"Vocabulary": []}}"#;
+        assert!(notes_markdown_from_json(empty, &sections)
+            .unwrap()
+            .ends_with("**Vocabulary**\n\nNone noted in this section.\n"));
+    }
+}
@@ -13,8 +13,10 @@ use std::sync::RwLock;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
+use super::grammar;
 use super::models;
 use super::sidecar::SidecarManager;
+use crate::summary::streaming::{ProgressCallback, StreamAccumulator};
 
 // ============================================================================
 // Request/Response Types
@@ -28,21 +30,91 @@ enum Request {
         max_tokens: Option<i32>,
         context_size: Option<u32>,
         model_path: Option<String>,
+        /// Layer count from the model metadata, for planning GPU offload
+        #[serde(skip_serializing_if = "Option::is_none")]
+        layer_count: Option<u32>,
         // Sampling parameters
         temperature: Option<f32>,
         top_k: Option<i32>,
         top_p: Option<f32>,
         stop_tokens: Option<Vec<String>>,
+        /// Ask the sidecar to send `token` messages while generating
+        stream: bool,
+        /// GBNF grammar the output must follow
+        #[serde(skip_serializing_if = "Option::is_none")]
+        grammar: Option<String>,
+    },
+    /// Count tokens with the model's vocabulary
+    Tokenize {
+        text: String,
+        context_size: Option<u32>,
+        model_path: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        layer_count: Option<u32>,
     },
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum Response {
-    Response { text: String, error: Option<String> },
+    Token { text: String },
+    Progress {
+        prompt_tokens: usize,
+        /// Prompt tokens taken from the KV cache (absent for older sidecars)
+        #[serde(default)]
+        cached_prompt_tokens: usize,
+        prompt_tokens_processed: usize,
+        generated_tokens: usize,
+        tokens_per_second: f64,
+    },
+    Response {
+        text: String,
+        error: Option<String>,
+        /// Sent by sidecars that report generation statistics
+        #[serde(default)]
+        stats: Option<GenerationStats>,
+    },
+    Cancelled { text: String },
+    Tokens { count: usize },
     Error { message: String },
 }
 
+/// Statistics of a completed generation, as reported by the sidecar
+#[derive(Debug, Deserialize)]
+struct GenerationStats {
+    prompt_tokens: usize,
+    /// Prompt tokens whose KV cache entries were reused from the previous request
+    cached_prompt_tokens: usize,
+    generated_tokens: usize,
+    prompt_seconds: f64,
+    generation_seconds: f64,
+    /// Prompt tokens requested and taken from the KV cache since the model was loaded
+    #[serde(default)]
+    prompt_tokens_since_load: u64,
+    #[serde(default)]
+    cached_prompt_tokens_since_load: u64,
+}
+
+impl GenerationStats {
+    /// Share of the prompt taken from the KV cache
+    fn cache_hit_ratio(&self) -> f64 {
+        if self.prompt_tokens == 0 {
+            0.0
+        } else {
+            self.cached_prompt_tokens as f64 / self.prompt_tokens as f64
+        }
+    }
+
+    /// Share of all prompts since the model was loaded taken from the KV cache
+    fn cache_hit_ratio_since_load(&self) -> f64 {
+        if self.prompt_tokens_since_load == 0 {
+            0.0
+        } else {
+            self.cached_prompt_tokens_since_load as f64 / self.prompt_tokens_since_load as f64
+        }
+    }
+}
+
 // ============================================================================
 // Global Sidecar Manager
 // ============================================================================
@@ -72,6 +144,17 @@ async fn get_sidecar_manager() -> Result<Arc<SidecarManager>> {
         .ok_or_else(|| anyhow!("Sidecar manager not initialized. Call init_sidecar_manager first."))
 }
 
+/// Get the global sidecar manager, creating it on first use
+async fn get_or_init_sidecar_manager(app_data_dir: &PathBuf) -> Result<Arc<SidecarManager>> {
+    let mut global_manager = SIDECAR_MANAGER.lock().await;
+    if global_manager.is_none() {
+        log::info!("Initializing sidecar manager");
+        let new_manager = SidecarManager::new(app_data_dir.clone())?;
+        *global_manager = Some(Arc::new(new_manager));
+    }
+    Ok(global_manager.clone().unwrap())
+}
+
 /// Get cached model path with read-through caching to avoid repeated filesystem I/O
 fn get_cached_model_path(app_data_dir: &PathBuf, model_name: &str) -> Result<PathBuf> {
     // Try read lock first (fast path for cache hits)
@@ -122,7 +205,9 @@ fn get_cached_model_path(app_data_dir: &PathBuf, model_name: &str) -> Result<Pat
 /// * `model_name` - Model name (e.g., "gemma3:1b")
 /// * `system_prompt` - System instructions for the model
 /// * `user_prompt` - User message/task
+/// * `output_schema` - Optional JSON schema; the output is then a JSON value matching it
 /// * `cancellation_token` - Optional token for cancellation
+/// * `on_progress` - Optional callback receiving the partial output as tokens are streamed
 ///
 /// # Returns
 /// Generated text
@@ -131,7 +216,9 @@ pub async fn generate_with_builtin(
     model_name: &str,
     system_prompt: &str,
     user_prompt: &str,
+    output_schema: Option<&serde_json::Value>,
     cancellation_token: Option<&CancellationToken>,
+    on_progress: Option<ProgressCallback<'_>>,
 ) -> Result<String> {
     // Check cancellation at start
     if let Some(token) = cancellation_token {
@@ -150,19 +237,17 @@ pub async fn generate_with_builtin(
     // Resolve model path with caching (avoids repeated filesystem I/O)
     let model_path = get_cached_model_path(app_data_dir, model_name)?;
 
+    // Constrain sampling to the schema, so the output always parses
+    let grammar = output_schema
+        .map(grammar::json_schema_to_gbnf)
+        .transpose()
+        .context("Failed to build grammar from output schema")?;
+
     // Apply model-specific chat template
     let formatted_prompt =
         models::format_prompt(&model_def.template, system_prompt, user_prompt)?;
     // Get or initialize sidecar manager
-    let manager = {
-        let mut global_manager = SIDECAR_MANAGER.lock().await;
-        if global_manager.is_none() {
-            log::info!("Initializing sidecar manager");
-            let new_manager = SidecarManager::new(app_data_dir.clone())?;
-            *global_manager = Some(Arc::new(new_manager));
-        }
-        global_manager.clone().unwrap()
-    };
+    let manager = get_or_init_sidecar_manager(app_data_dir).await?;
 
     // Ensure sidecar is running with this model
     manager.ensure_running(model_path.clone()).await?;
@@ -180,10 +265,13 @@ pub async fn generate_with_builtin(
         max_tokens: Some(models::DEFAULT_MAX_TOKENS),
         context_size: Some(model_def.context_size),
         model_path: Some(model_path.to_string_lossy().to_string()),
+        layer_count: (model_def.layer_count > 0).then_some(model_def.layer_count),
         temperature: Some(model_def.sampling.temperature),
         top_k: Some(model_def.sampling.top_k),
         top_p: Some(model_def.sampling.top_p),
         stop_tokens: Some(model_def.sampling.stop_tokens.clone()),
+        stream: true,
+        grammar,
     };
 
     let request_json = serde_json::to_string(&request)?;
@@ -193,25 +281,31 @@ pub async fn generate_with_builtin(
 
     log::info!("Sending generation request to sidecar");
 
-    // Race between send_request and cancellation token
-    let response_json = if let Some(token) = cancellation_token {
-        tokio::select! {
-            result = manager.send_request(request_json, timeout) => {
-                result?
-            }
-            _ = token.cancelled() => {
-                log::warn!("Generation cancelled by user, shutting down sidecar");
-                // Shutdown sidecar to stop generation immediately
-                if let Err(e) = manager.shutdown().await {
-                    log::error!("Failed to shutdown sidecar during cancellation: {}", e);
-                }
-                return Err(anyhow!("Generation cancelled by user"));
+    // Report streamed tokens and progress; the final response still carries the complete text
+    let mut output = StreamAccumulator::new(on_progress);
+    let on_message = |line: &str| match serde_json::from_str::<Response>(line) {
+        Ok(Response::Token { text }) => output.push(&text),
+        Ok(Response::Progress {
+            prompt_tokens,
+            cached_prompt_tokens,
+            prompt_tokens_processed,
+            generated_tokens,
+            tokens_per_second,
+        }) => {
+            output.set_prompt_progress(prompt_tokens_processed, cached_prompt_tokens, prompt_tokens);
+            if generated_tokens > 0 {
+                output.set_generation_stats(generated_tokens, tokens_per_second);
             }
         }
-    } else {
-        manager.send_request(request_json, timeout).await?
+        _ => log::debug!("Ignoring unexpected sidecar message: {}", line),
     };
 
+    // Cancellation is handled by the sidecar manager, which asks the sidecar to stop
+    let response_json = manager
+        .send_streaming_request(request_json, timeout, cancellation_token, on_message)
+        .await?;
+    output.finish();
+
     // Check cancellation before parsing response
     if let Some(token) = cancellation_token {
         if token.is_cancelled() {
@@ -224,15 +318,80 @@ pub async fn generate_with_builtin(
         .with_context(|| format!("Failed to parse response: {}", response_json))?;
 
     match response {
-        Response::Response { text, error } => {
+        Response::Response { text, error, stats } => {
             if let Some(err_msg) = error {
                 Err(anyhow!("Generation failed: {}", err_msg))
             } else {
                 log::info!("Generation completed: {} chars", text.len());
+                if let Some(stats) = stats {
+                    log::info!(
+                        "KV cache reused {}/{} prompt tokens ({:.1}%, {:.1}% since model load); prompt {:.2}s, {} tokens generated in {:.2}s",
+                        stats.cached_prompt_tokens,
+                        stats.prompt_tokens,
+                        stats.cache_hit_ratio() * 100.0,
+                        stats.cache_hit_ratio_since_load() * 100.0,
+                        stats.prompt_seconds,
+                        stats.generated_tokens,
+                        stats.generation_seconds
+                    );
+                }
                 Ok(text)
             }
         }
+        Response::Cancelled { text } => {
+            log::info!("Generation cancelled after {} chars", text.len());
+            Err(anyhow!("Generation cancelled by user"))
+        }
         Response::Error { message } => Err(anyhow!("Sidecar error: {}", message)),
+        Response::Token { .. } | Response::Progress { .. } | Response::Tokens { .. } => {
+            Err(anyhow!("Sidecar ended the stream without a response"))
+        }
+    }
+}
+
+/// Count the tokens of `text` with the vocabulary of a built-in model
+///
+/// Loads the model into the sidecar if needed (with the same context size a
+/// generation uses, so it isn't reloaded for the generation that follows).
+///
+/// # Arguments
+/// * `app_data_dir` - Application data directory (for model resolution)
+/// * `model_name` - Model name (e.g., "gemma3:1b")
+/// * `text` - Text to tokenize
+///
+/// # Returns
+/// Number of tokens, without the BOS token
+pub async fn count_tokens_with_builtin(
+    app_data_dir: &PathBuf,
+    model_name: &str,
+    text: &str,
+) -> Result<usize> {
+    let model_def = models::get_model_by_name(model_name)
+        .ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
+    let model_path = get_cached_model_path(app_data_dir, model_name)?;
+
+    let manager = get_or_init_sidecar_manager(app_data_dir).await?;
+    manager.ensure_running(model_path.clone()).await?;
+
+    let request = Request::Tokenize {
+        text: text.to_string(),
+        context_size: Some(model_def.context_size),
+        model_path: Some(model_path.to_string_lossy().to_string()),
+        layer_count: (model_def.layer_count > 0).then_some(model_def.layer_count),
+    };
+    let response_json = manager
+        .send_request(
+            serde_json::to_string(&request)?,
+            Duration::from_secs(models::TOKENIZE_TIMEOUT_SECS),
+        )
+        .await?;
+
+    match serde_json::from_str::<Response>(&response_json)
+        .with_context(|| format!("Failed to parse response: {}", response_json))?
+    {
+        Response::Tokens { count } => Ok(count),
+        Response::Error { message } => Err(anyhow!("Sidecar error: {}", message)),
+        _ => Err(anyhow!("Unexpected sidecar response: {}", response_json)),
     }
 }
 
@@ -300,14 +459,20 @@ mod tests {
             max_tokens: Some(512),
             context_size: Some(2048),
             model_path: Some("/path/to/model.gguf".to_string()),
+            layer_count: Some(26),
             temperature: Some(1.0),
             top_k: Some(64),
             top_p: Some(0.95),
             stop_tokens: Some(vec!["<end_of_turn>".to_string()]),
+            stream: true,
+            grammar: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("\"type\":\"generate\""));
+        assert!(json.contains("\"stream\":true"));
+        assert!(!json.contains("grammar"));
+        assert!(json.contains("\"layer_count\":26"));
         assert!(json.contains("\"prompt\":\"test prompt\""));
         assert!(json.contains("\"max_tokens\":512"));
         assert!(json.contains("\"temperature\":1.0"));
@@ -319,12 +484,82 @@ mod tests {
         let response: Response = serde_json::from_str(json).unwrap();
 
         match response {
-            Response::Response { text, error } => {
+            Response::Response { text, error, stats } => {
                 assert_eq!(text, "generated text");
                 assert!(error.is_none());
+                assert!(stats.is_none());
+            }
+            _ => panic!("Wrong response type"),
+        }
+    }
+
+    #[test]
+    fn test_response_stats_deserialization() {
+        let json = r#"{"type":"response","text":"notes","error":null,"stats":{"prompt_tokens":1200,"cached_prompt_tokens":900,"generated_tokens":300,"prompt_seconds":1.5,"generation_seconds":12.0,"prompt_tokens_since_load":4000,"cached_prompt_tokens_since_load":1000}}"#;
+
+        match serde_json::from_str::<Response>(json).unwrap() {
+            Response::Response {
+                stats: Some(stats), ..
+            } => {
+                assert_eq!(stats.cached_prompt_tokens, 900);
+                assert!((stats.cache_hit_ratio() - 0.75).abs() < f64::EPSILON);
+                assert!((stats.cache_hit_ratio_since_load() - 0.25).abs() < f64::EPSILON);
+            }
+            _ => panic!("Wrong response type"),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_round_trip() {
+        let request = Request::Tokenize {
+            text: "¿Qué hiciste ayer?".to_string(),
+            context_size: Some(8192),
+            model_path: Some("/path/to/model.gguf".to_string()),
+            layer_count: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"type\":\"tokenize\""));
+        assert!(!json.contains("layer_count"));
+
+        match serde_json::from_str::<Response>(r#"{"type":"tokens","count":7}"#).unwrap() {
+            Response::Tokens { count } => assert_eq!(count, 7),
+            _ => panic!("Wrong response type"),
+        }
+    }
+
+    #[test]
+    fn test_token_deserialization() {
+        let json = r#"{"type":"token","text":" mercado"}"#;
+        let response: Response = serde_json::from_str(json).unwrap();
+
+        match response {
+            Response::Token { text } => assert_eq!(text, " mercado"),
+            _ => panic!("Wrong response type"),
+        }
+    }
+
+    #[test]
+    fn test_progress_and_cancelled_deserialization() {
+        let json = r#"{"type":"progress","prompt_tokens":2048,"cached_prompt_tokens":256,"prompt_tokens_processed":512,"generated_tokens":0,"tokens_per_second":0.0}"#;
+        match serde_json::from_str::<Response>(json).unwrap() {
+            Response::Progress {
+                prompt_tokens,
+                cached_prompt_tokens,
+                prompt_tokens_processed,
+                ..
+            } => {
+                assert_eq!(prompt_tokens, 2048);
+                assert_eq!(cached_prompt_tokens, 256);
+                assert_eq!(prompt_tokens_processed, 512);
             }
             _ => panic!("Wrong response type"),
         }
+
+        let json = r#"{"type":"cancelled","text":"partial"}"#;
+        match serde_json::from_str::<Response>(json).unwrap() {
+            Response::Cancelled { text } => assert_eq!(text, "partial"),
+            _ => panic!("Wrong response type"),
+        }
     }
 
     #[test]
@@ -180,6 +180,38 @@ pub async fn builtin_ai_download_model<R: Runtime>(
     }
 }
 
+/// Register a local GGUF file as a built-in AI model
+#[tauri::command]
+pub async fn builtin_ai_add_local_model<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ModelManagerState>,
+    path: String,
+) -> Result<ModelInfo, String> {
+    let manager = {
+        // Ensure manager is initialized
+        {
+            let manager_lock = state.0.lock().await;
+            if manager_lock.is_none() {
+                drop(manager_lock);
+                init_model_manager(&app)
+                    .await
+                    .map_err(|e| format!("Failed to initialize model manager: {}", e))?;
+            }
+        }
+
+        let manager_lock = state.0.lock().await;
+        manager_lock
+            .as_ref()
+            .ok_or_else(|| "Model manager not initialized".to_string())?
+            .clone()
+    };
+
+    manager
+        .add_local_model(std::path::PathBuf::from(path))
+        .await
+        .map_err(|e| format!("Failed to add local model: {}", e))
+}
+
 /// Cancel an ongoing model download
 #[tauri::command]
 pub async fn builtin_ai_cancel_download<R: Runtime>(
@@ -212,7 +244,7 @@ pub async fn builtin_ai_cancel_download<R: Runtime>(
     Ok(())
 }
 
-/// Delete a corrupted or available model file
+/// Delete a corrupted or available model file (local models are only unregistered)
 #[tauri::command]
 pub async fn builtin_ai_delete_model(
     state: State<'_, ModelManagerState>,
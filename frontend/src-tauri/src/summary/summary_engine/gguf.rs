@@ -0,0 +1,243 @@
+// GGUF metadata reader for locally registered models
+// Only the key/value header is read; tensor data is never touched
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+/// Longest string value kept in memory (chat templates are a few KB)
+const MAX_STRING_LEN: u64 = 1024 * 1024;
+
+/// Model metadata from a GGUF header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GgufMetadata {
+    /// `general.architecture`, e.g. "llama", "qwen2", "gemma3"
+    pub architecture: Option<String>,
+    /// `general.name`
+    pub name: Option<String>,
+    /// `{architecture}.context_length` - context the model was trained with
+    pub context_length: Option<u32>,
+    /// `{architecture}.block_count` - number of transformer layers
+    pub block_count: Option<u32>,
+    /// `tokenizer.chat_template` - Jinja chat template embedded by the converter
+    pub chat_template: Option<String>,
+}
+
+/// Reads the metadata of the GGUF file at `path`
+pub fn read_metadata(path: &Path) -> Result<GgufMetadata> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    parse_metadata(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to read GGUF metadata from {}", path.display()))
+}
+
+#[derive(Debug)]
+enum MetadataValue {
+    Int(u64),
+    Str(String),
+    /// Arrays, floats and overlong strings, which aren't needed
+    Skipped,
+}
+
+/// Parses the GGUF header from `reader`, positioned at the start of the file
+pub fn parse_metadata<R: Read + Seek>(reader: &mut R) -> Result<GgufMetadata> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"GGUF" {
+        return Err(anyhow!("Not a GGUF file (magic {:?})", magic));
+    }
+
+    let version = read_u32(reader)?;
+    if !(2..=3).contains(&version) {
+        return Err(anyhow!("Unsupported GGUF version {}", version));
+    }
+    let _tensor_count = read_u64(reader)?;
+    let kv_count = read_u64(reader)?;
+
+    let mut values = HashMap::new();
+    for _ in 0..kv_count {
+        let key = read_string(reader)?.ok_or_else(|| anyhow!("Metadata key too long"))?;
+        let value_type = read_u32(reader)?;
+        let value = read_value(reader, value_type)?;
+        values.insert(key, value);
+    }
+
+    let string = |key: &str| match values.get(key) {
+        Some(MetadataValue::Str(s)) => Some(s.clone()),
+        _ => None,
+    };
+    let int = |key: &str| match values.get(key) {
+        Some(MetadataValue::Int(n)) => u32::try_from(*n).ok(),
+        _ => None,
+    };
+
+    let architecture = string("general.architecture");
+    let (context_length, block_count) = match &architecture {
+        Some(arch) => (
+            int(&format!("{}.context_length", arch)),
+            int(&format!("{}.block_count", arch)),
+        ),
+        None => (None, None),
+    };
+
+    Ok(GgufMetadata {
+        name: string("general.name"),
+        chat_template: string("tokenizer.chat_template"),
+        architecture,
+        context_length,
+        block_count,
+    })
+}
+
+fn read_value<R: Read + Seek>(reader: &mut R, value_type: u32) -> Result<MetadataValue> {
+    let value = match value_type {
+        // u8, i8, bool
+        0 | 1 | 7 => MetadataValue::Int(read_bytes::<1, _>(reader)?[0] as u64),
+        // u16, i16
+        2 | 3 => MetadataValue::Int(u16::from_le_bytes(read_bytes(reader)?) as u64),
+        // u32, i32
+        4 | 5 => MetadataValue::Int(read_u32(reader)? as u64),
+        // f32
+        6 => {
+            reader.seek(SeekFrom::Current(4))?;
+            MetadataValue::Skipped
+        }
+        8 => read_string(reader)?.map_or(MetadataValue::Skipped, MetadataValue::Str),
+        9 => {
+            let item_type = read_u32(reader)?;
+            let count = read_u64(reader)?;
+            skip_array(reader, item_type, count)?;
+            MetadataValue::Skipped
+        }
+        // u64, i64
+        10 | 11 => MetadataValue::Int(read_u64(reader)?),
+        // f64
+        12 => {
+            reader.seek(SeekFrom::Current(8))?;
+            MetadataValue::Skipped
+        }
+        other => return Err(anyhow!("Unknown metadata value type {}", other)),
+    };
+    Ok(value)
+}
+
+/// Skips an array without reading its items (token lists hold >100k strings)
+fn skip_array<R: Read + Seek>(reader: &mut R, item_type: u32, count: u64) -> Result<()> {
+    let item_size: u64 = match item_type {
+        0 | 1 | 7 => 1,
+        2 | 3 => 2,
+        4..=6 => 4,
+        10..=12 => 8,
+        8 => {
+            for _ in 0..count {
+                let len = read_u64(reader)?;
+                seek_forward(reader, len)?;
+            }
+            return Ok(());
+        }
+        9 => {
+            for _ in 0..count {
+                let nested_type = read_u32(reader)?;
+                let nested_count = read_u64(reader)?;
+                skip_array(reader, nested_type, nested_count)?;
+            }
+            return Ok(());
+        }
+        other => return Err(anyhow!("Unknown array item type {}", other)),
+    };
+    let len = count
+        .checked_mul(item_size)
+        .ok_or_else(|| anyhow!("Array too large"))?;
+    seek_forward(reader, len)
+}
+
+/// Reads a length-prefixed string, or skips it (returning `None`) if it's overlong
+fn read_string<R: Read + Seek>(reader: &mut R) -> Result<Option<String>> {
+    let len = read_u64(reader)?;
+    if len > MAX_STRING_LEN {
+        seek_forward(reader, len)?;
+        return Ok(None);
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+fn seek_forward<R: Seek>(reader: &mut R, len: u64) -> Result<()> {
+    let offset = i64::try_from(len).map_err(|_| anyhow!("Value too large"))?;
+    reader.seek(SeekFrom::Current(offset))?;
+    Ok(())
+}
+
+fn read_bytes<const N: usize, R: Read>(reader: &mut R) -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(reader)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn push_string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn push_key(buf: &mut Vec<u8>, key: &str, value_type: u32) {
+        push_string(buf, key);
+        buf.extend_from_slice(&value_type.to_le_bytes());
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"GGUF");
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.extend_from_slice(&7u64.to_le_bytes());
+
+        push_key(&mut buf, "general.architecture", 8);
+        push_string(&mut buf, "qwen2");
+        push_key(&mut buf, "general.name", 8);
+        push_string(&mut buf, "Qwen2.5 3B Instruct");
+        push_key(&mut buf, "general.file_type", 4);
+        buf.extend_from_slice(&15u32.to_le_bytes());
+        push_key(&mut buf, "qwen2.context_length", 4);
+        buf.extend_from_slice(&32768u32.to_le_bytes());
+        push_key(&mut buf, "qwen2.rope.freq_base", 6);
+        buf.extend_from_slice(&1_000_000f32.to_le_bytes());
+        // Vocabulary arrays are skipped without being read
+        push_key(&mut buf, "tokenizer.ggml.tokens", 9);
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes());
+        push_string(&mut buf, "<|im_start|>");
+        push_string(&mut buf, "hello");
+        push_key(&mut buf, "tokenizer.chat_template", 8);
+        push_string(&mut buf, "{% for message in messages %}<|im_start|>{{ message.role }}");
+
+        let metadata = parse_metadata(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(metadata.architecture.as_deref(), Some("qwen2"));
+        assert_eq!(metadata.name.as_deref(), Some("Qwen2.5 3B Instruct"));
+        assert_eq!(metadata.context_length, Some(32768));
+        assert_eq!(metadata.block_count, None);
+        assert!(metadata.chat_template.unwrap().contains("<|im_start|>"));
+    }
+
+    #[test]
+    fn test_rejects_non_gguf() {
+        let result = parse_metadata(&mut Cursor::new(b"ggjt\x01\x00\x00\x00".to_vec()));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,221 @@
+// JSON schema to GBNF conversion for constrained generation
+// llama.cpp samples only tokens the grammar allows, so the output always parses
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Whitespace between JSON tokens, limited so the model can't loop on it
+const WS_RULE: &str = r#"ws ::= | " " | "\n" [ \t]{0,20}"#;
+
+const STRING_RULE: &str =
+    r#"string ::= "\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\/bfnrt] | "u" [0-9a-fA-F]{4} ) )* "\"""#;
+
+const NUMBER_RULE: &str =
+    r#"number ::= "-"? ( [0-9] | [1-9] [0-9]{0,15} ) ( "." [0-9]+ )? ( [eE] [-+]? [0-9]{1,3} )?"#;
+
+const INTEGER_RULE: &str = r#"integer ::= "-"? ( [0-9] | [1-9] [0-9]{0,15} )"#;
+
+const BOOLEAN_RULE: &str = r#"boolean ::= "true" | "false""#;
+
+/// Converts a JSON schema into a GBNF grammar with `root` as start rule
+///
+/// Supports the subset needed for structured notes: objects, arrays, strings,
+/// numbers, integers, booleans and string enums. Every object property is
+/// required and emitted in the order of the schema's `required` list, followed
+/// by any remaining properties.
+pub fn json_schema_to_gbnf(schema: &Value) -> Result<String> {
+    let mut builder = GrammarBuilder::default();
+    let root = builder.visit(schema, "root")?;
+    if root != "root" {
+        builder.rules.push(format!("root ::= {}", root));
+    }
+
+    let mut grammar = builder.rules;
+    grammar.push(WS_RULE.to_string());
+    for (name, rule) in [
+        ("string", STRING_RULE),
+        ("number", NUMBER_RULE),
+        ("integer", INTEGER_RULE),
+        ("boolean", BOOLEAN_RULE),
+    ] {
+        if builder.primitives.contains(&name) {
+            grammar.push(rule.to_string());
+        }
+    }
+    Ok(grammar.join("\n") + "\n")
+}
+
+#[derive(Default)]
+struct GrammarBuilder {
+    rules: Vec<String>,
+    primitives: Vec<&'static str>,
+}
+
+impl GrammarBuilder {
+    /// Returns the rule matching `schema`, defining a rule called `name` for compound types
+    fn visit(&mut self, schema: &Value, name: &str) -> Result<String> {
+        if let Some(values) = schema.get("enum").and_then(|e| e.as_array()) {
+            let alternatives = values
+                .iter()
+                .map(|value| value.as_str().map(|s| literal(&json_string(s))))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| anyhow!("Only string enums are supported ({})", name))?;
+            if alternatives.is_empty() {
+                return Err(anyhow!("Empty enum ({})", name));
+            }
+            self.rules.push(format!("{} ::= {}", name, alternatives.join(" | ")));
+            return Ok(name.to_string());
+        }
+
+        let schema_type = schema
+            .get("type")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| anyhow!("Schema without a type ({})", name))?;
+
+        match schema_type {
+            "string" => Ok(self.primitive("string")),
+            "number" => Ok(self.primitive("number")),
+            "integer" => Ok(self.primitive("integer")),
+            "boolean" => Ok(self.primitive("boolean")),
+            "array" => {
+                let items = schema
+                    .get("items")
+                    .ok_or_else(|| anyhow!("Array without items ({})", name))?;
+                let item = self.visit(items, &format!("{}-item", name))?;
+                self.rules.push(format!(
+                    r#"{} ::= "[" ws ( {} ( "," ws {} )* )? ws "]""#,
+                    name, item, item
+                ));
+                Ok(name.to_string())
+            }
+            "object" => {
+                let properties = schema
+                    .get("properties")
+                    .and_then(|p| p.as_object())
+                    .ok_or_else(|| anyhow!("Object without properties ({})", name))?;
+
+                // serde_json sorts object keys, so the `required` list gives the order
+                let mut keys: Vec<&str> = schema
+                    .get("required")
+                    .and_then(|r| r.as_array())
+                    .map(|required| required.iter().filter_map(|k| k.as_str()).collect())
+                    .unwrap_or_default();
+                keys.retain(|key| properties.contains_key(*key));
+                for key in properties.keys() {
+                    if !keys.contains(&key.as_str()) {
+                        keys.push(key);
+                    }
+                }
+                if keys.is_empty() {
+                    return Err(anyhow!("Object without properties ({})", name));
+                }
+
+                let mut members = Vec::new();
+                for (index, key) in keys.iter().enumerate() {
+                    let value = self.visit(&properties[*key], &format!("{}-p{}", name, index))?;
+                    members.push(format!("{} ws \":\" ws {}", literal(&json_string(key)), value));
+                }
+                self.rules.push(format!(
+                    r#"{} ::= "{{" ws {} ws "}}""#,
+                    name,
+                    members.join(r#" "," ws "#)
+                ));
+                Ok(name.to_string())
+            }
+            other => Err(anyhow!("Unsupported schema type '{}' ({})", other, name)),
+        }
+    }
+
+    fn primitive(&mut self, name: &'static str) -> String {
+        if !self.primitives.contains(&name) {
+            self.primitives.push(name);
+        }
+        name.to_string()
+    }
+}
+
+/// JSON encoding of a string, including its quotes
+fn json_string(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+/// GBNF literal matching `text` exactly
+fn literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_object_grammar_follows_required_order() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string"},
+                "sections": {
+                    "type": "object",
+                    "properties": {
+                        "Vocabulary": {"type": "array", "items": {"type": "string"}},
+                        "Summary": {"type": "string"}
+                    },
+                    "required": ["Summary", "Vocabulary"]
+                }
+            },
+            "required": ["title", "sections"]
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).unwrap();
+        let lines: Vec<&str> = grammar.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            r#"root-p1-p1 ::= "[" ws ( string ( "," ws string )* )? ws "]""#
+        );
+        assert_eq!(
+            lines[1],
+            r#"root-p1 ::= "{" ws "\"Summary\"" ws ":" ws string "," ws "\"Vocabulary\"" ws ":" ws root-p1-p1 ws "}""#
+        );
+        assert_eq!(
+            lines[2],
+            r#"root ::= "{" ws "\"title\"" ws ":" ws string "," ws "\"sections\"" ws ":" ws root-p1 ws "}""#
+        );
+        assert!(grammar.contains("\nws ::= "));
+        assert!(grammar.contains("\nstring ::= "));
+        assert!(!grammar.contains("number ::= "));
+    }
+
+    #[test]
+    fn test_enum_and_escaping() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "status": {"enum": ["Reviewed", "Not \"reviewed\""]}
+            },
+            "required": ["status"]
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).unwrap();
+        assert!(grammar.starts_with(
+            r#"root-p0 ::= "\"Reviewed\"" | "\"Not \\\"reviewed\\\"\"""#
+        ));
+
+        assert!(json_schema_to_gbnf(&json!({"type": "null"})).is_err());
+        assert!(json_schema_to_gbnf(&json!({"type": "array"})).is_err());
+    }
+}
@@ -3,17 +3,19 @@
 
 pub mod client;
 pub mod commands;
+pub mod gguf;
+pub mod grammar;
 pub mod model_manager;
 pub mod models;
 pub mod sidecar;
 
 // Re-export commonly used types
-pub use client::{generate_with_builtin, is_sidecar_healthy, shutdown_sidecar_gracefully, force_shutdown_sidecar};
+pub use client::{count_tokens_with_builtin, generate_with_builtin, is_sidecar_healthy, shutdown_sidecar_gracefully, force_shutdown_sidecar};
 pub use commands::{
-    __cmd__builtin_ai_cancel_download, __cmd__builtin_ai_delete_model,
+    __cmd__builtin_ai_add_local_model, __cmd__builtin_ai_cancel_download, __cmd__builtin_ai_delete_model,
     __cmd__builtin_ai_download_model, __cmd__builtin_ai_get_available_summary_model,
     __cmd__builtin_ai_get_model_info, __cmd__builtin_ai_get_recommended_model, __cmd__builtin_ai_is_model_ready,
-    __cmd__builtin_ai_list_models, builtin_ai_cancel_download, builtin_ai_delete_model, builtin_ai_download_model,
+    __cmd__builtin_ai_list_models, builtin_ai_add_local_model, builtin_ai_cancel_download, builtin_ai_delete_model, builtin_ai_download_model,
     builtin_ai_get_available_summary_model, builtin_ai_get_model_info, builtin_ai_get_recommended_model, builtin_ai_is_model_ready,
     builtin_ai_list_models, init_model_manager, ModelManagerState,
 };
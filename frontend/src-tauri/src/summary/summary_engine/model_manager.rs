@@ -14,7 +14,12 @@ use tokio::io::{AsyncWriteExt, BufWriter};
 use tokio::sync::RwLock;
 use tokio::time::timeout;
 
-use super::models::{get_available_models, get_model_by_name};
+use crate::network::{self, Channel};
+
+use super::models::{
+    get_available_models, get_model_by_name, load_custom_models, register_custom_model,
+    unregister_custom_model,
+};
 
 // ============================================================================
 // Model Status Types
@@ -101,6 +106,12 @@ pub struct ModelInfo {
 
     /// GGUF filename on disk
     pub gguf_file: String,
+
+    /// Prompt format used for this model (e.g., "gemma3", "llama3")
+    pub template: String,
+
+    /// Whether this is a local GGUF file registered by the user
+    pub custom: bool,
 }
 
 // ============================================================================
@@ -172,6 +183,12 @@ impl ModelManager {
             log::info!("Created models directory: {}", self.models_dir.display());
         }
 
+        // Load registered local models before scanning, so they're picked up
+        match load_custom_models(&self.models_dir) {
+            Ok(count) => log::info!("Loaded {} registered local models", count),
+            Err(e) => log::error!("Failed to load local model registry: {}", e),
+        }
+
         // Scan for existing models
         self.scan_models().await?;
 
@@ -191,7 +208,10 @@ impl ModelManager {
         let mut models_map = HashMap::new();
 
         for model_def in model_defs {
-            let model_path = self.models_dir.join(&model_def.gguf_file);
+            let model_path = model_def
+                .local_path
+                .clone()
+                .unwrap_or_else(|| self.models_dir.join(&model_def.gguf_file));
             log::debug!(
                 "Checking model '{}' at path: {}",
                 model_def.name,
@@ -264,6 +284,9 @@ impl ModelManager {
                         ModelStatus::Error(format!("Failed to read metadata: {}", e))
                     }
                 }
+            } else if model_def.is_custom() {
+                log::warn!("Local model '{}': file missing", model_def.name);
+                ModelStatus::Error(format!("Model file not found: {}", model_path.display()))
             } else {
                 log::debug!("Model '{}': NOT FOUND", model_def.name);
                 ModelStatus::NotDownloaded
@@ -278,6 +301,8 @@ impl ModelManager {
                 context_size: model_def.context_size,
                 description: model_def.description.clone(),
                 gguf_file: model_def.gguf_file.clone(),
+                template: model_def.template.clone(),
+                custom: model_def.is_custom(),
             };
 
             models_map.insert(model_def.name.clone(), model_info);
@@ -333,6 +358,25 @@ impl ModelManager {
         }
     }
 
+    /// Register a local GGUF file as a model and return its info
+    pub async fn add_local_model(&self, path: PathBuf) -> Result<ModelInfo> {
+        log::info!("Adding local model: {}", path.display());
+
+        self.validate_gguf_file(&path).await?;
+
+        let models_dir = self.models_dir.clone();
+        let model_def =
+            tokio::task::spawn_blocking(move || register_custom_model(&models_dir, &path))
+                .await
+                .map_err(|e| anyhow!("Model registration task failed: {}", e))??;
+
+        self.scan_models().await?;
+
+        self.get_model_info(&model_def.name)
+            .await
+            .ok_or_else(|| anyhow!("Registered model '{}' not found after scan", model_def.name))
+    }
+
     /// Download a model with simple percentage callback (backward compatible)
     pub async fn download_model(
         &self,
@@ -368,6 +412,11 @@ impl ModelManager {
         let model_def = get_model_by_name(model_name)
             .ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
 
+        if model_def.is_custom() {
+            return Err(anyhow!("Local model '{}' can't be downloaded", model_name));
+        }
+        network::check(&model_def.download_url, Channel::ModelDownload)?;
+
         // Add to active downloads
         {
             let mut active = self.active_downloads.write().await;
@@ -826,6 +875,14 @@ impl ModelManager {
         let model_def = get_model_by_name(model_name)
             .ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
 
+        // Local models belong to the user: only forget them, keep the file
+        if model_def.is_custom() {
+            unregister_custom_model(&self.models_dir, model_name).await?;
+            self.available_models.write().await.remove(model_name);
+            log::info!("Removed local model '{}' from the registry", model_name);
+            return Ok(());
+        }
+
         let file_path = self.models_dir.join(&model_def.gguf_file);
 
         if file_path.exists() {
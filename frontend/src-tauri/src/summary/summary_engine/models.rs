@@ -1,9 +1,14 @@
 // Model definitions and prompt templates for built-in AI summary generation
-// Designed for easy extension - just add new entries to get_available_models()
+// Designed for easy extension - just add new entries to builtin_models()
+// Local GGUF files registered by the user are kept in a registry next to the downloads
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use super::gguf::{self, GgufMetadata};
 
 // ============================================================================
 // Model Definitions
@@ -59,11 +64,29 @@ pub struct ModelDef {
 
     /// Short description for UI
     pub description: String,
+
+    /// Absolute path of a user-registered local model (None for downloadable models)
+    #[serde(default)]
+    pub local_path: Option<PathBuf>,
 }
 
-/// Get all available built-in AI models
-/// Add new models here - the system will automatically detect and manage them
+impl ModelDef {
+    /// Whether this is a local GGUF file registered by the user
+    pub fn is_custom(&self) -> bool {
+        self.local_path.is_some()
+    }
+}
+
+/// Get all available built-in AI models, followed by registered local models
 pub fn get_available_models() -> Vec<ModelDef> {
+    let mut models = builtin_models();
+    models.extend(CUSTOM_MODELS.read().unwrap().iter().cloned());
+    models
+}
+
+/// Downloadable models shipped with the app
+/// Add new models here - the system will automatically detect and manage them
+fn builtin_models() -> Vec<ModelDef> {
     vec![
         // Gemma 3 1B - Fast tier
         ModelDef {
@@ -82,6 +105,7 @@ pub fn get_available_models() -> Vec<ModelDef> {
                 stop_tokens: vec!["<end_of_turn>".to_string()],
             },
             description: "Fastest model. Runs on any hardware with ~1GB RAM. Good for quick summaries.".to_string(),
+            local_path: None,
         },
         ModelDef {
             name: "gemma3:4b".to_string(),
@@ -99,6 +123,7 @@ pub fn get_available_models() -> Vec<ModelDef> {
                 stop_tokens: vec!["<end_of_turn>".to_string()],
             },
             description: "Balanced model. Great quality/speed trade-off. Requires ~3.5GB RAM.".to_string(),
+            local_path: None,
         },
     ]
 }
@@ -117,10 +142,15 @@ pub fn get_default_model() -> ModelDef {
 }
 
 /// Resolve model name to full file path in the models directory
+/// (or to the registered path of a local model)
 pub fn get_model_path(app_data_dir: &PathBuf, model_name: &str) -> Result<PathBuf> {
     let model = get_model_by_name(model_name)
         .ok_or_else(|| anyhow!("Unknown model: {}", model_name))?;
 
+    if let Some(local_path) = model.local_path {
+        return Ok(local_path);
+    }
+
     let models_dir = get_models_directory(app_data_dir);
     let model_path = models_dir.join(&model.gguf_file);
 
@@ -132,6 +162,176 @@ pub fn get_models_directory(app_data_dir: &PathBuf) -> PathBuf {
     app_data_dir.join("models").join("summary")
 }
 
+// ============================================================================
+// Local Model Registry
+// ============================================================================
+
+/// Registry file in the models directory
+const CUSTOM_MODELS_FILE: &str = "custom_models.json";
+
+/// Largest context used for local models, whatever they were trained with,
+/// as the KV cache for 128k tokens doesn't fit in typical RAM
+pub const MAX_CUSTOM_CONTEXT_SIZE: u32 = 32768;
+
+/// Context used when the GGUF file doesn't declare one
+const FALLBACK_CONTEXT_SIZE: u32 = 4096;
+
+/// Local models registered by the user, loaded from `CUSTOM_MODELS_FILE`
+static CUSTOM_MODELS: Lazy<RwLock<Vec<ModelDef>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Load the local model registry from the models directory
+/// Returns the number of registered models
+pub fn load_custom_models(models_dir: &Path) -> Result<usize> {
+    let registry_path = models_dir.join(CUSTOM_MODELS_FILE);
+    let models: Vec<ModelDef> = if registry_path.exists() {
+        let json = std::fs::read_to_string(&registry_path)
+            .with_context(|| format!("Failed to read {}", registry_path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid model registry {}", registry_path.display()))?
+    } else {
+        Vec::new()
+    };
+
+    let count = models.len();
+    *CUSTOM_MODELS.write().unwrap() = models;
+    Ok(count)
+}
+
+fn save_custom_models(models_dir: &Path, models: &[ModelDef]) -> Result<()> {
+    std::fs::create_dir_all(models_dir)?;
+    let registry_path = models_dir.join(CUSTOM_MODELS_FILE);
+    let json = serde_json::to_string_pretty(models)?;
+    std::fs::write(&registry_path, json)
+        .with_context(|| format!("Failed to write {}", registry_path.display()))
+}
+
+/// Register a local GGUF file as a built-in AI model
+///
+/// Reads the file's metadata to pick the prompt format, context size and layer count,
+/// then persists the registry. Registering an already registered file refreshes its entry.
+pub fn register_custom_model(models_dir: &Path, gguf_path: &Path) -> Result<ModelDef> {
+    let gguf_path = gguf_path
+        .canonicalize()
+        .with_context(|| format!("Model file not found: {}", gguf_path.display()))?;
+    let metadata = gguf::read_metadata(&gguf_path)?;
+    let size_mb = std::fs::metadata(&gguf_path)?.len() / (1024 * 1024);
+
+    let mut models = CUSTOM_MODELS.write().unwrap();
+    models.retain(|m| m.local_path.as_deref() != Some(gguf_path.as_path()));
+
+    let taken: Vec<String> = builtin_models()
+        .into_iter()
+        .chain(models.iter().cloned())
+        .map(|m| m.name)
+        .collect();
+    let model = custom_model_def(&gguf_path, &metadata, size_mb, &taken);
+
+    let mut updated = models.clone();
+    updated.push(model.clone());
+    save_custom_models(models_dir, &updated)?;
+    *models = updated;
+
+    log::info!(
+        "Registered local model '{}' ({}, template {}, {} tokens context, {} layers)",
+        model.name,
+        metadata.architecture.as_deref().unwrap_or("unknown architecture"),
+        model.template,
+        model.context_size,
+        model.layer_count
+    );
+    Ok(model)
+}
+
+/// Remove a local model from the registry (the GGUF file itself is kept)
+///
+/// The registry is copied under the lock and written after releasing it, so the
+/// lock isn't held during file I/O.
+pub async fn unregister_custom_model(models_dir: &Path, model_name: &str) -> Result<()> {
+    let updated: Vec<ModelDef> = {
+        let models = CUSTOM_MODELS.read().unwrap();
+        if !models.iter().any(|m| m.name == model_name) {
+            return Err(anyhow!("Unknown local model: {}", model_name));
+        }
+        models
+            .iter()
+            .filter(|m| m.name != model_name)
+            .cloned()
+            .collect()
+    };
+
+    tokio::fs::create_dir_all(models_dir).await?;
+    let registry_path = models_dir.join(CUSTOM_MODELS_FILE);
+    let json = serde_json::to_string_pretty(&updated)?;
+    tokio::fs::write(&registry_path, json)
+        .await
+        .with_context(|| format!("Failed to write {}", registry_path.display()))?;
+
+    CUSTOM_MODELS
+        .write()
+        .unwrap()
+        .retain(|m| m.name != model_name);
+    Ok(())
+}
+
+/// Model definition for a local GGUF file, named `local:<file stem>`
+/// (with a numeric suffix if that name is in `taken`)
+fn custom_model_def(
+    gguf_path: &Path,
+    metadata: &GgufMetadata,
+    size_mb: u64,
+    taken: &[String],
+) -> ModelDef {
+    let file_stem = gguf_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "model".to_string());
+    let slug: String = file_stem
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+        .collect();
+
+    let base_name = format!("local:{}", slug);
+    let mut name = base_name.clone();
+    let mut suffix = 2;
+    while taken.contains(&name) {
+        name = format!("{}-{}", base_name, suffix);
+        suffix += 1;
+    }
+
+    let architecture = metadata.architecture.as_deref().unwrap_or("unknown");
+    let template = detect_template(metadata.chat_template.as_deref(), architecture);
+    let context_size = metadata
+        .context_length
+        .unwrap_or(FALLBACK_CONTEXT_SIZE)
+        .min(MAX_CUSTOM_CONTEXT_SIZE);
+
+    ModelDef {
+        name,
+        display_name: metadata.name.clone().unwrap_or_else(|| file_stem.clone()),
+        gguf_file: gguf_path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        template: template.to_string(),
+        download_url: String::new(),
+        size_mb,
+        context_size,
+        layer_count: metadata.block_count.unwrap_or(0),
+        sampling: SamplingParams {
+            temperature: 0.7,
+            top_k: 40,
+            top_p: 0.95,
+            stop_tokens: template_stop_tokens(template),
+        },
+        description: format!(
+            "Local model ({}, {} prompt format, {} tokens context)",
+            architecture, template, context_size
+        ),
+        local_path: Some(gguf_path.to_path_buf()),
+    }
+}
+
 // ============================================================================
 // Prompt Templates (Model-Specific Formatting)
 // ============================================================================
@@ -145,6 +345,80 @@ pub const GEMMA3_TEMPLATE: &str = "\
 <start_of_turn>model
 ";
 
+/// ChatML format (also used by Qwen models)
+pub const CHATML_TEMPLATE: &str = "\
+<|im_start|>system
+{system_prompt}<|im_end|>
+<|im_start|>user
+{user_prompt}<|im_end|>
+<|im_start|>assistant
+";
+
+/// Llama 3 format (the sidecar adds `<|begin_of_text|>` as BOS token)
+pub const LLAMA3_TEMPLATE: &str = "\
+<|start_header_id|>system<|end_header_id|>
+
+{system_prompt}<|eot_id|><|start_header_id|>user<|end_header_id|>
+
+{user_prompt}<|eot_id|><|start_header_id|>assistant<|end_header_id|>
+
+";
+
+/// Mistral instruct format, which has no system role
+pub const MISTRAL_TEMPLATE: &str = "[INST] {system_prompt}\n\n{user_prompt} [/INST]";
+
+/// Phi 3 format
+pub const PHI3_TEMPLATE: &str = "\
+<|system|>
+{system_prompt}<|end|>
+<|user|>
+{user_prompt}<|end|>
+<|assistant|>
+";
+
+/// Pick the prompt format for a model from its embedded chat template,
+/// falling back to its architecture and finally to ChatML
+pub fn detect_template(chat_template: Option<&str>, architecture: &str) -> &'static str {
+    if let Some(chat_template) = chat_template {
+        if chat_template.contains("<|start_header_id|>") {
+            return "llama3";
+        }
+        if chat_template.contains("<start_of_turn>") {
+            return "gemma3";
+        }
+        if chat_template.contains("<|im_start|>") {
+            return if architecture.starts_with("qwen") { "qwen" } else { "chatml" };
+        }
+        if chat_template.contains("<|user|>") && chat_template.contains("<|end|>") {
+            return "phi3";
+        }
+        if chat_template.contains("[INST]") {
+            return "mistral";
+        }
+    }
+
+    match architecture {
+        arch if arch.starts_with("gemma") => "gemma3",
+        arch if arch.starts_with("qwen") => "qwen",
+        arch if arch.starts_with("phi3") => "phi3",
+        arch if arch.starts_with("mistral") => "mistral",
+        _ => "chatml",
+    }
+}
+
+/// End-of-turn markers of a prompt format
+pub fn template_stop_tokens(template_name: &str) -> Vec<String> {
+    let stop = match template_name {
+        "gemma3" => "<end_of_turn>",
+        "chatml" | "qwen" => "<|im_end|>",
+        "llama3" => "<|eot_id|>",
+        "mistral" => "</s>",
+        "phi3" => "<|end|>",
+        _ => return Vec::new(),
+    };
+    vec![stop.to_string()]
+}
+
 /// Format a prompt using the specified template
 ///
 /// # Arguments
@@ -161,6 +435,10 @@ pub fn format_prompt(
 ) -> Result<String> {
     let template = match template_name {
         "gemma3" => GEMMA3_TEMPLATE,
+        "chatml" | "qwen" => CHATML_TEMPLATE,
+        "llama3" => LLAMA3_TEMPLATE,
+        "mistral" => MISTRAL_TEMPLATE,
+        "phi3" => PHI3_TEMPLATE,
         _ => return Err(anyhow!("Unknown template: {}", template_name)),
     };
 
@@ -183,3 +461,81 @@ pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300; // 5 minutes
 
 /// Generation timeout (how long to wait for a response)
 pub const GENERATION_TIMEOUT_SECS: u64 = 900; // 15 minutes
+
+/// Tokenize timeout (includes loading the model)
+pub const TOKENIZE_TIMEOUT_SECS: u64 = 120;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_template() {
+        let llama3 = "{{- '<|start_header_id|>' + message['role'] + '<|end_header_id|>' }}";
+        assert_eq!(detect_template(Some(llama3), "llama"), "llama3");
+        assert_eq!(detect_template(Some("<|im_start|>system"), "qwen2"), "qwen");
+        assert_eq!(detect_template(Some("<|im_start|>system"), "llama"), "chatml");
+        assert_eq!(detect_template(Some("{{ '[INST] ' + content + ' [/INST]' }}"), "llama"), "mistral");
+        assert_eq!(detect_template(Some("<|user|>\n{{ content }}<|end|>"), "phi3"), "phi3");
+        assert_eq!(detect_template(None, "gemma3"), "gemma3");
+        assert_eq!(detect_template(None, "falcon"), "chatml");
+    }
+
+    #[test]
+    fn test_custom_model_def() {
+        let metadata = GgufMetadata {
+            architecture: Some("llama".to_string()),
+            name: Some("Meta Llama 3.1 8B Instruct".to_string()),
+            context_length: Some(131072),
+            block_count: Some(32),
+            chat_template: Some("<|start_header_id|>".to_string()),
+        };
+        let path = Path::new("/models/Meta-Llama-3.1-8B-Instruct Q4_K_M.gguf");
+
+        let model = custom_model_def(path, &metadata, 4693, &["local:meta-llama-3.1-8b-instruct-q4-k-m".to_string()]);
+        assert_eq!(model.name, "local:meta-llama-3.1-8b-instruct-q4-k-m-2");
+        assert_eq!(model.template, "llama3");
+        assert_eq!(model.context_size, MAX_CUSTOM_CONTEXT_SIZE);
+        assert_eq!(model.layer_count, 32);
+        assert_eq!(model.sampling.stop_tokens, vec!["<|eot_id|>".to_string()]);
+        assert!(model.is_custom());
+
+        let prompt = format_prompt(&model.template, "Be brief.", "Summarize.").unwrap();
+        assert!(prompt.ends_with("<|start_header_id|>assistant<|end_header_id|>\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unregister_custom_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = GgufMetadata {
+            architecture: Some("qwen2".to_string()),
+            name: None,
+            context_length: None,
+            block_count: Some(28),
+            chat_template: None,
+        };
+        let models: Vec<ModelDef> = ["/models/qwen-a.gguf", "/models/qwen-b.gguf"]
+            .iter()
+            .map(|path| custom_model_def(Path::new(path), &metadata, 900, &[]))
+            .collect();
+        std::fs::write(
+            dir.path().join(CUSTOM_MODELS_FILE),
+            serde_json::to_string(&models).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(load_custom_models(dir.path()).unwrap(), 2);
+
+        unregister_custom_model(dir.path(), "local:qwen-a")
+            .await
+            .unwrap();
+        assert!(get_model_by_name("local:qwen-a").is_none());
+        assert!(get_model_by_name("local:qwen-b").is_some());
+        assert!(unregister_custom_model(dir.path(), "local:qwen-a")
+            .await
+            .is_err());
+
+        // The registry file was rewritten without the model
+        assert_eq!(load_custom_models(dir.path()).unwrap(), 1);
+        assert!(get_model_by_name("local:qwen-b").is_some());
+    }
+}
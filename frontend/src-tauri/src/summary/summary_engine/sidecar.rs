@@ -11,12 +11,16 @@ use anyhow::{anyhow, Context, Result};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, ChildStdout};
 use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
 use super::models;
 
+/// How long a cancelled generation may take to stop before the sidecar is killed
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
 // ============================================================================
 // Sidecar State Management
 // ============================================================================
@@ -44,6 +48,10 @@ pub struct SidecarManager {
     /// Active request count (for graceful shutdown)
     active_request_count: Arc<AtomicUsize>,
 
+    /// Held for a whole request/response exchange, so streamed messages of
+    /// concurrent requests can't interleave
+    request_lock: Arc<Mutex<()>>,
+
     /// Path to llama-helper binary
     helper_binary_path: PathBuf,
 
@@ -56,19 +64,37 @@ pub struct SidecarManager {
 
 /// RAII guard for tracking active requests
 /// Decrements the active request count when dropped
+///
+/// A request dropped before its final response was read leaves unread messages
+/// on stdout, so the sidecar is marked unhealthy and restarted on next use.
 struct RequestGuard {
     counter: Arc<AtomicUsize>,
+    is_healthy: Arc<AtomicBool>,
+    completed: bool,
 }
 
 impl RequestGuard {
-    fn new(counter: Arc<AtomicUsize>) -> Self {
+    fn new(counter: Arc<AtomicUsize>, is_healthy: Arc<AtomicBool>) -> Self {
         counter.fetch_add(1, Ordering::SeqCst);
-        Self { counter }
+        Self {
+            counter,
+            is_healthy,
+            completed: false,
+        }
+    }
+
+    /// Marks the exchange as complete: the final response was read
+    fn complete(&mut self) {
+        self.completed = true;
     }
 }
 
 impl Drop for RequestGuard {
     fn drop(&mut self) {
+        if !self.completed {
+            log::warn!("Sidecar request ended without a final response, sidecar will be restarted");
+            self.is_healthy.store(false, Ordering::SeqCst);
+        }
         self.counter.fetch_sub(1, Ordering::SeqCst);
     }
 }
@@ -98,6 +124,7 @@ impl SidecarManager {
             is_healthy: Arc::new(AtomicBool::new(false)),
             should_shutdown: Arc::new(AtomicBool::new(false)),
             active_request_count: Arc::new(AtomicUsize::new(0)),
+            request_lock: Arc::new(Mutex::new(())),
             helper_binary_path,
             current_model_path: Arc::new(RwLock::new(None)),
             idle_timeout_secs,
@@ -350,34 +377,92 @@ impl SidecarManager {
 
     /// Send a request to the sidecar and wait for response
     pub async fn send_request(&self, request_json: String, timeout: Duration) -> Result<String> {
+        self.send_streaming_request(request_json, timeout, None, |_| {})
+            .await
+    }
+
+    /// Send a request to the sidecar and wait for its final response
+    ///
+    /// `token` and `progress` messages the sidecar streams before the final
+    /// response are passed to `on_message` as raw JSON lines. When the
+    /// cancellation token fires, a `cancel` request is sent and the final
+    /// (`cancelled`) response is still awaited, so the process keeps running;
+    /// only if it doesn't stop within `CANCEL_GRACE_PERIOD` is it shut down.
+    /// The timeout covers the whole exchange.
+    pub async fn send_streaming_request(
+        &self,
+        request_json: String,
+        timeout: Duration,
+        cancellation_token: Option<&CancellationToken>,
+        mut on_message: impl FnMut(&str) + Send,
+    ) -> Result<String> {
         // Track active request
-        let _guard = RequestGuard::new(self.active_request_count.clone());
+        let mut guard = RequestGuard::new(self.active_request_count.clone(), self.is_healthy.clone());
+        let _exchange = match cancellation_token {
+            Some(token) => tokio::select! {
+                exchange = self.request_lock.lock() => exchange,
+                _ = token.cancelled() => {
+                    // Nothing was sent yet
+                    guard.complete();
+                    return Err(anyhow!("Generation cancelled by user"));
+                }
+            },
+            None => self.request_lock.lock().await,
+        };
 
-        // Write request to stdin
-        {
-            let mut stdin_lock = self.stdin_writer.lock().await;
-            let stdin = stdin_lock
-                .as_mut()
-                .ok_or_else(|| anyhow!("Sidecar not running"))?;
-
-            stdin
-                .write_all(request_json.as_bytes())
-                .await
-                .context("Failed to write request to stdin")?;
-            stdin
-                .write_all(b"\n")
-                .await
-                .context("Failed to write newline")?;
-            stdin.flush().await.context("Failed to flush stdin")?;
-        }
+        self.write_request(&request_json).await?;
+
+        // Read streamed messages until the final response arrives
+        let read_until_response = async {
+            loop {
+                let line = self.read_response().await?;
+                if !is_stream_message(&line) {
+                    return Ok::<String, anyhow::Error>(line);
+                }
+                self.update_activity().await;
+                on_message(&line);
+            }
+        };
+
+        // Ask the sidecar to stop once cancelled, then give it some time to do so.
+        // Reading goes on meanwhile; it is only abandoned if this future completes.
+        let cancel_generation = async {
+            match cancellation_token {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+            log::warn!("Generation cancelled by user, asking sidecar to stop");
+            if let Err(e) = self.write_request(&serde_json::json!({"type": "cancel"}).to_string()).await {
+                return e;
+            }
+            tokio::time::sleep(CANCEL_GRACE_PERIOD).await;
+            anyhow!("Sidecar didn't stop within {:?} after cancellation", CANCEL_GRACE_PERIOD)
+        };
+
+        // The outer error means the sidecar ignored the cancel request
+        let exchange = async {
+            tokio::select! {
+                response = read_until_response => Ok(response),
+                error = cancel_generation => Err(error),
+            }
+        };
 
         // Read response from stdout with timeout
-        match tokio::time::timeout(timeout, self.read_response()).await {
-            Ok(Ok(response)) => {
+        match tokio::time::timeout(timeout, exchange).await {
+            Ok(Ok(Ok(response))) => {
+                guard.complete();
                 self.update_activity().await;
                 Ok(response)
             }
-            Ok(Err(e)) => Err(e),
+            Ok(Ok(Err(e))) => Err(e),
+            Ok(Err(e)) => {
+                // Shutdown sidecar to stop generation
+                log::error!("{}, shutting down sidecar", e);
+                if let Err(shutdown_err) = self.shutdown().await {
+                    log::error!("Failed to shutdown sidecar after cancellation: {}", shutdown_err);
+                }
+                Err(anyhow!("Generation cancelled by user"))
+            }
             Err(_) => {
                 // Timeout reached - shutdown sidecar to stop generation
                 log::error!("Request timeout after {:?}, shutting down sidecar", timeout);
@@ -389,6 +474,25 @@ impl SidecarManager {
         }
     }
 
+    /// Write a single request line to stdin
+    async fn write_request(&self, request_json: &str) -> Result<()> {
+        let mut stdin_lock = self.stdin_writer.lock().await;
+        let stdin = stdin_lock
+            .as_mut()
+            .ok_or_else(|| anyhow!("Sidecar not running"))?;
+
+        stdin
+            .write_all(request_json.as_bytes())
+            .await
+            .context("Failed to write request to stdin")?;
+        stdin
+            .write_all(b"\n")
+            .await
+            .context("Failed to write newline")?;
+        stdin.flush().await.context("Failed to flush stdin")?;
+        Ok(())
+    }
+
     /// Read a single line response from stdout
     async fn read_response(&self) -> Result<String> {
         let mut stdout_lock = self.stdout_reader.lock().await;
@@ -416,7 +520,12 @@ impl SidecarManager {
 
         // Note: We don't use send_request here to avoid incrementing active_request_count
         // for internal health checks, as that would prevent graceful shutdown
-        
+
+        // Skip the ping if a request is being exchanged right now
+        let Ok(_exchange) = self.request_lock.try_lock() else {
+            return Ok(());
+        };
+
         // Write request
         {
             let mut stdin_lock = self.stdin_writer.lock().await;
@@ -563,6 +672,7 @@ impl SidecarManager {
             is_healthy: self.is_healthy.clone(),
             should_shutdown: self.should_shutdown.clone(),
             active_request_count: self.active_request_count.clone(),
+            request_lock: self.request_lock.clone(),
             helper_binary_path: self.helper_binary_path.clone(),
             current_model_path: self.current_model_path.clone(),
             idle_timeout_secs: self.idle_timeout_secs,
@@ -611,6 +721,7 @@ impl SidecarManager {
             is_healthy: self.is_healthy.clone(),
             should_shutdown: self.should_shutdown.clone(),
             active_request_count: self.active_request_count.clone(),
+            request_lock: self.request_lock.clone(),
             helper_binary_path: self.helper_binary_path.clone(),
             current_model_path: self.current_model_path.clone(),
             idle_timeout_secs: self.idle_timeout_secs,
@@ -658,6 +769,20 @@ impl SidecarManager {
     }
 }
 
+/// Whether a line from the sidecar is a streamed `token` or `progress` message
+/// rather than a final response
+fn is_stream_message(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|message| {
+            message
+                .get("type")?
+                .as_str()
+                .map(|t| t == "token" || t == "progress")
+        })
+        .unwrap_or(false)
+}
+
 impl Drop for SidecarManager {
     fn drop(&mut self) {
         // Set shutdown flag
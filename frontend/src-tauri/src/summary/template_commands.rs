@@ -1,5 +1,10 @@
+use crate::database::models::LearnerProfile;
+use crate::database::repositories::learner_profile::LearnerProfileRepository;
+use crate::state::AppState;
 use crate::summary::templates;
+use crate::summary::SummaryService;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tauri::Runtime;
 use tracing::{info, warn};
 
@@ -14,6 +19,12 @@ pub struct TemplateInfo {
 
     /// Brief description of the template's purpose
     pub description: String,
+
+    /// Template version, bumped on every edit of a custom template
+    pub version: u32,
+
+    /// Built-in templates are read-only and can only be duplicated
+    pub is_builtin: bool,
 }
 
 /// Detailed template structure for preview/debugging
@@ -28,10 +39,33 @@ pub struct TemplateDetails {
     /// Description
     pub description: String,
 
+    /// Template version
+    pub version: u32,
+
+    /// Whether this is a read-only built-in template
+    pub is_builtin: bool,
+
     /// List of section titles in order
     pub sections: Vec<String>,
 }
 
+impl TemplateDetails {
+    fn from_template(template_id: String, template: templates::Template) -> Self {
+        Self {
+            is_builtin: templates::is_builtin_template(&template_id),
+            id: template_id,
+            name: template.name,
+            description: template.description,
+            version: template.version,
+            sections: template
+                .sections
+                .iter()
+                .map(|section| section.title.clone())
+                .collect(),
+        }
+    }
+}
+
 /// Lists all available templates
 ///
 /// Returns templates from both built-in (embedded) and custom (user data directory) sources.
@@ -50,6 +84,8 @@ pub async fn api_list_templates<R: Runtime>(
     let template_infos: Vec<TemplateInfo> = templates
         .into_iter()
         .map(|(id, name, description)| TemplateInfo {
+            version: templates::get_template(&id).map(|t| t.version).unwrap_or(1),
+            is_builtin: templates::is_builtin_template(&id),
             id,
             name,
             description,
@@ -65,30 +101,39 @@ pub async fn api_list_templates<R: Runtime>(
 ///
 /// # Arguments
 /// * `template_id` - Template identifier (e.g., "daily_standup")
+/// * `version` - Optional earlier version (defaults to the current one)
+/// * `meeting_id` - Optional session to resolve the template's variables and
+///   conditional sections for, showing the sections its notes would have
 ///
 /// # Returns
 /// TemplateDetails with full template structure
 #[tauri::command]
 pub async fn api_get_template_details<R: Runtime>(
     _app: tauri::AppHandle<R>,
+    state: tauri::State<'_, AppState>,
     template_id: String,
+    version: Option<u32>,
+    meeting_id: Option<String>,
 ) -> Result<TemplateDetails, String> {
-    info!("api_get_template_details called for template_id: {}", template_id);
-
-    let template = templates::get_template(&template_id)?;
-
-    let section_titles: Vec<String> = template
-        .sections
-        .iter()
-        .map(|section| section.title.clone())
-        .collect();
+    info!(
+        "api_get_template_details called for template_id: {}, version: {:?}, meeting_id: {:?}",
+        template_id, version, meeting_id
+    );
 
-    let details = TemplateDetails {
-        id: template_id,
-        name: template.name,
-        description: template.description,
-        sections: section_titles,
+    let mut template = match version {
+        Some(version) => templates::get_template_version(&template_id, version)?,
+        None => templates::get_template(&template_id)?,
     };
+    if let Some(meeting_id) = &meeting_id {
+        template = SummaryService::resolve_template_preview(
+            state.db_manager.pool(),
+            meeting_id,
+            &template,
+        )
+        .await;
+    }
+
+    let details = TemplateDetails::from_template(template_id, template);
 
     info!("Retrieved template details for '{}'", details.name);
 
@@ -123,6 +168,191 @@ pub async fn api_validate_template<R: Runtime>(
     }
 }
 
+/// Gets the raw JSON of a template, for editing in the template editor
+#[tauri::command]
+pub async fn api_get_template_json<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    template_id: String,
+) -> Result<String, String> {
+    info!("api_get_template_json called for template_id: {}", template_id);
+
+    let template = templates::get_template(&template_id)?;
+    serde_json::to_string_pretty(&template).map_err(|e| format!("Failed to serialize template: {}", e))
+}
+
+/// Creates a new custom template
+///
+/// # Arguments
+/// * `template_id` - Identifier for the new template (lowercase letters, digits, '_' or '-')
+/// * `template_json` - Raw JSON string of the template
+#[tauri::command]
+pub async fn api_create_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    template_id: String,
+    template_json: String,
+) -> Result<TemplateDetails, String> {
+    info!("api_create_template called for template_id: {}", template_id);
+
+    let template = templates::validate_and_parse_template(&template_json)?;
+    let template = templates::create_custom_template(&template_id, template).map_err(|e| {
+        warn!("Failed to create template '{}': {}", template_id, e);
+        e
+    })?;
+
+    Ok(TemplateDetails::from_template(template_id, template))
+}
+
+/// Saves changes to a custom template, bumping its version
+///
+/// The previous version is kept so summaries generated with it can still be traced back.
+#[tauri::command]
+pub async fn api_update_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    template_id: String,
+    template_json: String,
+) -> Result<TemplateDetails, String> {
+    info!("api_update_template called for template_id: {}", template_id);
+
+    let template = templates::validate_and_parse_template(&template_json)?;
+    let template = templates::update_custom_template(&template_id, template).map_err(|e| {
+        warn!("Failed to update template '{}': {}", template_id, e);
+        e
+    })?;
+
+    Ok(TemplateDetails::from_template(template_id, template))
+}
+
+/// Deletes a custom template. Built-in templates cannot be deleted.
+#[tauri::command]
+pub async fn api_delete_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    template_id: String,
+) -> Result<(), String> {
+    info!("api_delete_template called for template_id: {}", template_id);
+
+    templates::delete_custom_template(&template_id).map_err(|e| {
+        warn!("Failed to delete template '{}': {}", template_id, e);
+        e
+    })
+}
+
+/// Copies a built-in or custom template into a new, editable custom template
+///
+/// # Arguments
+/// * `source_id` - Template to copy
+/// * `new_id` - Identifier for the copy
+/// * `new_name` - Optional display name (defaults to "<name> (Copy)")
+#[tauri::command]
+pub async fn api_duplicate_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    source_id: String,
+    new_id: String,
+    new_name: Option<String>,
+) -> Result<TemplateDetails, String> {
+    info!(
+        "api_duplicate_template called: '{}' -> '{}'",
+        source_id, new_id
+    );
+
+    let template = templates::duplicate_template(&source_id, &new_id, new_name.as_deref())?;
+
+    Ok(TemplateDetails::from_template(new_id, template))
+}
+
+/// Imports a template JSON file as a custom template
+///
+/// # Arguments
+/// * `file_path` - Path of the JSON file to import
+/// * `template_id` - Optional identifier (defaults to one derived from the file name)
+#[tauri::command]
+pub async fn api_import_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    file_path: String,
+    template_id: Option<String>,
+) -> Result<TemplateDetails, String> {
+    info!("api_import_template called for file: {}", file_path);
+
+    let (template_id, template) =
+        templates::import_template(&PathBuf::from(&file_path), template_id.as_deref()).map_err(
+            |e| {
+                warn!("Failed to import template from {}: {}", file_path, e);
+                e
+            },
+        )?;
+
+    Ok(TemplateDetails::from_template(template_id, template))
+}
+
+/// Exports a template as a JSON file, e.g. to share it with another tutor
+#[tauri::command]
+pub async fn api_export_template<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    template_id: String,
+    file_path: String,
+) -> Result<(), String> {
+    info!(
+        "api_export_template called for template_id: {} -> {}",
+        template_id, file_path
+    );
+
+    templates::export_template(&template_id, &PathBuf::from(file_path))
+}
+
+/// Variables and section conditions available to templates, for the template editor
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateVariablesInfo {
+    /// Variable names usable as `{{name}}`
+    pub variables: Vec<String>,
+
+    /// Condition names usable in a section's `condition`
+    pub conditions: Vec<String>,
+}
+
+/// Lists the variables and conditions templates may use
+#[tauri::command]
+pub async fn api_list_template_variables<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+) -> Result<TemplateVariablesInfo, String> {
+    Ok(TemplateVariablesInfo {
+        variables: templates::TEMPLATE_VARIABLES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        conditions: templates::TEMPLATE_CONDITIONS
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+    })
+}
+
+/// Gets the learner profile used to fill in template variables
+#[tauri::command]
+pub async fn api_get_learner_profile<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+) -> Result<LearnerProfile, String> {
+    LearnerProfileRepository::get_profile(state.db_manager.pool())
+        .await
+        .map_err(|e| format!("Failed to load learner profile: {}", e))
+}
+
+/// Saves the learner profile (target/native language, level, tutor name)
+#[tauri::command]
+pub async fn api_save_learner_profile<R: Runtime>(
+    _app: tauri::AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    profile: LearnerProfile,
+) -> Result<(), String> {
+    info!("api_save_learner_profile called");
+
+    LearnerProfileRepository::save_profile(state.db_manager.pool(), &profile)
+        .await
+        .map_err(|e| {
+            warn!("Failed to save learner profile: {}", e);
+            format!("Failed to save learner profile: {}", e)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
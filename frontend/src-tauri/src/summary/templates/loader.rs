@@ -22,7 +22,7 @@ pub fn set_bundled_templates_dir(path: PathBuf) {
 /// - macOS: ~/Library/Application Support/Uchitil Live/templates/
 /// - Windows: %APPDATA%\Uchitil Live\templates\
 /// - Linux: ~/.config/Uchitil Live/templates/
-fn get_custom_templates_dir() -> Option<PathBuf> {
+pub(super) fn get_custom_templates_dir() -> Option<PathBuf> {
     let mut path = dirs::data_dir()?;
     path.push("Uchitil Live");
     path.push("templates");
@@ -85,6 +85,24 @@ fn load_custom_template(template_id: &str) -> Option<String> {
     }
 }
 
+/// Check whether a template identifier belongs to a built-in template
+///
+/// Built-in templates are the embedded defaults and the templates bundled with the
+/// app resources. They can be duplicated, but never overwritten or deleted.
+pub fn is_builtin_template(template_id: &str) -> bool {
+    if defaults::get_builtin_template(template_id).is_some() {
+        return true;
+    }
+
+    match BUNDLED_TEMPLATES_DIR.read() {
+        Ok(dir) => dir
+            .as_ref()
+            .map(|bundled_dir| bundled_dir.join(format!("{}.json", template_id)).is_file())
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
 /// Load and parse a template by identifier
 ///
 /// This function implements a fallback strategy:
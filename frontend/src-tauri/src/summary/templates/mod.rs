@@ -9,6 +9,9 @@
 //! - **Built-in templates**: JSON files in `frontend/src-tauri/templates/` embedded at compile time
 //! - **Custom templates**: JSON files in platform-specific app data directory
 //! - **Fallback strategy**: Custom templates override built-in templates with the same ID
+//! - **Storage**: Custom templates can be created, edited, deleted, duplicated from
+//!   built-ins, imported and exported. Built-in templates are read-only. Every edit
+//!   bumps the template `version` and archives the previous version.
 //!
 //! # Usage
 //!
@@ -36,17 +39,32 @@
 //! - Linux: `~/.config/Uchitil Live/templates/`
 //!
 //! Custom templates must follow the JSON schema defined in `types::Template`.
+//!
+//! # Variables and Conditional Sections
+//!
+//! Section titles, instructions and item formats may reference `{{variable}}`
+//! placeholders (see `TEMPLATE_VARIABLES`), resolved from the learner profile and
+//! the session. A section with a `condition` (see `TEMPLATE_CONDITIONS`) is only
+//! included when the condition holds. `Template::resolve` applies both before the
+//! template is turned into a prompt.
 
 mod defaults;
 mod loader;
+mod storage;
 mod types;
+mod variables;
 
 // Re-export public API
 pub use loader::{
-    get_template, list_template_ids, list_templates, set_bundled_templates_dir,
-    validate_and_parse_template,
+    get_template, is_builtin_template, list_template_ids, list_templates,
+    set_bundled_templates_dir, validate_and_parse_template,
+};
+pub use storage::{
+    create_custom_template, delete_custom_template, duplicate_template, export_template,
+    get_template_version, import_template, update_custom_template, validate_template_id,
 };
 pub use types::{Template, TemplateSection};
+pub use variables::{TemplateContext, TEMPLATE_CONDITIONS, TEMPLATE_VARIABLES};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,386 @@
+use super::loader::{
+    get_custom_templates_dir, get_template, is_builtin_template, validate_and_parse_template,
+};
+use super::types::Template;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Subdirectory of the custom templates directory holding previous template versions
+const HISTORY_DIR: &str = ".history";
+
+/// Maximum length of a template identifier
+const MAX_TEMPLATE_ID_LEN: usize = 64;
+
+/// Validates a template identifier
+///
+/// Identifiers become file names, so only lowercase ASCII letters, digits,
+/// `_` and `-` are allowed.
+pub fn validate_template_id(template_id: &str) -> Result<(), String> {
+    if template_id.is_empty() {
+        return Err("Template ID cannot be empty".to_string());
+    }
+
+    if template_id.len() > MAX_TEMPLATE_ID_LEN {
+        return Err(format!(
+            "Template ID cannot be longer than {} characters",
+            MAX_TEMPLATE_ID_LEN
+        ));
+    }
+
+    if !template_id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "Invalid template ID '{}'. Use lowercase letters, digits, '_' or '-'",
+            template_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Derives a template identifier from a file name (e.g. "My Lesson.json" -> "my_lesson")
+fn template_id_from_path(path: &Path) -> Result<String, String> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid template file name: {}", path.display()))?;
+
+    let id: String = stem
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    validate_template_id(&id)?;
+    Ok(id)
+}
+
+fn custom_templates_dir() -> Result<PathBuf, String> {
+    get_custom_templates_dir().ok_or_else(|| "Could not determine custom templates directory".to_string())
+}
+
+fn ensure_not_builtin(template_id: &str) -> Result<(), String> {
+    if is_builtin_template(template_id) {
+        return Err(format!(
+            "'{}' is a built-in template and cannot be modified. Duplicate it to make changes.",
+            template_id
+        ));
+    }
+    Ok(())
+}
+
+fn template_path(dir: &Path, template_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", template_id))
+}
+
+fn read_template_file(path: &Path) -> Result<Template, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read template file {}: {}", path.display(), e))?;
+    validate_and_parse_template(&content)
+}
+
+/// Writes a template as pretty JSON, via a temporary file so a crash never leaves
+/// a half-written template behind
+fn write_template_file(path: &Path, template: &Template) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+    }
+
+    let content = serde_json::to_string_pretty(template)
+        .map_err(|e| format!("Failed to serialize template: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to write template file {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save template file {}: {}", path.display(), e))
+}
+
+/// Returns the first version number not used by an archived version of the template
+///
+/// Normally 1, but a template re-created under the ID of a deleted one continues
+/// its numbering so archived versions are never overwritten.
+fn first_unused_version(dir: &Path, template_id: &str) -> u32 {
+    let history_dir = dir.join(HISTORY_DIR).join(template_id);
+    let highest = std::fs::read_dir(&history_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_str()?.to_string();
+                    name.strip_prefix('v')?.strip_suffix(".json")?.parse::<u32>().ok()
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0);
+
+    highest + 1
+}
+
+/// Creates a new template in `dir`, starting at version 1
+fn create_template_in(dir: &Path, template_id: &str, mut template: Template) -> Result<Template, String> {
+    validate_template_id(template_id)?;
+
+    let path = template_path(dir, template_id);
+    if path.exists() {
+        return Err(format!("Template '{}' already exists", template_id));
+    }
+
+    template.version = first_unused_version(dir, template_id);
+    template.validate()?;
+    write_template_file(&path, &template)?;
+
+    info!("Created custom template '{}'", template_id);
+    Ok(template)
+}
+
+/// Replaces an existing template in `dir`, archiving the previous version and
+/// bumping the version number
+fn update_template_in(dir: &Path, template_id: &str, mut template: Template) -> Result<Template, String> {
+    validate_template_id(template_id)?;
+
+    let path = template_path(dir, template_id);
+    if !path.exists() {
+        return Err(format!("Custom template '{}' not found", template_id));
+    }
+
+    let current = read_template_file(&path)?;
+    template.version = current.version + 1;
+    template.validate()?;
+
+    let archive_path = dir
+        .join(HISTORY_DIR)
+        .join(template_id)
+        .join(format!("v{}.json", current.version));
+    write_template_file(&archive_path, &current)?;
+    write_template_file(&path, &template)?;
+
+    info!(
+        "Updated custom template '{}' to version {}",
+        template_id, template.version
+    );
+    Ok(template)
+}
+
+/// Deletes a template from `dir`. Archived versions are kept so that summaries
+/// generated with the template remain traceable.
+fn delete_template_in(dir: &Path, template_id: &str) -> Result<(), String> {
+    validate_template_id(template_id)?;
+
+    let path = template_path(dir, template_id);
+    if !path.exists() {
+        return Err(format!("Custom template '{}' not found", template_id));
+    }
+
+    let current = read_template_file(&path).ok();
+    if let Some(current) = current {
+        let archive_path = dir
+            .join(HISTORY_DIR)
+            .join(template_id)
+            .join(format!("v{}.json", current.version));
+        write_template_file(&archive_path, &current)?;
+    }
+
+    std::fs::remove_file(&path)
+        .map_err(|e| format!("Failed to delete template '{}': {}", template_id, e))?;
+
+    info!("Deleted custom template '{}'", template_id);
+    Ok(())
+}
+
+/// Loads a specific version of a template from `dir`, current or archived
+fn load_template_version_in(dir: &Path, template_id: &str, version: u32) -> Result<Template, String> {
+    validate_template_id(template_id)?;
+
+    if let Ok(current) = read_template_file(&template_path(dir, template_id)) {
+        if current.version == version {
+            return Ok(current);
+        }
+    }
+
+    let archive_path = dir
+        .join(HISTORY_DIR)
+        .join(template_id)
+        .join(format!("v{}.json", version));
+    if !archive_path.exists() {
+        return Err(format!(
+            "Version {} of template '{}' not found",
+            version, template_id
+        ));
+    }
+
+    read_template_file(&archive_path)
+}
+
+/// Creates a new custom template
+///
+/// # Arguments
+/// * `template_id` - Identifier for the new template (must not exist yet)
+/// * `template` - Template content; its version is reset to 1
+///   (or continues after the archived versions of a deleted template with the same ID)
+pub fn create_custom_template(template_id: &str, template: Template) -> Result<Template, String> {
+    ensure_not_builtin(template_id)?;
+    create_template_in(&custom_templates_dir()?, template_id, template)
+}
+
+/// Updates an existing custom template. Built-in templates are rejected.
+pub fn update_custom_template(template_id: &str, template: Template) -> Result<Template, String> {
+    ensure_not_builtin(template_id)?;
+    update_template_in(&custom_templates_dir()?, template_id, template)
+}
+
+/// Deletes a custom template. Built-in templates are rejected.
+pub fn delete_custom_template(template_id: &str) -> Result<(), String> {
+    ensure_not_builtin(template_id)?;
+    delete_template_in(&custom_templates_dir()?, template_id)
+}
+
+/// Duplicates any template (built-in or custom) into a new custom template
+///
+/// # Arguments
+/// * `source_id` - Template to copy
+/// * `new_id` - Identifier for the copy
+/// * `new_name` - Optional display name (defaults to "<name> (Copy)")
+pub fn duplicate_template(
+    source_id: &str,
+    new_id: &str,
+    new_name: Option<&str>,
+) -> Result<Template, String> {
+    let mut template = get_template(source_id)?;
+    template.name = match new_name {
+        Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => format!("{} (Copy)", template.name),
+    };
+
+    create_custom_template(new_id, template)
+}
+
+/// Imports a template file into the custom templates directory
+///
+/// # Arguments
+/// * `path` - Template JSON file to import
+/// * `template_id` - Optional identifier (defaults to one derived from the file name)
+///
+/// # Returns
+/// The identifier and content of the imported template
+pub fn import_template(path: &Path, template_id: Option<&str>) -> Result<(String, Template), String> {
+    let template = read_template_file(path)?;
+    let template_id = match template_id {
+        Some(id) => id.to_string(),
+        None => template_id_from_path(path)?,
+    };
+
+    let template = create_custom_template(&template_id, template)?;
+    Ok((template_id, template))
+}
+
+/// Exports any template (built-in or custom) as a JSON file
+pub fn export_template(template_id: &str, path: &Path) -> Result<(), String> {
+    let template = get_template(template_id)?;
+    write_template_file(path, &template)?;
+
+    info!("Exported template '{}' to {}", template_id, path.display());
+    Ok(())
+}
+
+/// Loads a specific version of a template, e.g. the one an old summary was generated with
+pub fn get_template_version(template_id: &str, version: u32) -> Result<Template, String> {
+    if is_builtin_template(template_id) {
+        let template = get_template(template_id)?;
+        if template.version == version {
+            return Ok(template);
+        }
+    }
+
+    load_template_version_in(&custom_templates_dir()?, template_id, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::templates::TemplateSection;
+
+    fn template(name: &str) -> Template {
+        Template {
+            name: name.to_string(),
+            description: "A test template".to_string(),
+            version: 7,
+            sections: vec![TemplateSection {
+                title: "Summary".to_string(),
+                instruction: "Provide a summary".to_string(),
+                format: "paragraph".to_string(),
+                item_format: None,
+                example_item_format: None,
+                condition: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_validate_template_id() {
+        assert!(validate_template_id("my_lesson-2").is_ok());
+        assert!(validate_template_id("").is_err());
+        assert!(validate_template_id("../etc/passwd").is_err());
+        assert!(validate_template_id("Upper").is_err());
+    }
+
+    #[test]
+    fn test_template_id_from_path() {
+        assert_eq!(
+            template_id_from_path(Path::new("/tmp/My Lesson.json")).unwrap(),
+            "my_lesson"
+        );
+    }
+
+    #[test]
+    fn test_create_update_and_versions() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let created = create_template_in(dir.path(), "lesson", template("Lesson")).unwrap();
+        assert_eq!(created.version, 1);
+        assert!(create_template_in(dir.path(), "lesson", template("Lesson")).is_err());
+
+        let updated = update_template_in(dir.path(), "lesson", template("Lesson v2")).unwrap();
+        assert_eq!(updated.version, 2);
+
+        let v1 = load_template_version_in(dir.path(), "lesson", 1).unwrap();
+        assert_eq!(v1.name, "Lesson");
+        let v2 = load_template_version_in(dir.path(), "lesson", 2).unwrap();
+        assert_eq!(v2.name, "Lesson v2");
+        assert!(load_template_version_in(dir.path(), "lesson", 3).is_err());
+    }
+
+    #[test]
+    fn test_delete_keeps_history() {
+        let dir = tempfile::tempdir().unwrap();
+
+        create_template_in(dir.path(), "lesson", template("Lesson")).unwrap();
+        delete_template_in(dir.path(), "lesson").unwrap();
+
+        assert!(!template_path(dir.path(), "lesson").exists());
+        assert!(load_template_version_in(dir.path(), "lesson", 1).is_ok());
+        assert!(delete_template_in(dir.path(), "lesson").is_err());
+
+        let recreated = create_template_in(dir.path(), "lesson", template("Lesson again")).unwrap();
+        assert_eq!(recreated.version, 2);
+        assert_eq!(load_template_version_in(dir.path(), "lesson", 1).unwrap().name, "Lesson");
+    }
+
+    #[test]
+    fn test_builtin_templates_are_protected() {
+        assert!(update_custom_template("daily_standup", template("Hijack")).is_err());
+        assert!(delete_custom_template("standard_meeting").is_err());
+        assert!(create_custom_template("daily_standup", template("Hijack")).is_err());
+    }
+}
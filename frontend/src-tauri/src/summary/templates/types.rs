@@ -1,3 +1,4 @@
+use super::variables::{undefined_variables, validate_condition, TemplateContext};
 use serde::{Deserialize, Serialize};
 
 /// Represents a single section in a session template
@@ -19,6 +20,11 @@ pub struct TemplateSection {
     /// Alternative formatting hint
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example_item_format: Option<String>,
+
+    /// Optional condition; the section is only included when it holds
+    /// (e.g. "has_mic_speech" or "!has_system_speech")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
 }
 
 /// Represents a complete session template
@@ -30,10 +36,20 @@ pub struct Template {
     /// Brief description of the template's purpose
     pub description: String,
 
+    /// Template version, incremented whenever a user template is edited.
+    /// Recorded with each generated summary so old notes stay traceable to the
+    /// exact template they were generated from.
+    #[serde(default = "default_template_version")]
+    pub version: u32,
+
     /// List of sections in the template
     pub sections: Vec<TemplateSection>,
 }
 
+fn default_template_version() -> u32 {
+    1
+}
+
 impl Template {
     /// Validates the template structure
     pub fn validate(&self) -> Result<(), String> {
@@ -45,6 +61,10 @@ impl Template {
             return Err("Template description cannot be empty".to_string());
         }
 
+        if self.version == 0 {
+            return Err("Template version must be at least 1".to_string());
+        }
+
         if self.sections.is_empty() {
             return Err("Template must have at least one section".to_string());
         }
@@ -65,11 +85,71 @@ impl Template {
                     section.title, other
                 )),
             }
+
+            let texts = [
+                Some(&section.title),
+                Some(&section.instruction),
+                section.item_format.as_ref(),
+                section.example_item_format.as_ref(),
+            ];
+            let mut undefined: Vec<String> = Vec::new();
+            for text in texts.into_iter().flatten() {
+                for name in undefined_variables(text) {
+                    if !undefined.contains(&name) {
+                        undefined.push(name);
+                    }
+                }
+            }
+            if !undefined.is_empty() {
+                return Err(format!(
+                    "Section '{}' uses undefined variable(s): {}",
+                    section.title,
+                    undefined.join(", ")
+                ));
+            }
+
+            if let Some(condition) = &section.condition {
+                validate_condition(condition)
+                    .map_err(|e| format!("Section '{}': {}", section.title, e))?;
+            }
         }
 
         Ok(())
     }
 
+    /// Resolves the template for one session: drops sections whose condition
+    /// doesn't hold and fills in `{{variable}}` placeholders
+    pub fn resolve(&self, context: &TemplateContext) -> Template {
+        let sections = self
+            .sections
+            .iter()
+            .filter(|section| {
+                section
+                    .condition
+                    .as_deref()
+                    .map_or(true, |condition| context.evaluate(condition))
+            })
+            .map(|section| TemplateSection {
+                title: context.render(&section.title),
+                instruction: context.render(&section.instruction),
+                format: section.format.clone(),
+                item_format: section.item_format.as_deref().map(|f| context.render(f)),
+                example_item_format: section
+                    .example_item_format
+                    .as_deref()
+                    .map(|f| context.render(f)),
+                condition: None,
+            })
+            .collect();
+
+        Template {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            version: self.version,
+            sections,
+        }
+    }
+
     /// Generates a clean markdown template structure
     pub fn to_markdown_structure(&self) -> String {
         let mut markdown = String::from("# <Add Title here>\n\n");
@@ -120,12 +200,14 @@ mod tests {
         let template = Template {
             name: "Test Template".to_string(),
             description: "A test template".to_string(),
+            version: 1,
             sections: vec![TemplateSection {
                 title: "Summary".to_string(),
                 instruction: "Provide a summary".to_string(),
                 format: "paragraph".to_string(),
                 item_format: None,
                 example_item_format: None,
+                condition: None,
             }],
         };
 
@@ -137,6 +219,7 @@ mod tests {
         let template = Template {
             name: "".to_string(),
             description: "A test template".to_string(),
+            version: 1,
             sections: vec![],
         };
 
@@ -148,15 +231,87 @@ mod tests {
         let template = Template {
             name: "Test".to_string(),
             description: "Test".to_string(),
+            version: 1,
             sections: vec![TemplateSection {
                 title: "Test".to_string(),
                 instruction: "Test".to_string(),
                 format: "invalid".to_string(),
                 item_format: None,
                 example_item_format: None,
+                condition: None,
             }],
         };
 
         assert!(template.validate().is_err());
     }
+
+    fn section(title: &str, instruction: &str, condition: Option<&str>) -> TemplateSection {
+        TemplateSection {
+            title: title.to_string(),
+            instruction: instruction.to_string(),
+            format: "list".to_string(),
+            item_format: None,
+            example_item_format: None,
+            condition: condition.map(|c| c.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_undefined_variables() {
+        let template = Template {
+            name: "Lesson".to_string(),
+            description: "Lesson notes".to_string(),
+            version: 1,
+            sections: vec![section("Vocabulary", "New {{target_language}} words for {{studnet}}", None)],
+        };
+
+        let err = template.validate().unwrap_err();
+        assert!(err.contains("studnet"));
+        assert!(!err.contains("target_language"));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_condition() {
+        let template = Template {
+            name: "Lesson".to_string(),
+            description: "Lesson notes".to_string(),
+            version: 1,
+            sections: vec![section("Pronunciation Notes", "Mispronounced words", Some("has_video"))],
+        };
+
+        assert!(template.validate().is_err());
+    }
+
+    #[test]
+    fn test_resolve_filters_and_renders_sections() {
+        let template = Template {
+            name: "Lesson".to_string(),
+            description: "Lesson notes".to_string(),
+            version: 1,
+            sections: vec![
+                section("Vocabulary", "New {{target_language}} words", None),
+                section("Pronunciation Notes", "Mispronounced words", Some("has_mic_speech")),
+            ],
+        };
+
+        let mut context = TemplateContext::default();
+        context.set_variable("target_language", Some("German"));
+
+        let resolved = template.resolve(&context);
+        assert_eq!(resolved.sections.len(), 1);
+        assert_eq!(resolved.sections[0].instruction, "New German words");
+
+        context.set_condition("has_mic_speech", true);
+        assert_eq!(template.resolve(&context).sections.len(), 2);
+    }
+
+    #[test]
+    fn test_version_defaults_to_one() {
+        let template: Template = serde_json::from_str(
+            r#"{"name": "Test", "description": "Test", "sections": []}"#,
+        )
+        .unwrap();
+
+        assert_eq!(template.version, 1);
+    }
 }
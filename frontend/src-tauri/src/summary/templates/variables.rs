@@ -0,0 +1,173 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Matches `{{variable_name}}` placeholders, with optional whitespace inside the braces
+static VARIABLE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+/// Variables templates may reference, with the text used when no value is known
+///
+/// Profile variables come from the learner profile, session variables from the
+/// session being summarized.
+pub const TEMPLATE_VARIABLES: &[(&str, &str)] = &[
+    ("target_language", "the target language"),
+    ("native_language", "the learner's native language"),
+    ("learner_level", "unspecified"),
+    ("tutor_name", "the tutor"),
+    ("session_title", "this session"),
+    ("session_date", "the session date"),
+];
+
+/// Facts about a session that sections can be conditional on
+pub const TEMPLATE_CONDITIONS: &[&str] = &[
+    // The learner's microphone produced transcript segments
+    "has_mic_speech",
+    // The other side of the call (system audio) produced transcript segments
+    "has_system_speech",
+    // Notes of earlier sessions are passed as context
+    "has_previous_sessions",
+    // Open homework from earlier sessions is passed as context
+    "has_open_homework",
+];
+
+/// Values used to resolve a template for one session
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    /// Variable values by name; missing or empty values fall back to the defaults
+    /// in `TEMPLATE_VARIABLES`
+    pub variables: HashMap<String, String>,
+
+    /// Conditions from `TEMPLATE_CONDITIONS` that hold for this session
+    pub conditions: HashSet<String>,
+}
+
+impl TemplateContext {
+    /// Sets a variable, ignoring empty values
+    pub fn set_variable(&mut self, name: &str, value: Option<&str>) {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            self.variables.insert(name.to_string(), value.to_string());
+        }
+    }
+
+    /// Marks a condition as holding (or not)
+    pub fn set_condition(&mut self, name: &str, holds: bool) {
+        if holds {
+            self.conditions.insert(name.to_string());
+        } else {
+            self.conditions.remove(name);
+        }
+    }
+
+    /// Evaluates a section condition
+    ///
+    /// A condition is one of `TEMPLATE_CONDITIONS` or a variable name (true when the
+    /// variable has a value), optionally negated with a leading `!`.
+    pub fn evaluate(&self, condition: &str) -> bool {
+        let condition = condition.trim();
+        match condition.strip_prefix('!') {
+            Some(inner) => !self.evaluate(inner),
+            None => {
+                self.conditions.contains(condition) || self.variables.contains_key(condition)
+            }
+        }
+    }
+
+    /// Replaces `{{variable}}` placeholders in `text`
+    ///
+    /// Unknown variables are left untouched; `Template::validate` rejects them beforehand.
+    pub fn render(&self, text: &str) -> String {
+        VARIABLE_REGEX
+            .replace_all(text, |caps: &regex::Captures| {
+                let name = &caps[1];
+                match self.variables.get(name) {
+                    Some(value) => value.clone(),
+                    None => default_value(name)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| caps[0].to_string()),
+                }
+            })
+            .into_owned()
+    }
+}
+
+fn default_value(name: &str) -> Option<&'static str> {
+    TEMPLATE_VARIABLES
+        .iter()
+        .find(|(variable, _)| *variable == name)
+        .map(|(_, default)| *default)
+}
+
+/// Returns the names of all variables referenced in `text`, in order of appearance
+pub fn referenced_variables(text: &str) -> Vec<String> {
+    VARIABLE_REGEX
+        .captures_iter(text)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Returns the referenced variables that are not defined in `TEMPLATE_VARIABLES`
+pub fn undefined_variables(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    referenced_variables(text)
+        .into_iter()
+        .filter(|name| default_value(name).is_none() && seen.insert(name.clone()))
+        .collect()
+}
+
+/// Checks that a section condition only refers to known conditions or variables
+pub fn validate_condition(condition: &str) -> Result<(), String> {
+    let name = condition.trim().trim_start_matches('!').trim();
+    if TEMPLATE_CONDITIONS.contains(&name) || default_value(name).is_some() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown condition '{}'. Use one of: {}, or a variable name",
+            condition,
+            TEMPLATE_CONDITIONS.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_uses_values_and_defaults() {
+        let mut context = TemplateContext::default();
+        context.set_variable("target_language", Some("Spanish"));
+        context.set_variable("tutor_name", Some("  "));
+
+        assert_eq!(
+            context.render("Vocabulary in {{target_language}} chosen by {{ tutor_name }}"),
+            "Vocabulary in Spanish chosen by the tutor"
+        );
+        assert_eq!(context.render("Keep {{unknown}}"), "Keep {{unknown}}");
+    }
+
+    #[test]
+    fn test_undefined_variables() {
+        assert_eq!(
+            undefined_variables("{{native_lang}} {{target_language}} {{native_lang}}"),
+            vec!["native_lang".to_string()]
+        );
+        assert!(undefined_variables("No variables here").is_empty());
+    }
+
+    #[test]
+    fn test_conditions() {
+        let mut context = TemplateContext::default();
+        context.set_condition("has_mic_speech", true);
+        context.set_variable("learner_level", Some("B1"));
+
+        assert!(context.evaluate("has_mic_speech"));
+        assert!(!context.evaluate("!has_mic_speech"));
+        assert!(context.evaluate("learner_level"));
+        assert!(!context.evaluate("target_language"));
+
+        assert!(validate_condition("!has_system_speech").is_ok());
+        assert!(validate_condition("target_language").is_ok());
+        assert!(validate_condition("has_video").is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::summary::llm_client::LLMProvider;
+use crate::summary::summary_engine;
+use std::path::PathBuf;
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton, CoreBPE};
+use tracing::{info, warn};
+
+/// Counts tokens for sizing chunks and prompts
+///
+/// Uses the real vocabulary where it is available to us and a per-script
+/// estimate otherwise.
+pub enum Tokenizer {
+    /// Byte-pair encoding tables of OpenAI models
+    Bpe(&'static CoreBPE),
+    /// Per-script estimate scaled by this factor, so it matches the count of
+    /// a built-in model's own vocabulary for the transcript
+    Calibrated(f64),
+    /// Per-script estimate
+    Heuristic,
+}
+
+impl Tokenizer {
+    /// Picks the tokenizer for a model
+    ///
+    /// For built-in models, `sample` (usually the transcript) is tokenized by
+    /// llama-helper with the model's vocabulary and the estimate is calibrated
+    /// to that count. If that fails, the plain estimate is used.
+    pub async fn for_model(
+        provider: &LLMProvider,
+        model_name: &str,
+        app_data_dir: Option<&PathBuf>,
+        sample: &str,
+    ) -> Self {
+        match provider {
+            LLMProvider::OpenAI => openai_bpe(model_name),
+            // OpenRouter model ids are prefixed with the vendor ("openai/gpt-4o")
+            LLMProvider::OpenRouter => match model_name.strip_prefix("openai/") {
+                Some(model) => openai_bpe(model),
+                None => Tokenizer::Heuristic,
+            },
+            LLMProvider::BuiltInAI => match app_data_dir {
+                Some(app_data_dir) => calibrate_builtin(app_data_dir, model_name, sample).await,
+                None => Tokenizer::Heuristic,
+            },
+            _ => Tokenizer::Heuristic,
+        }
+    }
+
+    /// Number of tokens in `text`
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Tokenizer::Bpe(bpe) => bpe.encode_with_special_tokens(text).len(),
+            Tokenizer::Calibrated(factor) => (estimate_tokens(text) * factor).ceil() as usize,
+            Tokenizer::Heuristic => heuristic_token_count(text),
+        }
+    }
+}
+
+/// BPE tables for an OpenAI model: `cl100k_base` for GPT-3.5 and GPT-4,
+/// `o200k_base` for GPT-4o and everything newer
+fn openai_bpe(model_name: &str) -> Tokenizer {
+    let cl100k = model_name == "gpt-4"
+        || model_name.starts_with("gpt-4-")
+        || model_name.starts_with("gpt-3.5")
+        || model_name.starts_with("gpt-35");
+    if cl100k {
+        Tokenizer::Bpe(cl100k_base_singleton())
+    } else {
+        Tokenizer::Bpe(o200k_base_singleton())
+    }
+}
+
+async fn calibrate_builtin(app_data_dir: &PathBuf, model_name: &str, sample: &str) -> Tokenizer {
+    let estimate = estimate_tokens(sample);
+    if estimate < 1.0 {
+        return Tokenizer::Heuristic;
+    }
+    match summary_engine::count_tokens_with_builtin(app_data_dir, model_name, sample).await {
+        Ok(count) => {
+            let factor = count as f64 / estimate;
+            info!(
+                "{} tokenizes the transcript to {} tokens (estimate {:.0}, factor {:.2})",
+                model_name, count, estimate, factor
+            );
+            Tokenizer::Calibrated(factor)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to tokenize with {}: {}. Using the per-script estimate",
+                model_name, e
+            );
+            Tokenizer::Heuristic
+        }
+    }
+}
+
+/// Estimated token count, for when the model's tokenizer isn't available
+pub fn heuristic_token_count(text: &str) -> usize {
+    estimate_tokens(text).ceil() as usize
+}
+
+fn estimate_tokens(text: &str) -> f64 {
+    text.chars().map(tokens_per_char).sum()
+}
+
+/// Tokens per character by script
+///
+/// Calibrated on lesson dialogues against `cl100k_base` and `o200k_base`: the
+/// estimate is within about 25% of `cl100k_base` for every script and higher
+/// than the count of tokenizers with larger multilingual vocabularies, so
+/// chunks err on the side of fitting. Whitespace mostly merges into the
+/// following word, while digits and punctuation are often tokens of their own.
+fn tokens_per_char(c: char) -> f64 {
+    match c as u32 {
+        // Whitespace
+        0x09..=0x0D | 0x20 => 0.0,
+        // ASCII digits
+        0x30..=0x39 => 0.6,
+        // ASCII letters
+        0x41..=0x5A | 0x61..=0x7A => 0.27,
+        // ASCII punctuation and control characters
+        0x00..=0x7F => 0.8,
+        // Latin with diacritics
+        0x00C0..=0x024F | 0x1E00..=0x1EFF => 0.6,
+        // Greek
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => 0.9,
+        // Cyrillic
+        0x0400..=0x052F => 0.5,
+        // Hebrew
+        0x0590..=0x05FF => 0.95,
+        // Arabic
+        0x0600..=0x06FF | 0x0750..=0x077F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => 0.8,
+        // Indic scripts
+        0x0900..=0x0DFF => 0.95,
+        // Thai, Lao, Khmer
+        0x0E00..=0x0EFF | 0x1780..=0x17FF => 0.85,
+        // Hangul
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => 1.0,
+        // Kana
+        0x3040..=0x30FF => 0.9,
+        // Han
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF => 1.2,
+        // CJK and fullwidth punctuation
+        0x3000..=0x303F | 0xFF00..=0xFFEF => 0.8,
+        // Emoji, symbols and other scripts
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: &[&str] = &[
+        "Teacher: Today we're going to review the past tense. Student: Yesterday I went to the market.",
+        "Profesora: Hoy vamos a repasar el pretérito indefinido. Alumno: Ayer fui al mercado y compré verduras.",
+        "Учитель: Сегодня мы повторим прошедшее время. Ученик: Вчера я ходил на рынок и купил овощи.",
+        "المعلم: اليوم سنراجع الزمن الماضي. الطالب: أمس ذهبت إلى السوق واشتريت بعض الخضروات.",
+        "老师：今天我们复习过去时。学生：昨天我去了市场，买了一些蔬菜，然后和姐姐一起做了晚饭。",
+        "ครู: วันนี้เราจะทบทวนอดีตกาล นักเรียน: เมื่อวานผมไปตลาดและซื้อผัก",
+        "[00:12] Teacher: Let's start. [00:15] Student: OK, 25 minutes, page 104, exercise 3b.",
+    ];
+
+    #[test]
+    fn test_heuristic_tracks_bpe_across_scripts() {
+        let cl100k = Tokenizer::Bpe(cl100k_base_singleton());
+        let o200k = Tokenizer::Bpe(o200k_base_singleton());
+        for sample in SAMPLES {
+            let estimate = heuristic_token_count(sample) as f64;
+            let cl100k_ratio = estimate / cl100k.count(sample) as f64;
+            assert!(
+                (0.7..=1.3).contains(&cl100k_ratio),
+                "estimate {} is off by {:.2} for {:?}",
+                estimate,
+                cl100k_ratio,
+                sample
+            );
+            assert!(estimate >= 0.9 * o200k.count(sample) as f64, "{:?}", sample);
+        }
+    }
+
+    #[test]
+    fn test_heuristic_separates_scripts() {
+        // The same number of characters costs far more in Chinese than in English
+        let english = heuristic_token_count("abcdefghij");
+        let chinese = heuristic_token_count("今天我们复习过去时好的");
+        assert!(chinese > 3 * english);
+        assert_eq!(heuristic_token_count(""), 0);
+    }
+
+    #[test]
+    fn test_openai_model_tables() {
+        let uses = |model: &str, table: &'static CoreBPE| {
+            matches!(openai_bpe(model), Tokenizer::Bpe(bpe) if std::ptr::eq(bpe, table))
+        };
+        assert!(uses("gpt-4", cl100k_base_singleton()));
+        assert!(uses("gpt-3.5-turbo", cl100k_base_singleton()));
+        assert!(uses("gpt-4o-mini", o200k_base_singleton()));
+        assert!(uses("gpt-4.1", o200k_base_singleton()));
+        assert!(uses("gpt-5", o200k_base_singleton()));
+    }
+
+    #[test]
+    fn test_calibrated_scales_estimate() {
+        let text = "Ayer fui al mercado y compré verduras.";
+        let calibrated = Tokenizer::Calibrated(1.5);
+        assert_eq!(
+            calibrated.count(text),
+            (estimate_tokens(text) * 1.5).ceil() as usize
+        );
+    }
+}
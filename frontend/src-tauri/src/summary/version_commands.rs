@@ -0,0 +1,93 @@
+use crate::database::models::SummaryVersion;
+use crate::database::repositories::summary_version::SummaryVersionsRepository;
+use crate::state::AppState;
+use crate::summary::versions::{self, SectionDiff};
+use log::{error as log_error, info as log_info};
+use tauri::{AppHandle, Runtime};
+
+/// Lists the recorded versions of a session's summary, newest first
+#[tauri::command]
+pub async fn api_list_summary_versions<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+) -> Result<Vec<SummaryVersion>, String> {
+    log_info!("api_list_summary_versions called for meeting_id: {}", meeting_id);
+
+    SummaryVersionsRepository::list_versions(state.db_manager.pool(), &meeting_id)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to list summary versions for {}: {}", meeting_id, e);
+            format!("Failed to list summary versions: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn api_get_summary_version<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    version_number: i64,
+) -> Result<SummaryVersion, String> {
+    SummaryVersionsRepository::get_version(state.db_manager.pool(), &meeting_id, version_number)
+        .await
+        .map_err(|e| format!("Failed to load summary version: {}", e))?
+        .ok_or_else(|| format!("Summary version {} not found", version_number))
+}
+
+/// Compares two versions of a session's summary section by section
+///
+/// # Arguments
+/// * `from_version` - Older version
+/// * `to_version` - Newer version
+#[tauri::command]
+pub async fn api_diff_summary_versions<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    from_version: i64,
+    to_version: i64,
+) -> Result<Vec<SectionDiff>, String> {
+    log_info!(
+        "api_diff_summary_versions called for meeting_id: {} ({} -> {})",
+        meeting_id,
+        from_version,
+        to_version
+    );
+
+    let pool = state.db_manager.pool();
+    let mut markdowns = Vec::with_capacity(2);
+    for version_number in [from_version, to_version] {
+        let version = SummaryVersionsRepository::get_version(pool, &meeting_id, version_number)
+            .await
+            .map_err(|e| format!("Failed to load summary version: {}", e))?
+            .ok_or_else(|| format!("Summary version {} not found", version_number))?;
+        markdowns.push(versions::version_markdown(&version));
+    }
+
+    Ok(versions::diff_sections(&markdowns[0], &markdowns[1]))
+}
+
+/// Makes an earlier version the current summary again
+///
+/// Returns the new version recording the restore.
+#[tauri::command]
+pub async fn api_restore_summary_version<R: Runtime>(
+    _app: AppHandle<R>,
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    version_number: i64,
+) -> Result<SummaryVersion, String> {
+    log_info!(
+        "api_restore_summary_version called for meeting_id: {}, version: {}",
+        meeting_id,
+        version_number
+    );
+
+    versions::restore_version(state.db_manager.pool(), &meeting_id, version_number)
+        .await
+        .map_err(|e| {
+            log_error!("Failed to restore summary version for {}: {}", meeting_id, e);
+            e
+        })
+}
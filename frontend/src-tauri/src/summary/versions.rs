@@ -0,0 +1,300 @@
+use crate::database::models::SummaryVersion;
+use crate::database::repositories::{
+    citation::CitationsRepository,
+    summary::SummaryProcessesRepository,
+    summary_version::{NewSummaryVersion, SummaryVersionsRepository},
+    transcript::TranscriptsRepository,
+};
+use crate::summary::citations::{self, TimedSegment};
+use crate::summary::session_context::markdown_from_result;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+/// Number of summary versions kept per session; older ones are deleted
+pub const MAX_VERSIONS_PER_SESSION: usize = 20;
+
+/// How a section differs between two summary versions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionChange {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+/// Difference of one section between two summary versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionDiff {
+    /// Section title (empty for text before the first section)
+    pub title: String,
+    pub change: SectionChange,
+    /// Lines only present in the newer version
+    pub added_lines: Vec<String>,
+    /// Lines only present in the older version
+    pub removed_lines: Vec<String>,
+}
+
+/// Splits notes into (section title, non-empty lines) pairs, in order
+fn split_sections(markdown: &str) -> Vec<(String, Vec<String>)> {
+    let mut sections: Vec<(String, Vec<String>)> = vec![(String::new(), Vec::new())];
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        let heading = if trimmed.starts_with('#') {
+            Some(trimmed.trim_start_matches('#').trim())
+        } else if trimmed.len() > 4 && trimmed.starts_with("**") && trimmed.ends_with("**") {
+            Some(trimmed.trim_matches('*').trim())
+        } else {
+            None
+        };
+
+        match heading {
+            Some(title) => sections.push((title.to_string(), Vec::new())),
+            None if !trimmed.is_empty() => {
+                if let Some((_, lines)) = sections.last_mut() {
+                    lines.push(trimmed.to_string());
+                }
+            }
+            None => {}
+        }
+    }
+
+    sections.retain(|(title, lines)| !title.is_empty() || !lines.is_empty());
+    sections
+}
+
+/// Lines of `a` that are not in `b`, counting duplicates
+fn lines_not_in(a: &[String], b: &[String]) -> Vec<String> {
+    let mut remaining: Vec<&String> = b.iter().collect();
+    a.iter()
+        .filter(|line| match remaining.iter().position(|other| other == line) {
+            Some(pos) => {
+                remaining.remove(pos);
+                false
+            }
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Compares two versions of notes section by section
+///
+/// Sections are listed in the order of the newer version, followed by sections
+/// that only exist in the older one.
+pub fn diff_sections(old_markdown: &str, new_markdown: &str) -> Vec<SectionDiff> {
+    let old_sections = split_sections(old_markdown);
+    let new_sections = split_sections(new_markdown);
+    let mut diffs = Vec::new();
+
+    for (title, new_lines) in &new_sections {
+        let diff = match old_sections.iter().find(|(old_title, _)| old_title == title) {
+            Some((_, old_lines)) => {
+                let added_lines = lines_not_in(new_lines, old_lines);
+                let removed_lines = lines_not_in(old_lines, new_lines);
+                let change = if added_lines.is_empty() && removed_lines.is_empty() {
+                    SectionChange::Unchanged
+                } else {
+                    SectionChange::Changed
+                };
+                SectionDiff {
+                    title: title.clone(),
+                    change,
+                    added_lines,
+                    removed_lines,
+                }
+            }
+            None => SectionDiff {
+                title: title.clone(),
+                change: SectionChange::Added,
+                added_lines: new_lines.clone(),
+                removed_lines: Vec::new(),
+            },
+        };
+        diffs.push(diff);
+    }
+
+    for (title, old_lines) in &old_sections {
+        if !new_sections.iter().any(|(new_title, _)| new_title == title) {
+            diffs.push(SectionDiff {
+                title: title.clone(),
+                change: SectionChange::Removed,
+                added_lines: Vec::new(),
+                removed_lines: old_lines.clone(),
+            });
+        }
+    }
+
+    diffs
+}
+
+/// Markdown of a stored version (empty if the result has none)
+pub fn version_markdown(version: &SummaryVersion) -> String {
+    markdown_from_result(&version.result).unwrap_or_default()
+}
+
+/// Makes an earlier version the current summary of a session
+///
+/// - If the current summary was edited since the latest version, the edits are
+///   recorded as an `edited` version first, so restoring never loses them
+/// - The restored content is recorded as a new `restored` version
+/// - Transcript citations are re-linked for the restored notes
+///
+/// # Returns
+/// The new `restored` version
+pub async fn restore_version(
+    pool: &SqlitePool,
+    meeting_id: &str,
+    version_number: i64,
+) -> Result<SummaryVersion, String> {
+    let version = SummaryVersionsRepository::get_version(pool, meeting_id, version_number)
+        .await
+        .map_err(|e| format!("Failed to load summary version: {}", e))?
+        .ok_or_else(|| format!("Summary version {} not found", version_number))?;
+
+    let current = SummaryProcessesRepository::get_summary_data(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load current summary: {}", e))?;
+
+    if let Some(current) = &current {
+        let status = current.status.to_lowercase();
+        if status == "pending" || status == "processing" {
+            return Err("Cannot restore a version while a summary is being generated".to_string());
+        }
+    }
+
+    // Keep unversioned edits of the current summary
+    snapshot_current_result(pool, meeting_id).await?;
+
+    let updated = SummaryProcessesRepository::set_current_result(
+        pool,
+        meeting_id,
+        &version.result,
+        version.metadata.as_deref(),
+    )
+    .await
+    .map_err(|e| format!("Failed to restore summary: {}", e))?;
+    if !updated {
+        return Err(format!("No summary found for session {}", meeting_id));
+    }
+
+    let restored = SummaryVersionsRepository::add_version(
+        pool,
+        meeting_id,
+        NewSummaryVersion {
+            source: "restored",
+            restored_from: Some(version.version_number),
+            provider: version.provider.as_deref(),
+            model: version.model.as_deref(),
+            template_id: version.template_id.as_deref(),
+            template_version: version.template_version,
+            prompt_hash: version.prompt_hash.as_deref(),
+            result: &version.result,
+            metadata: version.metadata.as_deref(),
+            started_at: version.started_at,
+        },
+        MAX_VERSIONS_PER_SESSION,
+    )
+    .await
+    .map_err(|e| format!("Failed to record restored version: {}", e))?;
+
+    relink_citations(pool, meeting_id, &version_markdown(&version)).await;
+
+    info!(
+        "Restored summary version {} for meeting_id: {} (now version {})",
+        version.version_number, meeting_id, restored.version_number
+    );
+    Ok(restored)
+}
+
+/// Records the current summary as an `edited` version if it differs from the latest version
+///
+/// Called before the current summary is replaced, so user edits made since the
+/// last generation are never lost.
+///
+/// # Returns
+/// The new version, or `None` if there is no summary or it was already recorded
+pub async fn snapshot_current_result(
+    pool: &SqlitePool,
+    meeting_id: &str,
+) -> Result<Option<SummaryVersion>, String> {
+    let current = SummaryProcessesRepository::get_summary_data(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load current summary: {}", e))?;
+    let Some(current) = current else {
+        return Ok(None);
+    };
+    let Some(current_result) = &current.result else {
+        return Ok(None);
+    };
+
+    let latest = SummaryVersionsRepository::get_latest_version(pool, meeting_id)
+        .await
+        .map_err(|e| format!("Failed to load latest summary version: {}", e))?;
+    if latest.is_some_and(|latest| &latest.result == current_result) {
+        return Ok(None);
+    }
+
+    SummaryVersionsRepository::add_version(
+        pool,
+        meeting_id,
+        NewSummaryVersion {
+            source: "edited",
+            result: current_result,
+            metadata: current.metadata.as_deref(),
+            ..Default::default()
+        },
+        MAX_VERSIONS_PER_SESSION,
+    )
+    .await
+    .map(Some)
+    .map_err(|e| format!("Failed to record edited summary: {}", e))
+}
+
+/// Rebuilds the stored citations for notes that became current
+async fn relink_citations(pool: &SqlitePool, meeting_id: &str, markdown: &str) {
+    let segments = match TranscriptsRepository::get_timed_transcripts(pool, meeting_id).await {
+        Ok(transcripts) => TimedSegment::from_transcripts(transcripts),
+        Err(e) => {
+            warn!("Failed to load transcript timestamps for {}: {}", meeting_id, e);
+            return;
+        }
+    };
+
+    let (_, report) = citations::validate_citations(markdown, &segments);
+    if let Err(e) = CitationsRepository::replace_citations(pool, meeting_id, &report.citations).await {
+        warn!("Failed to re-link citations for {}: {}", meeting_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_sections() {
+        let old = "**Vocabulary**\n\n- el mercado\n- la tienda\n\n**Grammar Points**\n\n- Past tense\n\n**Notes**\n\n- Short lesson";
+        let new = "**Vocabulary**\n\n- el mercado\n- la manzana\n\n**Grammar Points**\n\n- Past tense\n\n**Homework**\n\n- Exercise 3";
+
+        let diffs = diff_sections(old, new);
+        let titles: Vec<&str> = diffs.iter().map(|d| d.title.as_str()).collect();
+        assert_eq!(titles, vec!["Vocabulary", "Grammar Points", "Homework", "Notes"]);
+
+        assert_eq!(diffs[0].change, SectionChange::Changed);
+        assert_eq!(diffs[0].added_lines, vec!["- la manzana".to_string()]);
+        assert_eq!(diffs[0].removed_lines, vec!["- la tienda".to_string()]);
+        assert_eq!(diffs[1].change, SectionChange::Unchanged);
+        assert_eq!(diffs[2].change, SectionChange::Added);
+        assert_eq!(diffs[3].change, SectionChange::Removed);
+        assert_eq!(diffs[3].removed_lines, vec!["- Short lesson".to_string()]);
+    }
+
+    #[test]
+    fn test_diff_counts_duplicate_lines() {
+        let diffs = diff_sections("**Notes**\n- a\n- a", "**Notes**\n- a");
+        assert_eq!(diffs[0].removed_lines, vec!["- a".to_string()]);
+        assert!(diffs[0].added_lines.is_empty());
+    }
+}
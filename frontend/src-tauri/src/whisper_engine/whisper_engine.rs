@@ -10,6 +10,7 @@ use anyhow::{Result, anyhow};
 use reqwest::Client;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use crate::network::{self, Channel};
 use crate::{perf_debug, perf_trace};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -955,7 +956,12 @@ impl WhisperEngine {
         };
         
         log::info!("Model URL for {}: {}", model_name, model_url);
-        
+
+        if let Err(e) = network::check(model_url, Channel::ModelDownload) {
+            self.active_downloads.write().await.remove(model_name);
+            return Err(e.into());
+        }
+
         // Generate correct filename - all models follow ggml-{model_name}.bin pattern
         let filename = format!("ggml-{}.bin", model_name);
         let file_path = self.models_dir.join(&filename);
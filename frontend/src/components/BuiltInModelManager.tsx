@@ -6,7 +6,7 @@ import { listen } from '@tauri-apps/api/event';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { cn } from '@/lib/utils';
-import { Download, RefreshCw, BadgeAlert, Trash2 } from 'lucide-react';
+import { Download, RefreshCw, BadgeAlert, Trash2, Plus } from 'lucide-react';
 import { toast } from 'sonner';
 
 interface ModelInfo {
@@ -20,6 +20,8 @@ interface ModelInfo {
   context_size: number;
   description: string;
   gguf_file: string;
+  template: string;
+  custom: boolean;
 }
 
 interface DownloadProgressInfo {
@@ -40,6 +42,8 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
   const [downloadProgress, setDownloadProgress] = useState<Record<string, number>>({});
   const [downloadProgressInfo, setDownloadProgressInfo] = useState<Record<string, DownloadProgressInfo>>({});
   const [downloadingModels, setDownloadingModels] = useState<Set<string>>(new Set());
+  const [localModelPath, setLocalModelPath] = useState<string>('');
+  const [isAddingLocalModel, setIsAddingLocalModel] = useState<boolean>(false);
 
   const fetchModels = async () => {
     try {
@@ -248,6 +252,24 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
     }
   };
 
+  const addLocalModel = async () => {
+    const path = localModelPath.trim();
+    if (!path) return;
+
+    try {
+      setIsAddingLocalModel(true);
+      const model = (await invoke('builtin_ai_add_local_model', { path })) as ModelInfo;
+      toast.success(`Added ${model.display_name} (${model.template} format)`);
+      setLocalModelPath('');
+      fetchModels();
+    } catch (error) {
+      console.error('Failed to add local model:', error);
+      toast.error(String(error));
+    } finally {
+      setIsAddingLocalModel(false);
+    }
+  };
+
   // Don't show loading spinner if we have downloads in progress - show the model list instead
   if (isLoading && downloadingModels.size === 0) {
     return (
@@ -275,6 +297,28 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
         <h4 className="text-sm font-bold">Built-in AI Models</h4>
       </div>
 
+      <div className="flex items-center gap-2 mb-4">
+        <input
+          type="text"
+          value={localModelPath}
+          onChange={(e) => setLocalModelPath(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') addLocalModel();
+          }}
+          placeholder="Path to a local .gguf model"
+          className="flex-1 px-3 py-1.5 text-sm border border-gray-200 rounded-md"
+        />
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!localModelPath.trim() || isAddingLocalModel}
+          onClick={addLocalModel}
+        >
+          <Plus className="mr-2 h-4 w-4" />
+          Add
+        </Button>
+      </div>
+
       <div className="grid gap-4">
         {models.map((model) => {
           const progress = downloadProgress[model.name];
@@ -352,7 +396,7 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
                       </p>
                     )}
                     <div className="text-xs text-gray-500">
-                      <span>{model.size_mb}MB • {model.context_size} tokens</span>
+                      <span>{model.size_mb}MB • {model.context_size} tokens{model.custom && ` • ${model.template} format`}</span>
                     </div>
                   </div>
                 </div>
@@ -389,8 +433,8 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
                     </Button>
                   )}
 
-                  {/* Error - Show Retry button */}
-                  {isError && !modelIsDownloading && (
+                  {/* Error - Show Retry button (local models can only be removed) */}
+                  {isError && !modelIsDownloading && !model.custom && (
                     <Button
                       variant="outline"
                       size="sm"
@@ -434,14 +478,14 @@ export function BuiltInModelManager({ selectedModel, onModelSelect }: BuiltInMod
                   )}
 
                   {/* Available - Show small trash icon (only if not currently selected) */}
-                  {isAvailable && !modelIsDownloading && selectedModel !== model.name && (
+                  {((isAvailable && selectedModel !== model.name) || (isError && model.custom)) && !modelIsDownloading && (
                     <button
                       className="p-2 rounded hover:bg-gray-100 transition-colors text-gray-500 hover:text-red-600"
                       onClick={(e) => {
                         e.stopPropagation();
                         deleteModel(model.name);
                       }}
-                      title="Delete model"
+                      title={model.custom ? 'Remove local model (the file is kept)' : 'Delete model'}
                     >
                       <Trash2 className="h-4 w-4" />
                     </button>
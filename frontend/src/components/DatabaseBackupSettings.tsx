@@ -0,0 +1,262 @@
+"use client"
+
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { relaunch } from "@tauri-apps/plugin-process"
+import { toast } from "sonner"
+import { Input } from "./ui/input"
+import { Button } from "./ui/button"
+import { Switch } from "./ui/switch"
+
+interface BackupInfo {
+  fileName: string
+  kind: "scheduled" | "manual" | "preRestore"
+  createdAt: string
+  sizeBytes: number
+}
+
+interface MaintenanceSettings {
+  backupsEnabled: boolean
+  backupIntervalHours: number
+  backupKeepCount: number
+  vacuumEnabled: boolean
+  lastVacuumAt: string | null
+}
+
+export interface IntegrityReport {
+  checkedAt: string
+  ok: boolean
+  problems: string[]
+}
+
+const KIND_LABELS: Record<BackupInfo["kind"], string> = {
+  scheduled: "Scheduled",
+  manual: "Manual",
+  preRestore: "Before restore",
+}
+
+const formatSize = (bytes: number) =>
+  bytes < 1024 * 1024 ? `${Math.max(1, Math.round(bytes / 1024))} KB` : `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+
+export function DatabaseBackupSettings() {
+  const [settings, setSettings] = useState<MaintenanceSettings | null>(null)
+  const [backups, setBackups] = useState<BackupInfo[]>([])
+  const [report, setReport] = useState<IntegrityReport | null>(null)
+  const [busy, setBusy] = useState(false)
+
+  const loadBackups = () =>
+    invoke<BackupInfo[]>("api_list_database_backups")
+      .then(setBackups)
+      .catch((err) => console.error("Failed to list database backups:", err))
+
+  useEffect(() => {
+    invoke<MaintenanceSettings>("api_get_database_maintenance_settings")
+      .then(setSettings)
+      .catch((err) => console.error("Failed to get database maintenance settings:", err))
+    invoke<IntegrityReport | null>("api_get_database_integrity_report")
+      .then(setReport)
+      .catch((err) => console.error("Failed to get database integrity report:", err))
+    loadBackups()
+  }, [])
+
+  if (!settings) {
+    return null
+  }
+
+  const saveSettings = async (changes: Partial<MaintenanceSettings>) => {
+    const updated = { ...settings, ...changes }
+    setSettings(updated)
+    try {
+      await invoke("api_save_database_maintenance_settings", { settings: updated })
+    } catch (err) {
+      toast.error("Failed to save backup settings", { description: String(err) })
+    }
+  }
+
+  // Runs one action at a time, reporting failures
+  const run = async (action: () => Promise<void>, failure: string) => {
+    setBusy(true)
+    try {
+      await action()
+    } catch (err) {
+      toast.error(failure, { description: String(err) })
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const handleBackupNow = () =>
+    run(async () => {
+      await invoke("api_create_database_backup")
+      toast.success("Database backed up")
+      await loadBackups()
+    }, "Backup failed")
+
+  const handleRestore = (backup: BackupInfo) => {
+    if (
+      !confirm(
+        `Restore the database from ${new Date(backup.createdAt).toLocaleString()}? Changes made since then will be replaced. The current database is saved as a backup first.`
+      )
+    ) {
+      return
+    }
+    run(async () => {
+      await invoke("api_restore_database_backup", { fileName: backup.fileName })
+      await loadBackups()
+      toast.success("Backup ready to restore", {
+        description: "Restart Uchitil Live to finish.",
+        action: { label: "Restart now", onClick: () => relaunch() },
+        duration: 10000,
+      })
+    }, "Restore failed")
+  }
+
+  const handleDelete = (backup: BackupInfo) => {
+    if (!confirm("Delete this backup? This cannot be undone.")) {
+      return
+    }
+    run(async () => {
+      await invoke("api_delete_database_backup", { fileName: backup.fileName })
+      await loadBackups()
+    }, "Failed to delete backup")
+  }
+
+  const handleCheckIntegrity = () =>
+    run(async () => {
+      const result = await invoke<IntegrityReport>("api_check_database_integrity")
+      setReport(result)
+      if (result.ok) {
+        toast.success("No problems found in the database")
+      }
+    }, "Integrity check failed")
+
+  const handleRunMaintenance = () =>
+    run(async () => {
+      await invoke("api_run_database_maintenance")
+      const updated = await invoke<MaintenanceSettings>("api_get_database_maintenance_settings")
+      setSettings(updated)
+      toast.success("Database optimized")
+    }, "Maintenance failed")
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <div className="flex items-center justify-between">
+          <div>
+            <h3 className="text-lg font-semibold text-gray-900 mb-2">Database Backups</h3>
+            <p className="text-sm text-gray-600">
+              Back up your sessions and lessons automatically. Backups of an encrypted database stay encrypted.
+            </p>
+          </div>
+          <Switch
+            checked={settings.backupsEnabled}
+            onCheckedChange={(backupsEnabled) => saveSettings({ backupsEnabled })}
+          />
+        </div>
+
+        {settings.backupsEnabled && (
+          <div className="flex gap-4 mt-4">
+            <label className="text-sm text-gray-700">
+              Every
+              <Input
+                type="number"
+                min={1}
+                className="w-20 inline-block mx-2"
+                value={settings.backupIntervalHours}
+                onChange={(e) => saveSettings({ backupIntervalHours: Math.max(1, Number(e.target.value)) })}
+              />
+              hours
+            </label>
+            <label className="text-sm text-gray-700">
+              Keep
+              <Input
+                type="number"
+                min={1}
+                className="w-20 inline-block mx-2"
+                value={settings.backupKeepCount}
+                onChange={(e) => saveSettings({ backupKeepCount: Math.max(1, Number(e.target.value)) })}
+              />
+              backups
+            </label>
+          </div>
+        )}
+      </div>
+
+      <div>
+        <div className="flex items-center justify-between mb-2">
+          <h4 className="text-sm font-semibold text-gray-900">Saved backups</h4>
+          <Button variant="outline" size="sm" onClick={handleBackupNow} disabled={busy}>
+            Back up now
+          </Button>
+        </div>
+        {backups.length === 0 ? (
+          <p className="text-sm text-gray-500">No backups yet.</p>
+        ) : (
+          <ul className="divide-y divide-gray-100 border border-gray-200 rounded-md">
+            {backups.map((backup) => (
+              <li key={backup.fileName} className="flex items-center justify-between px-3 py-2 text-sm">
+                <div>
+                  <span className="text-gray-900">{new Date(backup.createdAt).toLocaleString()}</span>
+                  <span className="text-gray-500 ml-2">
+                    {KIND_LABELS[backup.kind]} · {formatSize(backup.sizeBytes)}
+                  </span>
+                </div>
+                <div className="flex gap-2">
+                  <Button variant="outline" size="sm" onClick={() => handleRestore(backup)} disabled={busy}>
+                    Restore
+                  </Button>
+                  <Button variant="ghost" size="sm" onClick={() => handleDelete(backup)} disabled={busy}>
+                    Delete
+                  </Button>
+                </div>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+
+      <div>
+        <h4 className="text-sm font-semibold text-gray-900 mb-2">Database health</h4>
+        {report && !report.ok ? (
+          <div className="text-sm text-red-600 mb-2">
+            <p>
+              The last check found problems in the database. Restore a recent backup to recover your
+              lessons.
+            </p>
+            <ul className="list-disc ml-5 mt-1 max-h-32 overflow-y-auto">
+              {report.problems.map((problem, i) => (
+                <li key={i}>{problem}</li>
+              ))}
+            </ul>
+          </div>
+        ) : report ? (
+          <p className="text-sm text-gray-600 mb-2">
+            No problems found (checked {new Date(report.checkedAt).toLocaleString()}).
+          </p>
+        ) : null}
+        <div className="flex items-center justify-between">
+          <div className="text-sm text-gray-600">
+            <span>Optimize the database weekly (VACUUM and ANALYZE)</span>
+            {settings.lastVacuumAt && (
+              <span className="block text-gray-500">
+                Last run {new Date(settings.lastVacuumAt).toLocaleString()}
+              </span>
+            )}
+          </div>
+          <Switch
+            checked={settings.vacuumEnabled}
+            onCheckedChange={(vacuumEnabled) => saveSettings({ vacuumEnabled })}
+          />
+        </div>
+        <div className="flex gap-2 mt-3">
+          <Button variant="outline" size="sm" onClick={handleCheckIntegrity} disabled={busy}>
+            Check integrity
+          </Button>
+          <Button variant="outline" size="sm" onClick={handleRunMaintenance} disabled={busy}>
+            Optimize now
+          </Button>
+        </div>
+      </div>
+    </div>
+  )
+}
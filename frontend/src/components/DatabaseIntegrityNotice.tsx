@@ -0,0 +1,41 @@
+"use client"
+
+import { useEffect } from "react"
+import { useRouter } from "next/navigation"
+import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
+import { toast } from "sonner"
+import type { IntegrityReport } from "./DatabaseBackupSettings"
+
+const TOAST_ID = "database-integrity"
+
+/**
+ * Warns when the integrity check run at startup finds problems in the database
+ */
+export function DatabaseIntegrityNotice() {
+  const router = useRouter()
+
+  useEffect(() => {
+    const showReport = (report: IntegrityReport | null) => {
+      if (!report || report.ok) return
+      toast.error("Your database may be damaged", {
+        id: TOAST_ID,
+        description: `The startup check found ${report.problems.length} problem(s). Restore a backup in Settings > Preferences.`,
+        action: { label: "Open settings", onClick: () => router.push("/settings") },
+        duration: Infinity,
+      })
+    }
+
+    // The check may have finished before this listener was registered
+    invoke<IntegrityReport | null>("api_get_database_integrity_report")
+      .then(showReport)
+      .catch((err) => console.error("Failed to get database integrity report:", err))
+
+    const unlisten = listen<IntegrityReport>("database-integrity-report", (event) => showReport(event.payload))
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [router])
+
+  return null
+}
@@ -0,0 +1,163 @@
+"use client"
+
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { relaunch } from "@tauri-apps/plugin-process"
+import { toast } from "sonner"
+import { Input } from "./ui/input"
+import { Button } from "./ui/button"
+import type { EncryptionStatus } from "./EncryptionUnlockGate"
+
+const MIN_PASSPHRASE_CHARS = 8
+
+type Action = "enable" | "change" | "disable"
+
+export function EncryptionSettings() {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null)
+  const [action, setAction] = useState<Action | null>(null)
+  const [passphrase, setPassphrase] = useState("")
+  const [newPassphrase, setNewPassphrase] = useState("")
+  const [confirmation, setConfirmation] = useState("")
+  const [isSaving, setIsSaving] = useState(false)
+
+  const loadStatus = () =>
+    invoke<EncryptionStatus>("api_get_encryption_status")
+      .then(setStatus)
+      .catch((err) => console.error("Failed to get encryption status:", err))
+
+  useEffect(() => {
+    loadStatus()
+  }, [])
+
+  const reset = () => {
+    setAction(null)
+    setPassphrase("")
+    setNewPassphrase("")
+    setConfirmation("")
+  }
+
+  const choosesPassphrase = action === "enable" || action === "change"
+  const chosenPassphrase = action === "enable" ? passphrase : newPassphrase
+  const validationError = !choosesPassphrase
+    ? null
+    : chosenPassphrase.length < MIN_PASSPHRASE_CHARS
+      ? `Use at least ${MIN_PASSPHRASE_CHARS} characters`
+      : chosenPassphrase !== confirmation
+        ? "Passphrases don't match"
+        : null
+
+  const promptRelaunch = (message: string) => {
+    toast.success(message, {
+      description: "Restart Uchitil Live to finish.",
+      action: { label: "Restart now", onClick: () => relaunch() },
+      duration: 10000,
+    })
+  }
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault()
+    if (validationError) return
+    setIsSaving(true)
+    try {
+      if (action === "enable") {
+        await invoke("api_enable_encryption", { passphrase })
+        promptRelaunch("Encryption turned on")
+      } else if (action === "change") {
+        await invoke("api_change_encryption_passphrase", {
+          currentPassphrase: passphrase,
+          newPassphrase,
+        })
+        toast.success("Passphrase changed")
+      } else if (action === "disable") {
+        await invoke("api_disable_encryption", { passphrase })
+        promptRelaunch("Encryption will be turned off")
+      }
+      reset()
+      await loadStatus()
+    } catch (err) {
+      toast.error("Failed to update encryption", { description: String(err) })
+    } finally {
+      setIsSaving(false)
+    }
+  }
+
+  const isOn = !!status?.enabled && !status.disablePending
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-gray-900 mb-2">Encryption</h3>
+      <p className="text-sm text-gray-600 mb-4">
+        Encrypt session audio, transcripts and the database with a passphrase. You enter it each
+        time Uchitil Live starts. A forgotten passphrase can't be recovered, and neither can the
+        sessions.
+      </p>
+
+      {status?.disablePending && (
+        <p className="text-sm text-gray-600 mb-4">
+          Encryption is turned off at the next restart, after you unlock one last time.
+        </p>
+      )}
+
+      {!action && (
+        <div className="flex gap-2">
+          {isOn ? (
+            <>
+              <Button variant="outline" onClick={() => setAction("change")}>
+                Change passphrase
+              </Button>
+              <Button variant="outline" onClick={() => setAction("disable")}>
+                Turn off
+              </Button>
+            </>
+          ) : (
+            <Button variant="outline" onClick={() => setAction("enable")} disabled={!status}>
+              Turn on encryption
+            </Button>
+          )}
+        </div>
+      )}
+
+      {action && (
+        <form onSubmit={handleSubmit} className="space-y-3 max-w-sm">
+          <Input
+            type="password"
+            autoFocus
+            placeholder={action === "enable" ? "New passphrase" : "Current passphrase"}
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            disabled={isSaving}
+          />
+          {action === "change" && (
+            <Input
+              type="password"
+              placeholder="New passphrase"
+              value={newPassphrase}
+              onChange={(e) => setNewPassphrase(e.target.value)}
+              disabled={isSaving}
+            />
+          )}
+          {choosesPassphrase && (
+            <Input
+              type="password"
+              placeholder="Confirm new passphrase"
+              value={confirmation}
+              onChange={(e) => setConfirmation(e.target.value)}
+              disabled={isSaving}
+            />
+          )}
+          {validationError && chosenPassphrase && (
+            <p className="text-sm text-red-600">{validationError}</p>
+          )}
+          <div className="flex gap-2">
+            <Button type="submit" disabled={isSaving || !passphrase || !!validationError}>
+              {action === "enable" ? "Turn on" : action === "change" ? "Change" : "Turn off"}
+            </Button>
+            <Button type="button" variant="ghost" onClick={reset} disabled={isSaving}>
+              Cancel
+            </Button>
+          </div>
+        </form>
+      )}
+    </div>
+  )
+}
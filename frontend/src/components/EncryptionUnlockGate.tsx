@@ -0,0 +1,87 @@
+'use client'
+
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { Lock } from 'lucide-react'
+import { Input } from './ui/input'
+import { Button } from './ui/button'
+
+export interface EncryptionStatus {
+  enabled: boolean
+  unlocked: boolean
+  disablePending: boolean
+}
+
+/**
+ * Asks for the passphrase at launch when encryption is on, and renders the
+ * app only once the database and session files are unlocked
+ */
+export function EncryptionUnlockGate({ children }: { children: React.ReactNode }) {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null)
+  const [passphrase, setPassphrase] = useState('')
+  const [error, setError] = useState<string | null>(null)
+  const [isUnlocking, setIsUnlocking] = useState(false)
+
+  useEffect(() => {
+    invoke<EncryptionStatus>('api_get_encryption_status')
+      .then(setStatus)
+      .catch((err) => {
+        console.error('[EncryptionUnlockGate] Failed to get encryption status:', err)
+        // Encryption settings that can't be read keep the app locked
+        setStatus({ enabled: true, unlocked: false, disablePending: false })
+      })
+  }, [])
+
+  const handleUnlock = async (e: React.FormEvent) => {
+    e.preventDefault()
+    setIsUnlocking(true)
+    setError(null)
+    try {
+      await invoke('api_unlock_encryption', { passphrase })
+      setPassphrase('')
+      setStatus(await invoke<EncryptionStatus>('api_get_encryption_status'))
+    } catch (err) {
+      setError(String(err))
+    } finally {
+      setIsUnlocking(false)
+    }
+  }
+
+  if (!status) {
+    return null
+  }
+  if (!status.enabled || status.unlocked) {
+    return <>{children}</>
+  }
+
+  return (
+    <div className="flex h-screen items-center justify-center bg-gray-50">
+      <form
+        onSubmit={handleUnlock}
+        className="w-full max-w-sm bg-white rounded-lg border border-gray-200 p-6 shadow-sm space-y-4"
+      >
+        <div className="flex items-center gap-2">
+          <Lock className="w-5 h-5 text-gray-700" />
+          <h2 className="text-lg font-semibold text-gray-900">Unlock Uchitil Live</h2>
+        </div>
+        <p className="text-sm text-gray-600">
+          {status.disablePending
+            ? 'Enter your passphrase one last time to decrypt your sessions and turn encryption off.'
+            : 'Your sessions are encrypted. Enter your passphrase to open them.'}
+        </p>
+        <Input
+          type="password"
+          autoFocus
+          placeholder="Passphrase"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          disabled={isUnlocking}
+        />
+        {error && <p className="text-sm text-red-600">{error}</p>}
+        <Button type="submit" className="w-full" disabled={isUnlocking || !passphrase}>
+          {isUnlocking ? 'Unlocking...' : 'Unlock'}
+        </Button>
+      </form>
+    </div>
+  )
+}
@@ -22,7 +22,7 @@ import { cn, isOllamaNotInstalledError } from '@/lib/utils';
 import { toast } from 'sonner';
 
 export interface ModelConfig {
-  provider: 'ollama' | 'groq' | 'claude' | 'openai' | 'openrouter' | 'builtin-ai' | 'custom-openai';
+  provider: 'ollama' | 'groq' | 'claude' | 'gemini' | 'openai' | 'openrouter' | 'builtin-ai' | 'custom-openai';
   model: string;
   whisperModel: string;
   apiKey?: string | null;
@@ -51,6 +51,16 @@ interface OpenRouterModel {
   completion_price?: string;
 }
 
+interface GeminiModel {
+  id: string;
+  name: string;
+  context_length?: number;
+  output_token_limit?: number;
+}
+
+// Shown until the model list has been fetched with the user's API key
+const DEFAULT_GEMINI_MODELS = ['gemini-2.5-flash', 'gemini-2.5-pro', 'gemini-2.0-flash'];
+
 interface ModelSettingsModalProps {
   modelConfig: ModelConfig;
   setModelConfig: (config: ModelConfig | ((prev: ModelConfig) => ModelConfig)) => void;
@@ -79,6 +89,7 @@ export function ModelSettingsModal({
   const [openRouterModels, setOpenRouterModels] = useState<OpenRouterModel[]>([]);
   const [openRouterError, setOpenRouterError] = useState<string>('');
   const [isLoadingOpenRouter, setIsLoadingOpenRouter] = useState<boolean>(false);
+  const [geminiModels, setGeminiModels] = useState<GeminiModel[]>([]);
   const [ollamaEndpoint, setOllamaEndpoint] = useState<string>(modelConfig.ollamaEndpoint || '');
   const [isLoadingOllama, setIsLoadingOllama] = useState<boolean>(false);
   const [lastFetchedEndpoint, setLastFetchedEndpoint] = useState<string>(modelConfig.ollamaEndpoint || '');
@@ -169,6 +180,7 @@ export function ModelSettingsModal({
     ollama: models.map((model) => model.name),
     claude: ['claude-sonnet-4-5-20250929', 'claude-haiku-4-5-20251001', 'claude-opus-4-5-20251101'],
     groq: ['llama-3.3-70b-versatile'],
+    gemini: geminiModels.length > 0 ? geminiModels.map((m) => m.id) : DEFAULT_GEMINI_MODELS,
     openai: [
       'gpt-5',
       'gpt-5-mini',
@@ -202,6 +214,7 @@ export function ModelSettingsModal({
   const requiresApiKey =
     modelConfig.provider === 'claude' ||
     modelConfig.provider === 'groq' ||
+    modelConfig.provider === 'gemini' ||
     modelConfig.provider === 'openai' ||
     modelConfig.provider === 'openrouter';
 
@@ -458,6 +471,17 @@ export function ModelSettingsModal({
     }
   };
 
+  const loadGeminiModels = async (key?: string | null) => {
+    try {
+      // Falls back to the saved Gemini key when none is given
+      const data = (await invoke('get_gemini_models', { apiKey: key?.trim() || null })) as GeminiModel[];
+      setGeminiModels(data);
+    } catch (err) {
+      // Keep the default list, e.g. while no API key is saved yet
+      console.warn('Could not load Gemini models:', err);
+    }
+  };
+
   const loadBuiltinAiModels = async () => {
     if (builtinAiModels.length > 0) return; // Already loaded
 
@@ -689,6 +713,11 @@ export function ModelSettingsModal({
                   loadOpenRouterModels();
                 }
 
+                // Load Gemini models with the saved key
+                if (provider === 'gemini') {
+                  loadGeminiModels();
+                }
+
                 // Load Built-in AI models when selected
                 if (provider === 'builtin-ai') {
                   loadBuiltinAiModels();
@@ -718,6 +747,7 @@ export function ModelSettingsModal({
                 <SelectItem value="builtin-ai">Built-in AI (Offline, No API needed)</SelectItem>
                 <SelectItem value="claude">Claude</SelectItem>
                 <SelectItem value="custom-openai">Custom Server (OpenAI)</SelectItem>
+                <SelectItem value="gemini">Gemini</SelectItem>
                 <SelectItem value="groq">Groq</SelectItem>
                 <SelectItem value="ollama">Ollama</SelectItem>
                 <SelectItem value="openai">OpenAI</SelectItem>
@@ -6,6 +6,9 @@ import { FolderOpen } from "lucide-react"
 import { invoke } from "@tauri-apps/api/core"
 import Analytics from "@/lib/analytics"
 import AnalyticsConsentSwitch from "./AnalyticsConsentSwitch"
+import { EncryptionSettings } from "./EncryptionSettings"
+import { SecretStoreSettings } from "./SecretStoreSettings"
+import { DatabaseBackupSettings } from "./DatabaseBackupSettings"
 import { useConfig, NotificationSettings } from "@/contexts/ConfigContext"
 
 export function PreferenceSettings() {
@@ -220,6 +223,21 @@ export function PreferenceSettings() {
         </div>
       </div>
 
+      {/* Database Backups Section */}
+      <div className="bg-white rounded-lg border border-gray-200 p-6 shadow-sm">
+        <DatabaseBackupSettings />
+      </div>
+
+      {/* Encryption Section */}
+      <div className="bg-white rounded-lg border border-gray-200 p-6 shadow-sm">
+        <EncryptionSettings />
+      </div>
+
+      {/* API Key Storage Section */}
+      <div className="bg-white rounded-lg border border-gray-200 p-6 shadow-sm">
+        <SecretStoreSettings />
+      </div>
+
       {/* Analytics Section */}
       <div className="bg-white rounded-lg border border-gray-200 p-6 shadow-sm">
         <AnalyticsConsentSwitch />
@@ -0,0 +1,150 @@
+"use client"
+
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { toast } from "sonner"
+import { Input } from "./ui/input"
+import { Button } from "./ui/button"
+
+const MIN_PASSWORD_CHARS = 8
+
+interface SecretStoreStatus {
+  backend: "keyring" | "encryptedFile"
+  unlocked: boolean
+  hasMasterPassword: boolean
+}
+
+export function SecretStoreSettings() {
+  const [status, setStatus] = useState<SecretStoreStatus | null>(null)
+  const [isChanging, setIsChanging] = useState(false)
+  const [password, setPassword] = useState("")
+  const [newPassword, setNewPassword] = useState("")
+  const [confirmation, setConfirmation] = useState("")
+  const [isSaving, setIsSaving] = useState(false)
+
+  const loadStatus = () =>
+    invoke<SecretStoreStatus>("api_get_secret_store_status")
+      .then(setStatus)
+      .catch((err) => console.error("Failed to get secret store status:", err))
+
+  useEffect(() => {
+    loadStatus()
+  }, [])
+
+  const reset = () => {
+    setIsChanging(false)
+    setPassword("")
+    setNewPassword("")
+    setConfirmation("")
+  }
+
+  if (!status) {
+    return null
+  }
+
+  // Setting a master password or changing it asks for the new one twice
+  const settingPassword = !status.hasMasterPassword || isChanging
+  const chosenPassword = isChanging ? newPassword : password
+  const validationError = !settingPassword
+    ? null
+    : chosenPassword.length < MIN_PASSWORD_CHARS
+      ? `Use at least ${MIN_PASSWORD_CHARS} characters`
+      : chosenPassword !== confirmation
+        ? "Passwords don't match"
+        : null
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault()
+    if (validationError) return
+    setIsSaving(true)
+    try {
+      if (isChanging) {
+        await invoke("api_change_secret_store_master_password", {
+          currentPassword: password,
+          newPassword,
+        })
+        toast.success("Master password changed")
+      } else {
+        await invoke("api_unlock_secret_store", { masterPassword: password })
+        toast.success(status.hasMasterPassword ? "API keys unlocked" : "Master password set")
+      }
+      reset()
+      await loadStatus()
+    } catch (err) {
+      toast.error("Failed to update API key storage", { description: String(err) })
+    } finally {
+      setIsSaving(false)
+    }
+  }
+
+  return (
+    <div>
+      <h3 className="text-lg font-semibold text-gray-900 mb-2">API Key Storage</h3>
+      {status.backend === "keyring" ? (
+        <p className="text-sm text-gray-600">
+          API keys are stored in your system keychain.
+        </p>
+      ) : (
+        <>
+          <p className="text-sm text-gray-600 mb-4">
+            No system keychain is available, so API keys are stored in a file encrypted with a
+            master password. {status.unlocked
+              ? "API keys are unlocked."
+              : status.hasMasterPassword
+                ? "Enter the master password to use your saved API keys."
+                : "Set a master password to save API keys."}
+          </p>
+
+          {status.unlocked && status.hasMasterPassword && !isChanging ? (
+            <Button variant="outline" onClick={() => setIsChanging(true)}>
+              Change master password
+            </Button>
+          ) : (
+            <form onSubmit={handleSubmit} className="space-y-3 max-w-sm">
+              <Input
+                type="password"
+                placeholder={
+                  isChanging ? "Current master password" : status.hasMasterPassword ? "Master password" : "New master password"
+                }
+                value={password}
+                onChange={(e) => setPassword(e.target.value)}
+                disabled={isSaving}
+              />
+              {isChanging && (
+                <Input
+                  type="password"
+                  placeholder="New master password"
+                  value={newPassword}
+                  onChange={(e) => setNewPassword(e.target.value)}
+                  disabled={isSaving}
+                />
+              )}
+              {settingPassword && (
+                <Input
+                  type="password"
+                  placeholder="Confirm new master password"
+                  value={confirmation}
+                  onChange={(e) => setConfirmation(e.target.value)}
+                  disabled={isSaving}
+                />
+              )}
+              {validationError && chosenPassword && (
+                <p className="text-sm text-red-600">{validationError}</p>
+              )}
+              <div className="flex gap-2">
+                <Button type="submit" disabled={isSaving || !password || !!validationError}>
+                  {isChanging ? "Change" : status.hasMasterPassword ? "Unlock" : "Set master password"}
+                </Button>
+                {isChanging && (
+                  <Button type="button" variant="ghost" onClick={reset} disabled={isSaving}>
+                    Cancel
+                  </Button>
+                )}
+              </div>
+            </form>
+          )}
+        </>
+      )}
+    </div>
+  )
+}
@@ -10,7 +10,6 @@ import {
 } from './ui/dialog';
 import { Button } from './ui/button';
 import { updateService, UpdateInfo, UpdateProgress } from '@/services/updateService';
-import { check, Update } from '@tauri-apps/plugin-updater';
 import { relaunch } from '@tauri-apps/plugin-process';
 import { toast } from 'sonner';
 
@@ -24,7 +23,7 @@ export function UpdateDialog({ open, onOpenChange, updateInfo }: UpdateDialogPro
   const [isDownloading, setIsDownloading] = useState(false);
   const [progress, setProgress] = useState<UpdateProgress | null>(null);
   const [error, setError] = useState<string | null>(null);
-  const [update, setUpdate] = useState<Update | null>(null);
+  const [updateReady, setUpdateReady] = useState(false);
 
   useEffect(() => {
     if (open && updateInfo?.available) {
@@ -33,50 +32,41 @@ export function UpdateDialog({ open, onOpenChange, updateInfo }: UpdateDialogPro
       setProgress(null);
       setError(null);
 
-      // Get the update object when dialog opens
-      check().then((updateResult) => {
-        if (updateResult?.available) {
-          setUpdate(updateResult);
+      // Check again when dialog opens, so the backend holds the update to install
+      updateService.checkForUpdates(true).then((info) => {
+        if (info.available) {
+          setUpdateReady(true);
         } else {
           setError('Update no longer available');
         }
       }).catch((err) => {
-        console.error('Failed to get update object:', err);
-        setError('Failed to prepare update: ' + (err.message || 'Unknown error'));
+        console.error('Failed to prepare update:', err);
+        setError('Failed to prepare update: ' + (err?.message || err || 'Unknown error'));
       });
     } else {
       // Reset state when dialog closes
       setIsDownloading(false);
       setProgress(null);
       setError(null);
-      setUpdate(null);
+      setUpdateReady(false);
     }
   }, [open, updateInfo]);
 
   const handleDownloadAndInstall = async () => {
-    // Get update object if not already available
-    let updateToUse: Update | null = update;
-    if (!updateToUse) {
+    if (!updateReady) {
       try {
-        const updateResult = await check();
-        if (updateResult?.available) {
-          updateToUse = updateResult;
-          setUpdate(updateResult);
-        } else {
+        const info = await updateService.checkForUpdates(true);
+        if (!info.available) {
           setError('Update not available');
           return;
         }
+        setUpdateReady(true);
       } catch (err: any) {
-        setError('Failed to get update: ' + (err.message || 'Unknown error'));
+        setError('Failed to get update: ' + (err?.message || err || 'Unknown error'));
         return;
       }
     }
 
-    // At this point, updateToUse is guaranteed to be non-null
-    if (!updateToUse) {
-      return; // This should never happen, but TypeScript needs this check
-    }
-
     setIsDownloading(true);
     setError(null);
     setProgress({ downloaded: 0, total: 0, percentage: 0 });
@@ -85,8 +75,8 @@ export function UpdateDialog({ open, onOpenChange, updateInfo }: UpdateDialogPro
       let downloaded = 0;
       let contentLength = 0;
 
-      // Use the official Tauri updater API with progress callbacks
-      await updateToUse.downloadAndInstall((event) => {
+      // Downloaded by the backend, which reports the updater plugin's progress events
+      await updateService.downloadAndInstall((event) => {
         switch (event.event) {
           case 'Started':
             contentLength = event.data.contentLength || 0;
@@ -135,9 +125,9 @@ export function UpdateDialog({ open, onOpenChange, updateInfo }: UpdateDialogPro
       await relaunch();
     } catch (err: any) {
       console.error('Update failed:', err);
-      setError(err.message || 'Failed to download or install update');
+      setError(err?.message || err || 'Failed to download or install update');
       setIsDownloading(false);
-      toast.error('Update failed: ' + (err.message || 'Unknown error'));
+      toast.error('Update failed: ' + (err?.message || err || 'Unknown error'));
     }
   };
 
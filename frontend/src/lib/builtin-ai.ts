@@ -8,6 +8,8 @@ export interface BuiltInModelInfo {
   context_size: number;
   description: string;
   gguf_file: string;
+  template: string;
+  custom: boolean;
 }
 
 export type BuiltInModelStatus =
@@ -80,6 +82,10 @@ export class BuiltInAIAPI {
     return await invoke('builtin_ai_get_available_summary_model');
   }
 
+  static async addLocalModel(path: string): Promise<BuiltInModelInfo> {
+    return await invoke('builtin_ai_add_local_model', { path });
+  }
+
   static async downloadModel(modelName: string): Promise<void> {
     await invoke('builtin_ai_download_model', { modelName });
   }
@@ -3,10 +3,13 @@
  *
  * Handles automatic software updates using Tauri updater plugin.
  * Provides update checking, downloading, and installation functionality.
+ *
+ * Updates are checked and installed by backend commands, so the requests go
+ * through the network gate and are blocked in local-only mode.
  */
 
-import { check, Update } from '@tauri-apps/plugin-updater';
-import { relaunch } from '@tauri-apps/plugin-process';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { getVersion } from '@tauri-apps/api/app';
 
 export interface UpdateInfo {
@@ -24,6 +27,20 @@ export interface UpdateProgress {
   percentage: number;
 }
 
+/** Download event, in the shape of the updater plugin's events */
+export type UpdateDownloadEvent =
+  | { event: 'Started'; data: { contentLength?: number } }
+  | { event: 'Progress'; data: { chunkLength: number } }
+  | { event: 'Finished' };
+
+/** Update found by the `api_check_for_update` command */
+interface BackendUpdateInfo {
+  version: string;
+  currentVersion: string;
+  date?: string;
+  body?: string;
+}
+
 /**
  * Update Service
  * Singleton service for managing app updates
@@ -61,9 +78,9 @@ export class UpdateService {
 
     try {
       const currentVersion = await getVersion();
-      const update = await check();
+      const update = await invoke<BackendUpdateInfo | null>('api_check_for_update');
 
-      if (update?.available) {
+      if (update) {
         return {
           available: true,
           currentVersion,
@@ -86,30 +103,24 @@ export class UpdateService {
   }
 
   /**
-   * Download and install the available update
-   * @param update The update object from checkForUpdates
-   * @param onProgress Optional progress callback
-   * @returns Promise that resolves when download completes
+   * Download and install the update found by the last checkForUpdates call
+   * The caller relaunches the app afterwards.
+   * @param onEvent Optional download event callback
+   * @returns Promise that resolves when the update is installed
    */
   async downloadAndInstall(
-    update: Update,
-    onProgress?: (progress: UpdateProgress) => void
+    onEvent?: (event: UpdateDownloadEvent) => void
   ): Promise<void> {
+    const unlisten = onEvent
+      ? await listen<UpdateDownloadEvent>('update-download-progress', (event) => onEvent(event.payload))
+      : undefined;
     try {
-      // Download the update
-      await update.download();
-
-      // Notify progress if callback provided
-      if (onProgress) {
-        onProgress({ downloaded: 100, total: 100, percentage: 100 });
-      }
-
-      // Install and relaunch
-      await update.install();
-      await relaunch();
+      await invoke('api_install_update');
     } catch (error) {
       console.error('Failed to download/install update:', error);
       throw error;
+    } finally {
+      unlisten?.();
     }
   }
 
@@ -2,17 +2,21 @@ use std::io::{self, BufRead, Write};
 use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::pin::pin;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use encoding_rs;
 use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::context::LlamaContext;
 use llama_cpp_2::llama_backend::LlamaBackend;
 use llama_cpp_2::llama_batch::LlamaBatch;
 use llama_cpp_2::model::params::LlamaModelParams;
 use llama_cpp_2::model::{AddBos, LlamaModel, Special};
+use llama_cpp_2::token::LlamaToken;
+use ouroboros::self_referencing;
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -27,12 +31,32 @@ enum Request {
         max_tokens: Option<i32>,
         context_size: Option<u32>,
         model_path: Option<String>,
+        /// Layer count from the model metadata (estimated from the file size if absent)
+        #[serde(default)]
+        layer_count: Option<u32>,
         // Sampling parameters
         temperature: Option<f32>,
         top_k: Option<i32>,
         top_p: Option<f32>,
         stop_tokens: Option<Vec<String>>,
+        /// Send `Token` and `Progress` messages before the final response
+        #[serde(default)]
+        stream: bool,
+        /// GBNF grammar (start rule `root`) constraining the output
+        #[serde(default)]
+        grammar: Option<String>,
     },
+    /// Count the tokens of `text` with the model's vocabulary; answered with `Tokens`
+    Tokenize {
+        text: String,
+        context_size: Option<u32>,
+        model_path: Option<String>,
+        #[serde(default)]
+        layer_count: Option<u32>,
+    },
+    /// Stop the running generation; it ends with a `Cancelled` response.
+    /// Has no effect (and gets no response) when nothing is being generated.
+    Cancel,
     Ping,
     Shutdown,
 }
@@ -40,10 +64,67 @@ enum Request {
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum Response {
-    Response { text: String, error: Option<String> },
+    /// Text generated since the previous token message (only for streamed requests)
+    Token {
+        text: String,
+    },
+    /// Generation statistics, sent while the prompt is evaluated and every
+    /// `PROGRESS_INTERVAL_TOKENS` generated tokens (only for streamed requests)
+    Progress {
+        prompt_tokens: usize,
+        /// Prompt tokens taken from the KV cache instead of being evaluated
+        cached_prompt_tokens: usize,
+        prompt_tokens_processed: usize,
+        generated_tokens: usize,
+        tokens_per_second: f64,
+    },
+    Response {
+        text: String,
+        error: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stats: Option<GenerationStats>,
+    },
+    /// Final message of a cancelled generation, with the text generated until then
+    Cancelled {
+        text: String,
+    },
+    /// Token count of a `Tokenize` request (without BOS)
+    Tokens {
+        count: usize,
+    },
     Pong,
     Goodbye,
-    Error { message: String },
+    Error {
+        message: String,
+    },
+}
+
+/// Prompt tokens evaluated per batch; progress is reported after each batch
+const PROMPT_BATCH_SIZE: usize = 512;
+
+/// Generated tokens between two `Progress` messages
+const PROGRESS_INTERVAL_TOKENS: usize = 16;
+
+/// Result of a generation
+struct Generation {
+    text: String,
+    /// Whether generation stopped because of a `Cancel` request
+    cancelled: bool,
+    stats: Option<GenerationStats>,
+}
+
+/// Statistics of a completed generation, sent with the final response
+#[derive(Debug, Serialize)]
+struct GenerationStats {
+    prompt_tokens: usize,
+    /// Prompt tokens whose KV cache entries were reused from the previous request
+    cached_prompt_tokens: usize,
+    generated_tokens: usize,
+    prompt_seconds: f64,
+    generation_seconds: f64,
+    /// Prompt tokens requested and taken from the KV cache since the model was loaded
+    prompt_tokens_since_load: u64,
+    cached_prompt_tokens_since_load: u64,
 }
 
 // ============================================================================
@@ -182,9 +263,17 @@ fn calculate_gpu_layers(
 }
 
 /// Get default GPU layer count with smart detection
-fn get_default_gpu_layers(model_path: &PathBuf, context_size: u32) -> u32 {
+fn get_default_gpu_layers(
+    model_path: &PathBuf,
+    context_size: u32,
+    layer_count: Option<u32>,
+) -> u32 {
     let vram = detect_vram_gb();
-    // TODO: Use actual model metadata instead of heuristics
+
+    if let Some(layers) = layer_count.filter(|&n| n > 0) {
+        return calculate_gpu_layers(model_path, layers, vram, context_size);
+    }
+
     // Heuristic: Estimate total layers based on file size
     // 7B models (Q4) are ~4.1GB and have ~32-35 layers
     // 1B models (Q4) are ~1.1GB and have ~20-28 layers
@@ -201,11 +290,73 @@ fn get_default_gpu_layers(model_path: &PathBuf, context_size: u32) -> u32 {
 // Model State Management
 // ============================================================================
 
+/// A model and the context created from it
+///
+/// The context borrows the model, so both live in one self-referencing struct;
+/// the context is dropped before the model.
+#[self_referencing]
+struct ModelContext {
+    model: LlamaModel,
+    /// Created with the first generation, so counting tokens doesn't allocate a KV cache
+    #[borrows(model)]
+    #[not_covariant]
+    ctx: Option<LlamaContext<'this>>,
+}
+
+/// A loaded model together with its context
+///
+/// The context is kept between requests, so the KV cache entries of a prompt
+/// prefix shared with the previous request (system prompt, template
+/// instructions) can be reused instead of being evaluated again.
+struct LoadedModel {
+    model: ModelContext,
+    /// Tokens whose keys and values are in the context's KV cache, by position
+    cached_tokens: Vec<LlamaToken>,
+    path: PathBuf,
+    context_size: u32,
+    /// Prompt tokens requested and taken from the KV cache since loading
+    prompt_tokens_total: u64,
+    cached_prompt_tokens_total: u64,
+}
+
+impl LoadedModel {
+    fn new(model: LlamaModel, path: PathBuf, context_size: u32) -> Self {
+        Self {
+            model: ModelContext::new(model, |_| None),
+            cached_tokens: Vec::new(),
+            path,
+            context_size,
+            prompt_tokens_total: 0,
+            cached_prompt_tokens_total: 0,
+        }
+    }
+
+    /// Forgets the KV cache contents, e.g. after a failed decode left them in an unknown state
+    fn reset_cache(&mut self) {
+        self.model.with_ctx_mut(|ctx| {
+            if let Some(ctx) = ctx.as_mut() {
+                ctx.clear_kv_cache();
+            }
+        });
+        self.cached_tokens.clear();
+    }
+}
+
+/// Parameters of a `Generate` request, with defaults applied
+struct GenerateParams {
+    prompt: String,
+    max_tokens: i32,
+    temperature: f32,
+    top_k: i32,
+    top_p: f32,
+    stop_tokens: Vec<String>,
+    grammar: Option<String>,
+}
+
 struct ModelState {
+    // Declared before `backend`, so the model is freed first
+    loaded: Option<LoadedModel>,
     backend: LlamaBackend,
-    model: Option<LlamaModel>,
-    model_path: Option<PathBuf>,
-    context_size: u32,
     last_activity: Arc<AtomicU64>,
 }
 
@@ -213,10 +364,8 @@ impl ModelState {
     fn new() -> Result<Self> {
         let backend = LlamaBackend::init().context("Failed to init LlamaBackend")?;
         Ok(Self {
+            loaded: None,
             backend,
-            model: None,
-            model_path: None,
-            context_size: 2048,
             last_activity: Arc::new(AtomicU64::new(Self::current_timestamp())),
         })
     }
@@ -237,20 +386,30 @@ impl ModelState {
         Self::current_timestamp() - self.last_activity.load(Ordering::SeqCst)
     }
 
-    fn load_model_if_needed(&mut self, model_path: PathBuf, context_size: u32) -> Result<()> {
+    fn load_model_if_needed(
+        &mut self,
+        model_path: PathBuf,
+        context_size: u32,
+        layer_count: Option<u32>,
+    ) -> Result<()> {
         // Check if model is already loaded
-        if let Some(ref loaded_path) = self.model_path {
-            if loaded_path == &model_path && self.context_size == context_size {
+        if let Some(ref loaded) = self.loaded {
+            if loaded.path == model_path && loaded.context_size == context_size {
                 eprintln!("✓ Model already loaded");
                 self.update_activity();
                 return Ok(());
             }
         }
 
+        // Free the previous model (and its KV cache) before loading the next one
+        if self.loaded.take().is_some() {
+            eprintln!("🗑️ Unloaded previous model, KV cache invalidated");
+        }
+
         eprintln!("📥 Loading model: {}", model_path.display());
 
         // Detect GPU layers
-        let gpu_layers = get_default_gpu_layers(&model_path, context_size);
+        let gpu_layers = get_default_gpu_layers(&model_path, context_size, layer_count);
 
         // Configure model parameters with GPU offload
         let model_params = LlamaModelParams::default().with_n_gpu_layers(gpu_layers);
@@ -259,76 +418,240 @@ impl ModelState {
         let model = LlamaModel::load_from_file(&self.backend, model_path.clone(), &model_params)
             .with_context(|| format!("unable to load model at {:?}", model_path))?;
 
-        self.model = Some(model);
-        self.model_path = Some(model_path);
-        self.context_size = context_size;
+        self.loaded = Some(LoadedModel::new(model, model_path, context_size));
         self.update_activity();
 
         eprintln!("✅ Model loaded successfully");
         Ok(())
     }
 
+    /// Counts the tokens of `text` with the loaded model's vocabulary
+    fn count_tokens(&mut self, text: &str) -> Result<usize> {
+        let loaded = self.loaded.as_ref().context("No model loaded")?;
+        let tokens = loaded
+            .model
+            .borrow_model()
+            .str_to_token(text, AddBos::Never)
+            .context("failed to tokenize text")?;
+        self.update_activity();
+        Ok(tokens.len())
+    }
+
     fn generate(
         &mut self,
-        prompt: String,
-        max_tokens: i32,
-        temperature: f32,
-        top_k: i32,
-        top_p: f32,
-        stop_tokens: Vec<String>,
-    ) -> Result<String> {
-        let start_time = Instant::now();
-        let model = self.model.as_ref().context("Model not loaded")?;
-
-        // Calculate thread count (conservative default: max(1, (Cores / 2) + 2))
-        // This ensures the UI thread is never starved
-        let threads: i32 = std::thread::available_parallelism()
-            .map(|n| {
-                let cores = n.get() as i32;
-                ((cores / 2) + 2).max(1)
-            })
-            .unwrap_or(2);
+        params: GenerateParams,
+        cancel: &AtomicBool,
+        on_message: &mut dyn FnMut(Response),
+    ) -> Result<Generation> {
+        let result = self.generate_with_cache(params, cancel, on_message);
+        if result.is_err() {
+            if let Some(loaded) = self.loaded.as_mut() {
+                loaded.reset_cache();
+            }
+        }
+        self.update_activity();
+        result
+    }
 
-        let ctx_params = LlamaContextParams::default()
-            .with_n_ctx(Some(
-                NonZeroU32::new(self.context_size).context("Invalid ctx size")?,
-            ))
-            .with_n_batch(self.context_size)
-            .with_n_threads(threads)
-            .with_n_threads_batch(threads);
+    fn generate_with_cache(
+        &mut self,
+        params: GenerateParams,
+        cancel: &AtomicBool,
+        on_message: &mut dyn FnMut(Response),
+    ) -> Result<Generation> {
+        let backend = &self.backend;
+        let loaded = self.loaded.as_mut().context("Model not loaded")?;
+        let context_size = loaded.context_size;
+        let LoadedModel {
+            model,
+            cached_tokens,
+            ..
+        } = &mut *loaded;
+
+        let mut generation = model.with_mut(|fields| -> Result<Generation> {
+            if fields.ctx.is_none() {
+                // Calculate thread count (conservative default: max(1, (Cores / 2) + 2))
+                // This ensures the UI thread is never starved
+                let threads: i32 = std::thread::available_parallelism()
+                    .map(|n| {
+                        let cores = n.get() as i32;
+                        ((cores / 2) + 2).max(1)
+                    })
+                    .unwrap_or(2);
+
+                let ctx_params = LlamaContextParams::default()
+                    .with_n_ctx(Some(
+                        NonZeroU32::new(context_size).context("Invalid ctx size")?,
+                    ))
+                    .with_n_batch(context_size)
+                    .with_n_threads(threads)
+                    .with_n_threads_batch(threads);
+
+                *fields.ctx = Some(
+                    fields
+                        .model
+                        .new_context(backend, ctx_params)
+                        .context("unable to create the llama_context")?,
+                );
+                cached_tokens.clear();
+            }
+            let ctx = fields.ctx.as_mut().context("Context not created")?;
+            Self::generate_in_context(
+                fields.model,
+                ctx,
+                cached_tokens,
+                context_size,
+                params,
+                cancel,
+                on_message,
+            )
+        })?;
+
+        if let Some(stats) = generation.stats.as_mut() {
+            loaded.prompt_tokens_total += stats.prompt_tokens as u64;
+            loaded.cached_prompt_tokens_total += stats.cached_prompt_tokens as u64;
+            stats.prompt_tokens_since_load = loaded.prompt_tokens_total;
+            stats.cached_prompt_tokens_since_load = loaded.cached_prompt_tokens_total;
+            eprintln!(
+                "   • KV cache hit ratio since model load: {:.1}%",
+                loaded.cached_prompt_tokens_total as f64 / loaded.prompt_tokens_total.max(1) as f64
+                    * 100.0
+            );
+        }
+        Ok(generation)
+    }
 
-        let mut ctx = model
-            .new_context(&self.backend, ctx_params)
-            .context("unable to create the llama_context")?;
+    /// Evaluates the prompt in `ctx`, reusing the cached prefix, and generates the answer
+    fn generate_in_context(
+        model: &LlamaModel,
+        ctx: &mut LlamaContext<'_>,
+        cached_tokens: &mut Vec<LlamaToken>,
+        context_size: u32,
+        params: GenerateParams,
+        cancel: &AtomicBool,
+        on_message: &mut dyn FnMut(Response),
+    ) -> Result<Generation> {
+        let GenerateParams {
+            prompt,
+            max_tokens,
+            temperature,
+            top_k,
+            top_p,
+            stop_tokens,
+            grammar,
+        } = params;
 
+        let start_time = Instant::now();
         let tokens_list = model
             .str_to_token(&prompt, AddBos::Always)
             .with_context(|| "failed to tokenize prompt")?;
+        // Sampling needs the logits of at least one prompt token
+        if tokens_list.is_empty() {
+            anyhow::bail!("empty prompt");
+        }
 
-        eprintln!("📝 Tokenized prompt: {} tokens", tokens_list.len());
+        let prompt_tokens = tokens_list.len();
+        eprintln!("📝 Tokenized prompt: {} tokens", prompt_tokens);
+
+        // Reuse the cached prefix shared with the previous request. At least the
+        // last prompt token is evaluated again, as sampling needs its logits.
+        let mut n_keep = common_prefix_len(cached_tokens, &tokens_list).min(prompt_tokens - 1);
+        if n_keep < cached_tokens.len() {
+            let removed = ctx
+                .clear_kv_cache_seq(Some(0), Some(n_keep as u32), None)
+                .unwrap_or(false);
+            if !removed {
+                // Partial removal isn't supported by every cache type
+                ctx.clear_kv_cache();
+                n_keep = 0;
+            }
+            cached_tokens.truncate(n_keep);
+        }
+        eprintln!(
+            "♻️ KV cache: reusing {}/{} prompt tokens",
+            n_keep, prompt_tokens
+        );
 
-        // Use context size for batch capacity to handle long prompts
-        let batch_size = self.context_size as usize;
+        // Evaluate the rest of the prompt in batches, so progress can be reported
+        // and a cancellation doesn't have to wait for the whole prompt
+        let batch_size = PROMPT_BATCH_SIZE.min(context_size as usize);
         let mut batch = LlamaBatch::new(batch_size, 1);
+        let last_index: i32 = (prompt_tokens - 1) as i32;
+        let mut position: i32 = n_keep as i32;
+        for chunk in tokens_list[n_keep..].chunks(batch_size) {
+            if cancel.load(Ordering::SeqCst) {
+                eprintln!("🛑 Generation cancelled during prompt evaluation");
+                return Ok(Generation {
+                    text: String::new(),
+                    cancelled: true,
+                    stats: None,
+                });
+            }
 
-        let last_index: i32 = (tokens_list.len() - 1) as i32;
-        for (i, token) in (0_i32..).zip(tokens_list.into_iter()) {
-            let is_last = i == last_index;
-            batch
-                .add(token, i, &[0], is_last)
-                .context("Failed to add token to batch")?;
+            batch.clear();
+            for &token in chunk {
+                batch
+                    .add(token, position, &[0], position == last_index)
+                    .context("Failed to add token to batch")?;
+                position += 1;
+            }
+            ctx.decode(&mut batch).context("llama_decode() failed")?;
+            cached_tokens.extend_from_slice(chunk);
+
+            on_message(Response::Progress {
+                prompt_tokens,
+                cached_prompt_tokens: n_keep,
+                prompt_tokens_processed: position as usize,
+                generated_tokens: 0,
+                tokens_per_second: 0.0,
+            });
         }
-
-        ctx.decode(&mut batch).context("llama_decode() failed")?;
         let prompt_time = start_time.elapsed();
 
-        let n_prompt_tokens = batch.n_tokens();
+        let n_prompt_tokens = prompt_tokens as i32;
         let mut n_cur = n_prompt_tokens;
         let mut decoder = encoding_rs::UTF_8.new_decoder();
         let mut output = String::new();
+        // Bytes of `output` already sent as `Token` messages
+        let mut emitted = 0;
+        let mut cancelled = false;
 
         eprintln!("🔄 Starting generation (max_tokens: {})", max_tokens);
 
+        use llama_cpp_2::sampling::LlamaSampler;
+
+        // Build the sampler once, as the grammar sampler tracks the output parsed so far
+        let mut samplers = Vec::new();
+        if let Some(grammar) = &grammar {
+            eprintln!(
+                "📐 Constraining output with grammar ({} bytes)",
+                grammar.len()
+            );
+            samplers.push(
+                LlamaSampler::grammar(model, grammar, "root")
+                    .map_err(|e| anyhow::anyhow!("Invalid grammar: {}", e))?,
+            );
+        }
+        if temperature <= 0.0 {
+            // Greedy sampling for temp <= 0
+            samplers.push(LlamaSampler::greedy());
+        } else {
+            // Random sampling with temperature/top_k/top_p
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u32;
+
+            samplers.extend([
+                LlamaSampler::top_k(top_k),
+                LlamaSampler::top_p(top_p, 1),
+                LlamaSampler::temp(temperature),
+                LlamaSampler::dist(seed),
+            ]);
+        }
+        let sampler = LlamaSampler::chain_simple(samplers);
+        let mut sampler = pin!(sampler);
+
         loop {
             // Check if we've generated enough tokens
             if (n_cur - n_prompt_tokens) >= max_tokens {
@@ -336,29 +659,14 @@ impl ModelState {
                 break;
             }
 
-            use llama_cpp_2::sampling::LlamaSampler;
-
-            let sampler = if temperature <= 0.0 {
-                // Greedy sampling for temp <= 0
-                LlamaSampler::chain_simple([LlamaSampler::greedy()])
-            } else {
-                // Random sampling with temperature/top_k/top_p
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u32;
-
-                LlamaSampler::chain_simple([
-                    LlamaSampler::top_k(top_k),
-                    LlamaSampler::top_p(top_p, 1),
-                    LlamaSampler::temp(temperature),
-                    LlamaSampler::dist(seed),
-                ])
-            };
+            if cancel.load(Ordering::SeqCst) {
+                eprintln!("🛑 Generation cancelled (generated {} chars)", output.len());
+                cancelled = true;
+                break;
+            }
 
-            let mut sampler = pin!(sampler);
-            let token = sampler.as_mut().sample(&ctx, batch.n_tokens() - 1);
-            sampler.as_mut().accept(token);
+            // Sampling also accepts the token, advancing the grammar
+            let token = sampler.as_mut().sample(ctx, batch.n_tokens() - 1);
 
             if model.is_eog_token(token) {
                 eprintln!(
@@ -379,14 +687,14 @@ impl ModelState {
             // Check for model-specific stop tokens
             let mut should_stop = false;
             for stop_token in &stop_tokens {
-                if output.contains(stop_token) {
+                if let Some(stop_start) = output.find(stop_token.as_str()) {
                     eprintln!(
                         "✓ Stop token '{}' detected (generated {} chars)",
                         stop_token,
                         output.len()
                     );
-                    // Remove the stop token from output
-                    output = output.replace(stop_token, "").trim_end().to_string();
+                    // Remove the stop token and what follows it from output
+                    output.truncate(text_end_before_stop(&output, stop_start, emitted));
                     should_stop = true;
                     break;
                 }
@@ -395,19 +703,55 @@ impl ModelState {
                 break;
             }
 
+            // Hold back text that may turn out to be the start of a stop token
+            let safe_end = output.len() - partial_stop_token_len(&output, &stop_tokens);
+            if safe_end > emitted {
+                on_message(Response::Token {
+                    text: output[emitted..safe_end].to_string(),
+                });
+                emitted = safe_end;
+            }
+
             batch.clear();
             batch
                 .add(token, n_cur, &[0], true)
                 .context("Failed to add generated token to batch")?;
             n_cur += 1;
             ctx.decode(&mut batch).context("failed to eval")?;
+            cached_tokens.push(token);
+
+            let generated = (n_cur - n_prompt_tokens) as usize;
+            if generated % PROGRESS_INTERVAL_TOKENS == 0 {
+                let gen_secs = start_time
+                    .elapsed()
+                    .saturating_sub(prompt_time)
+                    .as_secs_f64();
+                on_message(Response::Progress {
+                    prompt_tokens,
+                    cached_prompt_tokens: n_keep,
+                    prompt_tokens_processed: prompt_tokens,
+                    generated_tokens: generated,
+                    tokens_per_second: if gen_secs > 0.0 {
+                        generated as f64 / gen_secs
+                    } else {
+                        0.0
+                    },
+                });
+            }
+        }
+
+        // Send the text held back for a stop token that never completed, so the
+        // streamed text adds up to the final text
+        if output.len() > emitted {
+            on_message(Response::Token {
+                text: output[emitted..].to_string(),
+            });
         }
 
         // Generation statistics
         let total_time = start_time.elapsed();
         let gen_time = total_time.saturating_sub(prompt_time);
         let output_tokens = (n_cur - n_prompt_tokens) as u64;
-        let prompt_tokens = n_prompt_tokens as u64;
 
         let tokens_per_sec = if gen_time.as_secs_f64() > 0.0 {
             output_tokens as f64 / gen_time.as_secs_f64()
@@ -417,21 +761,89 @@ impl ModelState {
 
         eprintln!("📊 Generation Statistics:");
         eprintln!("   • Prompt tokens: {}", prompt_tokens);
+        eprintln!(
+            "   • Cached prompt tokens: {} ({:.1}%)",
+            n_keep,
+            n_keep as f64 / prompt_tokens as f64 * 100.0
+        );
         eprintln!("   • Output tokens: {}", output_tokens);
         eprintln!("   • Prompt processing: {:.2}s", prompt_time.as_secs_f64());
         eprintln!("   • Generation time: {:.2}s", gen_time.as_secs_f64());
         eprintln!("   • Total time: {:.2}s", total_time.as_secs_f64());
         eprintln!("   • Speed: {:.2} tokens/sec", tokens_per_sec);
 
-        self.update_activity();
-        Ok(output)
+        Ok(Generation {
+            text: output,
+            cancelled,
+            stats: Some(GenerationStats {
+                prompt_tokens,
+                cached_prompt_tokens: n_keep,
+                generated_tokens: output_tokens as usize,
+                prompt_seconds: prompt_time.as_secs_f64(),
+                generation_seconds: gen_time.as_secs_f64(),
+                // Filled in by the caller, which keeps the totals
+                prompt_tokens_since_load: 0,
+                cached_prompt_tokens_since_load: 0,
+            }),
+        })
     }
 }
 
+/// Number of leading tokens `a` and `b` have in common
+fn common_prefix_len(a: &[LlamaToken], b: &[LlamaToken]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Length of the longest end of `text` that is the beginning of a stop token
+fn partial_stop_token_len(text: &str, stop_tokens: &[String]) -> usize {
+    stop_tokens
+        .iter()
+        .filter_map(|stop| {
+            (1..stop.len())
+                .rev()
+                .filter(|&n| stop.is_char_boundary(n))
+                .find(|&n| text.ends_with(&stop[..n]))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// End of the text before a stop token starting at `stop_start`
+///
+/// Trailing whitespace is trimmed, except for what was already streamed in
+/// `Token` messages, which ended at `emitted`.
+fn text_end_before_stop(text: &str, stop_start: usize, emitted: usize) -> usize {
+    text[..stop_start]
+        .trim_end()
+        .len()
+        .max(emitted.min(stop_start))
+}
+
 // ============================================================================
 // Main Loop with Keep-Alive Protocol
 // ============================================================================
 
+/// Final message of a `Generate` request
+fn generation_response(result: Result<Generation>) -> Response {
+    match result {
+        Ok(Generation {
+            text,
+            cancelled: true,
+            ..
+        }) => Response::Cancelled { text },
+        Ok(Generation { text, stats, .. }) => Response::Response {
+            text,
+            error: None,
+            stats,
+        },
+        Err(e) => Response::Response {
+            text: String::new(),
+            error: Some(format!("Generation failed: {}", e)),
+            stats: None,
+        },
+    }
+}
+
 fn send_response(response: &Response) -> Result<()> {
     let json = serde_json::to_string(response)?;
     println!("{}", json);
@@ -439,6 +851,50 @@ fn send_response(response: &Response) -> Result<()> {
     Ok(())
 }
 
+/// Reads requests from `input` (stdin) on a separate thread
+///
+/// `Cancel` requests set the `cancel` flag right away instead of being queued;
+/// the flag is cleared whenever a `Generate` request is queued, so a late cancel
+/// never stops the next generation. The channel closes on EOF.
+fn spawn_request_reader(
+    input: impl BufRead + Send + 'static,
+    cancel: Arc<AtomicBool>,
+) -> mpsc::Receiver<serde_json::Result<Request>> {
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for line in input.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    eprintln!("❌ Error reading stdin: {}", e);
+                    break;
+                }
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let request = serde_json::from_str::<Request>(line);
+            match request {
+                Ok(Request::Cancel) => {
+                    eprintln!("🛑 Cancel requested");
+                    cancel.store(true, Ordering::SeqCst);
+                    continue;
+                }
+                Ok(Request::Generate { .. }) => cancel.store(false, Ordering::SeqCst),
+                _ => {}
+            }
+            if sender.send(request).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
+
 fn main() -> Result<()> {
     // Get idle timeout from environment variable (default 5 minutes)
     let idle_timeout_secs = std::env::var("LLAMA_IDLE_TIMEOUT")
@@ -453,9 +909,10 @@ fn main() -> Result<()> {
 
     let mut state = ModelState::new()?;
 
-    let stdin = io::stdin();
-    let mut stdin_lock = stdin.lock();
-    let mut buffer = String::new();
+    // Read stdin on its own thread, so a `Cancel` request is seen while the
+    // main thread is busy generating
+    let cancel = Arc::new(AtomicBool::new(false));
+    let requests = spawn_request_reader(io::BufReader::new(io::stdin()), cancel.clone());
 
     loop {
         // Check idle timeout
@@ -465,97 +922,261 @@ fn main() -> Result<()> {
             break;
         }
 
-        // Read line from stdin
-        buffer.clear();
-        match stdin_lock.read_line(&mut buffer) {
-            Ok(0) => {
-                // EOF reached
-                eprintln!("📪 EOF received, shutting down");
-                break;
-            }
-            Ok(_) => {
-                let line = buffer.trim();
-                if line.is_empty() {
-                    continue;
-                }
+        let Ok(request) = requests.recv() else {
+            // EOF reached (or stdin failed), the reader thread has exited
+            eprintln!("📪 EOF received, shutting down");
+            break;
+        };
 
-                // Parse request
-                match serde_json::from_str::<Request>(line) {
-                    Ok(Request::Generate {
-                        prompt,
-                        max_tokens,
-                        context_size,
-                        model_path,
-                        temperature,
-                        top_k,
-                        top_p,
-                        stop_tokens,
-                    }) => {
-                        let max_tokens = max_tokens.unwrap_or(512);
-                        let context_size = context_size.unwrap_or(2048);
-
-                        // Sampling parameters with sensible defaults
-                        let temperature = temperature.unwrap_or(1.0);
-                        let top_k = top_k.unwrap_or(64);
-                        let top_p = top_p.unwrap_or(0.95);
-                        let stop_tokens = stop_tokens.unwrap_or_else(Vec::new);
-
-                        // Load model if path provided
-                        if let Some(path_str) = model_path {
-                            let path = PathBuf::from(path_str);
-                            if let Err(e) = state.load_model_if_needed(path, context_size) {
-                                send_response(&Response::Response {
-                                    text: String::new(),
-                                    error: Some(format!("Failed to load model: {}", e)),
-                                })?;
-                                continue;
-                            }
-                        }
+        match request {
+            Ok(Request::Generate {
+                prompt,
+                max_tokens,
+                context_size,
+                model_path,
+                layer_count,
+                temperature,
+                top_k,
+                top_p,
+                stop_tokens,
+                stream,
+                grammar,
+            }) => {
+                let max_tokens = max_tokens.unwrap_or(512);
+                let context_size = context_size.unwrap_or(2048);
+
+                // Sampling parameters with sensible defaults
+                let temperature = temperature.unwrap_or(1.0);
+                let top_k = top_k.unwrap_or(64);
+                let top_p = top_p.unwrap_or(0.95);
+                let stop_tokens = stop_tokens.unwrap_or_else(Vec::new);
+
+                // Load model if path provided
+                if let Some(path_str) = model_path {
+                    let path = PathBuf::from(path_str);
+                    if let Err(e) = state.load_model_if_needed(path, context_size, layer_count) {
+                        send_response(&Response::Response {
+                            text: String::new(),
+                            error: Some(format!("Failed to load model: {}", e)),
+                            stats: None,
+                        })?;
+                        continue;
+                    }
+                }
 
-                        // Generate response with sampling parameters
-                        match state.generate(
-                            prompt,
-                            max_tokens,
-                            temperature,
-                            top_k,
-                            top_p,
-                            stop_tokens,
-                        ) {
-                            Ok(text) => {
-                                send_response(&Response::Response { text, error: None })?;
-                            }
-                            Err(e) => {
-                                send_response(&Response::Response {
-                                    text: String::new(),
-                                    error: Some(format!("Generation failed: {}", e)),
-                                })?;
-                            }
+                // Stream tokens and progress as they are generated if requested
+                let mut on_message = |message: Response| {
+                    if stream {
+                        if let Err(e) = send_response(&message) {
+                            eprintln!("❌ Failed to send stream message: {}", e);
                         }
                     }
-                    Ok(Request::Ping) => {
-                        state.update_activity();
-                        send_response(&Response::Pong)?;
-                    }
-                    Ok(Request::Shutdown) => {
-                        eprintln!("🛑 Shutdown requested");
-                        send_response(&Response::Goodbye)?;
-                        break;
-                    }
-                    Err(e) => {
-                        eprintln!("❌ Failed to parse request: {}", e);
+                };
+
+                // Generate response with sampling parameters
+                let params = GenerateParams {
+                    prompt,
+                    max_tokens,
+                    temperature,
+                    top_k,
+                    top_p,
+                    stop_tokens,
+                    grammar,
+                };
+                let result = state.generate(params, &cancel, &mut on_message);
+                send_response(&generation_response(result))?;
+            }
+            Ok(Request::Tokenize {
+                text,
+                context_size,
+                model_path,
+                layer_count,
+            }) => {
+                let context_size = context_size.unwrap_or(2048);
+                if let Some(path_str) = model_path {
+                    let path = PathBuf::from(path_str);
+                    if let Err(e) = state.load_model_if_needed(path, context_size, layer_count) {
                         send_response(&Response::Error {
-                            message: format!("Invalid request: {}", e),
+                            message: format!("Failed to load model: {}", e),
                         })?;
+                        continue;
                     }
                 }
+                match state.count_tokens(&text) {
+                    Ok(count) => send_response(&Response::Tokens { count })?,
+                    Err(e) => send_response(&Response::Error {
+                        message: format!("Tokenization failed: {}", e),
+                    })?,
+                }
             }
-            Err(e) => {
-                eprintln!("❌ Error reading stdin: {}", e);
+            // Handled by the reader thread
+            Ok(Request::Cancel) => {}
+            Ok(Request::Ping) => {
+                state.update_activity();
+                send_response(&Response::Pong)?;
+            }
+            Ok(Request::Shutdown) => {
+                eprintln!("🛑 Shutdown requested");
+                send_response(&Response::Goodbye)?;
                 break;
             }
+            Err(e) => {
+                eprintln!("❌ Failed to parse request: {}", e);
+                send_response(&Response::Error {
+                    message: format!("Invalid request: {}", e),
+                })?;
+            }
         }
     }
 
     eprintln!("👋 llama-helper exiting");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops(tokens: &[&str]) -> Vec<String> {
+        tokens.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_partial_stop_token_len() {
+        let stop_tokens = stops(&["<end_of_turn>", "</s>"]);
+        assert_eq!(partial_stop_token_len("Hola <end_of", &stop_tokens), 7);
+        assert_eq!(partial_stop_token_len("Hola <", &stop_tokens), 1);
+        assert_eq!(partial_stop_token_len("Hola </", &stop_tokens), 2);
+        assert_eq!(partial_stop_token_len("Hola", &stop_tokens), 0);
+        assert_eq!(partial_stop_token_len("Hola", &[]), 0);
+        // A complete stop token is handled by the caller, not held back
+        assert_eq!(partial_stop_token_len("Hola </s>", &stops(&["</s>"])), 0);
+        // Only char boundaries of the stop token are considered
+        assert_eq!(partial_stop_token_len("¿Qué tal? ¿", &stops(&["¿fin?"])), 2);
+    }
+
+    #[test]
+    fn test_text_end_before_stop() {
+        let text = "Hola, ¿qué tal?  \n<end_of_turn>";
+        let stop_start = text.find("<end_of_turn>").unwrap();
+        let end = |emitted| &text[..text_end_before_stop(text, stop_start, emitted)];
+        // Whitespace that wasn't streamed yet is trimmed
+        assert_eq!(end(5), "Hola, ¿qué tal?");
+        // Whitespace that was already streamed stays, so the final text matches the stream
+        assert_eq!(end("Hola, ¿qué tal? ".len()), "Hola, ¿qué tal? ");
+        assert_eq!(end(stop_start), "Hola, ¿qué tal?  \n");
+    }
+
+    #[test]
+    fn test_common_prefix_len() {
+        let tokens = |ids: &[i32]| {
+            ids.iter()
+                .map(|&id| LlamaToken::new(id))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            common_prefix_len(&tokens(&[1, 2, 3]), &tokens(&[1, 2, 4, 5])),
+            2
+        );
+        // A prompt extending the cached tokens shares all of them
+        assert_eq!(common_prefix_len(&tokens(&[1, 2]), &tokens(&[1, 2, 3])), 2);
+        assert_eq!(common_prefix_len(&tokens(&[1, 2, 3]), &tokens(&[1, 2])), 2);
+        assert_eq!(common_prefix_len(&tokens(&[7]), &tokens(&[1, 2])), 0);
+        assert_eq!(common_prefix_len(&[], &tokens(&[1])), 0);
+    }
+
+    fn read_requests(input: &str) -> (Vec<Request>, bool) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let requests = spawn_request_reader(io::Cursor::new(input.to_string()), cancel.clone());
+        let requests = requests.iter().map(|request| request.unwrap()).collect();
+        (requests, cancel.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn test_cancel_is_handled_by_the_reader() {
+        // A cancel arriving during a generation sets the flag and isn't queued
+        let (requests, cancelled) = read_requests(
+            "{\"type\":\"generate\",\"prompt\":\"Hola\"}\n{\"type\":\"cancel\"}\n{\"type\":\"ping\"}\n",
+        );
+        assert!(matches!(
+            requests.as_slice(),
+            [Request::Generate { .. }, Request::Ping]
+        ));
+        assert!(cancelled);
+
+        // A late cancel doesn't stop the next generation
+        let (requests, cancelled) =
+            read_requests("{\"type\":\"cancel\"}\n{\"type\":\"generate\",\"prompt\":\"Hola\"}\n");
+        assert!(matches!(requests.as_slice(), [Request::Generate { .. }]));
+        assert!(!cancelled);
+    }
+
+    #[test]
+    fn test_cancelled_generation_gets_cancelled_response() {
+        let response = generation_response(Ok(Generation {
+            text: "Hola".to_string(),
+            cancelled: true,
+            stats: None,
+        }));
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({"type": "cancelled", "text": "Hola"})
+        );
+    }
+
+    /// Cancels a streamed generation after its first token, then generates again
+    /// with the same context. Run with `LLAMA_HELPER_TEST_MODEL` set to a GGUF
+    /// model and `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "needs a GGUF model in LLAMA_HELPER_TEST_MODEL"]
+    fn test_cancel_while_streaming_keeps_context_usable() {
+        let model_path =
+            std::env::var("LLAMA_HELPER_TEST_MODEL").expect("LLAMA_HELPER_TEST_MODEL is not set");
+        let mut state = ModelState::new().unwrap();
+        state
+            .load_model_if_needed(PathBuf::from(model_path), 2048, None)
+            .unwrap();
+        let params = || GenerateParams {
+            prompt: "Count from one to one hundred in Spanish:".to_string(),
+            max_tokens: 64,
+            temperature: 0.0,
+            top_k: 64,
+            top_p: 0.95,
+            stop_tokens: Vec::new(),
+            grammar: None,
+        };
+
+        let cancel = AtomicBool::new(false);
+        let mut streamed_tokens = 0;
+        let generation = state
+            .generate(params(), &cancel, &mut |message| {
+                if let Response::Token { .. } = message {
+                    streamed_tokens += 1;
+                    cancel.store(true, Ordering::SeqCst);
+                }
+            })
+            .unwrap();
+        assert!(generation.cancelled);
+        assert_eq!(streamed_tokens, 1);
+        assert!(matches!(
+            generation_response(Ok(generation)),
+            Response::Cancelled { .. }
+        ));
+
+        // The next request runs to completion and reuses the evaluated prompt
+        cancel.store(false, Ordering::SeqCst);
+        let generation = state.generate(params(), &cancel, &mut |_| {}).unwrap();
+        assert!(!generation.cancelled);
+        assert!(!generation.text.is_empty());
+        let stats = generation.stats.unwrap();
+        assert_eq!(stats.cached_prompt_tokens, stats.prompt_tokens - 1);
+        assert_eq!(
+            stats.prompt_tokens_since_load,
+            2 * stats.prompt_tokens as u64
+        );
+        assert_eq!(
+            stats.cached_prompt_tokens_since_load,
+            stats.cached_prompt_tokens as u64
+        );
+    }
+}